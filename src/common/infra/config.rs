@@ -18,11 +18,12 @@ use std::sync::Arc;
 use config::{
     RwAHashMap, RwHashMap,
     meta::{
-        alerts::alert::Alert,
+        alerts::{alert::Alert, maintenance_window::MaintenanceWindow},
         destinations::{Destination, Template},
         folder::Folder,
         function::Transform,
         promql::ClusterLeader,
+        quota::OrgQuota,
         ratelimit::CachedUserRoles,
         stream::StreamParams,
         user::User,
@@ -56,6 +57,9 @@ pub static USERS: Lazy<RwHashMap<String, infra::table::users::UserRecord>> =
     Lazy::new(DashMap::default);
 pub static ORG_USERS: Lazy<RwHashMap<String, infra::table::org_users::OrgUserRecord>> =
     Lazy::new(DashMap::default);
+pub static API_KEYS: Lazy<RwHashMap<String, infra::table::api_keys::ApiKeyRecord>> =
+    Lazy::new(DashMap::default);
+pub static ORG_QUOTAS: Lazy<RwHashMap<String, OrgQuota>> = Lazy::new(DashMap::default);
 pub static USERS_RUM_TOKEN: Lazy<Arc<RwHashMap<String, infra::table::org_users::OrgUserRecord>>> =
     Lazy::new(|| Arc::new(DashMap::default()));
 pub static ROOT_USER: Lazy<RwHashMap<String, User>> = Lazy::new(DashMap::default);
@@ -75,6 +79,11 @@ pub static REALTIME_ALERT_TRIGGERS: Lazy<RwAHashMap<String, db_scheduler::Trigge
     Lazy::new(Default::default);
 pub static ALERTS_TEMPLATES: Lazy<RwHashMap<String, Template>> = Lazy::new(Default::default);
 pub static DESTINATIONS: Lazy<RwHashMap<String, Destination>> = Lazy::new(Default::default);
+// Key for alert firing states cache is org/alert_id
+pub static ALERT_FIRING_STATES: Lazy<RwHashMap<String, bool>> = Lazy::new(Default::default);
+// Key for maintenance windows cache is org/window_id
+pub static MAINTENANCE_WINDOWS: Lazy<RwHashMap<String, MaintenanceWindow>> =
+    Lazy::new(Default::default);
 pub static SYSLOG_ROUTES: Lazy<RwHashMap<String, SyslogRoute>> = Lazy::new(Default::default);
 pub static SYSLOG_ENABLED: Lazy<Arc<RwLock<bool>>> = Lazy::new(|| Arc::new(RwLock::new(false)));
 pub static ENRICHMENT_TABLES: Lazy<RwHashMap<String, StreamTable>> = Lazy::new(Default::default);
@@ -19,6 +19,7 @@ pub mod ingestion;
 pub mod loki;
 pub mod maxmind;
 pub mod middleware_data;
+pub mod org_bundle;
 pub mod organization;
 pub mod proxy;
 pub mod saved_view;
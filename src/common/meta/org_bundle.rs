@@ -0,0 +1,292 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::str::FromStr;
+
+use arrow_schema::Schema;
+use config::{
+    meta::{
+        alerts::alert::Alert,
+        dashboards::Dashboard,
+        destinations::{Destination, Template},
+        folder::{Folder, FolderType},
+        function::Transform,
+        pipeline::Pipeline,
+        stream::StreamType,
+    },
+    utils::json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::common::meta::saved_view::View;
+
+/// Version of the bundle format written by this build.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Kinds of objects an org bundle carries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleKind {
+    Folders,
+    Dashboards,
+    Alerts,
+    Destinations,
+    Templates,
+    Functions,
+    Pipelines,
+    SavedViews,
+    EnrichmentTables,
+    Streams,
+}
+
+impl BundleKind {
+    pub const ALL: [BundleKind; 10] = [
+        BundleKind::Folders,
+        BundleKind::Dashboards,
+        BundleKind::Alerts,
+        BundleKind::Destinations,
+        BundleKind::Templates,
+        BundleKind::Functions,
+        BundleKind::Pipelines,
+        BundleKind::SavedViews,
+        BundleKind::EnrichmentTables,
+        BundleKind::Streams,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BundleKind::Folders => "folders",
+            BundleKind::Dashboards => "dashboards",
+            BundleKind::Alerts => "alerts",
+            BundleKind::Destinations => "destinations",
+            BundleKind::Templates => "templates",
+            BundleKind::Functions => "functions",
+            BundleKind::Pipelines => "pipelines",
+            BundleKind::SavedViews => "saved_views",
+            BundleKind::EnrichmentTables => "enrichment_tables",
+            BundleKind::Streams => "streams",
+        }
+    }
+}
+
+/// Stream data to carry in a bundle. Only logs streams can have their data exported.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct StreamDataRange {
+    pub stream_name: String,
+    #[serde(default)]
+    pub stream_type: StreamType,
+    /// Start of the range in microseconds.
+    pub start_time: i64,
+    /// End of the range in microseconds.
+    pub end_time: i64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct OrgBundleExportRequest {
+    /// Kinds of objects to export, all of them when empty.
+    #[serde(default)]
+    pub kinds: Vec<BundleKind>,
+    /// Stream data to export along with the objects.
+    #[serde(default)]
+    pub data: Vec<StreamDataRange>,
+}
+
+/// What to do with a bundle object whose name is already taken in the org.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// Keep the existing object. Folders are reused, so their contents are still imported.
+    #[default]
+    Skip,
+    /// Replace the existing object.
+    Overwrite,
+    /// Import the object under a new name. Streams are referenced by name everywhere, so they
+    /// are skipped instead.
+    Rename,
+}
+
+impl FromStr for ConflictStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(ConflictStrategy::Skip),
+            "overwrite" => Ok(ConflictStrategy::Overwrite),
+            "rename" => Ok(ConflictStrategy::Rename),
+            _ => Err(format!("Invalid conflict strategy: {s}")),
+        }
+    }
+}
+
+/// First file of a bundle archive, describing what the bundle carries.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub version: u32,
+    /// Org the bundle was exported from.
+    pub org_id: String,
+    pub exported_at: i64,
+    pub kinds: Vec<BundleKind>,
+    #[serde(default)]
+    pub data: Vec<StreamDataRange>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleFolder {
+    pub folder_type: FolderType,
+    pub folder_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl BundleFolder {
+    pub fn new(folder_type: FolderType, folder: Folder) -> Self {
+        Self {
+            folder_type,
+            folder_id: folder.folder_id,
+            name: folder.name,
+            description: folder.description,
+        }
+    }
+
+    pub fn folder(&self) -> Folder {
+        Folder {
+            folder_id: self.folder_id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleDashboard {
+    pub folder_id: String,
+    pub dashboard: Dashboard,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleAlert {
+    pub folder_id: String,
+    pub alert: Alert,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleEnrichmentTable {
+    pub name: String,
+    pub records: Vec<json::Value>,
+}
+
+/// Schema of a stream with its settings in the metadata.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleStream {
+    pub stream_name: String,
+    pub stream_type: StreamType,
+    pub schema: Schema,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BundleStreamData {
+    pub range: StreamDataRange,
+    pub records: Vec<json::Value>,
+}
+
+/// Objects of an org, in the order they are imported.
+#[derive(Default, Serialize, Deserialize)]
+pub struct OrgBundle {
+    pub templates: Vec<Template>,
+    pub destinations: Vec<Destination>,
+    pub functions: Vec<Transform>,
+    pub folders: Vec<BundleFolder>,
+    pub streams: Vec<BundleStream>,
+    pub enrichment_tables: Vec<BundleEnrichmentTable>,
+    pub pipelines: Vec<Pipeline>,
+    pub alerts: Vec<BundleAlert>,
+    pub dashboards: Vec<BundleDashboard>,
+    pub saved_views: Vec<View>,
+    pub data: Vec<BundleStreamData>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportAction {
+    Created,
+    Overwritten,
+    Renamed,
+    Skipped,
+    Failed,
+}
+
+/// Outcome of importing one bundle object.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportItem {
+    pub kind: BundleKind,
+    /// Name of the object in the bundle.
+    pub name: String,
+    pub action: ImportAction,
+    /// Name the object was imported under, when it was renamed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_name: Option<String>,
+    /// Id of the object in the exporting org.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_id: Option<String>,
+    /// Id of the object in this org.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ImportItem {
+    pub fn new(kind: BundleKind, name: &str, action: ImportAction) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            action,
+            new_name: None,
+            old_id: None,
+            new_id: None,
+            error: None,
+        }
+    }
+
+    pub fn failed(kind: BundleKind, name: &str, error: impl ToString) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Self::new(kind, name, ImportAction::Failed)
+        }
+    }
+
+    pub fn with_ids(mut self, old_id: Option<String>, new_id: Option<String>) -> Self {
+        self.old_id = old_id;
+        self.new_id = new_id;
+        self
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct DataImportResult {
+    pub stream_name: String,
+    pub stream_type: StreamType,
+    pub records: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct OrgBundleImportResponse {
+    pub items: Vec<ImportItem>,
+    pub data: Vec<DataImportResult>,
+}
@@ -792,7 +792,10 @@ impl FromRequest for AuthExtractor {
             let auth_tokens: AuthTokens =
                 json::from_str(std::str::from_utf8(&val).unwrap_or_default()).unwrap_or_default();
             let access_token = auth_tokens.access_token;
-            if access_token.starts_with("Basic") || access_token.starts_with("Bearer") {
+            if access_token.starts_with("Basic")
+                || access_token.starts_with("Bearer")
+                || access_token.starts_with("session ")
+            {
                 access_token
             } else {
                 format!("Bearer {}", access_token)
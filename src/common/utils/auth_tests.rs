@@ -2966,6 +2966,8 @@ mod tests {
                 cookie_same_site_lax: bool::default(),
                 cookie_secure_only: bool::default(),
                 ext_auth_salt: String::default(),
+                client_cert_mapping_path: String::default(),
+                client_cert_deny_fingerprints: String::default(),
                 action_server_token: String::default(),
             },
             report_server: config::ReportServer {
@@ -2984,6 +2986,7 @@ mod tests {
                 tls_cert_path: String::default(),
                 tls_key_path: String::default(),
                 tls_min_version: String::default(),
+                tls_client_ca_path: String::default(),
                 tls_root_certificates: String::default(),
             },
             grpc: config::Grpc {
@@ -2998,6 +3001,7 @@ mod tests {
                 tls_enabled: bool::default(),
                 tls_cert_domain: String::default(),
                 tls_cert_path: String::default(),
+                tls_client_ca_path: String::default(),
                 tls_key_path: String::default(),
             },
             websocket: config::WebSocket {
@@ -3092,6 +3096,9 @@ mod tests {
                 usage_reporting_creds: String::default(),
                 usage_batch_size: usize::default(),
                 usage_publish_interval: i64::default(),
+                audit_enabled: bool::default(),
+                audit_retention_days: i64::default(),
+                audit_redact_fields: String::default(),
                 mmdb_data_dir: String::default(),
                 mmdb_disable_download: bool::default(),
                 mmdb_update_duration_days: u64::default(),
@@ -3189,6 +3196,8 @@ mod tests {
                 http_keep_alive_disabled: bool::default(),
                 default_max_query_range_days: i64::default(),
                 max_dashboard_series: usize::default(),
+                dashboard_max_versions: usize::default(),
+                dashboard_versions_retention_days: i64::default(),
                 ingest_allowed_upto: i64::default(),
                 ingest_allowed_in_future: i64::default(),
                 ingest_flatten_level: u32::default(),
@@ -3212,6 +3221,10 @@ mod tests {
                 job_runtime_blocking_worker_num: usize::default(),
                 job_runtime_shutdown_timeout: u64::default(),
                 calculate_stats_interval: u64::default(),
+                quota_refresh_interval: u64::default(),
+                org_bundle_max_size: u64::default(),
+                org_bundle_max_file_size: u64::default(),
+                org_bundle_max_export_records: usize::default(),
                 enrichment_table_max_size: usize::default(),
                 http_request_timeout: u64::default(),
                 http_keep_alive: u64::default(),
@@ -3220,7 +3233,9 @@ mod tests {
                 alert_schedule_interval: i64::default(),
                 alert_schedule_concurrency: i64::default(),
                 alert_schedule_timeout: i64::default(),
+                alert_backtest_max_evaluations: usize::default(),
                 report_schedule_timeout: i64::default(),
+                report_data_max_rows: usize::default(),
                 derived_stream_schedule_interval: i64::default(),
                 scheduler_max_retries: i32::default(),
                 pause_alerts_on_retries: bool::default(),
@@ -3264,6 +3279,8 @@ mod tests {
                 file_download_priority_queue_window_secs: Default::default(),
                 file_download_enable_priority_queue: Default::default(),
                 histogram_enabled: Default::default(),
+                wasm_function_fuel_limit: u64::default(),
+                wasm_function_memory_limit: usize::default(),
                 calculate_stats_step_limit: Default::default(),
             },
             compact: config::Compact {
@@ -3463,12 +3480,30 @@ mod tests {
                 remote_request_timeout: u64::default(),
                 remote_request_max_retry_time: u64::default(),
                 max_connections: usize::default(),
+                dedup_max_keys: usize::default(),
+                dead_letter_replay_max_records: usize::default(),
                 wal_size_limit: u64::default(),
             },
             encryption: config::Encryption {
                 algorithm: String::default(),
                 master_key: String::default(),
             },
+            oidc: config::Oidc {
+                enabled: bool::default(),
+                issuer_url: String::default(),
+                client_id: String::default(),
+                client_secret: String::default(),
+                redirect_url: String::default(),
+                ui_redirect_url: String::default(),
+                scopes: String::default(),
+                groups_claim: String::default(),
+                group_role_mapping: String::default(),
+                domain_role_mapping: String::default(),
+                default_org: String::default(),
+                default_role: String::default(),
+                trust_unverified_email: bool::default(),
+                session_ttl: i64::default(),
+            },
         }
     }
 }
@@ -32,6 +32,10 @@ pub async fn get_all_transform_keys(org_id: &str) -> Vec<String> {
         .clone()
         .iter()
         .filter_map(|transform| {
+            // WASM functions can't be called from SQL
+            if transform.is_wasm() {
+                return None;
+            }
             let key = transform.key();
             key.strip_prefix(org_key).map(|x| x.to_string())
         })
@@ -46,7 +46,7 @@ pub type RwAHashSet<K> = tokio::sync::RwLock<HashSet<K>>;
 pub type RwBTreeMap<K, V> = tokio::sync::RwLock<BTreeMap<K, V>>;
 
 // for DDL commands and migrations
-pub const DB_SCHEMA_VERSION: u64 = 5;
+pub const DB_SCHEMA_VERSION: u64 = 6;
 pub const DB_SCHEMA_KEY: &str = "/db_schema_version/";
 
 // global version variables
@@ -424,6 +424,7 @@ pub struct Config {
     pub pipeline: Pipeline,
     pub health_check: HealthCheck,
     pub encryption: Encryption,
+    pub oidc: Oidc,
 }
 
 #[derive(EnvConfig)]
@@ -598,6 +599,18 @@ pub struct Auth {
     pub ext_auth_salt: String,
     #[env_config(name = "O2_ACTION_SERVER_TOKEN")]
     pub action_server_token: String,
+    #[env_config(
+        name = "ZO_CLIENT_CERT_MAPPING_PATH",
+        default = "",
+        help = "JSON file mapping client certificate subjects or SANs to an org and a service account"
+    )]
+    pub client_cert_mapping_path: String,
+    #[env_config(
+        name = "ZO_CLIENT_CERT_DENY_FINGERPRINTS",
+        default = "",
+        help = "comma separated hex SHA-256 fingerprints of revoked client certificates"
+    )]
+    pub client_cert_deny_fingerprints: String,
 }
 
 #[derive(EnvConfig)]
@@ -616,6 +629,12 @@ pub struct Http {
     pub tls_key_path: String,
     #[env_config(name = "ZO_HTTP_TLS_MIN_VERSION", default = "", help = "Supported values: "1.2" or "1.3", default is all_version")]
     pub tls_min_version: String,
+    #[env_config(
+        name = "ZO_HTTP_TLS_CLIENT_CA_PATH",
+        default = "",
+        help = "CA certificates to verify client certificates with, enables optional mutual TLS"
+    )]
+    pub tls_client_ca_path: String,
     #[env_config(
         name = "ZO_HTTP_TLS_ROOT_CERTIFICATES",
         default = "webpki",
@@ -654,6 +673,12 @@ pub struct Grpc {
     pub tls_cert_path: String,
     #[env_config(name = "ZO_GRPC_TLS_KEY_PATH", default = "")]
     pub tls_key_path: String,
+    #[env_config(
+        name = "ZO_GRPC_TLS_CLIENT_CA_PATH",
+        default = "",
+        help = "CA certificates to verify client certificates with, enables optional mutual TLS"
+    )]
+    pub tls_client_ca_path: String,
 }
 
 #[derive(EnvConfig)]
@@ -891,6 +916,24 @@ pub struct Common {
     )]
     // in seconds
     pub usage_publish_interval: i64,
+    #[env_config(
+        name = "ZO_AUDIT_ENABLED",
+        default = false,
+        help = "record every non-GET API call in the _audit stream of its organization"
+    )]
+    pub audit_enabled: bool,
+    #[env_config(
+        name = "ZO_AUDIT_RETENTION_DAYS",
+        default = 0,
+        help = "data retention of the _audit streams in days, 0 uses ZO_COMPACT_DATA_RETENTION_DAYS"
+    )]
+    pub audit_retention_days: i64,
+    #[env_config(
+        name = "ZO_AUDIT_REDACT_FIELDS",
+        default = "password,passcode,token,secret,key,credentials,authorization",
+        help = "request body fields containing any of these words are redacted in audit records"
+    )]
+    pub audit_redact_fields: String,
     #[env_config(name = "ZO_MMDB_DATA_DIR")] // ./data/openobserve/mmdb/
     pub mmdb_data_dir: String,
     #[env_config(name = "ZO_MMDB_DISABLE_DOWNLOAD", default = false)]
@@ -1286,6 +1329,30 @@ pub struct Limit {
     pub calculate_stats_interval: u64,
     #[env_config(name = "ZO_CALCULATE_STATS_STEP_LIMIT", default = 1000000)] // records
     pub calculate_stats_step_limit: i64,
+    #[env_config(
+        name = "ZO_QUOTA_REFRESH_INTERVAL",
+        default = 60,
+        help = "How often ingesters refresh the usage counted against org quotas, in seconds"
+    )]
+    pub quota_refresh_interval: u64,
+    #[env_config(
+        name = "ZO_ORG_BUNDLE_MAX_SIZE",
+        default = 1024,
+        help = "Maximum uncompressed size of an imported org bundle, unit is MB"
+    )]
+    pub org_bundle_max_size: u64,
+    #[env_config(
+        name = "ZO_ORG_BUNDLE_MAX_FILE_SIZE",
+        default = 256,
+        help = "Maximum uncompressed size of each file of an imported org bundle, unit is MB"
+    )]
+    pub org_bundle_max_file_size: u64,
+    #[env_config(
+        name = "ZO_ORG_BUNDLE_MAX_EXPORT_RECORDS",
+        default = 1000000,
+        help = "Maximum number of records exported for each stream data range of an org bundle"
+    )]
+    pub org_bundle_max_export_records: usize,
     #[env_config(name = "ZO_ACTIX_REQ_TIMEOUT", default = 5)] // seconds
     pub http_request_timeout: u64,
     #[env_config(name = "ZO_ACTIX_KEEP_ALIVE", default = 5)] // seconds
@@ -1302,8 +1369,20 @@ pub struct Limit {
     pub alert_schedule_concurrency: i64,
     #[env_config(name = "ZO_ALERT_SCHEDULE_TIMEOUT", default = 90)] // seconds
     pub alert_schedule_timeout: i64,
+    #[env_config(
+        name = "ZO_ALERT_BACKTEST_MAX_EVALUATIONS",
+        default = 1000,
+        help = "Maximum number of evaluations one alert backtest replays"
+    )]
+    pub alert_backtest_max_evaluations: usize,
     #[env_config(name = "ZO_REPORT_SCHEDULE_TIMEOUT", default = 300)] // seconds
     pub report_schedule_timeout: i64,
+    #[env_config(
+        name = "ZO_REPORT_DATA_MAX_ROWS",
+        default = 100000,
+        help = "Maximum number of rows of each panel in csv and xlsx reports, 0 means unlimited"
+    )]
+    pub report_data_max_rows: usize,
     #[env_config(name = "ZO_DERIVED_STREAM_SCHEDULE_INTERVAL", default = 300)] // seconds
     pub derived_stream_schedule_interval: i64,
     #[env_config(name = "ZO_SCHEDULER_MAX_RETRIES", default = 3)]
@@ -1466,6 +1545,18 @@ pub struct Limit {
         help = "maximum series to display in charts"
     )]
     pub max_dashboard_series: usize,
+    #[env_config(
+        name = "ZO_DASHBOARD_MAX_VERSIONS",
+        default = 50,
+        help = "maximum saved versions kept for each dashboard, 0 keeps all the versions"
+    )]
+    pub dashboard_max_versions: usize,
+    #[env_config(
+        name = "ZO_DASHBOARD_VERSIONS_RETENTION_DAYS",
+        default = 0,
+        help = "unit: Day. Saved versions of a dashboard older than this are removed, 0 keeps them forever. The latest version is always kept"
+    )]
+    pub dashboard_versions_retention_days: i64,
     #[env_config(
         name = "ZO_SEARCH_MINI_PARTITION_DURATION_SECS",
         default = 60,
@@ -1478,6 +1569,18 @@ pub struct Limit {
         default = true
     )]
     pub histogram_enabled: bool,
+    #[env_config(
+        name = "ZO_WASM_FUNCTION_FUEL_LIMIT",
+        default = 10000000,
+        help = "Maximum fuel (roughly instructions) a WASM function may consume per invocation"
+    )]
+    pub wasm_function_fuel_limit: u64,
+    #[env_config(
+        name = "ZO_WASM_FUNCTION_MEMORY_LIMIT",
+        default = 64,
+        help = "unit: MB. Maximum linear memory a WASM function instance may grow to"
+    )]
+    pub wasm_function_memory_limit: usize,
 }
 
 #[derive(EnvConfig)]
@@ -1878,6 +1981,18 @@ pub struct Pipeline {
         help = "pipeline exporter client max connections"
     )]
     pub max_connections: usize,
+    #[env_config(
+        name = "ZO_PIPELINE_DEDUP_MAX_KEYS",
+        default = 100000,
+        help = "Maximum number of keys remembered by each dedup node of a pipeline, the oldest keys are evicted first. 0 means no limit"
+    )]
+    pub dedup_max_keys: usize,
+    #[env_config(
+        name = "ZO_PIPELINE_DEAD_LETTER_REPLAY_MAX_RECORDS",
+        default = 10000,
+        help = "Maximum number of dead letter records replayed per replay request"
+    )]
+    pub dead_letter_replay_max_records: usize,
 }
 
 #[derive(EnvConfig)]
@@ -1888,6 +2003,77 @@ pub struct Encryption {
     pub master_key: String,
 }
 
+#[derive(EnvConfig)]
+pub struct Oidc {
+    #[env_config(
+        name = "ZO_OIDC_ENABLED",
+        default = false,
+        help = "Enable the built-in OIDC login of the open source build"
+    )]
+    pub enabled: bool,
+    #[env_config(name = "ZO_OIDC_ISSUER_URL", default = "")]
+    pub issuer_url: String,
+    #[env_config(name = "ZO_OIDC_CLIENT_ID", default = "")]
+    pub client_id: String,
+    #[env_config(
+        name = "ZO_OIDC_CLIENT_SECRET",
+        default = "",
+        help = "Leave empty for public clients, which rely on PKCE only"
+    )]
+    pub client_secret: String,
+    #[env_config(
+        name = "ZO_OIDC_REDIRECT_URL",
+        default = "",
+        help = "Callback registered at the IdP, default is {ZO_WEB_URL}{ZO_BASE_URI}/config/redirect"
+    )]
+    pub redirect_url: String,
+    #[env_config(
+        name = "ZO_OIDC_UI_REDIRECT_URL",
+        default = "",
+        help = "Where the UI is sent after login, default is {ZO_WEB_URL}{ZO_BASE_URI}/web/cb"
+    )]
+    pub ui_redirect_url: String,
+    #[env_config(
+        name = "ZO_OIDC_SCOPES",
+        default = "openid email profile groups offline_access"
+    )]
+    pub scopes: String,
+    #[env_config(name = "ZO_OIDC_GROUPS_CLAIM", default = "groups")]
+    pub groups_claim: String,
+    #[env_config(
+        name = "ZO_OIDC_GROUP_ROLE_MAPPING",
+        default = "",
+        help = "comma separated group=org:role entries, e.g. o2-admins=default:admin"
+    )]
+    pub group_role_mapping: String,
+    #[env_config(
+        name = "ZO_OIDC_DOMAIN_ROLE_MAPPING",
+        default = "",
+        help = "comma separated domain=org:role entries, e.g. example.com=default:viewer"
+    )]
+    pub domain_role_mapping: String,
+    #[env_config(
+        name = "ZO_OIDC_DEFAULT_ORG",
+        default = "",
+        help = "Org users without any matching claim join, leave empty to deny them"
+    )]
+    pub default_org: String,
+    #[env_config(name = "ZO_OIDC_DEFAULT_ROLE", default = "viewer")]
+    pub default_role: String,
+    #[env_config(
+        name = "ZO_OIDC_TRUST_UNVERIFIED_EMAIL",
+        default = false,
+        help = "Let id tokens without a verified email log in as the existing user of that email"
+    )]
+    pub trust_unverified_email: bool,
+    #[env_config(
+        name = "ZO_OIDC_SESSION_TTL",
+        default = 3600,
+        help = "Lifetime of a login session in seconds, refresh tokens live for ZO_COOKIE_MAX_AGE"
+    )]
+    pub session_ttl: i64,
+}
+
 #[derive(EnvConfig)]
 pub struct HealthCheck {
     #[env_config(name = "ZO_HEALTH_CHECK_ENABLED", default = true)]
@@ -1980,6 +2166,11 @@ pub fn init() -> Config {
     if let Err(e) = check_encryption_config(&mut cfg) {
         panic!("encryption config error: {e}");
     }
+
+    // check oidc config
+    if let Err(e) = check_oidc_config(&mut cfg) {
+        panic!("oidc config error: {e}");
+    }
     // check health check config
     if let Err(e) = check_health_check_config(&mut cfg) {
         panic!("health check config error: {e}");
@@ -2722,6 +2913,9 @@ fn check_pipeline_config(cfg: &mut Config) -> Result<(), anyhow::Error> {
     if cfg.pipeline.remote_request_max_retry_time == 0 {
         cfg.pipeline.remote_request_max_retry_time = 86400; // 24 hours, in seconds
     }
+    if cfg.pipeline.dead_letter_replay_max_records == 0 {
+        cfg.pipeline.dead_letter_replay_max_records = 10000;
+    }
 
     if cfg.pipeline.wal_size_limit == 0 {
         cfg.pipeline.wal_size_limit = cfg.limit.disk_free as u64 / 2; // 50%
@@ -2787,6 +2981,31 @@ fn check_encryption_config(cfg: &mut Config) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+fn check_oidc_config(cfg: &mut Config) -> Result<(), anyhow::Error> {
+    if !cfg.oidc.enabled {
+        return Ok(());
+    }
+    if cfg.oidc.issuer_url.is_empty() || cfg.oidc.client_id.is_empty() {
+        return Err(anyhow::anyhow!(
+            "ZO_OIDC_ISSUER_URL and ZO_OIDC_CLIENT_ID are required when ZO_OIDC_ENABLED is true"
+        ));
+    }
+    cfg.oidc.issuer_url = cfg.oidc.issuer_url.trim_end_matches('/').to_string();
+    if cfg.oidc.redirect_url.is_empty() {
+        cfg.oidc.redirect_url = format!(
+            "{}{}/config/redirect",
+            cfg.common.web_url, cfg.common.base_uri
+        );
+    }
+    if cfg.oidc.ui_redirect_url.is_empty() {
+        cfg.oidc.ui_redirect_url = format!("{}{}/web/cb", cfg.common.web_url, cfg.common.base_uri);
+    }
+    if cfg.oidc.session_ttl <= 0 {
+        cfg.oidc.session_ttl = 3600;
+    }
+    Ok(())
+}
+
 fn check_tcp_tls_config(cfg: &mut Config) -> Result<(), anyhow::Error> {
     if cfg.tcp.tcp_tls_enabled
         && (cfg.tcp.tcp_tls_cert_path.is_empty()
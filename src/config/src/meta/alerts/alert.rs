@@ -72,6 +72,10 @@ pub struct Alert {
     pub updated_at: Option<DateTime<FixedOffset>>,
     #[serde(default)]
     pub last_edited_by: Option<String>,
+    /// Ids of the parent alerts. The notifications of the alert are suppressed while one of
+    /// them is firing.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
 }
 
 impl PartialEq for Alert {
@@ -104,6 +108,7 @@ impl Default for Alert {
             updated_at: None,
             last_edited_by: None,
             last_satisfied_at: None,
+            depends_on: vec![],
         }
     }
 }
@@ -120,6 +125,20 @@ impl Alert {
             .to_string()
     }
 
+    /// Ids of the alerts whose states this alert reads, its parents and the alerts combined by
+    /// its composite condition. They are evaluated before it within a scheduler tick.
+    pub fn upstream_alert_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.depends_on.iter().map(|id| id.as_str()).collect();
+        if let Some(composite) = self.query_condition.composite.as_ref() {
+            for id in composite.alert_ids() {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        ids
+    }
+
     /// Checks the last satisfied at time for the alert from the scheduled_jobs table first.
     /// If it is not present, then it uses the last_satisfied_at time from the alert table.
     /// Use this function instead of `get_last_satisfied_at_from_table` to get the actual timestamp.
@@ -0,0 +1,51 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::utils::json::{Map, Value};
+
+/// One evaluation a backtest replayed.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct BacktestEvaluation {
+    /// Start of the evaluated time range, in microseconds.
+    pub start_time: i64,
+    /// End of the evaluated time range, which is when the scheduler would have run the alert, in
+    /// microseconds.
+    pub end_time: i64,
+    /// Whether the alert would have fired and sent its notifications.
+    pub fired: bool,
+    /// Number of rows the query returned.
+    pub row_count: usize,
+    /// Rows the query returned, up to the requested number of rows.
+    #[schema(value_type = Vec<Object>)]
+    pub rows: Vec<Map<String, Value>>,
+    /// End of the silence period the alert would have entered after firing, in microseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub silenced_until: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Timeline of the evaluations of an alert over a past time range.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct BacktestResult {
+    pub evaluations: Vec<BacktestEvaluation>,
+    /// Number of evaluations the alert fired in.
+    pub fired: usize,
+    /// Whether the time range has more evaluations than one backtest replays.
+    pub truncated: bool,
+}
@@ -0,0 +1,341 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, FixedOffset, TimeZone, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::alert::Alert;
+use crate::utils::str::wildcard_match;
+
+/// A period during which the notifications of the targeted alerts are suppressed. The alerts
+/// are still evaluated and their evaluations recorded.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceWindow {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Start of the window, or of its first occurrence when recurring (microseconds).
+    pub start_time: i64,
+    /// Length of each occurrence (minutes).
+    pub duration_minutes: i64,
+    /// Cron expression with seconds of the starts of the occurrences. The window happens once
+    /// when it is not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cron: Option<String>,
+    /// No occurrence starts after this time when set (microseconds).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<i64>,
+    /// IANA name of the timezone used to evaluate the cron expression, e.g. `Europe/Paris`.
+    /// It follows the daylight saving time changes and takes precedence over `tz_offset`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Timezone offset in minutes used to evaluate the cron expression when `timezone` isn't
+    /// set. The negative secs means the Western Hemisphere
+    #[serde(default)]
+    pub tz_offset: i32,
+    #[serde(default)]
+    pub targets: MaintenanceTargets,
+    #[serde(default)]
+    pub created_by: String,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+/// Selects the alerts of a maintenance window. An alert is targeted when it matches every
+/// non-empty selector, so a window without selectors targets all the alerts of the org.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceTargets {
+    /// Ids of the alert folders.
+    #[serde(default)]
+    pub folders: Vec<String>,
+    /// Alert name patterns, `*` matches any sequence of characters.
+    #[serde(default)]
+    pub alert_names: Vec<String>,
+    /// Labels the context attributes of the alert must all have.
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl MaintenanceWindow {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Maintenance window name is required".to_string());
+        }
+        if self.duration_minutes <= 0 {
+            return Err("Maintenance window duration should be greater than 0".to_string());
+        }
+        if DateTime::<Utc>::from_timestamp_micros(self.start_time).is_none() {
+            return Err("Maintenance window start_time is invalid".to_string());
+        }
+        if FixedOffset::east_opt(self.tz_offset * 60).is_none() {
+            return Err("Maintenance window tz_offset is invalid".to_string());
+        }
+        if let Some(timezone) = self.timezone.as_ref() {
+            if Tz::from_str(timezone).is_err() {
+                return Err(format!(
+                    "Maintenance window timezone [{timezone}] is invalid"
+                ));
+            }
+        }
+        if let Some(cron) = self.cron.as_ref() {
+            if let Err(e) = Schedule::from_str(cron) {
+                return Err(format!(
+                    "Maintenance window cron expression is invalid: {e}"
+                ));
+            }
+        }
+        if self.until.is_some_and(|until| until < self.start_time) {
+            return Err("Maintenance window until should be after start_time".to_string());
+        }
+        Ok(())
+    }
+
+    fn duration_micros(&self) -> i64 {
+        Duration::try_minutes(self.duration_minutes)
+            .and_then(|d| d.num_microseconds())
+            .unwrap_or_default()
+    }
+
+    /// Returns the end of the occurrence of the window active at `now` (microseconds).
+    pub fn active_until(&self, now: i64) -> Option<i64> {
+        if !self.enabled || now < self.start_time {
+            return None;
+        }
+        let duration = self.duration_micros();
+        let Some(cron) = self.cron.as_ref() else {
+            let end = self.start_time + duration;
+            return (now < end).then_some(end);
+        };
+        let schedule = Schedule::from_str(cron).ok()?;
+        match self.timezone.as_deref().map(Tz::from_str) {
+            Some(Ok(tz)) => self.occurrence_end(&schedule, &tz, now),
+            _ => self.occurrence_end(&schedule, &FixedOffset::east_opt(self.tz_offset * 60)?, now),
+        }
+    }
+
+    /// Returns the end of the occurrence of the schedule evaluated in `tz` active at `now`.
+    fn occurrence_end<T: TimeZone>(&self, schedule: &Schedule, tz: &T, now: i64) -> Option<i64> {
+        let duration = self.duration_micros();
+        // occurrences starting after this time can't have ended yet
+        let from = DateTime::<Utc>::from_timestamp_micros(now - duration)?.with_timezone(tz);
+        schedule
+            .after(&from)
+            .map(|start| start.timestamp_micros())
+            .take_while(|start| *start <= now)
+            .filter(|start| *start >= self.start_time)
+            .filter(|start| self.until.is_none_or(|until| *start <= until))
+            .map(|start| start + duration)
+            .max()
+    }
+
+    /// Checks whether the window targets the given alert of the folder.
+    pub fn targets(&self, folder_id: &str, alert: &Alert) -> bool {
+        let targets = &self.targets;
+        if !targets.folders.is_empty() && !targets.folders.iter().any(|f| f == folder_id) {
+            return false;
+        }
+        if !targets.alert_names.is_empty()
+            && !targets
+                .alert_names
+                .iter()
+                .any(|pattern| wildcard_match(pattern, &alert.name))
+        {
+            return false;
+        }
+        targets.labels.iter().all(|(key, value)| {
+            alert
+                .context_attributes
+                .as_ref()
+                .and_then(|attrs| attrs.get(key))
+                .is_some_and(|v| v == value)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(s: &str) -> i64 {
+        DateTime::parse_from_rfc3339(s).unwrap().timestamp_micros()
+    }
+
+    #[test]
+    fn test_one_off_window() {
+        let window = MaintenanceWindow {
+            name: "deploy".to_string(),
+            enabled: true,
+            start_time: micros("2025-03-01T10:00:00Z"),
+            duration_minutes: 180,
+            ..Default::default()
+        };
+        assert!(window.validate().is_ok());
+        assert_eq!(window.active_until(micros("2025-03-01T09:59:59Z")), None);
+        assert_eq!(
+            window.active_until(micros("2025-03-01T10:00:00Z")),
+            Some(micros("2025-03-01T13:00:00Z"))
+        );
+        assert_eq!(window.active_until(micros("2025-03-01T13:00:00Z")), None);
+
+        let disabled = MaintenanceWindow {
+            enabled: false,
+            ..window
+        };
+        assert_eq!(disabled.active_until(micros("2025-03-01T11:00:00Z")), None);
+    }
+
+    #[test]
+    fn test_recurring_window() {
+        // 02:00 to 04:00 on Sundays at UTC+2
+        let window = MaintenanceWindow {
+            name: "weekly".to_string(),
+            enabled: true,
+            start_time: micros("2025-03-01T00:00:00Z"),
+            duration_minutes: 120,
+            cron: Some("0 0 2 * * Sun".to_string()),
+            until: Some(micros("2025-03-20T00:00:00Z")),
+            tz_offset: 120,
+            ..Default::default()
+        };
+        assert!(window.validate().is_ok());
+        // 2025-03-02 is a Sunday, 02:00 at UTC+2 is 00:00 UTC
+        assert_eq!(window.active_until(micros("2025-03-01T23:59:00Z")), None);
+        assert_eq!(
+            window.active_until(micros("2025-03-02T01:30:00Z")),
+            Some(micros("2025-03-02T02:00:00Z"))
+        );
+        assert_eq!(window.active_until(micros("2025-03-02T02:00:00Z")), None);
+        assert_eq!(window.active_until(micros("2025-03-03T01:00:00Z")), None);
+        assert!(
+            window
+                .active_until(micros("2025-03-09T00:00:00Z"))
+                .is_some()
+        );
+        // after until
+        assert_eq!(window.active_until(micros("2025-03-23T00:30:00Z")), None);
+        // occurrences before start_time are ignored
+        let late = MaintenanceWindow {
+            start_time: micros("2025-03-02T00:30:00Z"),
+            ..window
+        };
+        assert_eq!(late.active_until(micros("2025-03-02T01:00:00Z")), None);
+        assert_eq!(
+            late.active_until(micros("2025-03-09T01:00:00Z")),
+            Some(micros("2025-03-09T02:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_recurring_window_timezone() {
+        // 09:00 to 10:00 every day in Paris, which switches to summer time on 2025-03-30
+        let window = MaintenanceWindow {
+            name: "daily".to_string(),
+            enabled: true,
+            start_time: micros("2025-03-01T00:00:00Z"),
+            duration_minutes: 60,
+            cron: Some("0 0 9 * * *".to_string()),
+            timezone: Some("Europe/Paris".to_string()),
+            tz_offset: 60,
+            ..Default::default()
+        };
+        assert!(window.validate().is_ok());
+        assert_eq!(
+            window.active_until(micros("2025-03-29T08:30:00Z")),
+            Some(micros("2025-03-29T09:00:00Z"))
+        );
+        assert_eq!(window.active_until(micros("2025-03-30T08:30:00Z")), None);
+        assert_eq!(
+            window.active_until(micros("2025-03-30T07:30:00Z")),
+            Some(micros("2025-03-30T08:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let window = MaintenanceWindow {
+            name: "w".to_string(),
+            start_time: micros("2025-03-01T00:00:00Z"),
+            duration_minutes: 60,
+            ..Default::default()
+        };
+        assert!(window.validate().is_ok());
+        let invalid = [
+            MaintenanceWindow {
+                name: " ".to_string(),
+                ..window.clone()
+            },
+            MaintenanceWindow {
+                duration_minutes: 0,
+                ..window.clone()
+            },
+            MaintenanceWindow {
+                cron: Some("every sunday".to_string()),
+                ..window.clone()
+            },
+            MaintenanceWindow {
+                until: Some(window.start_time - 1),
+                ..window.clone()
+            },
+            MaintenanceWindow {
+                timezone: Some("Mars/Olympus".to_string()),
+                ..window.clone()
+            },
+        ];
+        for w in invalid {
+            assert!(w.validate().is_err(), "{w:?}");
+        }
+    }
+
+    #[test]
+    fn test_targets() {
+        let mut alert = Alert::default();
+        alert.name = "staging-api-errors".to_string();
+        alert.context_attributes = Some(HashMap::from([
+            ("env".to_string(), "staging".to_string()),
+            ("team".to_string(), "api".to_string()),
+        ]));
+        let mut window = MaintenanceWindow::default();
+        assert!(window.targets("default", &alert));
+
+        window.targets.labels = HashMap::from([("env".to_string(), "staging".to_string())]);
+        assert!(window.targets("default", &alert));
+        window
+            .targets
+            .labels
+            .insert("team".to_string(), "web".to_string());
+        assert!(!window.targets("default", &alert));
+        window.targets.labels.clear();
+
+        window.targets.alert_names = vec!["prod-*".to_string(), "*-api-*".to_string()];
+        assert!(window.targets("default", &alert));
+        window.targets.folders = vec!["infra".to_string()];
+        assert!(!window.targets("default", &alert));
+        assert!(window.targets("infra", &alert));
+    }
+}
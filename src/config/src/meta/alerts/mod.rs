@@ -20,6 +20,7 @@ use cron::Schedule;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+pub use crate::meta::condition::{Condition, Operator};
 use crate::{
     meta::search::SearchEventType,
     utils::{
@@ -29,6 +30,8 @@ use crate::{
 };
 
 pub mod alert;
+pub mod backtest;
+pub mod maintenance_window;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct TriggerCondition {
@@ -289,6 +292,156 @@ pub struct QueryCondition {
     pub search_event_type: Option<SearchEventType>,
     #[serde(default)]
     pub multi_time_range: Option<Vec<CompareHistoricData>>,
+    /// Baseline settings of the anomaly query type.
+    #[serde(default)]
+    pub anomaly: Option<AnomalyCondition>,
+    /// Alert states combined by the composite query type.
+    #[serde(default)]
+    pub composite: Option<CompositeCondition>,
+}
+
+/// Compares a SQL or PromQL series against a baseline computed from the same weekday and hour
+/// of the previous weeks.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct AnomalyCondition {
+    #[serde(default)]
+    pub method: AnomalyMethod,
+    /// Number of previous weeks the baseline is computed from.
+    #[serde(default = "default_anomaly_baseline_weeks")]
+    pub baseline_weeks: i64,
+    /// How far, in median absolute deviations, a value can be from the expected value before
+    /// it's anomalous. Lower values are more sensitive.
+    #[serde(default = "default_anomaly_sensitivity")]
+    pub sensitivity: f64,
+    /// Number of consecutive anomalous points needed to fire, so single spikes are ignored.
+    #[serde(default = "default_anomaly_min_consecutive")]
+    pub min_consecutive: usize,
+    /// Column of the SQL result that holds the time of each point.
+    #[serde(default = "default_anomaly_timestamp_column")]
+    pub timestamp_column: String,
+    /// Column of the SQL result that holds the value of each point.
+    #[serde(default = "default_anomaly_value_column")]
+    pub value_column: String,
+}
+
+impl Default for AnomalyCondition {
+    fn default() -> Self {
+        Self {
+            method: AnomalyMethod::default(),
+            baseline_weeks: default_anomaly_baseline_weeks(),
+            sensitivity: default_anomaly_sensitivity(),
+            min_consecutive: default_anomaly_min_consecutive(),
+            timestamp_column: default_anomaly_timestamp_column(),
+            value_column: default_anomaly_value_column(),
+        }
+    }
+}
+
+fn default_anomaly_baseline_weeks() -> i64 {
+    4
+}
+
+fn default_anomaly_sensitivity() -> f64 {
+    3.0
+}
+
+fn default_anomaly_min_consecutive() -> usize {
+    3
+}
+
+fn default_anomaly_timestamp_column() -> String {
+    "_timestamp".to_string()
+}
+
+fn default_anomaly_value_column() -> String {
+    "value".to_string()
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub enum AnomalyMethod {
+    /// Expected value is the median of the baseline, the spread its median absolute deviation.
+    #[default]
+    #[serde(rename = "median_mad")]
+    MedianMad,
+    /// Expected value is the Holt-Winters forecast of the baseline.
+    #[serde(rename = "holt_winters")]
+    HoltWinters,
+}
+
+/// Combines the firing states of other alerts of the org, e.g.
+/// `{"and": [{"alert_id": "..."}, {"not": {"alert_id": "..."}}]}`.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum CompositeCondition {
+    OrNode { or: Vec<CompositeCondition> },
+    AndNode { and: Vec<CompositeCondition> },
+    NotNode { not: Box<CompositeCondition> },
+    AlertNode { alert_id: String },
+}
+
+impl CompositeCondition {
+    /// Returns the ids of the alerts referenced in the tree, without duplicates.
+    pub fn alert_ids(&self) -> Vec<&str> {
+        let mut ids = Vec::new();
+        self.collect_alert_ids(&mut ids);
+        ids
+    }
+
+    fn collect_alert_ids<'a>(&'a self, ids: &mut Vec<&'a str>) {
+        match self {
+            CompositeCondition::OrNode { or: nodes }
+            | CompositeCondition::AndNode { and: nodes } => {
+                nodes.iter().for_each(|node| node.collect_alert_ids(ids))
+            }
+            CompositeCondition::NotNode { not } => not.collect_alert_ids(ids),
+            CompositeCondition::AlertNode { alert_id } => {
+                if !ids.contains(&alert_id.as_str()) {
+                    ids.push(alert_id);
+                }
+            }
+        }
+    }
+
+    /// Replaces the id of each referenced alert with the one returned by `f`.
+    pub fn map_alert_ids(&mut self, f: &impl Fn(&str) -> String) {
+        match self {
+            CompositeCondition::OrNode { or: nodes }
+            | CompositeCondition::AndNode { and: nodes } => {
+                nodes.iter_mut().for_each(|node| node.map_alert_ids(f))
+            }
+            CompositeCondition::NotNode { not } => not.map_alert_ids(f),
+            CompositeCondition::AlertNode { alert_id } => *alert_id = f(alert_id),
+        }
+    }
+
+    /// Evaluates the tree, `is_firing` gives the state of each referenced alert.
+    pub fn evaluate(&self, is_firing: &impl Fn(&str) -> bool) -> bool {
+        match self {
+            CompositeCondition::OrNode { or } => or.iter().any(|node| node.evaluate(is_firing)),
+            CompositeCondition::AndNode { and } => and.iter().all(|node| node.evaluate(is_firing)),
+            CompositeCondition::NotNode { not } => !not.evaluate(is_firing),
+            CompositeCondition::AlertNode { alert_id } => is_firing(alert_id),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            CompositeCondition::OrNode { or: nodes }
+            | CompositeCondition::AndNode { and: nodes } => {
+                if nodes.is_empty() {
+                    return Err("Composite condition and/or node should not be empty".to_string());
+                }
+                nodes.iter().try_for_each(|node| node.validate())
+            }
+            CompositeCondition::NotNode { not } => not.validate(),
+            CompositeCondition::AlertNode { alert_id } => {
+                if alert_id.trim().is_empty() {
+                    return Err("Composite condition alert_id should not be empty".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize, ToSchema)]
@@ -366,6 +519,34 @@ impl ConditionList {
             ConditionList::EndCondition(_) => 1,
         }
     }
+
+    /// Checks the values of all the conditions in the tree can be used with their operators
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ConditionList::OrNode { or: conditions }
+            | ConditionList::AndNode { and: conditions } => conditions
+                .iter()
+                .try_for_each(|condition| condition.validate()),
+            ConditionList::LegacyConditions(conditions) => conditions
+                .iter()
+                .try_for_each(|condition| condition.validate()),
+            ConditionList::NotNode { not } => not.validate(),
+            ConditionList::EndCondition(condition) => condition.validate(),
+        }
+    }
+
+    /// Checks whether any condition in the tree matches the predicate
+    pub fn any_condition(&self, f: &impl Fn(&Condition) -> bool) -> bool {
+        match self {
+            ConditionList::OrNode { or: conditions }
+            | ConditionList::AndNode { and: conditions } => conditions
+                .iter()
+                .any(|condition| condition.any_condition(f)),
+            ConditionList::LegacyConditions(conditions) => conditions.iter().any(f),
+            ConditionList::NotNode { not } => not.any_condition(f),
+            ConditionList::EndCondition(condition) => f(condition),
+        }
+    }
 }
 
 // Define a separate iterator struct for ConditionList
@@ -488,6 +669,10 @@ pub enum QueryType {
     SQL,
     #[serde(rename = "promql")]
     PromQL,
+    #[serde(rename = "anomaly")]
+    Anomaly,
+    #[serde(rename = "composite")]
+    Composite,
 }
 
 impl std::fmt::Display for QueryType {
@@ -496,6 +681,8 @@ impl std::fmt::Display for QueryType {
             QueryType::Custom => write!(f, "custom"),
             QueryType::SQL => write!(f, "sql"),
             QueryType::PromQL => write!(f, "promql"),
+            QueryType::Anomaly => write!(f, "anomaly"),
+            QueryType::Composite => write!(f, "composite"),
         }
     }
 }
@@ -506,64 +693,13 @@ impl From<&str> for QueryType {
             "custom" => QueryType::Custom,
             "sql" => QueryType::SQL,
             "promql" => QueryType::PromQL,
+            "anomaly" => QueryType::Anomaly,
+            "composite" => QueryType::Composite,
             _ => QueryType::Custom,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
-pub struct Condition {
-    pub column: String,
-    pub operator: Operator,
-    #[schema(value_type = Object)]
-    pub value: Value,
-    #[serde(default)]
-    pub ignore_case: bool,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
-pub enum Operator {
-    #[serde(rename = "=")]
-    EqualTo,
-    #[serde(rename = "!=")]
-    NotEqualTo,
-    #[serde(rename = ">")]
-    GreaterThan,
-    #[serde(rename = ">=")]
-    GreaterThanEquals,
-    #[serde(rename = "<")]
-    LessThan,
-    #[serde(rename = "<=")]
-    LessThanEquals,
-    #[serde(rename = "contains")]
-    #[serde(alias = "Contains")]
-    Contains,
-    #[serde(rename = "not_contains")]
-    #[serde(alias = "NotContains")]
-    NotContains,
-}
-
-impl Default for Operator {
-    fn default() -> Self {
-        Self::EqualTo
-    }
-}
-
-impl std::fmt::Display for Operator {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Operator::EqualTo => write!(f, "="),
-            Operator::NotEqualTo => write!(f, "!="),
-            Operator::GreaterThan => write!(f, ">"),
-            Operator::GreaterThanEquals => write!(f, ">="),
-            Operator::LessThan => write!(f, "<"),
-            Operator::LessThanEquals => write!(f, "<="),
-            Operator::Contains => write!(f, "contains"),
-            Operator::NotContains => write!(f, "not contains"),
-        }
-    }
-}
-
 #[cfg(test)]
 mod test {
     use chrono::{DateTime, FixedOffset, TimeZone};
@@ -1025,4 +1161,31 @@ mod test {
 }"#;
         assert_eq!(serialized, expected_json);
     }
+
+    #[test]
+    fn test_composite_condition() {
+        let json = r#"{
+            "and": [
+                {"alert_id": "latency"},
+                {"or": [{"alert_id": "db_cpu"}, {"not": {"alert_id": "latency"}}]}
+            ]
+        }"#;
+        let condition: CompositeCondition = serde_json::from_str(json).unwrap();
+        assert!(condition.validate().is_ok());
+        assert_eq!(condition.alert_ids(), vec!["latency", "db_cpu"]);
+
+        let firing = |firing: &'static [&'static str]| {
+            move |id: &str| firing.iter().any(|firing_id| *firing_id == id)
+        };
+        assert!(condition.evaluate(&firing(&["latency", "db_cpu"])));
+        assert!(!condition.evaluate(&firing(&["latency"])));
+        assert!(!condition.evaluate(&firing(&["db_cpu"])));
+        assert!(!condition.evaluate(&firing(&[])));
+
+        let empty: CompositeCondition = serde_json::from_str(r#"{"or": []}"#).unwrap();
+        assert!(empty.validate().is_err());
+        let blank: CompositeCondition =
+            serde_json::from_str(r#"{"not": {"alert_id": " "}}"#).unwrap();
+        assert!(blank.validate().is_err());
+    }
 }
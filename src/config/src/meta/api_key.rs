@@ -0,0 +1,229 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{meta::user::UserRole, utils::str::wildcard_match};
+
+/// Every API key starts with this prefix, which tells them apart from passwords and tokens.
+pub const API_KEY_PREFIX: &str = "o2ak_";
+
+/// Length of the random part of an API key.
+pub const API_KEY_RANDOM_LEN: usize = 40;
+
+/// Path segments of the search endpoints, as `INGESTION_EP` does for ingestion.
+pub const SEARCH_EP: [&str; 8] = [
+    "_search",
+    "_search_partition",
+    "_search_multi",
+    "_search_stream",
+    "_around",
+    "_values",
+    "query",
+    "query_range",
+];
+
+/// First path segments, after the org, of the endpoints API keys can never be used for: the
+/// ones managing users, roles and credentials.
+pub const API_KEY_DENIED_EP: [&str; 8] = [
+    "passcode",
+    "rumtoken",
+    "users",
+    "service_accounts",
+    "api_keys",
+    "roles",
+    "groups",
+    "invites",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// Ingestion endpoints.
+    Ingest,
+    /// Search endpoints.
+    Search,
+    /// Read only access to every other endpoint.
+    ReadOnly,
+}
+
+/// What a request does, as far as API key scopes are concerned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiRequestKind {
+    Ingest,
+    Search,
+    Read,
+    Write,
+    /// Endpoints of `API_KEY_DENIED_EP`, which no key is allowed to call.
+    Denied,
+}
+
+impl ApiKeyScope {
+    pub fn allows(&self, kind: ApiRequestKind) -> bool {
+        matches!(
+            (self, kind),
+            (ApiKeyScope::Ingest, ApiRequestKind::Ingest)
+                | (ApiKeyScope::Search, ApiRequestKind::Search)
+                | (ApiKeyScope::ReadOnly, ApiRequestKind::Read)
+        )
+    }
+}
+
+/// A named API key of a user or service account. The key itself is only returned once, when it
+/// is created, and only its hash is stored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ApiKey {
+    pub id: String,
+    pub org_id: String,
+    pub user_email: String,
+    pub name: String,
+    /// The first characters of the key, to help recognize it.
+    pub key_prefix: String,
+    /// Kinds of requests the key can be used for. Empty means everything the owner can do.
+    #[serde(default)]
+    pub scopes: Vec<ApiKeyScope>,
+    /// Stream name patterns, with `*` as wildcard, the key is restricted to. Requests which
+    /// don't name a stream in their path are rejected when set. Empty means all streams.
+    #[serde(default)]
+    pub streams: Vec<String>,
+    /// Expiry time in microseconds, the key never expires when not set.
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    /// Time in microseconds the key was last used at, updated at most once a minute.
+    #[serde(default)]
+    pub last_used_at: Option<i64>,
+    pub created_at: i64,
+}
+
+impl ApiKey {
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Checks whether the scopes and stream patterns of the key allow the request.
+    pub fn allows(&self, kind: ApiRequestKind, stream_name: Option<&str>) -> bool {
+        if kind == ApiRequestKind::Denied {
+            return false;
+        }
+        let in_scope = self.scopes.is_empty() || self.scopes.iter().any(|s| s.allows(kind));
+        let in_streams = self.streams.is_empty()
+            || stream_name.is_some_and(|name| {
+                self.streams
+                    .iter()
+                    .any(|pattern| wildcard_match(pattern, name))
+            });
+        in_scope && in_streams
+    }
+
+    /// Role the requests made with the key run as: the role of its owner, lowered to what the
+    /// scopes of the key need. A key never acts as the root user or as an admin.
+    pub fn role(&self, owner_role: UserRole) -> UserRole {
+        let can_write = self.scopes.is_empty() || self.scopes.contains(&ApiKeyScope::Ingest);
+        match owner_role {
+            UserRole::Root | UserRole::Admin if can_write => UserRole::Editor,
+            UserRole::Root | UserRole::Admin | UserRole::Editor if !can_write => UserRole::Viewer,
+            role => role,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyRequest {
+    pub name: String,
+    /// Owner of the key, defaults to the caller. Only admins can create keys for others, such
+    /// as service accounts.
+    #[serde(default)]
+    pub user_email: Option<String>,
+    #[serde(default)]
+    pub scopes: Vec<ApiKeyScope>,
+    #[serde(default)]
+    pub streams: Vec<String>,
+    /// Expiry time in microseconds.
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyCreateResponse {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    /// The key to authenticate with, it can't be retrieved again.
+    pub key: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyList {
+    pub list: Vec<ApiKey>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_key(scopes: Vec<ApiKeyScope>, streams: Vec<&str>) -> ApiKey {
+        ApiKey {
+            id: "id".to_string(),
+            org_id: "default".to_string(),
+            user_email: "shipper@example.com".to_string(),
+            name: "shipper".to_string(),
+            key_prefix: "o2ak_abcd".to_string(),
+            scopes,
+            streams: streams.into_iter().map(String::from).collect(),
+            expires_at: Some(100),
+            last_used_at: None,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_api_key_allows() {
+        let key = api_key(vec![], vec![]);
+        assert!(key.allows(ApiRequestKind::Write, None));
+        assert!(!key.is_expired(99));
+        assert!(key.is_expired(100));
+
+        let key = api_key(vec![ApiKeyScope::Ingest], vec!["k8s_*"]);
+        assert!(key.allows(ApiRequestKind::Ingest, Some("k8s_logs")));
+        assert!(!key.allows(ApiRequestKind::Ingest, Some("audit")));
+        assert!(!key.allows(ApiRequestKind::Ingest, None));
+        assert!(!key.allows(ApiRequestKind::Search, Some("k8s_logs")));
+
+        let key = api_key(vec![ApiKeyScope::Search, ApiKeyScope::ReadOnly], vec![]);
+        assert!(key.allows(ApiRequestKind::Search, None));
+        assert!(key.allows(ApiRequestKind::Read, None));
+        assert!(!key.allows(ApiRequestKind::Write, None));
+        assert!(!key.allows(ApiRequestKind::Ingest, None));
+        assert!(!key.allows(ApiRequestKind::Denied, None));
+    }
+
+    #[test]
+    fn test_api_key_role() {
+        let key = api_key(vec![], vec![]);
+        assert_eq!(key.role(UserRole::Root), UserRole::Editor);
+        assert_eq!(key.role(UserRole::Admin), UserRole::Editor);
+        assert_eq!(key.role(UserRole::Viewer), UserRole::Viewer);
+        assert!(!key.allows(ApiRequestKind::Denied, None));
+
+        let key = api_key(vec![ApiKeyScope::Ingest], vec![]);
+        assert_eq!(key.role(UserRole::Admin), UserRole::Editor);
+        assert_eq!(key.role(UserRole::ServiceAccount), UserRole::ServiceAccount);
+
+        let key = api_key(vec![ApiKeyScope::Search, ApiKeyScope::ReadOnly], vec![]);
+        assert_eq!(key.role(UserRole::Root), UserRole::Viewer);
+        assert_eq!(key.role(UserRole::Editor), UserRole::Viewer);
+        assert_eq!(key.role(UserRole::User), UserRole::User);
+    }
+}
@@ -0,0 +1,591 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Conditions on a single field of a record, shared by alerts and pipeline condition nodes.
+//!
+//! Evaluation follows the same rules for every operator:
+//! - a missing or null field only matches `not_exists`, and `=` with a null (or `"null"`) value.
+//! - an array field matches a positive operator when any of its elements matches, and a negated
+//!   operator (`!=`, `not_contains`, `not_in`, `not_between`) when none of them matches.
+//! - numbers and numeric strings are compared as numbers, booleans and `"true"`/`"false"` as
+//!   booleans, anything else is compared as text. Objects are compared as their JSON text.
+//! - `ignore_case` applies to every text comparison, including regex matches.
+
+use std::{borrow::Cow, cmp::Ordering, net::IpAddr, str::FromStr};
+
+use hashbrown::HashMap;
+use ipnetwork::IpNetwork;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::utils::json::{Map, Value};
+
+/// Compiled regex patterns of the conditions, `None` for invalid patterns.
+static REGEX_CACHE: Lazy<RwLock<HashMap<String, Option<Regex>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+const REGEX_CACHE_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Condition {
+    pub column: String,
+    pub operator: Operator,
+    #[schema(value_type = Object)]
+    pub value: Value,
+    #[serde(default)]
+    pub ignore_case: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum Operator {
+    #[serde(rename = "=")]
+    EqualTo,
+    #[serde(rename = "!=")]
+    NotEqualTo,
+    #[serde(rename = ">")]
+    GreaterThan,
+    #[serde(rename = ">=")]
+    GreaterThanEquals,
+    #[serde(rename = "<")]
+    LessThan,
+    #[serde(rename = "<=")]
+    LessThanEquals,
+    #[serde(rename = "contains")]
+    #[serde(alias = "Contains")]
+    Contains,
+    #[serde(rename = "not_contains")]
+    #[serde(alias = "NotContains")]
+    NotContains,
+    /// The value is a regex pattern.
+    #[serde(rename = "regex")]
+    Regex,
+    /// The value is an array, or a comma separated list.
+    #[serde(rename = "in")]
+    In,
+    #[serde(rename = "not_in")]
+    NotIn,
+    /// The value is ignored.
+    #[serde(rename = "exists")]
+    Exists,
+    #[serde(rename = "not_exists")]
+    NotExists,
+    #[serde(rename = "starts_with")]
+    StartsWith,
+    #[serde(rename = "ends_with")]
+    EndsWith,
+    /// The value is a CIDR block, or an array of them.
+    #[serde(rename = "cidr_match")]
+    CidrMatch,
+    /// The value is an array of the lower and upper bounds, both inclusive.
+    #[serde(rename = "between")]
+    Between,
+    #[serde(rename = "not_between")]
+    NotBetween,
+}
+
+impl Default for Operator {
+    fn default() -> Self {
+        Self::EqualTo
+    }
+}
+
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operator::EqualTo => write!(f, "="),
+            Operator::NotEqualTo => write!(f, "!="),
+            Operator::GreaterThan => write!(f, ">"),
+            Operator::GreaterThanEquals => write!(f, ">="),
+            Operator::LessThan => write!(f, "<"),
+            Operator::LessThanEquals => write!(f, "<="),
+            Operator::Contains => write!(f, "contains"),
+            Operator::NotContains => write!(f, "not contains"),
+            Operator::Regex => write!(f, "regex"),
+            Operator::In => write!(f, "in"),
+            Operator::NotIn => write!(f, "not in"),
+            Operator::Exists => write!(f, "exists"),
+            Operator::NotExists => write!(f, "not exists"),
+            Operator::StartsWith => write!(f, "starts with"),
+            Operator::EndsWith => write!(f, "ends with"),
+            Operator::CidrMatch => write!(f, "cidr match"),
+            Operator::Between => write!(f, "between"),
+            Operator::NotBetween => write!(f, "not between"),
+        }
+    }
+}
+
+impl Operator {
+    /// Whether the operator is a plain comparison, the only operators PromQL conditions and
+    /// trigger thresholds support.
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Operator::EqualTo
+                | Operator::NotEqualTo
+                | Operator::GreaterThan
+                | Operator::GreaterThanEquals
+                | Operator::LessThan
+                | Operator::LessThanEquals
+        )
+    }
+
+    /// Gets the positive operator of a negated operator.
+    fn negated(&self) -> Option<Operator> {
+        match self {
+            Operator::NotEqualTo => Some(Operator::EqualTo),
+            Operator::NotContains => Some(Operator::Contains),
+            Operator::NotIn => Some(Operator::In),
+            Operator::NotBetween => Some(Operator::Between),
+            _ => None,
+        }
+    }
+}
+
+impl Condition {
+    pub fn evaluate(&self, row: &Map<String, Value>) -> bool {
+        let val = match row.get(&self.column) {
+            Some(Value::Null) | None => {
+                return match self.operator {
+                    Operator::NotExists => true,
+                    Operator::EqualTo => is_null(&self.value),
+                    _ => false,
+                };
+            }
+            Some(val) => val,
+        };
+        match self.operator {
+            Operator::Exists => return true,
+            Operator::NotExists => return false,
+            _ => {}
+        }
+        if let Some(positive) = self.operator.negated() {
+            return !self.matches(positive, val);
+        }
+        self.matches(self.operator, val)
+    }
+
+    /// Gets the items of the value of a list operator (`in`, `cidr_match`, `between`), either
+    /// an array or a comma separated string.
+    pub fn values(&self) -> Vec<Value> {
+        list(&self.value)
+    }
+
+    /// Checks the value of the condition can be used with its operator.
+    pub fn validate(&self) -> Result<(), String> {
+        match self.operator {
+            Operator::Regex => {
+                let pattern = text(&self.value);
+                if cached_regex(&pattern, self.ignore_case).is_none() {
+                    return Err(format!(
+                        "Invalid regex pattern [{pattern}] for column {}",
+                        self.column
+                    ));
+                }
+            }
+            Operator::In | Operator::NotIn if list(&self.value).is_empty() => {
+                return Err(format!(
+                    "Operator [{}] needs a list of values for column {}",
+                    self.operator, self.column
+                ));
+            }
+            Operator::CidrMatch => {
+                let blocks = list(&self.value);
+                if blocks.is_empty()
+                    || blocks
+                        .iter()
+                        .any(|block| IpNetwork::from_str(&text(block)).is_err())
+                {
+                    return Err(format!(
+                        "Operator [{}] needs valid CIDR blocks for column {}",
+                        self.operator, self.column
+                    ));
+                }
+            }
+            Operator::Between | Operator::NotBetween if list(&self.value).len() != 2 => {
+                return Err(format!(
+                    "Operator [{}] needs the lower and upper bounds for column {}",
+                    self.operator, self.column
+                ));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Matches a present, non null value against a positive operator.
+    fn matches(&self, operator: Operator, val: &Value) -> bool {
+        if let Value::Array(items) = val {
+            return items
+                .iter()
+                .filter(|item| !item.is_null())
+                .any(|item| self.matches(operator, item));
+        }
+        let ignore_case = self.ignore_case;
+        match operator {
+            Operator::EqualTo => !is_null(&self.value) && equals(val, &self.value, ignore_case),
+            Operator::GreaterThan => {
+                compare(val, &self.value, ignore_case) == Some(Ordering::Greater)
+            }
+            Operator::GreaterThanEquals => matches!(
+                compare(val, &self.value, ignore_case),
+                Some(Ordering::Greater | Ordering::Equal)
+            ),
+            Operator::LessThan => compare(val, &self.value, ignore_case) == Some(Ordering::Less),
+            Operator::LessThanEquals => matches!(
+                compare(val, &self.value, ignore_case),
+                Some(Ordering::Less | Ordering::Equal)
+            ),
+            Operator::Contains => fold(&text(val), ignore_case)
+                .contains(fold(&text(&self.value), ignore_case).as_ref()),
+            Operator::StartsWith => fold(&text(val), ignore_case)
+                .starts_with(fold(&text(&self.value), ignore_case).as_ref()),
+            Operator::EndsWith => fold(&text(val), ignore_case)
+                .ends_with(fold(&text(&self.value), ignore_case).as_ref()),
+            Operator::Regex => cached_regex(&text(&self.value), ignore_case)
+                .is_some_and(|re| re.is_match(&text(val))),
+            Operator::In => list(&self.value)
+                .iter()
+                .any(|item| equals(val, item, ignore_case)),
+            Operator::CidrMatch => {
+                let Ok(ip) = IpAddr::from_str(text(val).trim()) else {
+                    return false;
+                };
+                list(&self.value).iter().any(|block| {
+                    IpNetwork::from_str(&text(block)).is_ok_and(|network| network.contains(ip))
+                })
+            }
+            Operator::Between => {
+                let bounds = list(&self.value);
+                let [lower, upper] = bounds.as_slice() else {
+                    return false;
+                };
+                matches!(
+                    compare(val, lower, ignore_case),
+                    Some(Ordering::Greater | Ordering::Equal)
+                ) && matches!(
+                    compare(val, upper, ignore_case),
+                    Some(Ordering::Less | Ordering::Equal)
+                )
+            }
+            // negated operators and the existence checks are handled by `evaluate`
+            Operator::NotEqualTo
+            | Operator::NotContains
+            | Operator::NotIn
+            | Operator::NotBetween
+            | Operator::Exists
+            | Operator::NotExists => false,
+        }
+    }
+}
+
+fn is_null(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s == "null",
+        _ => false,
+    }
+}
+
+/// Gets the text of a value. Surrounding double quotes of strings are removed, so `""` is the
+/// empty string.
+fn text(value: &Value) -> Cow<'_, str> {
+    match value {
+        Value::String(s) => Cow::Borrowed(s.trim_matches('"')),
+        Value::Number(n) => Cow::Owned(n.to_string()),
+        Value::Bool(b) => Cow::Owned(b.to_string()),
+        Value::Null => Cow::Borrowed(""),
+        other => Cow::Owned(other.to_string()),
+    }
+}
+
+fn fold(s: &str, ignore_case: bool) -> Cow<'_, str> {
+    if ignore_case {
+        Cow::Owned(s.to_lowercase())
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim_matches('"').trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) => s.trim_matches('"').trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn equals(val: &Value, other: &Value, ignore_case: bool) -> bool {
+    if val.is_number() || other.is_number() {
+        if let (Some(a), Some(b)) = (as_number(val), as_number(other)) {
+            return a == b;
+        }
+    }
+    if val.is_boolean() || other.is_boolean() {
+        if let (Some(a), Some(b)) = (as_bool(val), as_bool(other)) {
+            return a == b;
+        }
+    }
+    fold(&text(val), ignore_case) == fold(&text(other), ignore_case)
+}
+
+/// Orders numbers and numeric strings as numbers and other scalars as text. Booleans can't be
+/// ordered.
+fn compare(val: &Value, other: &Value, ignore_case: bool) -> Option<Ordering> {
+    if val.is_boolean() || other.is_boolean() {
+        return None;
+    }
+    if let (Some(a), Some(b)) = (as_number(val), as_number(other)) {
+        return a.partial_cmp(&b);
+    }
+    if val.is_number() {
+        // a number can't be ordered against a value that isn't numeric
+        return None;
+    }
+    Some(fold(&text(val), ignore_case).cmp(&fold(&text(other), ignore_case)))
+}
+
+/// Gets the items of a list value, either an array or a comma separated string.
+fn list(value: &Value) -> Vec<Value> {
+    match value {
+        Value::Array(items) => items.clone(),
+        Value::String(s) => s
+            .split(',')
+            .map(|item| item.trim())
+            .filter(|item| !item.is_empty())
+            .map(|item| Value::String(item.to_string()))
+            .collect(),
+        Value::Null => vec![],
+        other => vec![other.clone()],
+    }
+}
+
+fn cached_regex(pattern: &str, ignore_case: bool) -> Option<Regex> {
+    let pattern = if ignore_case {
+        Cow::Owned(format!("(?i){pattern}"))
+    } else {
+        Cow::Borrowed(pattern)
+    };
+    if let Some(re) = REGEX_CACHE.read().get(pattern.as_ref()) {
+        return re.clone();
+    }
+    let re = Regex::new(&pattern).ok();
+    let mut cache = REGEX_CACHE.write();
+    if cache.len() >= REGEX_CACHE_CAPACITY {
+        cache.clear();
+    }
+    cache.insert(pattern.into_owned(), re.clone());
+    re
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::json::json;
+
+    fn cond(column: &str, operator: Operator, value: Value) -> Condition {
+        Condition {
+            column: column.to_string(),
+            operator,
+            value,
+            ignore_case: false,
+        }
+    }
+
+    fn row() -> Map<String, Value> {
+        json!({
+            "level": "Error",
+            "code": 503,
+            "latency": "12.5",
+            "ok": false,
+            "empty": null,
+            "host": "web-01.eu",
+            "ip": "10.1.2.3",
+            "ip6": "2001:db8::1",
+            "tags": ["prod", "eu"],
+            "meta": {"a": 1},
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    }
+
+    #[test]
+    fn test_deserialize_operators() {
+        let ops: Vec<Operator> = serde_json::from_str(
+            r#"["=", "contains", "Contains", "NotContains", "regex", "in", "not_in", "exists",
+                "not_exists", "starts_with", "ends_with", "cidr_match", "between", "not_between"]"#,
+        )
+        .unwrap();
+        assert_eq!(ops[1], Operator::Contains);
+        assert_eq!(ops[2], Operator::Contains);
+        assert_eq!(ops[3], Operator::NotContains);
+        assert_eq!(ops[13], Operator::NotBetween);
+        assert_eq!(
+            serde_json::to_string(&Operator::NotContains).unwrap(),
+            "\"not_contains\""
+        );
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let row = row();
+        assert!(cond("level", Operator::EqualTo, json!("Error")).evaluate(&row));
+        assert!(!cond("level", Operator::EqualTo, json!("error")).evaluate(&row));
+        assert!(cond("code", Operator::EqualTo, json!("503")).evaluate(&row));
+        assert!(cond("code", Operator::EqualTo, json!(503.0)).evaluate(&row));
+        assert!(cond("code", Operator::GreaterThan, json!(500)).evaluate(&row));
+        assert!(cond("code", Operator::LessThanEquals, json!("503")).evaluate(&row));
+        assert!(!cond("code", Operator::GreaterThan, json!("abc")).evaluate(&row));
+        // numeric strings are compared as numbers, not as text
+        assert!(cond("latency", Operator::GreaterThan, json!(9)).evaluate(&row));
+        assert!(cond("level", Operator::LessThan, json!("F")).evaluate(&row));
+        assert!(cond("ok", Operator::EqualTo, json!("false")).evaluate(&row));
+        assert!(cond("ok", Operator::NotEqualTo, json!(true)).evaluate(&row));
+        assert!(!cond("ok", Operator::GreaterThan, json!(true)).evaluate(&row));
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        let row = row();
+        let mut c = cond("level", Operator::EqualTo, json!("error"));
+        c.ignore_case = true;
+        assert!(c.evaluate(&row));
+        c.operator = Operator::Regex;
+        c.value = json!("^ERR");
+        assert!(c.evaluate(&row));
+        c.operator = Operator::In;
+        c.value = json!(["warn", "ERROR"]);
+        assert!(c.evaluate(&row));
+        c.operator = Operator::StartsWith;
+        c.value = json!("eR");
+        assert!(c.evaluate(&row));
+    }
+
+    #[test]
+    fn test_text_operators() {
+        let row = row();
+        assert!(cond("host", Operator::Contains, json!("01")).evaluate(&row));
+        assert!(cond("host", Operator::NotContains, json!("us")).evaluate(&row));
+        assert!(cond("host", Operator::StartsWith, json!("web-")).evaluate(&row));
+        assert!(cond("host", Operator::EndsWith, json!(".eu")).evaluate(&row));
+        assert!(!cond("host", Operator::EndsWith, json!(".us")).evaluate(&row));
+        assert!(cond("host", Operator::Regex, json!(r"^web-\d+\.")).evaluate(&row));
+        assert!(!cond("host", Operator::Regex, json!("(")).evaluate(&row));
+        assert!(cond("code", Operator::StartsWith, json!("50")).evaluate(&row));
+        assert!(cond("host", Operator::Contains, json!("\"\"")).evaluate(&row));
+    }
+
+    #[test]
+    fn test_lists_and_ranges() {
+        let row = row();
+        assert!(cond("code", Operator::In, json!([500, 503])).evaluate(&row));
+        assert!(cond("code", Operator::In, json!("500, 503")).evaluate(&row));
+        assert!(cond("level", Operator::NotIn, json!(["Warn", "Info"])).evaluate(&row));
+        assert!(!cond("level", Operator::NotIn, json!("Error,Warn")).evaluate(&row));
+        assert!(cond("code", Operator::Between, json!([500, 599])).evaluate(&row));
+        assert!(cond("code", Operator::Between, json!([503, 503])).evaluate(&row));
+        assert!(!cond("code", Operator::Between, json!([200, 299])).evaluate(&row));
+        assert!(cond("latency", Operator::NotBetween, json!([0, 10])).evaluate(&row));
+        assert!(!cond("code", Operator::Between, json!([500])).evaluate(&row));
+    }
+
+    #[test]
+    fn test_cidr_match() {
+        let row = row();
+        assert!(cond("ip", Operator::CidrMatch, json!("10.0.0.0/8")).evaluate(&row));
+        assert!(
+            cond(
+                "ip",
+                Operator::CidrMatch,
+                json!(["192.168.0.0/16", "10.1.2.0/24"])
+            )
+            .evaluate(&row)
+        );
+        assert!(!cond("ip", Operator::CidrMatch, json!("10.1.3.0/24")).evaluate(&row));
+        assert!(cond("ip6", Operator::CidrMatch, json!("2001:db8::/32")).evaluate(&row));
+        assert!(!cond("ip6", Operator::CidrMatch, json!("10.0.0.0/8")).evaluate(&row));
+        assert!(!cond("host", Operator::CidrMatch, json!("10.0.0.0/8")).evaluate(&row));
+    }
+
+    #[test]
+    fn test_missing_and_null() {
+        let row = row();
+        for column in ["missing", "empty"] {
+            assert!(!cond(column, Operator::Exists, Value::Null).evaluate(&row));
+            assert!(cond(column, Operator::NotExists, Value::Null).evaluate(&row));
+            assert!(cond(column, Operator::EqualTo, json!("null")).evaluate(&row));
+            assert!(cond(column, Operator::EqualTo, Value::Null).evaluate(&row));
+            assert!(!cond(column, Operator::NotEqualTo, json!("x")).evaluate(&row));
+            assert!(!cond(column, Operator::NotContains, json!("x")).evaluate(&row));
+            assert!(!cond(column, Operator::NotIn, json!(["x"])).evaluate(&row));
+            assert!(!cond(column, Operator::LessThan, json!(1)).evaluate(&row));
+        }
+        assert!(cond("level", Operator::Exists, Value::Null).evaluate(&row));
+        assert!(!cond("level", Operator::NotExists, Value::Null).evaluate(&row));
+        assert!(!cond("level", Operator::EqualTo, json!("null")).evaluate(&row));
+        assert!(cond("level", Operator::NotEqualTo, Value::Null).evaluate(&row));
+    }
+
+    #[test]
+    fn test_arrays_and_objects() {
+        let row = row();
+        assert!(cond("tags", Operator::EqualTo, json!("prod")).evaluate(&row));
+        assert!(cond("tags", Operator::In, json!(["dev", "eu"])).evaluate(&row));
+        assert!(!cond("tags", Operator::NotEqualTo, json!("prod")).evaluate(&row));
+        assert!(cond("tags", Operator::NotContains, json!("us")).evaluate(&row));
+        assert!(cond("tags", Operator::Exists, Value::Null).evaluate(&row));
+        assert!(cond("meta", Operator::Contains, json!("\"a\":1")).evaluate(&row));
+        assert!(cond("meta", Operator::Exists, Value::Null).evaluate(&row));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(cond("a", Operator::Regex, json!("^a+$")).validate().is_ok());
+        assert!(cond("a", Operator::Regex, json!("(")).validate().is_err());
+        assert!(cond("a", Operator::In, json!([])).validate().is_err());
+        assert!(cond("a", Operator::NotIn, json!("a,b")).validate().is_ok());
+        assert!(
+            cond("a", Operator::CidrMatch, json!("10.0.0.0/8"))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            cond("a", Operator::CidrMatch, json!(["10.0.0.0/8", "nope"]))
+                .validate()
+                .is_err()
+        );
+        assert!(
+            cond("a", Operator::Between, json!([1, 2]))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            cond("a", Operator::NotBetween, json!([1]))
+                .validate()
+                .is_err()
+        );
+        assert!(cond("a", Operator::Exists, Value::Null).validate().is_ok());
+    }
+}
@@ -13,7 +13,12 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use chrono::{DateTime, FixedOffset, Utc};
+use std::fmt::Write;
+
+use chrono::{
+    DateTime, FixedOffset, Utc,
+    format::{Item, StrftimeItems},
+};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -23,14 +28,164 @@ use crate::meta::alerts::default_align_time;
 #[derive(Serialize, Debug, Deserialize, Clone, ToSchema)]
 pub enum ReportDestination {
     #[serde(rename = "email")]
-    Email(String), // Supports email only
+    Email(String),
+    /// Delivers the report files to the http endpoint of a pipeline destination.
+    #[serde(rename = "http")]
+    Http(ReportHttpDestination),
+    /// Writes the report files to a storage account.
+    #[serde(rename = "storage")]
+    Storage(ReportStorageDestination),
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, ToSchema, PartialEq, Eq)]
+pub struct ReportHttpDestination {
+    /// Name of the pipeline destination defining the endpoint.
+    pub destination: String,
+    #[serde(default)]
+    pub delivery: ReportHttpDelivery,
+    /// Validity of the presigned links in seconds, for the link delivery.
+    #[serde(default = "default_link_expiry_secs")]
+    pub link_expiry_secs: u64,
+}
+
+fn default_link_expiry_secs() -> u64 {
+    // the longest validity of S3 presigned urls
+    7 * 24 * 3600
+}
+
+#[derive(Serialize, Debug, Default, Deserialize, Clone, Copy, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportHttpDelivery {
+    /// The files are uploaded as a multipart/form-data body.
+    #[default]
+    Multipart,
+    /// A json body links to the files written by the storage destination of the report, with
+    /// presigned urls.
+    Link,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, ToSchema, PartialEq, Eq)]
+pub struct ReportStorageDestination {
+    /// The storage account, the default account when empty.
+    #[serde(default)]
+    pub account: String,
+    /// Directory of the files under `reports/{org_id}/`.
+    #[serde(default)]
+    pub prefix: String,
+    /// Path of the files under the prefix. `{file}` is the name of the report file, `{report}`
+    /// the name of the report and the `%` specifiers of strftime are the date of the report in
+    /// its timezone.
+    #[serde(default = "default_storage_pattern")]
+    pub pattern: String,
+}
+
+pub fn default_storage_pattern() -> String {
+    "%Y/%m/%d/{file}".to_string()
+}
+
+impl ReportStorageDestination {
+    /// Checks the pattern names each report file, its date specifiers are valid and it stays
+    /// under the prefix.
+    pub fn is_valid_pattern(&self) -> bool {
+        self.pattern.contains("{file}")
+            && is_relative_path(&self.pattern)
+            && !StrftimeItems::new(&self.pattern).any(|item| matches!(item, Item::Error))
+    }
+
+    /// Checks the prefix stays under the reports of the org.
+    pub fn is_valid_prefix(&self) -> bool {
+        is_relative_path(&self.prefix)
+    }
+
+    /// Returns the key of the report file written at the given date. The files of an org are
+    /// all kept under `reports/{org_id}/`.
+    pub fn file_key<Tz: chrono::TimeZone>(
+        &self,
+        org_id: &str,
+        report_name: &str,
+        file: &str,
+        date: &DateTime<Tz>,
+    ) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let mut path = String::new();
+        // the pattern is validated when the report is saved
+        let _ = write!(path, "{}", date.format(&self.pattern));
+        let path = path
+            .replace("{report}", &path_segment(report_name))
+            .replace("{file}", &path_segment(file));
+        let prefix = self.prefix.trim_matches('/');
+        if prefix.is_empty() {
+            format!("reports/{org_id}/{path}")
+        } else {
+            format!("reports/{org_id}/{prefix}/{path}")
+        }
+    }
+}
+
+/// Strips the slashes and leading dots of a name, so it can't add or climb path segments.
+fn path_segment(name: &str) -> String {
+    name.replace('/', "").trim_start_matches('.').to_string()
 }
 
-#[derive(Serialize, Debug, Default, Deserialize, Clone, ToSchema)]
+/// Whether the path is relative and has no `..` segment.
+fn is_relative_path(path: &str) -> bool {
+    !path.starts_with('/') && !path.split('/').any(|segment| segment == "..")
+}
+
+#[derive(Serialize, Debug, Default, Deserialize, Clone, Copy, ToSchema, PartialEq, Eq)]
 pub enum ReportMediaType {
+    /// A capture of the dashboard rendered by a headless browser
     #[default]
-    #[serde(rename = "pdf")]
-    Pdf, // Supports Pdf only
+    #[serde(rename = "pdf", alias = "Pdf")]
+    Pdf,
+    /// The results of the panel queries, one csv file per panel
+    #[serde(rename = "csv")]
+    Csv,
+    /// The results of the panel queries, one sheet per panel
+    #[serde(rename = "xlsx")]
+    Xlsx,
+}
+
+impl ReportMediaType {
+    /// Whether the report carries the results of the panel queries instead of a capture of the
+    /// dashboard, such reports don't need a headless browser.
+    pub fn is_data(&self) -> bool {
+        matches!(self, Self::Csv | Self::Xlsx)
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Pdf => "application/pdf",
+            Self::Csv => "text/csv",
+            Self::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+}
+
+impl std::fmt::Display for ReportMediaType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.file_extension())
+    }
+}
+
+impl From<&str> for ReportMediaType {
+    fn from(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "csv" => Self::Csv,
+            "xlsx" => Self::Xlsx,
+            _ => Self::Pdf,
+        }
+    }
 }
 
 #[derive(Serialize, Debug, Default, Deserialize, Clone, ToSchema, PartialEq, Eq)]
@@ -301,6 +456,8 @@ impl ReportListFilters {
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeZone;
+
     use super::*;
 
     #[test]
@@ -319,4 +476,78 @@ mod tests {
             serde_json::from_str(&json_using_alias).unwrap();
         assert_eq!(email_details, email_details_from_alias);
     }
+    #[test]
+    fn test_media_type() {
+        let media_type: ReportMediaType = serde_json::from_str(r#""xlsx""#).unwrap();
+        assert_eq!(media_type, ReportMediaType::Xlsx);
+        assert_eq!(
+            ReportMediaType::from(media_type.to_string().as_str()),
+            media_type
+        );
+        assert_eq!(ReportMediaType::from("unknown"), ReportMediaType::Pdf);
+        assert!(ReportMediaType::Csv.is_data());
+        assert!(!ReportMediaType::Pdf.is_data());
+    }
+    #[test]
+    fn test_storage_file_key() {
+        let destination = ReportStorageDestination {
+            account: "".to_string(),
+            prefix: "archive/reports/".to_string(),
+            pattern: "%Y/%m/%d/{report}-%H%M-{file}".to_string(),
+        };
+        assert!(destination.is_valid_pattern());
+        assert!(destination.is_valid_prefix());
+        let date = FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 8, 4, 23, 30, 0)
+            .unwrap();
+        assert_eq!(
+            destination.file_key("default", "weekly", "Errors.csv", &date),
+            "reports/default/archive/reports/2025/08/04/weekly-2330-Errors.csv"
+        );
+        // names with slashes stay in the same directory
+        assert_eq!(
+            destination.file_key("default", "../weekly", "a/b.csv", &date),
+            "reports/default/archive/reports/2025/08/04/weekly-2330-ab.csv"
+        );
+
+        let destination: ReportDestination =
+            serde_json::from_str(r#"{"storage": {"prefix": "reports"}}"#).unwrap();
+        let ReportDestination::Storage(destination) = destination else {
+            panic!("expected a storage destination");
+        };
+        assert_eq!(destination.pattern, default_storage_pattern());
+        assert!(
+            !ReportStorageDestination {
+                pattern: "%Y/report.csv".to_string(),
+                ..destination.clone()
+            }
+            .is_valid_pattern()
+        );
+        assert!(
+            !ReportStorageDestination {
+                pattern: "%Q/{file}".to_string(),
+                ..destination.clone()
+            }
+            .is_valid_pattern()
+        );
+        for pattern in ["/{file}", "../{file}", "%Y/../../{file}"] {
+            assert!(
+                !ReportStorageDestination {
+                    pattern: pattern.to_string(),
+                    ..destination.clone()
+                }
+                .is_valid_pattern()
+            );
+        }
+        for prefix in ["/archive", "..", "archive/../../other_org"] {
+            assert!(
+                !ReportStorageDestination {
+                    prefix: prefix.to_string(),
+                    ..destination.clone()
+                }
+                .is_valid_prefix()
+            );
+        }
+    }
 }
@@ -0,0 +1,285 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Saved versions of a dashboard. Every save of a dashboard is kept as a numbered version, not
+//! to be confused with the schema `version` of the dashboard definition.
+
+use hashbrown::HashSet;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::Dashboard;
+use crate::utils::json::{self, Value};
+
+/// Fields identifying the items of an array, in order of preference. Tabs are identified by
+/// `tabId`, panels by `id` and variables by `name`.
+const ITEM_KEY_FIELDS: [&str; 3] = ["id", "tabId", "name"];
+
+/// Fields of the dashboard definition which aren't compared between versions.
+const IGNORED_FIELDS: [&str; 3] = ["dashboardId", "updatedAt", "hash"];
+
+/// A saved version of a dashboard.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct SavedVersion {
+    /// Number of the version, the first saved version of a dashboard is 1
+    pub version: i32,
+    /// User who saved the version
+    pub author: String,
+    pub title: String,
+    /// Time the version was saved, in microseconds
+    pub created_at: i64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct SavedVersionList {
+    pub list: Vec<SavedVersion>,
+}
+
+/// Differences between two saved versions of a dashboard. Tabs, panels and variables are
+/// matched by their id or name, so moving them around only shows the changed positions.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct SavedVersionDiff {
+    pub from_version: i32,
+    pub to_version: i32,
+    pub changes: Vec<DiffChange>,
+}
+
+/// A value added, removed or changed between two versions of a dashboard.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct DiffChange {
+    /// Path of the value, with the id or name of matched items in brackets, e.g.
+    /// `tabs[default].panels[Panel_ID1].title`
+    pub path: String,
+    /// Value in the older version, not set when the value was added
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub from: Option<Value>,
+    /// Value in the newer version, not set when the value was removed
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub to: Option<Value>,
+}
+
+impl SavedVersionDiff {
+    /// Computes the changes needed to go from the `from` dashboard to the `to` dashboard.
+    pub fn new(from: (i32, &Dashboard), to: (i32, &Dashboard)) -> Self {
+        let mut changes = Vec::new();
+        if from.1.version != to.1.version {
+            changes.push(DiffChange {
+                path: "version".to_string(),
+                from: Some(from.1.version.into()),
+                to: Some(to.1.version.into()),
+            });
+        }
+        diff_values("", &definition(from.1), &definition(to.1), &mut changes);
+        Self {
+            from_version: from.0,
+            to_version: to.0,
+            changes,
+        }
+    }
+}
+
+/// Returns the definition of the dashboard for its schema version, without the fields which
+/// change on every save.
+fn definition(dashboard: &Dashboard) -> Value {
+    let mut value = match json::to_value(dashboard) {
+        Ok(Value::Object(mut obj)) => obj
+            .remove(&format!("v{}", dashboard.version))
+            .unwrap_or_default(),
+        _ => Value::Null,
+    };
+    if let Some(obj) = value.as_object_mut() {
+        for field in IGNORED_FIELDS {
+            obj.remove(field);
+        }
+    }
+    value
+}
+
+fn diff_values(path: &str, from: &Value, to: &Value, changes: &mut Vec<DiffChange>) {
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            for (key, from_value) in from.iter() {
+                let path = join_path(path, key);
+                match to.get(key) {
+                    Some(to_value) => diff_values(&path, from_value, to_value, changes),
+                    None => changes.push(removed(path, from_value)),
+                }
+            }
+            for (key, to_value) in to.iter().filter(|(key, _)| !from.contains_key(*key)) {
+                changes.push(added(join_path(path, key), to_value));
+            }
+        }
+        (Value::Array(from), Value::Array(to)) => match item_key_field(from, to) {
+            Some(field) => {
+                let key = |item: &Value| item_key(item, field).unwrap_or_default();
+                for from_item in from.iter() {
+                    let path = format!("{path}[{}]", key(from_item));
+                    match to.iter().find(|to_item| key(to_item) == key(from_item)) {
+                        Some(to_item) => diff_values(&path, from_item, to_item, changes),
+                        None => changes.push(removed(path, from_item)),
+                    }
+                }
+                for to_item in to.iter() {
+                    if !from.iter().any(|from_item| key(from_item) == key(to_item)) {
+                        changes.push(added(format!("{path}[{}]", key(to_item)), to_item));
+                    }
+                }
+            }
+            None => {
+                for (i, from_item) in from.iter().enumerate() {
+                    let path = format!("{path}[{i}]");
+                    match to.get(i) {
+                        Some(to_item) => diff_values(&path, from_item, to_item, changes),
+                        None => changes.push(removed(path, from_item)),
+                    }
+                }
+                for (i, to_item) in to.iter().enumerate().skip(from.len()) {
+                    changes.push(added(format!("{path}[{i}]"), to_item));
+                }
+            }
+        },
+        (from, to) if from != to => changes.push(DiffChange {
+            path: path.to_string(),
+            from: Some(from.clone()),
+            to: Some(to.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// Returns the field identifying every item of both arrays, if the items have one with unique
+/// values.
+fn item_key_field(from: &[Value], to: &[Value]) -> Option<&'static str> {
+    ITEM_KEY_FIELDS.into_iter().find(|field| {
+        [from, to].iter().all(|items| {
+            let mut keys = HashSet::with_capacity(items.len());
+            items
+                .iter()
+                .all(|item| item_key(item, field).is_some_and(|key| keys.insert(key)))
+        })
+    })
+}
+
+fn item_key(item: &Value, field: &str) -> Option<String> {
+    match item.get(field)? {
+        Value::String(s) => Some(s.to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn added(path: String, value: &Value) -> DiffChange {
+    DiffChange {
+        path,
+        from: None,
+        to: Some(value.clone()),
+    }
+}
+
+fn removed(path: String, value: &Value) -> DiffChange {
+    DiffChange {
+        path,
+        from: Some(value.clone()),
+        to: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::dashboards::v5;
+
+    fn dashboard(value: Value) -> Dashboard {
+        json::from_value::<v5::Dashboard>(value).unwrap().into()
+    }
+
+    #[test]
+    fn test_saved_version_diff() {
+        let panel = |id: &str, title: &str| {
+            json::json!({
+                "id": id,
+                "type": "line",
+                "title": title,
+                "description": "",
+                "config": { "show_legends": true, "decimals": 2 },
+                "queries": [],
+                "layout": { "x": 0, "y": 0, "w": 12, "h": 9, "i": 1 },
+            })
+        };
+        let from = dashboard(json::json!({
+            "version": 5,
+            "dashboardId": "d1",
+            "title": "Services",
+            "description": "",
+            "owner": "root@example.com",
+            "created": "2025-01-01T00:00:00Z",
+            "tabs": [{
+                "tabId": "default",
+                "name": "Default",
+                "panels": [panel("p1", "Errors"), panel("p2", "Latency")],
+            }],
+        }));
+        let to = dashboard(json::json!({
+            "version": 5,
+            "dashboardId": "d1",
+            "title": "Services",
+            "description": "Service health",
+            "owner": "root@example.com",
+            "created": "2025-01-01T00:00:00Z",
+            "tabs": [{
+                "tabId": "default",
+                "name": "Default",
+                "panels": [panel("p3", "Traffic"), panel("p1", "Error rate")],
+            }],
+        }));
+
+        let diff = SavedVersionDiff::new((1, &from), (2, &to));
+        assert_eq!(diff.from_version, 1);
+        assert_eq!(diff.to_version, 2);
+        let paths = diff
+            .changes
+            .iter()
+            .map(|change| change.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "description",
+                "tabs[default].panels[p1].title",
+                "tabs[default].panels[p2]",
+                "tabs[default].panels[p3]",
+            ]
+        );
+        assert_eq!(diff.changes[1].from, Some("Errors".into()));
+        assert_eq!(diff.changes[1].to, Some("Error rate".into()));
+        assert!(diff.changes[2].to.is_none());
+        assert!(diff.changes[3].from.is_none());
+
+        let same = SavedVersionDiff::new((1, &from), (1, &from));
+        assert!(same.changes.is_empty());
+    }
+}
@@ -28,6 +28,21 @@ use crate::{meta::stream::StreamType, utils::json};
 pub static RESULT_ARRAY: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^#[ \s]*Result[ \s]*Array[ \s]*#").unwrap());
 
+// Checks for #Wasm:<function_name>#, used by query functions to run a saved WASM function
+pub static WASM_FUNCTION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^#[ \s]*Wasm[ \s]*:[ \s]*([a-zA-Z0-9_\-]+)[ \s]*#").unwrap());
+
+pub const TRANS_TYPE_VRL: u8 = 0;
+pub const TRANS_TYPE_WASM: u8 = 2;
+
+/// Returns the name of the saved WASM function referenced by the query function, if any.
+pub fn get_wasm_function_name(query_fn: &str) -> Option<&str> {
+    WASM_FUNCTION
+        .captures(query_fn.trim())
+        .and_then(|caps| caps.get(1))
+        .map(|name| name.as_str())
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Transform {
@@ -39,7 +54,7 @@ pub struct Transform {
     #[serde(default)]
     pub num_args: u8,
     #[serde(default = "default_trans_type")]
-    pub trans_type: Option<u8>, // 0=vrl 1=lua
+    pub trans_type: Option<u8>, // 0=vrl 1=lua 2=wasm (base64 encoded module in `function`)
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub streams: Option<Vec<StreamOrder>>,
@@ -47,7 +62,11 @@ pub struct Transform {
 
 impl Transform {
     pub fn is_vrl(&self) -> bool {
-        self.trans_type == Some(0)
+        self.trans_type == Some(TRANS_TYPE_VRL)
+    }
+
+    pub fn is_wasm(&self) -> bool {
+        self.trans_type == Some(TRANS_TYPE_WASM)
     }
 
     pub fn is_result_array_vrl(&self) -> bool {
@@ -195,4 +214,15 @@ mod tests {
         assert_eq!(f1.name, f2.name);
         assert_eq!(format!("{:?}", f1), format!("{:?}", f2));
     }
+
+    #[test]
+    fn test_get_wasm_function_name() {
+        assert_eq!(get_wasm_function_name("#Wasm:redact#"), Some("redact"));
+        assert_eq!(
+            get_wasm_function_name(" # Wasm : mask_pii # \n ."),
+            Some("mask_pii")
+        );
+        assert_eq!(get_wasm_function_name("#ResultArray#\n ."), None);
+        assert_eq!(get_wasm_function_name(".a = 1 \n ."), None);
+    }
 }
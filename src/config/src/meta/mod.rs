@@ -15,8 +15,10 @@
 
 pub mod actions;
 pub mod alerts;
+pub mod api_key;
 pub mod bitvec;
 pub mod cluster;
+pub mod condition;
 pub mod dashboards;
 pub mod destinations;
 pub mod folder;
@@ -28,8 +30,10 @@ pub mod organization;
 pub mod otlp;
 pub mod pipeline;
 pub mod promql;
+pub mod quota;
 pub mod ratelimit;
 pub mod search;
+pub mod secrets;
 pub mod self_reporting;
 pub mod short_url;
 pub mod sql;
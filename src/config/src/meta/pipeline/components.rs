@@ -18,9 +18,15 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::meta::{
-    alerts::{QueryCondition, TriggerCondition},
-    stream::{RemoteStreamParams, RoutingCondition, StreamParams, StreamType},
+use crate::{
+    meta::{
+        alerts::{QueryCondition, TriggerCondition},
+        stream::{RemoteStreamParams, RoutingCondition, StreamParams, StreamType},
+    },
+    utils::{
+        hash::{Sum64, murmur3},
+        json::{Map, Value, get_string_value},
+    },
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -57,6 +63,9 @@ pub struct DerivedStream {
     /// The datetime from when the pipeline should check for ingested data
     #[serde(skip_serializing_if = "Option::is_none")]
     pub start_at: Option<i64>,
+    /// The user who last saved the pipeline, the query runs with their row filters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_edited_by: Option<String>,
 }
 
 impl DerivedStream {
@@ -139,6 +148,8 @@ pub enum NodeData {
     Query(DerivedStream),
     Function(FunctionParams),
     Condition(ConditionParams),
+    Sampling(SamplingParams),
+    Dedup(DedupParams),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
@@ -157,6 +168,131 @@ pub struct ConditionParams {
     pub conditions: Vec<RoutingCondition>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SamplingParams {
+    /// Fraction of records to keep when no rule matches, from 0.0 to 1.0
+    #[serde(default = "default_sampling_rate")]
+    pub rate: f64,
+    /// When set, the decision is made by hashing this field's value so that all records
+    /// sharing the same value are kept or dropped together. Otherwise records are sampled
+    /// randomly.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash_field: Option<String>,
+    /// Per-key overrides. The first rule whose conditions all match decides the rate.
+    #[serde(default)]
+    pub rules: Vec<SamplingRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SamplingRule {
+    pub conditions: Vec<RoutingCondition>,
+    pub rate: f64,
+}
+
+impl SamplingParams {
+    /// Returns the sampling rate applicable to the given flattened record.
+    pub fn rate_for(&self, record: &Map<String, Value>) -> f64 {
+        self.rules
+            .iter()
+            .find(|rule| rule.conditions.iter().all(|cond| cond.evaluate(record)))
+            .map(|rule| rule.rate)
+            .unwrap_or(self.rate)
+    }
+
+    /// Decides whether the given flattened record is kept. `random` is a value in [0.0, 1.0)
+    /// used when no `hash_field` is configured or the record does not contain it.
+    pub fn should_keep(&self, record: &Map<String, Value>, random: f64) -> bool {
+        let rate = self.rate_for(record);
+        if rate >= 1.0 {
+            return true;
+        }
+        if rate <= 0.0 {
+            return false;
+        }
+        let point = match self.hash_field.as_ref().and_then(|field| record.get(field)) {
+            Some(val) => {
+                let hash = murmur3::new().sum64(&get_string_value(val));
+                hash as f64 / u64::MAX as f64
+            }
+            None => random,
+        };
+        point < rate
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.rate) {
+            return Err("SamplingNode rate must be between 0 and 1".to_string());
+        }
+        for rule in self.rules.iter() {
+            if rule.conditions.is_empty() {
+                return Err("SamplingNode rules must have non-empty conditions".to_string());
+            }
+            if !(0.0..=1.0).contains(&rule.rate) {
+                return Err("SamplingNode rule rate must be between 0 and 1".to_string());
+            }
+            for condition in rule.conditions.iter() {
+                condition.validate()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DedupParams {
+    /// Fields whose combined values identify a duplicate record
+    pub fields: Vec<String>,
+    /// Records with a key already seen within this many seconds are dropped
+    pub window_secs: i64,
+}
+
+impl DedupParams {
+    /// Builds the hash of the dedup key for the given flattened record. Missing fields are
+    /// treated as empty values.
+    pub fn key_hash(&self, record: &Map<String, Value>) -> u64 {
+        let key = self
+            .fields
+            .iter()
+            .map(|field| record.get(field).map(get_string_value).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\u{1f}");
+        murmur3::new().sum64(&key)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.fields.is_empty() {
+            return Err("DedupNode must have at least one field".to_string());
+        }
+        if self.window_secs <= 0 {
+            return Err("DedupNode window must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl NodeData {
+    /// Checks the node type specific parameters.
+    pub fn validate_params(&self) -> Result<(), String> {
+        match self {
+            NodeData::Condition(params) if params.conditions.is_empty() => {
+                Err("ConditionNode must have non-empty conditions".to_string())
+            }
+            NodeData::Condition(params) => params
+                .conditions
+                .iter()
+                .try_for_each(|condition| condition.validate()),
+            NodeData::Sampling(params) => params.validate(),
+            NodeData::Dedup(params) => params.validate(),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn default_sampling_rate() -> f64 {
+    1.0
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 struct Position {
     x: f32,
@@ -273,4 +409,123 @@ mod tests {
         let node_data = json::from_value::<NodeData>(payload);
         assert!(node_data.is_ok());
     }
+
+    #[test]
+    fn test_condition_node_validate() {
+        let payload = json::json!({
+            "node_type": "condition",
+            "conditions": [
+              {"column": "client_ip", "operator": "cidr_match", "value": "10.0.0.0/8"},
+              {"column": "level", "operator": "Contains", "value": "err"}
+            ]
+        });
+        let node_data = json::from_value::<NodeData>(payload).unwrap();
+        assert!(node_data.validate_params().is_ok());
+
+        let payload = json::json!({
+            "node_type": "condition",
+            "conditions": [{"column": "path", "operator": "regex", "value": "(unclosed"}]
+        });
+        let node_data = json::from_value::<NodeData>(payload).unwrap();
+        assert!(node_data.validate_params().is_err());
+    }
+
+    #[test]
+    fn test_sampling_node_serialization() {
+        let payload = json::json!({
+            "node_type": "sampling",
+            "rate": 0.01,
+            "hash_field": "trace_id",
+            "rules": [
+              {
+                "conditions": [{ "column": "level", "operator": "=", "value": "error" }],
+                "rate": 1.0
+              }
+            ]
+        });
+        let node_data = json::from_value::<NodeData>(payload).unwrap();
+        let NodeData::Sampling(params) = node_data else {
+            panic!("expected sampling node");
+        };
+        assert_eq!(params.rate, 0.01);
+        assert_eq!(params.hash_field.as_deref(), Some("trace_id"));
+        assert_eq!(params.rules.len(), 1);
+        assert!(NodeData::Sampling(params).validate_params().is_ok());
+
+        let invalid = NodeData::Sampling(SamplingParams {
+            rate: 1.5,
+            hash_field: None,
+            rules: vec![],
+        });
+        assert!(invalid.validate_params().is_err());
+    }
+
+    #[test]
+    fn test_sampling_should_keep() {
+        let params = SamplingParams {
+            rate: 0.0,
+            hash_field: Some("trace_id".to_string()),
+            rules: vec![SamplingRule {
+                conditions: vec![RoutingCondition {
+                    column: "level".to_string(),
+                    operator: crate::meta::stream::Operator::EqualTo,
+                    value: json::json!("error"),
+                    ignore_case: false,
+                }],
+                rate: 1.0,
+            }],
+        };
+        let error = json::json!({"level": "error", "trace_id": "abc"});
+        let debug = json::json!({"level": "debug", "trace_id": "abc"});
+        assert!(params.should_keep(error.as_object().unwrap(), 0.5));
+        assert!(!params.should_keep(debug.as_object().unwrap(), 0.5));
+
+        // consistent hashing: same key always yields the same decision
+        let params = SamplingParams {
+            rate: 0.5,
+            hash_field: Some("trace_id".to_string()),
+            rules: vec![],
+        };
+        let first = params.should_keep(debug.as_object().unwrap(), 0.0);
+        for random in [0.1, 0.5, 0.9] {
+            assert_eq!(
+                params.should_keep(debug.as_object().unwrap(), random),
+                first
+            );
+        }
+
+        // without hash field the random point decides
+        let params = SamplingParams {
+            rate: 0.5,
+            hash_field: None,
+            rules: vec![],
+        };
+        assert!(params.should_keep(debug.as_object().unwrap(), 0.2));
+        assert!(!params.should_keep(debug.as_object().unwrap(), 0.7));
+    }
+
+    #[test]
+    fn test_dedup_key_hash() {
+        let params = DedupParams {
+            fields: vec!["host".to_string(), "message".to_string()],
+            window_secs: 60,
+        };
+        let a = json::json!({"host": "h1", "message": "m", "other": 1});
+        let b = json::json!({"host": "h1", "message": "m", "other": 2});
+        let c = json::json!({"host": "h2", "message": "m"});
+        assert_eq!(
+            params.key_hash(a.as_object().unwrap()),
+            params.key_hash(b.as_object().unwrap())
+        );
+        assert_ne!(
+            params.key_hash(a.as_object().unwrap()),
+            params.key_hash(c.as_object().unwrap())
+        );
+
+        let invalid = NodeData::Dedup(DedupParams {
+            fields: vec![],
+            window_secs: 60,
+        });
+        assert!(invalid.validate_params().is_err());
+    }
 }
@@ -58,6 +58,10 @@ pub struct Pipeline {
     pub source: PipelineSource,
     pub nodes: Vec<Node>,
     pub edges: Vec<Edge>,
+    /// Logs stream that receives the records failing in any node of this pipeline
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_letter: Option<StreamParams>,
 }
 
 impl Pipeline {
@@ -84,12 +88,14 @@ impl Pipeline {
     /// 1. non-empty nodes list
     /// 2. non-empty edges list
     /// 3. 1st node in nodes list is either StreamNode or QueryNode
-    /// 4. non-empty `conditions` in all ConditionNode nodes in nodes list
+    /// 4. non-empty `conditions` in all ConditionNode nodes in nodes list, valid rates in
+    ///    SamplingNode nodes and non-empty fields and window in DedupNode nodes
     /// 5. every node is reachable
     /// 6. all leaf nodes are of type StreamNode
     /// 7. In the same branch, unchecked `after_flattened` FunctionNode can't follow checked
     ///    `after_flattened` checked FunctionNode
     /// 8. EnrichmentTables can only be used in Scheduled pipelines
+    /// 9. dead letter destination, if any, is a logs stream in the same org
     ///
     /// If all satisfies, populates the [Pipeline::source] with the first node in nodes list
     pub fn validate(&mut self) -> Result<()> {
@@ -131,10 +137,8 @@ impl Pipeline {
         let cfg = get_config();
         for node in self.nodes.iter_mut() {
             // ck 4
-            if matches!(&node.data, NodeData::Condition(condition_params) if condition_params.conditions.is_empty())
-            {
-                return Err(anyhow!("ConditionNode must have non-empty conditions"));
-            } else if let NodeData::Stream(stream_params) = &mut node.data {
+            node.data.validate_params().map_err(|e| anyhow!(e))?;
+            if let NodeData::Stream(stream_params) = &mut node.data {
                 // ck 8
                 if stream_params.stream_type == StreamType::EnrichmentTables
                     && matches!(&self.source, PipelineSource::Realtime(_))
@@ -150,6 +154,20 @@ impl Pipeline {
             }
         }
 
+        // ck 9
+        if let Some(dead_letter) = self.dead_letter.as_mut() {
+            if dead_letter.stream_type != StreamType::Logs {
+                return Err(anyhow!("Dead letter destination must be a logs stream"));
+            }
+            if dead_letter.stream_name.is_empty() {
+                return Err(anyhow!("Dead letter destination stream name is empty"));
+            }
+            dead_letter.org_id = self.org.clone().into();
+            if !cfg.common.skip_formatting_stream_name {
+                dead_letter.stream_name = format_stream_name(&dead_letter.stream_name).into();
+            }
+        }
+
         // ck 5
         if self.edges.len() < self.nodes.len() - 1 {
             return Err(anyhow!(
@@ -308,6 +326,7 @@ where
     String: Type<R::Database> + Decode<'r, R::Database>,
     i32: Type<R::Database> + Decode<'r, R::Database>,
     bool: Type<R::Database> + Decode<'r, R::Database>,
+    Option<String>: Type<R::Database> + Decode<'r, R::Database>,
 {
     fn from_row(row: &'r R) -> Result<Self, Error> {
         let id: String = row.try_get("id")?;
@@ -348,6 +367,12 @@ where
             )
         };
 
+        let dead_letter = row
+            .try_get::<Option<String>, _>("dead_letter")
+            .ok()
+            .flatten()
+            .and_then(|raw| json::from_str(&raw).ok());
+
         Ok(Pipeline {
             id,
             version,
@@ -358,6 +383,7 @@ where
             source,
             nodes,
             edges,
+            dead_letter,
         })
     }
 }
@@ -378,6 +404,234 @@ pub struct PipelineDependencyResponse {
     pub list: Vec<PipelineDependencyItem>,
 }
 
+/// A record that failed in one of the nodes of a pipeline, written to the pipeline's dead letter
+/// stream.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DeadLetterRecord {
+    pub _timestamp: i64,
+    pub pipeline_id: String,
+    pub pipeline_name: String,
+    pub node_id: String,
+    pub node_type: String,
+    pub error: String,
+    /// The original record received by the pipeline, serialized as JSON. Not available for
+    /// failures that can't be traced back to a single input record.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeadLetterReplayRequest {
+    /// Start of the time range to replay, in microseconds
+    pub start_time: i64,
+    /// End of the time range to replay, in microseconds
+    pub end_time: i64,
+    /// Replaying runs the original records through the whole pipeline again, so the branches
+    /// which didn't fail for them get them twice. Replays are refused unless this is set.
+    #[serde(default)]
+    pub allow_duplicates: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeadLetterReplayResponse {
+    pub replayed: usize,
+    /// Whether records are left to replay in the time range, beyond the number of records
+    /// replayed per request. Replay the same time range again to continue.
+    pub has_more: bool,
+}
+
+/// An immutable snapshot of a pipeline, stored every time the pipeline is saved.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PipelineVersion {
+    pub pipeline_id: String,
+    pub version: i32,
+    pub author: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Time the version was saved, in microseconds
+    pub created_at: i64,
+    pub pipeline: Pipeline,
+}
+
+impl PipelineVersion {
+    pub fn new(pipeline: &Pipeline, author: &str, comment: Option<String>) -> Self {
+        Self {
+            pipeline_id: pipeline.id.clone(),
+            version: pipeline.version,
+            author: author.to_string(),
+            comment,
+            created_at: chrono::Utc::now().timestamp_micros(),
+            pipeline: pipeline.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct PipelineVersionList {
+    pub list: Vec<PipelineVersion>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct PipelineRollbackRequest {
+    /// Version to roll the pipeline back to
+    pub version: i32,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// Differences between two versions of a pipeline. Nodes and edges are matched by id.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PipelineVersionDiff {
+    pub from_version: i32,
+    pub to_version: i32,
+    /// Top level properties that differ, e.g. `name` or `dead_letter`
+    pub changed_fields: Vec<String>,
+    pub added_nodes: Vec<Node>,
+    pub removed_nodes: Vec<Node>,
+    pub changed_nodes: Vec<NodeChange>,
+    pub added_edges: Vec<Edge>,
+    pub removed_edges: Vec<Edge>,
+    pub changed_edges: Vec<EdgeChange>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct NodeChange {
+    pub from: Node,
+    pub to: Node,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct EdgeChange {
+    pub from: Edge,
+    pub to: Edge,
+}
+
+impl PipelineVersionDiff {
+    /// Computes the changes needed to go from the `from` pipeline to the `to` pipeline.
+    pub fn new(from: &Pipeline, to: &Pipeline) -> Self {
+        let mut changed_fields = Vec::new();
+        if from.name != to.name {
+            changed_fields.push("name".to_string());
+        }
+        if from.description != to.description {
+            changed_fields.push("description".to_string());
+        }
+        if from.source != to.source {
+            changed_fields.push("source".to_string());
+        }
+        if from.dead_letter != to.dead_letter {
+            changed_fields.push("dead_letter".to_string());
+        }
+
+        let from_nodes: HashMap<_, _> = from.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        let to_nodes: HashMap<_, _> = to.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+        let mut changed_nodes = Vec::new();
+        let mut added_nodes = Vec::new();
+        for node in &to.nodes {
+            match from_nodes.get(node.id.as_str()) {
+                Some(prev) if *prev != node => changed_nodes.push(NodeChange {
+                    from: (*prev).clone(),
+                    to: node.clone(),
+                }),
+                Some(_) => {}
+                None => added_nodes.push(node.clone()),
+            }
+        }
+        let removed_nodes = from
+            .nodes
+            .iter()
+            .filter(|node| !to_nodes.contains_key(node.id.as_str()))
+            .cloned()
+            .collect();
+
+        let from_edges: HashMap<_, _> = from.edges.iter().map(|e| (e.id.as_str(), e)).collect();
+        let to_edges: HashMap<_, _> = to.edges.iter().map(|e| (e.id.as_str(), e)).collect();
+        let mut changed_edges = Vec::new();
+        let mut added_edges = Vec::new();
+        for edge in &to.edges {
+            match from_edges.get(edge.id.as_str()) {
+                Some(prev) if *prev != edge => changed_edges.push(EdgeChange {
+                    from: (*prev).clone(),
+                    to: edge.clone(),
+                }),
+                Some(_) => {}
+                None => added_edges.push(edge.clone()),
+            }
+        }
+        let removed_edges = from
+            .edges
+            .iter()
+            .filter(|edge| !to_edges.contains_key(edge.id.as_str()))
+            .cloned()
+            .collect();
+
+        Self {
+            from_version: from.version,
+            to_version: to.version,
+            changed_fields,
+            added_nodes,
+            removed_nodes,
+            changed_nodes,
+            added_edges,
+            removed_edges,
+            changed_edges,
+        }
+    }
+}
+
+/// Dry run of a saved or unsaved pipeline against sample records.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct PipelineTestRequest {
+    pub pipeline: Pipeline,
+    #[schema(value_type = Vec<Object>)]
+    pub records: Vec<json::Value>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct PipelineTestResponse {
+    /// Trace of every node of the pipeline, in execution order
+    pub nodes: Vec<PipelineTestNodeResult>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PipelineTestNodeResult {
+    pub node_id: String,
+    pub node_type: String,
+    pub received: Vec<PipelineTestRecord>,
+    pub emitted: Vec<PipelineTestRecord>,
+    /// For ConditionNode only, whether each of the received records passed the conditions
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition_passed: Option<Vec<bool>>,
+    pub errors: Vec<PipelineTestError>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PipelineTestRecord {
+    /// Position of the originating sample record. Not available for records produced by a
+    /// function returning an array of records.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    /// Id of the child node the record was emitted to, or the destination stream name for
+    /// records emitted by a leaf node
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[schema(value_type = Object)]
+    pub record: json::Value,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct PipelineTestError {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub index: Option<usize>,
+    pub error: String,
+}
+
 /// DFS traversal to check:
 /// 1. all leaf nodes are of StreamNode
 /// 2. No `After Flattened` unchecked FunctionNode follows `After Flatten` checked FunctionNode in
@@ -576,4 +830,113 @@ mod tests {
         let new_nodes = json::from_str::<Option<Vec<Node>>>(&nodes);
         assert!(new_nodes.is_ok());
     }
+
+    #[test]
+    fn test_pipeline_dead_letter_validation() {
+        let payload = json::json!(
+          {
+            "name": "pipeline test",
+            "org": "prod",
+            "nodes": [
+              {
+                "id": "1",
+                "data": {
+                  "node_type": "stream",
+                  "org_id": "prod",
+                  "stream_name": "default",
+                  "stream_type": "logs"
+                },
+                "position": { "x": 100, "y": 100 },
+                "io_type": "input",
+              },
+              {
+                "id": "2",
+                "data": {
+                  "node_type": "stream",
+                  "org_id": "prod",
+                  "stream_name": "dest",
+                  "stream_type": "logs"
+                },
+                "position": { "x": 300, "y": 100 },
+                "io_type": "output",
+              }
+            ],
+            "edges": [{ "id": "e1-2", "source": "1", "target": "2" }],
+            "dead_letter": {
+              "org_id": "other",
+              "stream_name": "pipeline_dlq",
+              "stream_type": "logs"
+            }
+          }
+        );
+        let mut pl = json::from_value::<Pipeline>(payload).unwrap();
+        assert!(pl.validate().is_ok());
+        let dead_letter = pl.dead_letter.as_ref().unwrap();
+        assert_eq!(dead_letter.org_id.as_str(), "prod");
+
+        pl.dead_letter.as_mut().unwrap().stream_type = StreamType::Metrics;
+        assert!(pl.validate().is_err());
+    }
+
+    #[test]
+    fn test_pipeline_version_diff() {
+        let payload = json::json!(
+          {
+            "pipeline_id": "pl1",
+            "version": 1,
+            "name": "pipeline test",
+            "org": "prod",
+            "nodes": [
+              {
+                "id": "1",
+                "data": {
+                  "node_type": "stream",
+                  "org_id": "prod",
+                  "stream_name": "default",
+                  "stream_type": "logs"
+                },
+                "position": { "x": 100, "y": 100 },
+                "io_type": "input",
+              },
+              {
+                "id": "2",
+                "data": {
+                  "node_type": "stream",
+                  "org_id": "prod",
+                  "stream_name": "dest",
+                  "stream_type": "logs"
+                },
+                "position": { "x": 300, "y": 100 },
+                "io_type": "output",
+              }
+            ],
+            "edges": [{ "id": "e1-2", "source": "1", "target": "2" }]
+          }
+        );
+        let from = json::from_value::<Pipeline>(payload).unwrap();
+        let mut to = from.clone();
+        to.version = 2;
+        to.description = "route to archive".to_string();
+        to.nodes[1].data = NodeData::Stream(StreamParams::new("prod", "archive", StreamType::Logs));
+        let mut added = to.nodes[1].clone();
+        added.id = "3".to_string();
+        to.nodes.push(added);
+        to.edges = vec![Edge::new("1".to_string(), "3".to_string())];
+
+        let diff = PipelineVersionDiff::new(&from, &to);
+        assert_eq!(diff.from_version, 1);
+        assert_eq!(diff.to_version, 2);
+        assert_eq!(diff.changed_fields, vec!["description".to_string()]);
+        assert_eq!(diff.added_nodes.len(), 1);
+        assert_eq!(diff.added_nodes[0].id, "3");
+        assert!(diff.removed_nodes.is_empty());
+        assert_eq!(diff.changed_nodes.len(), 1);
+        assert_eq!(diff.changed_nodes[0].to.id, "2");
+        assert_eq!(diff.added_edges[0].id, "e1-3");
+        assert_eq!(diff.removed_edges[0].id, "e1-2");
+        assert!(diff.changed_edges.is_empty());
+
+        let same = PipelineVersionDiff::new(&from, &from);
+        assert!(same.changed_fields.is_empty() && same.changed_nodes.is_empty());
+    }
 }
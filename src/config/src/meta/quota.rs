@@ -0,0 +1,303 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Header naming the quota which rejected an ingestion request.
+pub const QUOTA_KIND_HEADER: &str = "x-o2-quota-kind";
+/// Header with the limit of the quota which rejected an ingestion request.
+pub const QUOTA_LIMIT_HEADER: &str = "x-o2-quota-limit";
+/// Header with the usage of the quota which rejected an ingestion request.
+pub const QUOTA_USAGE_HEADER: &str = "x-o2-quota-usage";
+
+fn default_soft_limit_percent() -> u8 {
+    80
+}
+
+/// Ingestion, storage and search limits of an org. Limits which aren't set are unlimited.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OrgQuota {
+    /// Bytes the org can ingest per UTC day across the cluster. Ingesters read the daily
+    /// ingestion of the org from the usage stream. When usage isn't reported to the cluster, each
+    /// of them enforces an even share of this limit among the online ingesters.
+    #[serde(default)]
+    pub max_daily_ingest_bytes: Option<u64>,
+    /// Compressed bytes the org can store.
+    #[serde(default)]
+    pub max_storage_bytes: Option<u64>,
+    /// Streams the org can have. Ingestion into existing streams continues once it is reached.
+    #[serde(default)]
+    pub max_streams: Option<u64>,
+    /// Percentage of a limit at which a warning is reported to the org's errors stream.
+    #[serde(default = "default_soft_limit_percent")]
+    pub soft_limit_percent: u8,
+    /// Limits on the searches of the org.
+    #[serde(default)]
+    pub search: OrgSearchLimits,
+}
+
+impl Default for OrgQuota {
+    fn default() -> Self {
+        Self {
+            max_daily_ingest_bytes: None,
+            max_storage_bytes: None,
+            max_streams: None,
+            soft_limit_percent: default_soft_limit_percent(),
+            search: OrgSearchLimits::default(),
+        }
+    }
+}
+
+/// Guardrails on the searches of an org, checked once the files of a search are known and before
+/// it is sent to the queriers. Limits which aren't set are unlimited.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct OrgSearchLimits {
+    /// Searches of the org which can run at once on each querier node planning them. The nodes
+    /// count their searches on their own, so the org can run this many searches on every node.
+    #[serde(default)]
+    pub max_concurrent_queries_per_node: Option<u32>,
+    /// Bytes a single search can scan.
+    #[serde(default)]
+    pub max_query_scan_bytes: Option<u64>,
+    /// Bytes the searches of the org can scan per UTC hour.
+    #[serde(default)]
+    pub max_hourly_scan_bytes: Option<u64>,
+    /// Whether searches over the concurrency limit wait for a slot, until their timeout, rather
+    /// than being rejected right away.
+    #[serde(default)]
+    pub queue_when_busy: bool,
+}
+
+impl OrgSearchLimits {
+    pub fn is_empty(&self) -> bool {
+        self.max_concurrent_queries_per_node.is_none()
+            && self.max_query_scan_bytes.is_none()
+            && self.max_hourly_scan_bytes.is_none()
+    }
+}
+
+/// Searches of an org against its limits, as seen by the querier node answering.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct OrgSearchStatus {
+    pub limits: OrgSearchLimits,
+    pub running: u64,
+    pub queued: u64,
+    /// Searches rejected by the limits since the node started.
+    pub rejected: u64,
+    pub hourly_scan_bytes: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaKind {
+    DailyIngestBytes,
+    StorageBytes,
+    Streams,
+}
+
+impl QuotaKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaKind::DailyIngestBytes => "daily_ingest_bytes",
+            QuotaKind::StorageBytes => "storage_bytes",
+            QuotaKind::Streams => "streams",
+        }
+    }
+}
+
+impl Display for QuotaKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Usage of an org counted against its quota.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct QuotaUsage {
+    pub daily_ingest_bytes: u64,
+    pub storage_bytes: u64,
+    pub streams: u64,
+}
+
+impl QuotaUsage {
+    pub fn get(&self, kind: QuotaKind) -> u64 {
+        match kind {
+            QuotaKind::DailyIngestBytes => self.daily_ingest_bytes,
+            QuotaKind::StorageBytes => self.storage_bytes,
+            QuotaKind::Streams => self.streams,
+        }
+    }
+}
+
+/// A quota of the org which is used up, or close to it for soft limit warnings.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaExceeded {
+    pub org_id: String,
+    pub kind: QuotaKind,
+    pub limit: u64,
+    pub usage: u64,
+}
+
+impl Display for QuotaExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Quota exceeded for this organization [{}]: {} usage {} reached the limit {}",
+            self.org_id, self.kind, self.usage, self.limit
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct OrgQuotaResponse {
+    pub quota: OrgQuota,
+    pub usage: QuotaUsage,
+}
+
+impl OrgQuota {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.soft_limit_percent == 0 || self.soft_limit_percent > 100 {
+            return Err("soft_limit_percent must be between 1 and 100".to_string());
+        }
+        if self.search.max_concurrent_queries_per_node == Some(0) {
+            return Err("search.max_concurrent_queries_per_node must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn limit(&self, kind: QuotaKind) -> Option<u64> {
+        match kind {
+            QuotaKind::DailyIngestBytes => self.max_daily_ingest_bytes,
+            QuotaKind::StorageBytes => self.max_storage_bytes,
+            QuotaKind::Streams => self.max_streams,
+        }
+    }
+
+    /// Returns the first used up quota. The stream quota only applies to new streams, so it is
+    /// only checked when `new_stream` is set.
+    pub fn exceeded(
+        &self,
+        org_id: &str,
+        usage: &QuotaUsage,
+        new_stream: bool,
+    ) -> Option<QuotaExceeded> {
+        let mut kinds = vec![QuotaKind::DailyIngestBytes, QuotaKind::StorageBytes];
+        if new_stream {
+            kinds.push(QuotaKind::Streams);
+        }
+        kinds.into_iter().find_map(|kind| {
+            let limit = self.limit(kind)?;
+            let usage = usage.get(kind);
+            (usage >= limit).then(|| QuotaExceeded {
+                org_id: org_id.to_string(),
+                kind,
+                limit,
+                usage,
+            })
+        })
+    }
+
+    /// Returns the quotas whose usage reached the soft limit.
+    pub fn soft_limit_reached(&self, org_id: &str, usage: &QuotaUsage) -> Vec<QuotaExceeded> {
+        [
+            QuotaKind::DailyIngestBytes,
+            QuotaKind::StorageBytes,
+            QuotaKind::Streams,
+        ]
+        .into_iter()
+        .filter_map(|kind| {
+            let limit = self.limit(kind)?;
+            let usage = usage.get(kind);
+            let soft_limit = limit as u128 * self.soft_limit_percent as u128 / 100;
+            (usage as u128 >= soft_limit).then(|| QuotaExceeded {
+                org_id: org_id.to_string(),
+                kind,
+                limit,
+                usage,
+            })
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_exceeded() {
+        let quota = OrgQuota {
+            max_daily_ingest_bytes: Some(1000),
+            max_streams: Some(2),
+            ..Default::default()
+        };
+        let mut usage = QuotaUsage {
+            daily_ingest_bytes: 500,
+            storage_bytes: u64::MAX,
+            streams: 2,
+        };
+        assert_eq!(quota.exceeded("default", &usage, false), None);
+        assert_eq!(
+            quota
+                .exceeded("default", &usage, true)
+                .map(|exceeded| exceeded.kind),
+            Some(QuotaKind::Streams)
+        );
+
+        usage.daily_ingest_bytes = 1000;
+        let exceeded = quota.exceeded("default", &usage, false).unwrap();
+        assert_eq!(exceeded.kind, QuotaKind::DailyIngestBytes);
+        assert_eq!((exceeded.limit, exceeded.usage), (1000, 1000));
+    }
+
+    #[test]
+    fn test_soft_limit_reached() {
+        let quota = OrgQuota {
+            max_daily_ingest_bytes: Some(1000),
+            max_storage_bytes: Some(1000),
+            soft_limit_percent: 80,
+            ..Default::default()
+        };
+        let usage = QuotaUsage {
+            daily_ingest_bytes: 799,
+            storage_bytes: 800,
+            streams: 100,
+        };
+        let reached = quota.soft_limit_reached("default", &usage);
+        assert_eq!(reached.len(), 1);
+        assert_eq!(reached[0].kind, QuotaKind::StorageBytes);
+
+        assert!(quota.validate().is_ok());
+        let quota = OrgQuota {
+            soft_limit_percent: 0,
+            ..Default::default()
+        };
+        assert!(quota.validate().is_err());
+    }
+
+    #[test]
+    fn test_search_limits() {
+        let quota: OrgQuota = serde_json::from_str(r#"{"max_streams": 10}"#).unwrap();
+        assert!(quota.search.is_empty());
+
+        let quota: OrgQuota =
+            serde_json::from_str(r#"{"search": {"max_concurrent_queries_per_node": 0}}"#).unwrap();
+        assert!(!quota.search.is_empty());
+        assert!(quota.validate().is_err());
+    }
+}
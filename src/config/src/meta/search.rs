@@ -750,6 +750,9 @@ impl TryFrom<json::Value> for SearchHistoryHitResponse {
 #[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
 pub struct QueryStatusResponse {
     pub status: Vec<QueryStatus>,
+    /// Searches of the org against its limits, when it has any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org_limits: Option<super::quota::OrgSearchStatus>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
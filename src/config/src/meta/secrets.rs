@@ -0,0 +1,148 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Value returned in place of credentials which can only be written.
+pub const REDACTED_VALUE: &str = "******";
+
+/// Matches `${secret:name}` references to the secrets of an org.
+static SECRET_REF: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\$\{secret:([A-Za-z0-9_.\-]+)\}").unwrap());
+
+/// Headers whose values are treated as credentials and redacted when read back.
+const SENSITIVE_HEADER_PARTS: [&str; 6] = ["auth", "token", "key", "secret", "password", "cookie"];
+
+/// Creates a secret, its value can be written but never read back.
+#[derive(Clone, Debug, Deserialize, ToSchema)]
+pub struct SecretRequest {
+    pub name: String,
+    pub value: String,
+}
+
+/// Replaces the value of a secret.
+#[derive(Clone, Debug, Deserialize, ToSchema)]
+pub struct SecretUpdateRequest {
+    pub value: String,
+}
+
+/// A secret of an org, without its value.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct SecretInfo {
+    pub name: String,
+    pub created_at: i64,
+    pub created_by: String,
+}
+
+/// Secret names are what references use, see [secret_refs].
+pub fn is_valid_secret_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 128
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Gets the names of the secrets the text references.
+pub fn secret_refs(text: &str) -> Vec<&str> {
+    SECRET_REF
+        .captures_iter(text)
+        .filter_map(|cap| cap.get(1).map(|v| v.as_str()))
+        .collect()
+}
+
+/// Whether the text references a secret.
+pub fn has_secret_ref(text: &str) -> bool {
+    SECRET_REF.is_match(text)
+}
+
+/// Replaces the secret references in the text, `lookup` gets the value of a secret.
+pub fn replace_secret_refs<E>(
+    text: &str,
+    mut lookup: impl FnMut(&str) -> Result<String, E>,
+) -> Result<String, E> {
+    let mut resolved = String::with_capacity(text.len());
+    let mut last = 0;
+    for cap in SECRET_REF.captures_iter(text) {
+        let whole = cap.get(0).unwrap();
+        resolved.push_str(&text[last..whole.start()]);
+        resolved.push_str(&lookup(&cap[1])?);
+        last = whole.end();
+    }
+    resolved.push_str(&text[last..]);
+    Ok(resolved)
+}
+
+/// Whether the value of the header is a plaintext credential, values which reference secrets
+/// are not, e.g. `Bearer ${secret:token}`.
+pub fn is_sensitive_header(name: &str, value: &str) -> bool {
+    let name = name.to_lowercase();
+    SENSITIVE_HEADER_PARTS
+        .iter()
+        .any(|part| name.contains(part))
+        && !SECRET_REF.is_match(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_refs() {
+        assert_eq!(
+            secret_refs("Bearer ${secret:pagerduty_key} ${secret:other.key-1}"),
+            vec!["pagerduty_key", "other.key-1"]
+        );
+        assert!(secret_refs("Bearer ${secret:}").is_empty());
+        assert!(secret_refs("${pagerduty_key}").is_empty());
+    }
+
+    #[test]
+    fn test_replace_secret_refs() {
+        let resolved = replace_secret_refs::<()>("Token ${secret:a}/${secret:b}", |name| {
+            Ok(format!("<{name}>"))
+        })
+        .unwrap();
+        assert_eq!(resolved, "Token <a>/<b>");
+        assert!(replace_secret_refs("${secret:a}", |_| Err(())).is_err());
+        assert_eq!(
+            replace_secret_refs::<()>("plain", |_| unreachable!()).unwrap(),
+            "plain"
+        );
+    }
+
+    #[test]
+    fn test_is_sensitive_header() {
+        assert!(is_sensitive_header("Authorization", "Bearer abc"));
+        assert!(is_sensitive_header("X-Api-Key", "abc"));
+        assert!(!is_sensitive_header(
+            "Authorization",
+            "Bearer ${secret:token}"
+        ));
+        assert!(!is_sensitive_header("Content-Type", "application/json"));
+    }
+
+    #[test]
+    fn test_is_valid_secret_name() {
+        assert!(is_valid_secret_name("pagerduty_key"));
+        assert!(is_valid_secret_name("slack.webhook-1"));
+        assert!(!is_valid_secret_name(""));
+        assert!(!is_valid_secret_name("bad name"));
+        assert!(!is_valid_secret_name("bad/name"));
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::json::{self, Value};
+
+pub const AUDIT_STREAM: &str = "_audit";
+
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Request bodies longer than this are truncated in audit records.
+pub const MAX_AUDIT_BODY_LEN: usize = 64 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditData {
+    pub _timestamp: i64,
+    pub org_id: String,
+    pub user_email: String,
+    pub http_method: String,
+    pub http_path: String,
+    pub http_query_params: String,
+    pub http_body: String,
+    pub http_response_code: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_msg: Option<String>,
+}
+
+/// Redacts the values of the JSON request body whose keys contain any of `redact_fields`, case
+/// insensitively. Bodies which aren't JSON are only recorded by their size, as they can't be
+/// redacted.
+pub fn redact_body(body: &[u8], redact_fields: &[&str]) -> String {
+    if body.is_empty() {
+        return String::new();
+    }
+    let Ok(mut value) = json::from_slice::<Value>(body) else {
+        return format!("[{} bytes]", body.len());
+    };
+    redact_value(&mut value, redact_fields);
+    let mut body = value.to_string();
+    if body.len() > MAX_AUDIT_BODY_LEN {
+        let mut end = MAX_AUDIT_BODY_LEN;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str("...");
+    }
+    body
+}
+
+fn redact_value(value: &mut Value, redact_fields: &[&str]) {
+    match value {
+        Value::Object(map) => {
+            for (key, val) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if redact_fields
+                    .iter()
+                    .any(|field| !field.is_empty() && key.contains(field))
+                {
+                    *val = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(val, redact_fields);
+                }
+            }
+        }
+        Value::Array(values) => {
+            for val in values.iter_mut() {
+                redact_value(val, redact_fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_body() {
+        let fields = ["password", "token", "secret"];
+        assert_eq!(redact_body(b"", &fields), "");
+        assert_eq!(redact_body(b"name=a&password=b", &fields), "[17 bytes]");
+
+        let body = br#"{"email":"a@b.c","password":"p","nested":[{"apiToken":"t","n":1}]}"#;
+        let redacted: Value = json::from_str(&redact_body(body, &fields)).unwrap();
+        assert_eq!(redacted["email"], "a@b.c");
+        assert_eq!(redacted["password"], REDACTED);
+        assert_eq!(redacted["nested"][0]["apiToken"], REDACTED);
+        assert_eq!(redacted["nested"][0]["n"], 1);
+
+        let long = json::to_vec(&"x".repeat(MAX_AUDIT_BODY_LEN * 2)).unwrap();
+        assert_eq!(redact_body(&long, &fields).len(), MAX_AUDIT_BODY_LEN + 3);
+    }
+}
@@ -17,7 +17,10 @@ use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
-use crate::meta::stream::StreamParams;
+use crate::meta::{
+    quota::{QuotaExceeded, QuotaKind},
+    stream::StreamParams,
+};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -37,6 +40,7 @@ pub enum ErrorSource {
     Dashboard,
     Ingestion,
     Pipeline(PipelineError),
+    Quota(QuotaWarning),
     Search,
     Other,
 }
@@ -71,6 +75,30 @@ impl PipelineError {
     }
 }
 
+/// An org quota whose usage reached its soft limit.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct QuotaWarning {
+    pub quota_kind: QuotaKind,
+    pub quota_limit: u64,
+    pub quota_usage: u64,
+    pub error: String,
+}
+
+impl From<QuotaExceeded> for QuotaWarning {
+    fn from(value: QuotaExceeded) -> Self {
+        Self {
+            quota_kind: value.kind,
+            quota_limit: value.limit,
+            quota_usage: value.usage,
+            error: format!(
+                "{} usage {} reached the soft limit of the quota {}",
+                value.kind, value.usage, value.limit
+            ),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct NodeErrors {
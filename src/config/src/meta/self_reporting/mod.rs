@@ -13,6 +13,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use audit::AuditData;
 use error::ErrorData;
 use tokio::{
     sync::{mpsc, oneshot},
@@ -20,6 +21,7 @@ use tokio::{
 };
 use usage::{TriggerData, UsageData};
 
+pub mod audit;
 pub mod error;
 pub mod usage;
 
@@ -40,6 +42,7 @@ pub enum ReportingData {
     Usage(Box<UsageData>),
     Trigger(Box<TriggerData>),
     Error(Box<ErrorData>),
+    Audit(Box<AuditData>),
 }
 
 #[derive(Debug)]
@@ -38,6 +38,9 @@ pub enum TriggerDataStatus {
     ConditionNotSatisfied,
     #[serde(rename = "skipped")]
     Skipped,
+    /// The condition was satisfied but a maintenance window suppressed the notification
+    #[serde(rename = "suppressed")]
+    Suppressed,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -222,6 +225,8 @@ pub enum UsageType {
     Syslog,
     #[serde(rename = "enrichment_table")]
     EnrichmentTable,
+    #[serde(rename = "pipeline")]
+    Pipeline,
 }
 
 impl UsageType {
@@ -287,6 +292,7 @@ impl std::fmt::Display for UsageType {
             UsageType::Retention => write!(f, "data_retention"),
             UsageType::Syslog => write!(f, "syslog"),
             UsageType::EnrichmentTable => write!(f, "enrichment_table"),
+            UsageType::Pipeline => write!(f, "pipeline"),
         }
     }
 }
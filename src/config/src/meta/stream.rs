@@ -22,12 +22,13 @@ use serde::{Deserialize, Serialize, Serializer, ser::SerializeStruct};
 use utoipa::ToSchema;
 
 use super::bitvec::BitVec;
+pub use crate::meta::condition::{Condition as RoutingCondition, Operator};
 use crate::{
     get_config,
     meta::self_reporting::usage::Stats,
     utils::{
         hash::{Sum64, gxhash},
-        json::{self, Map, Value},
+        json,
     },
 };
 
@@ -584,6 +585,10 @@ pub struct UpdateStreamSettings {
     pub index_original_data: Option<bool>,
     #[serde(default)]
     pub index_all_values: Option<bool>,
+    #[serde(default)]
+    pub field_policies: UpdateSettingsWrapper<FieldPolicy>,
+    #[serde(default)]
+    pub row_filters: UpdateSettingsWrapper<RowFilter>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
@@ -601,6 +606,59 @@ impl PartialEq for DistinctField {
 }
 impl Eq for DistinctField {}
 
+/// Hides or masks a field in the searches of users that have any of the given roles or groups.
+/// Hidden fields behave as if they don't exist in the stream, masked fields read as
+/// [FIELD_MASK_VALUE].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+pub struct FieldPolicy {
+    pub field: String,
+    #[serde(default)]
+    pub action: FieldPolicyAction,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+impl FieldPolicy {
+    /// Checks if the policy applies to a user with the given roles and groups.
+    pub fn applies_to(&self, roles: &[String], groups: &[String]) -> bool {
+        self.roles.iter().any(|role| roles.contains(role))
+            || self.groups.iter().any(|group| groups.contains(group))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldPolicyAction {
+    #[default]
+    Hide,
+    Mask,
+}
+
+pub const FIELD_MASK_VALUE: &str = "***";
+
+/// Limits the records that users with any of the given roles or groups see in the stream to the
+/// ones matching the sql predicate, like `team = 'payments'`. A user bound to several filters sees
+/// the records matching any of them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
+pub struct RowFilter {
+    pub name: String,
+    pub filter: String,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+impl RowFilter {
+    /// Checks if the filter applies to a user with the given roles and groups.
+    pub fn applies_to(&self, roles: &[String], groups: &[String]) -> bool {
+        self.roles.iter().any(|role| roles.contains(role))
+            || self.groups.iter().any(|group| groups.contains(group))
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, PartialEq)]
 pub struct TimeRange {
     /// Start timestamp in microseconds
@@ -715,6 +773,12 @@ pub struct StreamSettings {
     pub index_original_data: bool,
     #[serde(default)]
     pub index_all_values: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub field_policies: Vec<FieldPolicy>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub row_filters: Vec<RowFilter>,
 }
 
 impl Serialize for StreamSettings {
@@ -744,6 +808,16 @@ impl Serialize for StreamSettings {
         state.serialize_field("extended_retention_days", &self.extended_retention_days)?;
         state.serialize_field("index_original_data", &self.index_original_data)?;
         state.serialize_field("index_all_values", &self.index_all_values)?;
+        if !self.field_policies.is_empty() {
+            state.serialize_field("field_policies", &self.field_policies)?;
+        } else {
+            state.skip_field("field_policies")?;
+        }
+        if !self.row_filters.is_empty() {
+            state.serialize_field("row_filters", &self.row_filters)?;
+        } else {
+            state.skip_field("row_filters")?;
+        }
 
         match self.defined_schema_fields.as_ref() {
             Some(fields) => {
@@ -907,6 +981,16 @@ impl From<&str> for StreamSettings {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        let field_policies = settings
+            .get("field_policies")
+            .and_then(|v| json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        let row_filters = settings
+            .get("row_filters")
+            .and_then(|v| json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
         Self {
             partition_time_level,
             partition_keys,
@@ -924,6 +1008,8 @@ impl From<&str> for StreamSettings {
             extended_retention_days,
             index_original_data,
             index_all_values,
+            field_policies,
+            row_filters,
         }
     }
 }
@@ -1015,127 +1101,6 @@ pub struct PartitioningDetails {
     pub partition_time_level: Option<PartitionTimeLevel>,
 }
 
-// Code Duplicated from alerts
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
-pub struct RoutingCondition {
-    pub column: String,
-    pub operator: Operator,
-    #[schema(value_type = Object)]
-    pub value: Value,
-    #[serde(default)]
-    pub ignore_case: bool,
-}
-// Code Duplicated from alerts
-impl RoutingCondition {
-    pub fn evaluate(&self, row: &Map<String, Value>) -> bool {
-        let val = match row.get(&self.column) {
-            Some(val) => val,
-            None => {
-                // field not found -> dropped
-                return false;
-            }
-        };
-        match val {
-            Value::String(v) => {
-                let val = v.as_str();
-                let con_val = self.value.as_str().unwrap_or_default().trim_matches('"'); // "" is interpreted as empty string
-                match self.operator {
-                    Operator::EqualTo => val == con_val,
-                    Operator::NotEqualTo => val != con_val,
-                    Operator::GreaterThan => val > con_val,
-                    Operator::GreaterThanEquals => val >= con_val,
-                    Operator::LessThan => val < con_val,
-                    Operator::LessThanEquals => val <= con_val,
-                    Operator::Contains => val.contains(con_val),
-                    Operator::NotContains => !val.contains(con_val),
-                }
-            }
-            Value::Number(_) => {
-                let val = val.as_f64().unwrap_or_default();
-                let con_val = if self.value.is_number() {
-                    self.value.as_f64().unwrap_or_default()
-                } else {
-                    self.value
-                        .as_str()
-                        .unwrap_or_default()
-                        .parse()
-                        .unwrap_or_default()
-                };
-                match self.operator {
-                    Operator::EqualTo => val == con_val,
-                    Operator::NotEqualTo => val != con_val,
-                    Operator::GreaterThan => val > con_val,
-                    Operator::GreaterThanEquals => val >= con_val,
-                    Operator::LessThan => val < con_val,
-                    Operator::LessThanEquals => val <= con_val,
-                    _ => false,
-                }
-            }
-            Value::Bool(v) => {
-                let val = v.to_owned();
-                let con_val = if self.value.is_boolean() {
-                    self.value.as_bool().unwrap_or_default()
-                } else {
-                    self.value
-                        .as_str()
-                        .unwrap_or_default()
-                        .parse()
-                        .unwrap_or_default()
-                };
-                match self.operator {
-                    Operator::EqualTo => val == con_val,
-                    Operator::NotEqualTo => val != con_val,
-                    _ => false,
-                }
-            }
-            Value::Null => {
-                matches!(self.operator, Operator::EqualTo)
-                    && matches!(&self.value, Value::String(v) if v == "null")
-            }
-            _ => false,
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
-pub enum Operator {
-    #[serde(rename = "=")]
-    EqualTo,
-    #[serde(rename = "!=")]
-    NotEqualTo,
-    #[serde(rename = ">")]
-    GreaterThan,
-    #[serde(rename = ">=")]
-    GreaterThanEquals,
-    #[serde(rename = "<")]
-    LessThan,
-    #[serde(rename = "<=")]
-    LessThanEquals,
-    Contains,
-    NotContains,
-}
-
-impl Default for Operator {
-    fn default() -> Self {
-        Self::EqualTo
-    }
-}
-
-impl std::fmt::Display for Operator {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Operator::EqualTo => write!(f, "="),
-            Operator::NotEqualTo => write!(f, "!="),
-            Operator::GreaterThan => write!(f, ">"),
-            Operator::GreaterThanEquals => write!(f, ">="),
-            Operator::LessThan => write!(f, "<"),
-            Operator::LessThanEquals => write!(f, "<="),
-            Operator::Contains => write!(f, "contains"),
-            Operator::NotContains => write!(f, "not contains"),
-        }
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct EnrichmentTableMetaStreamStats {
     pub start_time: i64,
@@ -1265,6 +1230,47 @@ mod tests {
         assert_eq!(format!("{}", StreamPartitionType::Prefix), "prefix");
     }
 
+    #[test]
+    fn test_stream_settings_field_policies() {
+        let settings = StreamSettings {
+            field_policies: vec![FieldPolicy {
+                field: "email".to_string(),
+                action: FieldPolicyAction::Mask,
+                roles: vec!["viewer".to_string()],
+                groups: vec![],
+            }],
+            ..Default::default()
+        };
+        let data = json::to_string(&settings).unwrap();
+        let parsed = StreamSettings::from(data.as_str());
+        assert_eq!(parsed.field_policies, settings.field_policies);
+
+        let policy = &parsed.field_policies[0];
+        assert!(policy.applies_to(&["viewer".to_string()], &[]));
+        assert!(!policy.applies_to(&["admin".to_string()], &["viewer".to_string()]));
+
+        let parsed = StreamSettings::from("{}");
+        assert!(parsed.field_policies.is_empty());
+    }
+
+    #[test]
+    fn test_stream_settings_row_filters() {
+        let settings = StreamSettings {
+            row_filters: vec![RowFilter {
+                name: "payments".to_string(),
+                filter: "team = 'payments'".to_string(),
+                roles: vec![],
+                groups: vec!["payments".to_string()],
+            }],
+            ..Default::default()
+        };
+        let data = json::to_string(&settings).unwrap();
+        let parsed = StreamSettings::from(data.as_str());
+        assert_eq!(parsed.row_filters, settings.row_filters);
+        assert!(parsed.row_filters[0].applies_to(&[], &["payments".to_string()]));
+        assert!(!parsed.row_filters[0].applies_to(&["payments".to_string()], &[]));
+    }
+
     #[cfg(feature = "gxhash")]
     #[test]
     fn test_hash_partition() {
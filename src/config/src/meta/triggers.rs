@@ -82,10 +82,14 @@ pub struct ScheduledTriggerData {
     pub tolerance: i64,
     #[serde(default)]
     pub last_satisfied_at: Option<i64>,
+    /// Whether the conditions of the alert were satisfied at its last evaluation. Read by the
+    /// composite alerts and the dependent alerts.
+    #[serde(default)]
+    pub firing: bool,
 }
 
 impl ScheduledTriggerData {
-    /// Does not reset the last_satisfied_at field
+    /// Does not reset the last_satisfied_at and firing fields
     pub fn reset(&mut self) {
         self.period_end_time = None;
         self.tolerance = 0;
@@ -45,6 +45,27 @@ impl StringExt for String {
     }
 }
 
+/// Matches the value against a pattern where `*` matches any sequence of characters.
+pub fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let parts = pattern.split('*').collect::<Vec<_>>();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !value.starts_with(first) || value.len() < first.len() + last.len() || !value.ends_with(last)
+    {
+        return false;
+    }
+    let mut rest = &value[first.len()..value.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,4 +76,15 @@ mod tests {
         let needle = "unitTest";
         assert!(find(haystack, needle));
     }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("k8s_logs", "k8s_logs"));
+        assert!(!wildcard_match("k8s_logs", "k8s_logs_2"));
+        assert!(wildcard_match("k8s_*", "k8s_logs"));
+        assert!(wildcard_match("*_logs", "k8s_logs"));
+        assert!(wildcard_match("k8s_*_prod", "k8s_app_prod"));
+        assert!(!wildcard_match("k8s_*_prod", "k8s_prod"));
+        assert!(wildcard_match("*", "anything"));
+    }
 }
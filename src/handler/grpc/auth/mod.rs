@@ -22,9 +22,46 @@ use crate::{
         infra::config::ROOT_USER,
         utils::auth::{get_hash, is_root_user},
     },
-    service::db::org_users::get_cached_user_org,
+    service::{
+        db::org_users::get_cached_user_org,
+        tls::{ClientCertAuth, authenticate_client_cert, cert_fingerprint},
+    },
 };
 
+/// Auth of the ingestion services, where clients with a certificate mapped to a service account
+/// of the org need no credentials. Other clients go through [`check_auth`].
+pub fn check_ingest_auth(req: Request<()>) -> Result<Request<()>, Status> {
+    let cfg = config::get_config();
+    let metadata = req.metadata();
+    let client_cert = req
+        .peer_certs()
+        .and_then(|certs| certs.first().map(|cert| cert.to_vec()));
+    let cert_org_id = metadata
+        .get(&cfg.grpc.org_header_key)
+        .and_then(|org_id| org_id.to_str().ok());
+    if let (Some(cert), Some(org_id)) = (client_cert, cert_org_id) {
+        match authenticate_client_cert(&cert, org_id) {
+            ClientCertAuth::Revoked => {
+                log::warn!(
+                    "Rejected revoked client certificate {}",
+                    cert_fingerprint(&cert)
+                );
+                return Err(Status::unauthenticated("Client certificate revoked"));
+            }
+            ClientCertAuth::ServiceAccount(user_id) => {
+                let Ok(user_id_metadata) = MetadataValue::try_from(&user_id) else {
+                    return Err(Status::unauthenticated("No valid auth token[6]"));
+                };
+                let mut req = req;
+                req.metadata_mut().append("user_id", user_id_metadata);
+                return Ok(req);
+            }
+            ClientCertAuth::Unmapped => {}
+        }
+    }
+    check_auth(req)
+}
+
 pub fn check_auth(req: Request<()>) -> Result<Request<()>, Status> {
     let cfg = config::get_config();
     let metadata = req.metadata();
@@ -32,13 +69,13 @@ pub fn check_auth(req: Request<()>) -> Result<Request<()>, Status> {
         return Err(Status::unauthenticated("No valid auth token[1]"));
     }
 
-    let token = req
-        .metadata()
+    let Some(token) = metadata
         .get("authorization")
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .to_string();
+        .and_then(|token| token.to_str().ok())
+        .map(|token| token.to_string())
+    else {
+        return Err(Status::unauthenticated("No valid auth token[1]"));
+    };
     if token.is_empty() {
         if get_internal_grpc_token().is_empty() {
             log::error!("Internal grpc token is not set");
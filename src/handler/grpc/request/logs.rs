@@ -81,6 +81,9 @@ impl LogsService for LogsServer {
                     partial_success: None,
                 }))
             }
+            Err(infra::errors::Error::QuotaExceeded(exceeded)) => {
+                Err(Status::resource_exhausted(exceeded.to_string()))
+            }
             Err(e) => Err(Status::internal(e.to_string())),
         }
     }
@@ -13,6 +13,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use actix_web::http::StatusCode;
 use config::{meta::otlp::OtlpRequestType, metrics};
 use opentelemetry_proto::tonic::collector::metrics::v1::{
     ExportMetricsServiceRequest, ExportMetricsServiceResponse,
@@ -53,6 +54,14 @@ impl MetricsService for MetricsIngester {
             OtlpRequestType::Grpc,
         )
         .await;
+        if resp
+            .as_ref()
+            .is_ok_and(|resp| resp.status() == StatusCode::TOO_MANY_REQUESTS)
+        {
+            return Err(Status::resource_exhausted(
+                "Quota exceeded for this organization",
+            ));
+        }
         if resp.is_ok() {
             // metrics
             let time = start.elapsed().as_secs_f64();
@@ -13,6 +13,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use actix_web::http::StatusCode;
 use config::{meta::otlp::OtlpRequestType, metrics};
 use opentelemetry_proto::tonic::collector::trace::v1::{
     ExportTraceServiceRequest, ExportTraceServiceResponse, trace_service_server::TraceService,
@@ -61,6 +62,14 @@ impl TraceService for TraceServer {
             in_stream_name,
         )
         .await;
+        if resp
+            .as_ref()
+            .is_ok_and(|resp| resp.status() == StatusCode::TOO_MANY_REQUESTS)
+        {
+            return Err(Status::resource_exhausted(
+                "Quota exceeded for this organization",
+            ));
+        }
         if resp.is_ok() {
             // metrics
             let time = start.elapsed().as_secs_f64();
@@ -163,3 +163,16 @@ pub async fn token_validator(
 
     Err((ErrorForbidden("Not Supported"), req))
 }
+
+/// Gets the user of a `session` token issued by the built-in OIDC login.
+#[cfg(not(feature = "enterprise"))]
+pub async fn get_user_from_session(auth_str: &str) -> Option<String> {
+    let session_id = auth_str.strip_prefix("session ")?.trim();
+    crate::service::oidc::get_session_user(session_id).await
+}
+
+/// Sessions of the enterprise build hold the IdP token and are resolved by the extractor.
+#[cfg(feature = "enterprise")]
+pub async fn get_user_from_session(_auth_str: &str) -> Option<String> {
+    None
+}
@@ -25,7 +25,10 @@ use actix_web::{
 };
 use config::{
     get_config,
-    meta::user::{DBUser, UserRole},
+    meta::{
+        api_key::{API_KEY_PREFIX, ApiRequestKind},
+        user::{DBUser, User, UserRole},
+    },
     utils::base64,
 };
 #[cfg(feature = "enterprise")]
@@ -50,7 +53,11 @@ use crate::{
             redirect_response::RedirectResponseBuilder,
         },
     },
-    service::{db, users},
+    service::{
+        api_keys, db,
+        tls::{ClientCertAuth, ClientCertificate, authenticate_client_cert, cert_fingerprint},
+        users,
+    },
 };
 
 pub const PKCE_STATE_ORG: &str = "o2_pkce_state";
@@ -78,8 +85,10 @@ pub async fn validator(
         let auth_token: AuthTokensExt =
             config::utils::json::from_str(&auth_info.auth).unwrap_or_default();
         validate_credentials_ext(user_id, password, path, auth_token).await
+    } else if auth_info.auth.starts_with("session ") {
+        validate_session_user(user_id, path).await
     } else {
-        validate_credentials(user_id, password.trim(), path).await
+        validate_credentials(user_id, password.trim(), req.method(), path).await
     } {
         Ok(res) => {
             if res.is_valid {
@@ -155,6 +164,7 @@ pub async fn validate_token(token: &str, org_id: &str) -> Result<(), Error> {
 pub async fn validate_credentials(
     user_id: &str,
     user_password: &str,
+    method: &Method,
     path: &str,
 ) -> Result<TokenValidationResponse, Error> {
     let mut path_columns = path.split('/').collect::<Vec<&str>>();
@@ -164,40 +174,7 @@ pub async fn validate_credentials(
         }
     }
 
-    let user = if path_columns.last().unwrap_or(&"").eq(&"organizations") {
-        let db_user = db::user::get_db_user(user_id).await;
-        match db_user {
-            Ok(user) => {
-                let all_users = user.get_all_users();
-                if all_users.is_empty() {
-                    None
-                } else {
-                    all_users.first().cloned()
-                }
-            }
-            Err(e) => {
-                log::debug!("Error getting user in validate_credentials: {}", e);
-                None
-            }
-        }
-    } else {
-        match path.find('/') {
-            Some(index) => {
-                let org_id = if path_columns.len() > 1 && path_columns[0].eq(V2_API_PREFIX) {
-                    path_columns[1]
-                } else {
-                    &path[0..index]
-                };
-
-                if is_root_user(user_id) {
-                    users::get_user(Some(DEFAULT_ORG), user_id).await
-                } else {
-                    users::get_user(Some(org_id), user_id).await
-                }
-            }
-            None => users::get_user(None, user_id).await,
-        }
-    };
+    let user = get_user_for_path(user_id, path, &path_columns).await;
 
     if user.is_none() {
         return Ok(TokenValidationResponse {
@@ -239,6 +216,30 @@ pub async fn validate_credentials(
         }
     }
 
+    if user_password.starts_with(API_KEY_PREFIX) {
+        // API keys never fall back to the password check
+        let api_key = match request_org_id(path, &path_columns) {
+            Some(org_id) => {
+                let (kind, stream_name) =
+                    api_keys::request_kind(method, org_path_columns(&path_columns));
+                api_keys::validate(org_id, &user.email, user_password, kind, stream_name).await
+            }
+            None => None,
+        };
+        let Some(api_key) = api_key else {
+            return Ok(TokenValidationResponse::default());
+        };
+        return Ok(TokenValidationResponse {
+            is_valid: true,
+            user_email: user.email,
+            is_internal_user: !user.is_external,
+            user_role: Some(api_key.role(user.role)),
+            user_name: user.first_name.to_owned(),
+            family_name: user.last_name,
+            given_name: user.first_name,
+        });
+    }
+
     if user.role.eq(&UserRole::ServiceAccount) && user.token.eq(&user_password) {
         return Ok(TokenValidationResponse {
             is_valid: true,
@@ -302,6 +303,99 @@ pub async fn validate_credentials(
     }
 }
 
+/// Gets the user a request acts as, in the org of the request path.
+async fn get_user_for_path(user_id: &str, path: &str, path_columns: &[&str]) -> Option<User> {
+    if path_columns.last().unwrap_or(&"").eq(&"organizations") {
+        let db_user = db::user::get_db_user(user_id).await;
+        match db_user {
+            Ok(user) => {
+                let all_users = user.get_all_users();
+                if all_users.is_empty() {
+                    None
+                } else {
+                    all_users.first().cloned()
+                }
+            }
+            Err(e) => {
+                log::debug!("Error getting user in validate_credentials: {}", e);
+                None
+            }
+        }
+    } else {
+        match path.find('/') {
+            Some(index) => {
+                let org_id = if path_columns.len() > 1 && path_columns[0].eq(V2_API_PREFIX) {
+                    path_columns[1]
+                } else {
+                    &path[0..index]
+                };
+
+                if is_root_user(user_id) {
+                    users::get_user(Some(DEFAULT_ORG), user_id).await
+                } else {
+                    users::get_user(Some(org_id), user_id).await
+                }
+            }
+            None => users::get_user(None, user_id).await,
+        }
+    }
+}
+
+/// Validates the user of a login session, the session itself is checked by the caller.
+async fn validate_session_user(
+    user_id: &str,
+    path: &str,
+) -> Result<TokenValidationResponse, Error> {
+    let mut path_columns = path.split('/').collect::<Vec<&str>>();
+    if let Some(v) = path_columns.last() {
+        if v.is_empty() {
+            path_columns.pop();
+        }
+    }
+    let Some(user) = get_user_for_path(user_id, path, &path_columns).await else {
+        return Ok(TokenValidationResponse::default());
+    };
+    if !path.contains("/user")
+        || user.role.eq(&UserRole::Admin)
+        || user.role.eq(&UserRole::Root)
+        || user.email.eq(user_id)
+    {
+        Ok(TokenValidationResponse {
+            is_valid: true,
+            user_email: user.email,
+            is_internal_user: !user.is_external,
+            user_role: Some(user.role),
+            user_name: user.first_name.to_owned(),
+            family_name: user.last_name,
+            given_name: user.first_name,
+        })
+    } else {
+        Err(ErrorForbidden("Not allowed"))
+    }
+}
+
+/// Returns the org of an org scoped request path.
+fn request_org_id<'a>(path: &'a str, path_columns: &[&'a str]) -> Option<&'a str> {
+    if path_columns.last().unwrap_or(&"").eq(&"organizations") {
+        return None;
+    }
+    let index = path.find('/')?;
+    if path_columns.len() > 2 && path_columns[0].eq(V2_API_PREFIX) {
+        Some(path_columns[1])
+    } else {
+        Some(&path[0..index]).filter(|org_id| !org_id.is_empty())
+    }
+}
+
+/// Returns the path columns following the org of an org scoped request path.
+fn org_path_columns<'a, 'b>(path_columns: &'b [&'a str]) -> &'b [&'a str] {
+    if path_columns.len() > 2 && path_columns[0].eq(V2_API_PREFIX) {
+        &path_columns[2..]
+    } else {
+        &path_columns[1..]
+    }
+}
+
 #[cfg(feature = "enterprise")]
 pub async fn validate_credentials_ext(
     user_id: &str,
@@ -550,7 +644,7 @@ pub async fn validator_aws(
                     .map(|s| s.to_string())
                     .collect::<Vec<String>>();
 
-                match validate_credentials(&creds[0], &creds[1], path).await {
+                match validate_credentials(&creds[0], &creds[1], req.method(), path).await {
                     Ok(res) => {
                         if res.is_valid {
                             let mut req = req;
@@ -594,7 +688,7 @@ pub async fn validator_gcp(
                 .map(|s| s.to_string())
                 .collect::<Vec<String>>();
 
-            match validate_credentials(&creds[0], &creds[1], path).await {
+            match validate_credentials(&creds[0], &creds[1], req.method(), path).await {
                 Ok(res) => {
                     if res.is_valid {
                         let mut req = req;
@@ -683,6 +777,12 @@ async fn oo_validator_internal(
     } else if auth_info.auth.starts_with("Bearer") {
         log::debug!("Bearer token found");
         super::token::token_validator(req, auth_info).await
+    } else if auth_info.auth.starts_with("session ") {
+        log::debug!("Session token found");
+        match super::token::get_user_from_session(&auth_info.auth).await {
+            Some(user_email) => validator(req, &user_email, "", auth_info, path_prefix).await,
+            None => Err((ErrorUnauthorized("Unauthorized Access"), req)),
+        }
     } else if auth_info.auth.starts_with("{\"auth_ext\":") {
         log::debug!("Auth ext token found");
         let auth_tokens: AuthTokensExt =
@@ -768,6 +868,44 @@ fn get_user_details(decoded: String) -> Option<(String, String)> {
     Some((username, password))
 }
 
+/// Authenticates ingestion requests of clients which presented a mapped mutual TLS certificate.
+/// Only the exact ingestion routes are authenticated this way. Returns `false` along with the
+/// request when it has to be authenticated with its credentials.
+fn validate_client_cert(
+    mut req: ServiceRequest,
+    path: &str,
+    path_columns: &[&str],
+) -> Result<(ServiceRequest, bool), (Error, ServiceRequest)> {
+    let Some(cert) = req.conn_data::<ClientCertificate>().cloned() else {
+        return Ok((req, false));
+    };
+    let Some(org_id) = request_org_id(path, path_columns) else {
+        return Ok((req, false));
+    };
+    let (kind, _) = api_keys::request_kind(req.method(), org_path_columns(path_columns));
+    if kind != ApiRequestKind::Ingest {
+        return Ok((req, false));
+    }
+    match authenticate_client_cert(&cert.0, org_id) {
+        ClientCertAuth::Revoked => {
+            log::warn!(
+                "Rejected revoked client certificate {}",
+                cert_fingerprint(&cert.0)
+            );
+            Err((ErrorUnauthorized("Unauthorized Access"), req))
+        }
+        ClientCertAuth::Unmapped => Ok((req, false)),
+        ClientCertAuth::ServiceAccount(user_email) => {
+            let Ok(user_id) = header::HeaderValue::from_str(&user_email) else {
+                return Err((ErrorUnauthorized("Unauthorized Access"), req));
+            };
+            req.headers_mut()
+                .insert(header::HeaderName::from_static("user_id"), user_id);
+            Ok((req, true))
+        }
+    }
+}
+
 /// Validates the authentication information in the incoming request and returns the request if
 /// valid, or an error if invalid.
 ///
@@ -790,6 +928,12 @@ pub async fn oo_validator(
     let path_columns = path.split('/').collect::<Vec<&str>>();
     let is_short_url = is_short_url_path(&path_columns);
 
+    let req = match validate_client_cert(req, &path, &path_columns) {
+        Ok((req, true)) => return Ok(req),
+        Ok((req, false)) => req,
+        Err(e) => return Err(e),
+    };
+
     let auth_info = match auth_result {
         Ok(info) => info,
         Err(e) => {
@@ -1155,20 +1299,25 @@ mod tests {
         .unwrap();
 
         assert!(
-            validate_credentials(init_user, pwd, "default/_bulk")
+            validate_credentials(init_user, pwd, &Method::POST, "default/_bulk")
+                .await
+                .unwrap()
+                .is_valid
+        );
+        assert!(
+            !validate_credentials("", pwd, &Method::POST, "default/_bulk")
                 .await
                 .unwrap()
                 .is_valid
         );
         assert!(
-            !validate_credentials("", pwd, "default/_bulk")
+            !validate_credentials("", pwd, &Method::GET, "/")
                 .await
                 .unwrap()
                 .is_valid
         );
-        assert!(!validate_credentials("", pwd, "/").await.unwrap().is_valid);
         assert!(
-            !validate_credentials(user_id, pwd, "/")
+            !validate_credentials(user_id, pwd, &Method::GET, "/")
                 .await
                 .unwrap()
                 .is_valid
@@ -1176,17 +1325,40 @@ mod tests {
         // TODO: In these unit tests, is_root_user function does not work,
         // So, the below test case will not work, move these tests to integration tests
         // assert!(
-        //     validate_credentials(user_id, pwd, "default/user")
+        //     validate_credentials(user_id, pwd, &Method::GET, "default/user")
         //         .await
         //         .unwrap()
         //         .is_valid
         // );
         assert!(
-            !validate_credentials(user_id, "x", "default/user")
+            !validate_credentials(user_id, "x", &Method::GET, "default/user")
                 .await
                 .unwrap()
                 .is_valid
         );
         assert!(validate_user(init_user, pwd).await.unwrap().is_valid);
     }
+
+    #[test]
+    fn test_request_org_id() {
+        let org_of = |path: &str| {
+            let path_columns = path.split('/').collect::<Vec<&str>>();
+            request_org_id(path, &path_columns).map(String::from)
+        };
+        assert_eq!(org_of("default/k8s_logs/_json").as_deref(), Some("default"));
+        assert_eq!(org_of("v2/default/alerts").as_deref(), Some("default"));
+        assert_eq!(org_of("default/organizations"), None);
+        assert_eq!(org_of("organizations"), None);
+        assert_eq!(org_of("/"), None);
+    }
+
+    #[test]
+    fn test_org_path_columns() {
+        assert_eq!(
+            org_path_columns(&["default", "k8s_logs", "_json"]),
+            &["k8s_logs", "_json"]
+        );
+        assert_eq!(org_path_columns(&["v2", "default", "alerts"]), &["alerts"]);
+        assert_eq!(org_path_columns(&["default", "_bulk"]), &["_bulk"]);
+    }
 }
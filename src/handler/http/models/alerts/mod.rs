@@ -94,6 +94,11 @@ pub struct Alert {
     #[serde(default)]
     #[schema(read_only)]
     pub last_edited_by: Option<String>,
+
+    /// Ids of the parent alerts. The notifications of the alert are suppressed
+    /// while one of them is firing.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema, PartialEq)]
@@ -165,6 +170,10 @@ pub struct QueryCondition {
     pub search_event_type: Option<SearchEventType>,
     #[serde(default)]
     pub multi_time_range: Option<Vec<CompareHistoricData>>,
+    #[serde(default)]
+    pub anomaly: Option<meta_alerts::AnomalyCondition>,
+    #[serde(default)]
+    pub composite: Option<meta_alerts::CompositeCondition>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema, PartialEq)]
@@ -209,6 +218,10 @@ pub enum QueryType {
     SQL,
     #[serde(rename = "promql")]
     PromQL,
+    #[serde(rename = "anomaly")]
+    Anomaly,
+    #[serde(rename = "composite")]
+    Composite,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
@@ -237,6 +250,26 @@ pub enum Operator {
     LessThanEquals,
     Contains,
     NotContains,
+    #[serde(rename = "regex")]
+    Regex,
+    #[serde(rename = "in")]
+    In,
+    #[serde(rename = "not_in")]
+    NotIn,
+    #[serde(rename = "exists")]
+    Exists,
+    #[serde(rename = "not_exists")]
+    NotExists,
+    #[serde(rename = "starts_with")]
+    StartsWith,
+    #[serde(rename = "ends_with")]
+    EndsWith,
+    #[serde(rename = "cidr_match")]
+    CidrMatch,
+    #[serde(rename = "between")]
+    Between,
+    #[serde(rename = "not_between")]
+    NotBetween,
 }
 
 impl Default for Operator {
@@ -304,6 +337,7 @@ impl From<(meta_alerts::alert::Alert, Option<Trigger>)> for Alert {
             owner: alert.owner,
             updated_at: alert.updated_at.map(|t| t.timestamp()),
             last_edited_by: alert.last_edited_by,
+            depends_on: alert.depends_on,
         }
     }
 }
@@ -356,6 +390,8 @@ impl From<meta_alerts::QueryCondition> for QueryCondition {
             multi_time_range: value
                 .multi_time_range
                 .map(|cs| cs.into_iter().map(|c| c.into()).collect()),
+            anomaly: value.anomaly,
+            composite: value.composite,
         }
     }
 }
@@ -394,6 +430,8 @@ impl From<meta_alerts::QueryType> for QueryType {
             meta_alerts::QueryType::Custom => Self::Custom,
             meta_alerts::QueryType::SQL => Self::SQL,
             meta_alerts::QueryType::PromQL => Self::PromQL,
+            meta_alerts::QueryType::Anomaly => Self::Anomaly,
+            meta_alerts::QueryType::Composite => Self::Composite,
         }
     }
 }
@@ -420,6 +458,16 @@ impl From<meta_alerts::Operator> for Operator {
             meta_alerts::Operator::LessThanEquals => Self::LessThanEquals,
             meta_alerts::Operator::Contains => Self::Contains,
             meta_alerts::Operator::NotContains => Self::NotContains,
+            meta_alerts::Operator::Regex => Self::Regex,
+            meta_alerts::Operator::In => Self::In,
+            meta_alerts::Operator::NotIn => Self::NotIn,
+            meta_alerts::Operator::Exists => Self::Exists,
+            meta_alerts::Operator::NotExists => Self::NotExists,
+            meta_alerts::Operator::StartsWith => Self::StartsWith,
+            meta_alerts::Operator::EndsWith => Self::EndsWith,
+            meta_alerts::Operator::CidrMatch => Self::CidrMatch,
+            meta_alerts::Operator::Between => Self::Between,
+            meta_alerts::Operator::NotBetween => Self::NotBetween,
         }
     }
 }
@@ -475,6 +523,7 @@ impl From<Alert> for meta_alerts::alert::Alert {
         alert.enabled = value.enabled;
         alert.tz_offset = value.tz_offset;
         alert.owner = value.owner;
+        alert.depends_on = value.depends_on;
 
         alert
     }
@@ -528,6 +577,8 @@ impl From<QueryCondition> for meta_alerts::QueryCondition {
             multi_time_range: value
                 .multi_time_range
                 .map(|cs| cs.into_iter().map(|c| c.into()).collect()),
+            anomaly: value.anomaly,
+            composite: value.composite,
         }
     }
 }
@@ -566,6 +617,8 @@ impl From<QueryType> for meta_alerts::QueryType {
             QueryType::Custom => Self::Custom,
             QueryType::SQL => Self::SQL,
             QueryType::PromQL => Self::PromQL,
+            QueryType::Anomaly => Self::Anomaly,
+            QueryType::Composite => Self::Composite,
         }
     }
 }
@@ -592,6 +645,16 @@ impl From<Operator> for meta_alerts::Operator {
             Operator::LessThanEquals => Self::LessThanEquals,
             Operator::Contains => Self::Contains,
             Operator::NotContains => Self::NotContains,
+            Operator::Regex => Self::Regex,
+            Operator::In => Self::In,
+            Operator::NotIn => Self::NotIn,
+            Operator::Exists => Self::Exists,
+            Operator::NotExists => Self::NotExists,
+            Operator::StartsWith => Self::StartsWith,
+            Operator::EndsWith => Self::EndsWith,
+            Operator::CidrMatch => Self::CidrMatch,
+            Operator::Between => Self::Between,
+            Operator::NotBetween => Self::NotBetween,
         }
     }
 }
@@ -35,6 +35,27 @@ pub struct CreateAlertRequestBody {
 #[derive(Clone, Debug, Deserialize, ToSchema)]
 pub struct UpdateAlertRequestBody(pub Alert);
 
+/// HTTP request body for `BacktestAlert` endpoint.
+#[derive(Clone, Debug, Deserialize, ToSchema)]
+pub struct BacktestAlertRequestBody {
+    /// The alert to backtest, it doesn't need to be saved.
+    pub alert: Alert,
+
+    /// Start of the time range to replay the alert over, in microseconds.
+    pub start_time: i64,
+
+    /// End of the time range to replay the alert over, in microseconds.
+    pub end_time: i64,
+
+    /// Maximum number of rows returned for each evaluation, defaults to 100.
+    #[serde(default = "default_backtest_max_rows")]
+    pub max_rows: usize,
+}
+
+fn default_backtest_max_rows() -> usize {
+    100
+}
+
 /// HTTP request body for `MoveAlerts` endpoint.
 #[derive(Clone, Debug, Deserialize, ToSchema)]
 pub struct MoveAlertsRequestBody {
@@ -13,7 +13,11 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use config::meta::{alerts::alert as meta_alerts, folder as meta_folders, triggers::Trigger};
+use config::meta::{
+    alerts::{alert as meta_alerts, backtest::BacktestResult},
+    folder as meta_folders,
+    triggers::Trigger,
+};
 use serde::{Deserialize, Serialize};
 use svix_ksuid::Ksuid;
 use utoipa::ToSchema;
@@ -47,6 +51,10 @@ pub struct ListAlertsResponseBodyItem {
     pub is_real_time: bool,
 }
 
+/// HTTP response body for `BacktestAlert` endpoint.
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct BacktestAlertResponseBody(pub BacktestResult);
+
 /// HTTP response body for `EnableAlert` endpoint.
 #[derive(Clone, Debug, Serialize, ToSchema)]
 pub struct EnableAlertResponseBody {
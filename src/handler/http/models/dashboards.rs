@@ -15,13 +15,15 @@
 
 use chrono::{DateTime, FixedOffset, Utc};
 use config::meta::{
-    dashboards::{Dashboard as MetaDashboard, v1, v2, v3, v4, v5},
+    dashboards::{Dashboard as MetaDashboard, v1, v2, v3, v4, v5, versions::SavedVersion},
     folder::Folder as MetaFolder,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use utoipa::ToSchema;
 
+use crate::service::dashboards::grafana::ConversionReport;
+
 /// HTTP request body for the `CreateDashboard` endpoint.
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateDashboardRequestBody(JsonValue);
@@ -42,6 +44,28 @@ pub struct UpdateDashboardRequestBody(JsonValue);
 #[derive(Debug, Serialize, ToSchema)]
 pub struct UpdateDashboardResponseBody(DashboardDetails);
 
+/// HTTP request body for the `ImportGrafanaDashboard` endpoint, the Grafana dashboard JSON.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportGrafanaDashboardRequestBody(pub JsonValue);
+
+/// HTTP response body for the `ImportGrafanaDashboard` endpoint.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportGrafanaDashboardResponseBody {
+    /// The created dashboard.
+    pub dashboard: DashboardDetails,
+    /// What could not be converted from the Grafana dashboard.
+    pub report: ConversionReport,
+}
+
+/// HTTP response body for the `GetDashboardVersion` endpoint.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GetDashboardVersionResponseBody {
+    /// The saved version.
+    pub version: SavedVersion,
+    /// The dashboard as it was saved in this version.
+    pub dashboard: DashboardDetails,
+}
+
 /// HTTP URL query component that contains parameters for listing dashboards.
 #[derive(Debug, Deserialize, utoipa::IntoParams)]
 #[into_params(style = Form, parameter_in = Query)]
@@ -173,6 +197,24 @@ impl From<MetaDashboard> for UpdateDashboardResponseBody {
     }
 }
 
+impl From<(MetaDashboard, ConversionReport)> for ImportGrafanaDashboardResponseBody {
+    fn from((dashboard, report): (MetaDashboard, ConversionReport)) -> Self {
+        Self {
+            dashboard: dashboard.into(),
+            report,
+        }
+    }
+}
+
+impl From<(SavedVersion, MetaDashboard)> for GetDashboardVersionResponseBody {
+    fn from((version, dashboard): (SavedVersion, MetaDashboard)) -> Self {
+        Self {
+            version,
+            dashboard: dashboard.into(),
+        }
+    }
+}
+
 impl ListDashboardsQuery {
     pub fn into(self, org_id: &str) -> config::meta::dashboards::ListDashboardsParams {
         let mut query = match &self {
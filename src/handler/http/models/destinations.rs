@@ -174,6 +174,7 @@ impl From<meta_dest::Template> for Template {
             is_default: value.is_default.then_some(true),
             template_type,
             title,
+            engine: value.engine,
         }
     }
 }
@@ -194,6 +195,7 @@ impl Template {
             is_default: self.is_default.unwrap_or_default(),
             template_type,
             body: self.body,
+            engine: self.engine,
         }
     }
 }
@@ -285,4 +287,25 @@ pub struct Template {
     pub template_type: DestinationType,
     #[serde(default)]
     pub title: String,
+    /// Engine the body and title are rendered with, `legacy` for `{var}` placeholders or
+    /// `jinja`
+    #[serde(default)]
+    pub engine: meta_dest::TemplateEngine,
+}
+
+/// Template to render without saving it.
+#[derive(Clone, Debug, Default, Deserialize, ToSchema)]
+pub struct TemplateTestRequest {
+    pub template: Template,
+    /// Variables to render the template with, sample alert data when not given.
+    #[serde(default)]
+    pub context: Option<meta_dest::TemplateContext>,
+}
+
+#[derive(Clone, Debug, Serialize, ToSchema)]
+pub struct TemplateTestResponse {
+    pub body: String,
+    /// Rendered title of email templates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
 }
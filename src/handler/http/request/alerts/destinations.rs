@@ -134,7 +134,7 @@ pub async fn update_destination(
 async fn get_destination(path: web::Path<(String, String)>) -> Result<HttpResponse, Error> {
     let (org_id, name) = path.into_inner();
     match destinations::get(&org_id, &name).await {
-        Ok(data) => Ok(MetaHttpResponse::json(Destination::from(data))),
+        Ok(data) => Ok(MetaHttpResponse::json(Destination::from(data.redact()))),
         Err(e) => Ok(MetaHttpResponse::not_found(e)),
     }
 }
@@ -194,7 +194,9 @@ async fn list_destinations(
 
     match destinations::list(&org_id, module, _permitted).await {
         Ok(data) => Ok(MetaHttpResponse::json(
-            data.into_iter().map(Destination::from).collect::<Vec<_>>(),
+            data.into_iter()
+                .map(|dest| Destination::from(dest.redact()))
+                .collect::<Vec<_>>(),
         )),
         Err(e) => Ok(MetaHttpResponse::bad_request(e)),
     }
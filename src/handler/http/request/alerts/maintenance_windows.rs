@@ -0,0 +1,207 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::Error;
+
+use actix_web::{HttpResponse, delete, get, http::StatusCode, post, put, web};
+use config::meta::alerts::maintenance_window::MaintenanceWindow;
+
+use crate::{
+    common::{meta::http::HttpResponse as MetaHttpResponse, utils::auth::UserEmail},
+    service::alerts::maintenance_windows::{self, MaintenanceWindowError},
+};
+
+impl From<MaintenanceWindowError> for HttpResponse {
+    fn from(value: MaintenanceWindowError) -> Self {
+        match &value {
+            MaintenanceWindowError::InfraError(err) => MetaHttpResponse::internal_error(err),
+            MaintenanceWindowError::Invalid(_) => MetaHttpResponse::bad_request(value),
+            MaintenanceWindowError::AlreadyExists => MetaHttpResponse::conflict(value),
+            MaintenanceWindowError::NotFound => MetaHttpResponse::not_found(value),
+        }
+    }
+}
+
+/// CreateMaintenanceWindow
+///
+/// #{"ratelimit_module":"Alerts", "ratelimit_module_operation":"create"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "CreateMaintenanceWindow",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    request_body(
+        content = MaintenanceWindow,
+        description = "Maintenance window data",
+        content_type = "application/json",
+        example = json!({
+            "name": "sunday-maintenance",
+            "start_time": 1740960000000000_i64,
+            "duration_minutes": 120,
+            "cron": "0 0 2 * * Sun",
+            "timezone": "Europe/Berlin",
+            "tz_offset": 60,
+            "targets": {
+                "folders": ["infra"],
+                "alert_names": ["db-*"],
+                "labels": {"env": "staging"},
+            },
+        }),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 400, description = "Error",   content_type = "application/json", body = HttpResponse),
+        (status = 409, description = "Conflict", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/alerts/maintenance_windows")]
+pub async fn create_maintenance_window(
+    path: web::Path<String>,
+    window: web::Json<MaintenanceWindow>,
+    user_email: UserEmail,
+) -> Result<HttpResponse, Error> {
+    let org_id = path.into_inner();
+    match maintenance_windows::save(&org_id, None, window.into_inner(), &user_email.user_id).await {
+        Ok(v) => Ok(MetaHttpResponse::json(
+            MetaHttpResponse::message(StatusCode::OK, "Maintenance window saved")
+                .with_id(v.id)
+                .with_name(v.name),
+        )),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// UpdateMaintenanceWindow
+///
+/// #{"ratelimit_module":"Alerts", "ratelimit_module_operation":"update"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "UpdateMaintenanceWindow",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("window_id" = String, Path, description = "Maintenance window id"),
+    ),
+    request_body(content = MaintenanceWindow, description = "Maintenance window data", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = HttpResponse),
+        (status = 400, description = "Error",    content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[put("/{org_id}/alerts/maintenance_windows/{window_id}")]
+pub async fn update_maintenance_window(
+    path: web::Path<(String, String)>,
+    window: web::Json<MaintenanceWindow>,
+    user_email: UserEmail,
+) -> Result<HttpResponse, Error> {
+    let (org_id, id) = path.into_inner();
+    match maintenance_windows::save(&org_id, Some(&id), window.into_inner(), &user_email.user_id)
+        .await
+    {
+        Ok(_) => Ok(MetaHttpResponse::ok("Maintenance window updated")),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// GetMaintenanceWindow
+///
+/// #{"ratelimit_module":"Alerts", "ratelimit_module_operation":"get"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "GetMaintenanceWindow",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("window_id" = String, Path, description = "Maintenance window id"),
+    ),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = MaintenanceWindow),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/alerts/maintenance_windows/{window_id}")]
+async fn get_maintenance_window(path: web::Path<(String, String)>) -> Result<HttpResponse, Error> {
+    let (org_id, id) = path.into_inner();
+    match maintenance_windows::get(&org_id, &id) {
+        Ok(data) => Ok(MetaHttpResponse::json(data)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// ListMaintenanceWindows
+///
+/// #{"ratelimit_module":"Alerts", "ratelimit_module_operation":"list"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "ListMaintenanceWindows",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = Vec<MaintenanceWindow>),
+    )
+)]
+#[get("/{org_id}/alerts/maintenance_windows")]
+async fn list_maintenance_windows(path: web::Path<String>) -> Result<HttpResponse, Error> {
+    let org_id = path.into_inner();
+    Ok(MetaHttpResponse::json(maintenance_windows::list(&org_id)))
+}
+
+/// DeleteMaintenanceWindow
+///
+/// #{"ratelimit_module":"Alerts", "ratelimit_module_operation":"delete"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "DeleteMaintenanceWindow",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("window_id" = String, Path, description = "Maintenance window id"),
+    ),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+        (status = 500, description = "Failure",  content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[delete("/{org_id}/alerts/maintenance_windows/{window_id}")]
+async fn delete_maintenance_window(
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, Error> {
+    let (org_id, id) = path.into_inner();
+    match maintenance_windows::delete(&org_id, &id).await {
+        Ok(_) => Ok(MetaHttpResponse::ok("Maintenance window deleted")),
+        Err(e) => Ok(e.into()),
+    }
+}
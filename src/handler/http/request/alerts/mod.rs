@@ -27,15 +27,21 @@ use crate::{
     handler::http::{
         models::alerts::{
             requests::{
-                CreateAlertRequestBody, EnableAlertQuery, ListAlertsQuery, MoveAlertsRequestBody,
-                UpdateAlertRequestBody,
+                BacktestAlertRequestBody, CreateAlertRequestBody, EnableAlertQuery,
+                ListAlertsQuery, MoveAlertsRequestBody, UpdateAlertRequestBody,
+            },
+            responses::{
+                BacktestAlertResponseBody, EnableAlertResponseBody, GetAlertResponseBody,
+                ListAlertsResponseBody,
             },
-            responses::{EnableAlertResponseBody, GetAlertResponseBody, ListAlertsResponseBody},
         },
         request::dashboards::get_folder,
     },
     service::{
-        alerts::alert::{self, AlertError},
+        alerts::{
+            alert::{self, AlertError},
+            backtest,
+        },
         db::scheduler,
     },
 };
@@ -43,6 +49,7 @@ use crate::{
 #[allow(deprecated)]
 pub mod deprecated;
 pub mod destinations;
+pub mod maintenance_windows;
 pub mod templates;
 
 impl From<AlertError> for HttpResponse {
@@ -66,6 +73,14 @@ impl From<AlertError> for HttpResponse {
             AlertError::SqlMissingQuery => MetaHttpResponse::bad_request(value),
             AlertError::SqlContainsSelectStar => MetaHttpResponse::bad_request(value),
             AlertError::PromqlMissingQuery => MetaHttpResponse::bad_request(value),
+            AlertError::AnomalyMissingQuery => MetaHttpResponse::bad_request(value),
+            AlertError::AnomalyInvalidCondition(_) => MetaHttpResponse::bad_request(value),
+            AlertError::InvalidCondition(_) => MetaHttpResponse::bad_request(value),
+            AlertError::CompositeMissingCondition => MetaHttpResponse::bad_request(value),
+            AlertError::DependencyNotFound(_) => MetaHttpResponse::bad_request(value),
+            AlertError::DependencyRealtime(_) => MetaHttpResponse::bad_request(value),
+            AlertError::DependencyCycle(_) => MetaHttpResponse::bad_request(value),
+            AlertError::AlertReferenced(_) => MetaHttpResponse::conflict(value),
             AlertError::SendNotificationError { .. } => MetaHttpResponse::internal_error(value),
             AlertError::GetDestinationWithTemplateError(err) => {
                 MetaHttpResponse::internal_error(err)
@@ -78,6 +93,10 @@ impl From<AlertError> for HttpResponse {
             AlertError::PermissionDenied => MetaHttpResponse::forbidden("Unauthorized access"),
             AlertError::UserNotFound => MetaHttpResponse::forbidden("Unauthorized access"),
             AlertError::AlertIdMissing => MetaHttpResponse::bad_request(value),
+            AlertError::BacktestRealtime => MetaHttpResponse::bad_request(value),
+            AlertError::BacktestComposite => MetaHttpResponse::bad_request(value),
+            AlertError::BacktestInvalidRange(_) => MetaHttpResponse::bad_request(value),
+            AlertError::BacktestFailed(_) => MetaHttpResponse::internal_error(value),
         }
     }
 }
@@ -417,3 +436,54 @@ async fn move_alerts(
         Err(e) => e.into(),
     }
 }
+
+/// BacktestAlert
+///
+/// Replays the evaluations the scheduler would have run for the alert over a past time range and
+/// returns them, without saving the alert or sending any notification.
+///
+/// #{"ratelimit_module":"Alerts", "ratelimit_module_operation":"get"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Alerts",
+    operation_id = "BacktestAlert",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    request_body(content = BacktestAlertRequestBody, description = "Alert and the time range to replay it over", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = BacktestAlertResponseBody),
+        (status = 400, description = "Error",   content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/v2/{org_id}/alerts/backtest")]
+async fn backtest_alert(
+    path: web::Path<String>,
+    req_body: web::Json<BacktestAlertRequestBody>,
+    user_email: UserEmail,
+) -> HttpResponse {
+    let org_id = path.into_inner();
+    let req_body = req_body.into_inner();
+
+    let mut alert: MetaAlert = req_body.alert.into();
+    // the alert is evaluated as the user, so their row filters apply to the replayed searches
+    alert.owner = Some(user_email.user_id.clone());
+    alert.last_edited_by = Some(user_email.user_id);
+
+    match backtest::backtest(
+        &org_id,
+        alert,
+        req_body.start_time,
+        req_body.end_time,
+        req_body.max_rows,
+    )
+    .await
+    {
+        Ok(result) => MetaHttpResponse::json(BacktestAlertResponseBody(result)),
+        Err(e) => e.into(),
+    }
+}
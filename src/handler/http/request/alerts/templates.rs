@@ -19,7 +19,7 @@ use actix_web::{HttpRequest, HttpResponse, delete, get, http::StatusCode, post,
 
 use crate::{
     common::meta::http::HttpResponse as MetaHttpResponse,
-    handler::http::models::destinations::Template,
+    handler::http::models::destinations::{Template, TemplateTestRequest, TemplateTestResponse},
     service::{alerts::templates, db::alerts::templates::TemplateError},
 };
 
@@ -219,3 +219,50 @@ async fn delete_template(path: web::Path<(String, String)>) -> Result<HttpRespon
         Err(e) => Ok(e.into()),
     }
 }
+
+/// TestTemplate
+///
+/// Renders a template without saving it, with the given variables or with sample alert data.
+///
+/// #{"ratelimit_module":"Templates", "ratelimit_module_operation":"get"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Templates",
+    operation_id = "TestTemplate",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+      ),
+    request_body(
+        content = TemplateTestRequest,
+        description = "Template and variables to render it with",
+        content_type = "application/json",
+        example = json!({
+            "template": {
+                "name": "slack",
+                "type": "http",
+                "engine": "jinja",
+                "body": "{\"text\": {{ (alert.name ~ ': ' ~ (rows | map(attribute='host') | join(', '))) | tojson }}}",
+            },
+        }),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = TemplateTestResponse),
+        (status = 400, description = "Error",   content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/alerts/templates/test")]
+pub async fn test_template(
+    path: web::Path<String>,
+    req: web::Json<TemplateTestRequest>,
+) -> Result<HttpResponse, Error> {
+    let org_id = path.into_inner();
+    let req = req.into_inner();
+    let tmpl = req.template.into(&org_id);
+    match templates::test(&org_id, &tmpl, req.context) {
+        Ok((body, title)) => Ok(MetaHttpResponse::json(TemplateTestResponse { body, title })),
+        Err(e) => Ok(e.into()),
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use actix_web::{HttpResponse, Responder, delete, get, post, web};
+use config::meta::api_key::{ApiKeyList, ApiKeyRequest};
+
+use crate::{
+    common::{meta::http::HttpResponse as MetaHttpResponse, utils::auth::UserEmail},
+    service::api_keys::{self, ApiKeyError},
+};
+
+impl From<ApiKeyError> for HttpResponse {
+    fn from(value: ApiKeyError) -> Self {
+        match value {
+            ApiKeyError::InfraError(err) => MetaHttpResponse::internal_error(err),
+            ApiKeyError::Internal(err) => MetaHttpResponse::internal_error(err),
+            ApiKeyError::MissingName
+            | ApiKeyError::ExpiresInPast
+            | ApiKeyError::UserNotFound(_) => MetaHttpResponse::bad_request(value),
+            ApiKeyError::NameAlreadyExists => MetaHttpResponse::conflict(value),
+            ApiKeyError::Forbidden => MetaHttpResponse::forbidden(value),
+            ApiKeyError::NotFound => MetaHttpResponse::not_found(value),
+        }
+    }
+}
+
+/// ListApiKeys
+///
+/// #{"ratelimit_module":"API Keys", "ratelimit_module_operation":"list"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "ApiKeys",
+    operation_id = "ListApiKeys",
+    security(
+        ("Authorization" = [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = ApiKeyList),
+        (status = 500, description = "Internal Server Error", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/api_keys")]
+pub async fn list(org_id: web::Path<String>, user_email: UserEmail) -> impl Responder {
+    let org_id = org_id.into_inner();
+    match api_keys::list(&org_id, &user_email.user_id).await {
+        Ok(list) => HttpResponse::Ok().json(ApiKeyList { list }),
+        Err(err) => err.into(),
+    }
+}
+
+/// CreateApiKey
+///
+/// #{"ratelimit_module":"API Keys", "ratelimit_module_operation":"create"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "ApiKeys",
+    operation_id = "CreateApiKey",
+    security(
+        ("Authorization" = [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    request_body(
+        content = ApiKeyRequest,
+        description = "API key details",
+        content_type = "application/json",
+        example = json!({
+            "name": "k8s-shipper",
+            "user_email": "shipper@example.com",
+            "scopes": ["ingest"],
+            "streams": ["k8s_*"],
+            "expires_at": 1767225600000000_i64,
+        }),
+    ),
+    responses(
+        (status = 200, description = "Success, the key is only returned once", content_type = "application/json", body = ApiKeyCreateResponse),
+        (status = 400, description = "Bad Request", content_type = "application/json", body = HttpResponse),
+        (status = 403, description = "Forbidden", content_type = "application/json", body = HttpResponse),
+        (status = 409, description = "Conflict", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/api_keys")]
+pub async fn create(
+    org_id: web::Path<String>,
+    body: web::Json<ApiKeyRequest>,
+    user_email: UserEmail,
+) -> impl Responder {
+    let org_id = org_id.into_inner();
+    match api_keys::create(&org_id, &user_email.user_id, body.into_inner()).await {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(err) => err.into(),
+    }
+}
+
+/// DeleteApiKey
+///
+/// #{"ratelimit_module":"API Keys", "ratelimit_module_operation":"delete"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "ApiKeys",
+    operation_id = "DeleteApiKey",
+    security(
+        ("Authorization" = [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("key_id" = String, Path, description = "API key id"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 403, description = "Forbidden", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[delete("/{org_id}/api_keys/{key_id}")]
+pub async fn delete(path: web::Path<(String, String)>, user_email: UserEmail) -> impl Responder {
+    let (org_id, key_id) = path.into_inner();
+    match api_keys::delete(&org_id, &key_id, &user_email.user_id).await {
+        Ok(()) => MetaHttpResponse::ok("API key deleted"),
+        Err(err) => err.into(),
+    }
+}
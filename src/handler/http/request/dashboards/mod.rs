@@ -14,16 +14,20 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use actix_web::{HttpRequest, HttpResponse, Responder, delete, get, http, patch, post, put, web};
+use chrono::SecondsFormat;
+use config::meta::dashboards::versions::SavedVersion;
 use hashbrown::HashMap;
 
 use crate::{
     common::{meta::http::HttpResponse as MetaHttpResponse, utils::auth::UserEmail},
     handler::http::models::dashboards::{
         CreateDashboardRequestBody, CreateDashboardResponseBody, GetDashboardResponseBody,
-        ListDashboardsQuery, ListDashboardsResponseBody, MoveDashboardRequestBody,
-        MoveDashboardsRequestBody, UpdateDashboardRequestBody, UpdateDashboardResponseBody,
+        GetDashboardVersionResponseBody, ImportGrafanaDashboardRequestBody,
+        ImportGrafanaDashboardResponseBody, ListDashboardsQuery, ListDashboardsResponseBody,
+        MoveDashboardRequestBody, MoveDashboardsRequestBody, UpdateDashboardRequestBody,
+        UpdateDashboardResponseBody,
     },
-    service::dashboards::{self, DashboardError},
+    service::dashboards::{self, DashboardError, grafana},
 };
 
 pub mod reports;
@@ -34,12 +38,15 @@ impl From<DashboardError> for HttpResponse {
         match value {
             DashboardError::InfraError(err) => MetaHttpResponse::internal_error(err),
             DashboardError::DashboardNotFound => MetaHttpResponse::not_found("Dashboard not found"),
-            DashboardError::UpdateMissingHash => MetaHttpResponse::internal_error(
-                "Request to update existing dashboard with missing or invalid hash value. BUG",
-            ),
-            DashboardError::UpdateConflictingHash => MetaHttpResponse::conflict(
-                "Conflict: Failed to save due to concurrent changes. Please refresh the page after backing up your work to avoid losing changes.",
+            DashboardError::UpdateMissingHash => MetaHttpResponse::bad_request(
+                "Please provide the hash of the dashboard being updated, as returned when the dashboard was loaded",
             ),
+            DashboardError::UpdateConflictingHash(latest) => {
+                MetaHttpResponse::conflict(conflict_message(latest))
+            }
+            DashboardError::VersionNotFound(version) => {
+                MetaHttpResponse::not_found(format!("Dashboard version {version} not found"))
+            }
             DashboardError::PutMissingTitle => {
                 MetaHttpResponse::internal_error("Dashboard should have title")
             }
@@ -111,7 +118,9 @@ pub async fn create_dashboard(
 
     set_dashboard_owner_if_empty(&mut dashboard, &user_email.user_id);
 
-    let saved = match dashboards::create_dashboard(&org_id, &folder, dashboard).await {
+    let saved = match dashboards::create_dashboard(&org_id, &folder, dashboard, &user_email.user_id)
+        .await
+    {
         Ok(saved) => saved,
         Err(err) => return err.into(),
     };
@@ -119,6 +128,57 @@ pub async fn create_dashboard(
     MetaHttpResponse::json(resp_body)
 }
 
+/// ImportGrafanaDashboard
+///
+/// #{"ratelimit_module":"Dashboards", "ratelimit_module_operation":"create"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Dashboards",
+    operation_id = "ImportGrafanaDashboard",
+    security(
+        ("Authorization" = [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("folder" = Option<String>, Query, description = "Folder of the dashboard, default folder when not set"),
+    ),
+    request_body(
+        content = ImportGrafanaDashboardRequestBody,
+        description = "Grafana dashboard JSON model",
+    ),
+    responses(
+        (status = StatusCode::CREATED, description = "Dashboard imported", body = ImportGrafanaDashboardResponseBody),
+        (status = StatusCode::BAD_REQUEST, description = "Invalid Grafana dashboard", body = HttpResponse),
+        (status = StatusCode::INTERNAL_SERVER_ERROR, description = "Internal Server Error", body = HttpResponse),
+    ),
+)]
+#[post("/{org_id}/dashboards/import")]
+pub async fn import_grafana_dashboard(
+    path: web::Path<String>,
+    req_body: web::Json<ImportGrafanaDashboardRequestBody>,
+    req: HttpRequest,
+    user_email: UserEmail,
+) -> impl Responder {
+    let org_id = path.into_inner();
+    let folder = get_folder(req);
+    let (dashboard, report) = match grafana::convert(&req_body.into_inner().0) {
+        Ok(converted) => converted,
+        Err(e) => return MetaHttpResponse::bad_request(e),
+    };
+    let mut dashboard: config::meta::dashboards::Dashboard = dashboard.into();
+
+    set_dashboard_owner_if_empty(&mut dashboard, &user_email.user_id);
+
+    let saved = match dashboards::create_dashboard(&org_id, &folder, dashboard, &user_email.user_id)
+        .await
+    {
+        Ok(saved) => saved,
+        Err(err) => return err.into(),
+    };
+    let resp_body: ImportGrafanaDashboardResponseBody = (saved, report).into();
+    MetaHttpResponse::json(resp_body)
+}
+
 /// UpdateDashboard
 ///
 /// #{"ratelimit_module":"Dashboards", "ratelimit_module_operation":"update"}#
@@ -132,6 +192,7 @@ pub async fn create_dashboard(
     params(
         ("org_id" = String, Path, description = "Organization name"),
         ("dashboard_id" = String, Path, description = "Dashboard ID"),
+        ("hash" = String, Query, description = "Hash of the dashboard when it was loaded"),
     ),
     request_body(
         content = UpdateDashboardRequestBody,
@@ -139,7 +200,9 @@ pub async fn create_dashboard(
     ),
     responses(
         (status = StatusCode::OK, description = "Dashboard updated", body = UpdateDashboardResponseBody),
+        (status = StatusCode::BAD_REQUEST, description = "Missing hash of the dashboard", body = HttpResponse),
         (status = StatusCode::NOT_FOUND, description = "Dashboard not found", body = HttpResponse),
+        (status = StatusCode::CONFLICT, description = "Dashboard changed since it was loaded", body = HttpResponse),
         (status = StatusCode::INTERNAL_SERVER_ERROR, description = "Failed to update the dashboard", body = HttpResponse),
     ),
 )]
@@ -163,8 +226,15 @@ async fn update_dashboard(
 
     set_dashboard_owner_if_empty(&mut dashboard, &user_email.user_id);
 
-    let saved = match dashboards::update_dashboard(&org_id, &dashboard_id, &folder, dashboard, hash)
-        .await
+    let saved = match dashboards::update_dashboard(
+        &org_id,
+        &dashboard_id,
+        &folder,
+        dashboard,
+        hash,
+        &user_email.user_id,
+    )
+    .await
     {
         Ok(saved) => saved,
         Err(err) => return err.into(),
@@ -372,6 +442,158 @@ async fn move_dashboards(
     }
 }
 
+/// ListDashboardVersions
+///
+/// #{"ratelimit_module":"Dashboards", "ratelimit_module_operation":"get"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Dashboards",
+    operation_id = "ListDashboardVersions",
+    security(
+        ("Authorization" = [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("dashboard_id" = String, Path, description = "Dashboard ID"),
+    ),
+    responses(
+        (status = StatusCode::OK, body = SavedVersionList),
+        (status = StatusCode::NOT_FOUND, description = "Dashboard not found", body = HttpResponse),
+    ),
+)]
+#[get("/{org_id}/dashboards/{dashboard_id}/versions")]
+async fn list_dashboard_versions(path: web::Path<(String, String)>) -> impl Responder {
+    let (org_id, dashboard_id) = path.into_inner();
+    match dashboards::list_dashboard_versions(&org_id, &dashboard_id).await {
+        Ok(versions) => MetaHttpResponse::json(versions),
+        Err(err) => err.into(),
+    }
+}
+
+/// DiffDashboardVersions
+///
+/// #{"ratelimit_module":"Dashboards", "ratelimit_module_operation":"get"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Dashboards",
+    operation_id = "DiffDashboardVersions",
+    security(
+        ("Authorization" = [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("dashboard_id" = String, Path, description = "Dashboard ID"),
+        ("from" = i32, Query, description = "Version to compare from"),
+        ("to" = i32, Query, description = "Version to compare to"),
+    ),
+    responses(
+        (status = StatusCode::OK, body = SavedVersionDiff),
+        (status = StatusCode::BAD_REQUEST, description = "Missing versions to compare", body = HttpResponse),
+        (status = StatusCode::NOT_FOUND, description = "Dashboard or version not found", body = HttpResponse),
+    ),
+)]
+#[get("/{org_id}/dashboards/{dashboard_id}/versions/diff")]
+async fn diff_dashboard_versions(
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+) -> impl Responder {
+    let (org_id, dashboard_id) = path.into_inner();
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string()).unwrap();
+    let (Some(from), Some(to)) = (
+        query.get("from").and_then(|v| v.parse::<i32>().ok()),
+        query.get("to").and_then(|v| v.parse::<i32>().ok()),
+    ) else {
+        return MetaHttpResponse::bad_request(
+            "Please provide the versions to compare as `from` and `to`",
+        );
+    };
+    match dashboards::diff_dashboard_versions(&org_id, &dashboard_id, from, to).await {
+        Ok(diff) => MetaHttpResponse::json(diff),
+        Err(err) => err.into(),
+    }
+}
+
+/// GetDashboardVersion
+///
+/// #{"ratelimit_module":"Dashboards", "ratelimit_module_operation":"get"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Dashboards",
+    operation_id = "GetDashboardVersion",
+    security(
+        ("Authorization" = [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("dashboard_id" = String, Path, description = "Dashboard ID"),
+        ("version" = i32, Path, description = "Saved version of the dashboard"),
+    ),
+    responses(
+        (status = StatusCode::OK, body = GetDashboardVersionResponseBody),
+        (status = StatusCode::NOT_FOUND, description = "Dashboard or version not found", body = HttpResponse),
+    ),
+)]
+#[get("/{org_id}/dashboards/{dashboard_id}/versions/{version}")]
+async fn get_dashboard_version(path: web::Path<(String, String, i32)>) -> impl Responder {
+    let (org_id, dashboard_id, version) = path.into_inner();
+    match dashboards::get_dashboard_version(&org_id, &dashboard_id, version).await {
+        Ok(saved) => {
+            let resp_body: GetDashboardVersionResponseBody = saved.into();
+            MetaHttpResponse::json(resp_body)
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// RestoreDashboardVersion
+///
+/// #{"ratelimit_module":"Dashboards", "ratelimit_module_operation":"update"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Dashboards",
+    operation_id = "RestoreDashboardVersion",
+    security(
+        ("Authorization" = [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("dashboard_id" = String, Path, description = "Dashboard ID"),
+        ("version" = i32, Path, description = "Saved version of the dashboard to restore"),
+        ("hash" = String, Query, description = "Hash of the dashboard when it was loaded"),
+    ),
+    responses(
+        (status = StatusCode::OK, description = "Dashboard restored", body = UpdateDashboardResponseBody),
+        (status = StatusCode::BAD_REQUEST, description = "Missing hash of the dashboard", body = HttpResponse),
+        (status = StatusCode::NOT_FOUND, description = "Dashboard or version not found", body = HttpResponse),
+        (status = StatusCode::CONFLICT, description = "Dashboard changed since it was loaded", body = HttpResponse),
+    ),
+)]
+#[put("/{org_id}/dashboards/{dashboard_id}/versions/{version}/restore")]
+async fn restore_dashboard_version(
+    path: web::Path<(String, String, i32)>,
+    req: HttpRequest,
+    user_email: UserEmail,
+) -> impl Responder {
+    let (org_id, dashboard_id, version) = path.into_inner();
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string()).unwrap();
+    let hash = query.get("hash").map(|h| h.as_str());
+    match dashboards::restore_dashboard_version(
+        &org_id,
+        &dashboard_id,
+        version,
+        hash,
+        &user_email.user_id,
+    )
+    .await
+    {
+        Ok(saved) => {
+            let resp_body: UpdateDashboardResponseBody = saved.into();
+            MetaHttpResponse::json(resp_body)
+        }
+        Err(err) => err.into(),
+    }
+}
+
 pub fn get_folder(req: HttpRequest) -> String {
     let query = web::Query::<HashMap<String, String>>::from_query(req.query_string()).unwrap();
     crate::common::utils::http::get_folder(&query)
@@ -385,6 +607,22 @@ fn get_user_id(req: HttpRequest) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Returns the message of a conflicting update, naming who last saved the
+/// dashboard and when.
+fn conflict_message(latest: Option<SavedVersion>) -> String {
+    let changed_by = latest
+        .map(|version| {
+            let at = chrono::DateTime::from_timestamp_micros(version.created_at)
+                .map(|t| format!(" at {}", t.to_rfc3339_opts(SecondsFormat::Secs, true)))
+                .unwrap_or_default();
+            format!(" by {}{at}", version.author)
+        })
+        .unwrap_or_default();
+    format!(
+        "Conflict: the dashboard was changed{changed_by} after you loaded it. Please refresh the page after backing up your work to avoid losing changes."
+    )
+}
+
 fn set_dashboard_owner_if_empty(
     dashboard: &mut config::meta::dashboards::Dashboard,
     user_email: &str,
@@ -51,6 +51,8 @@ impl From<ReportError> for HttpResponse {
             ReportError::DbError(e) => MetaHttpResponse::internal_error(e),
             ReportError::SendReportError(e) => MetaHttpResponse::internal_error(e),
             ReportError::CreateDefaultFolderError => MetaHttpResponse::internal_error(value),
+            ReportError::ReportServerEmailOnly => MetaHttpResponse::bad_request(value),
+            ReportError::InvalidDestination(e) => MetaHttpResponse::bad_request(e),
         }
     }
 }
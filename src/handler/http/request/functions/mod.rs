@@ -31,7 +31,7 @@ use config::meta::function::{TestVRLRequest, Transform};
     params(
         ("org_id" = String, Path, description = "Organization name"),
     ),
-    request_body(content = Transform, description = "Function data", content_type = "application/json"),
+    request_body(content = Transform, description = "Function data, WASM functions set transType to 2 and function to the base64 encoded module", content_type = "application/json"),
     responses(
         (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
         (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
@@ -139,7 +139,7 @@ async fn delete_function(path: web::Path<(String, String)>) -> Result<HttpRespon
         ("org_id" = String, Path, description = "Organization name"),
         ("name" = String, Path, description = "Function name"),
     ),
-    request_body(content = Transform, description = "Function data", content_type = "application/json"),
+    request_body(content = Transform, description = "Function data, WASM functions set transType to 2 and function to the base64 encoded module", content_type = "application/json"),
     responses(
         (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
         (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
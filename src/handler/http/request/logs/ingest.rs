@@ -29,7 +29,10 @@ use crate::{
         },
     },
     handler::http::request::{CONTENT_TYPE_JSON, CONTENT_TYPE_PROTO},
-    service::logs::{self, otlp::handle_request},
+    service::{
+        logs::{self, otlp::handle_request},
+        quotas::quota_exceeded_response,
+    },
 };
 
 /// _bulk ES compatible ingestion API
@@ -70,7 +73,9 @@ pub async fn bulk(
         Ok(v) => MetaHttpResponse::json(v),
         Err(e) => {
             log::error!("Error processing request {org_id}/_bulk: {e}");
-            if matches!(e, infra::errors::Error::ResourceError(_)) {
+            if let infra::errors::Error::QuotaExceeded(exceeded) = &e {
+                quota_exceeded_response(exceeded)
+            } else if matches!(e, infra::errors::Error::ResourceError(_)) {
                 HttpResponse::ServiceUnavailable().json(MetaHttpResponse::error(
                     http::StatusCode::SERVICE_UNAVAILABLE,
                     e,
@@ -143,7 +148,9 @@ pub async fn multi(
         },
         Err(e) => {
             log::error!("Error processing request {org_id}/{stream_name}/_multi: {e}");
-            if matches!(e, infra::errors::Error::ResourceError(_)) {
+            if let infra::errors::Error::QuotaExceeded(exceeded) = &e {
+                quota_exceeded_response(exceeded)
+            } else if matches!(e, infra::errors::Error::ResourceError(_)) {
                 HttpResponse::ServiceUnavailable().json(MetaHttpResponse::error(
                     http::StatusCode::SERVICE_UNAVAILABLE,
                     e,
@@ -216,7 +223,9 @@ pub async fn json(
         },
         Err(e) => {
             log::error!("Error processing request {org_id}/{stream_name}/_json: {e}");
-            if matches!(e, infra::errors::Error::ResourceError(_)) {
+            if let infra::errors::Error::QuotaExceeded(exceeded) = &e {
+                quota_exceeded_response(exceeded)
+            } else if matches!(e, infra::errors::Error::ResourceError(_)) {
                 HttpResponse::ServiceUnavailable().json(MetaHttpResponse::error(
                     http::StatusCode::SERVICE_UNAVAILABLE,
                     e,
@@ -287,7 +296,9 @@ pub async fn handle_kinesis_request(
             }),
             Err(e) => {
                 log::error!("Error processing kinesis request: {e}");
-                if matches!(e, infra::errors::Error::ResourceError(_)) {
+                if let infra::errors::Error::QuotaExceeded(exceeded) = &e {
+                    quota_exceeded_response(exceeded)
+                } else if matches!(e, infra::errors::Error::ResourceError(_)) {
                     HttpResponse::ServiceUnavailable().json(KinesisFHIngestionResponse {
                         request_id,
                         timestamp: request_time,
@@ -331,7 +342,9 @@ pub async fn handle_gcp_request(
                     "Error processing request {org_id}/{stream_name}/_gcp: {:?}",
                     e
                 );
-                if matches!(e, infra::errors::Error::ResourceError(_)) {
+                if let infra::errors::Error::QuotaExceeded(exceeded) = &e {
+                    quota_exceeded_response(exceeded)
+                } else if matches!(e, infra::errors::Error::ResourceError(_)) {
                     HttpResponse::ServiceUnavailable().json(MetaHttpResponse::error(
                         http::StatusCode::SERVICE_UNAVAILABLE,
                         e,
@@ -420,7 +433,9 @@ pub async fn otlp_logs_write(
                 in_stream_name,
                 e
             );
-            if matches!(e, infra::errors::Error::ResourceError(_)) {
+            if let infra::errors::Error::QuotaExceeded(exceeded) = &e {
+                quota_exceeded_response(exceeded)
+            } else if matches!(e, infra::errors::Error::ResourceError(_)) {
                 HttpResponse::ServiceUnavailable().json(MetaHttpResponse::error(
                     http::StatusCode::SERVICE_UNAVAILABLE,
                     e,
@@ -480,7 +495,9 @@ pub async fn hec(
         Err(e) => {
             log::error!("Error processing request {org_id}/_hec: {e}");
             let res = HecResponse::from(HecStatus::Custom(e.to_string(), 400));
-            if matches!(e, infra::errors::Error::ResourceError(_)) {
+            if let infra::errors::Error::QuotaExceeded(exceeded) = &e {
+                quota_exceeded_response(exceeded)
+            } else if matches!(e, infra::errors::Error::ResourceError(_)) {
                 HttpResponse::ServiceUnavailable().json(res)
             } else {
                 HttpResponse::BadRequest().json(res)
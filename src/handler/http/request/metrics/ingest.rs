@@ -20,7 +20,7 @@ use actix_web::{HttpRequest, HttpResponse, http, post, web};
 use crate::{
     common::meta::http::HttpResponse as MetaHttpResponse,
     handler::http::request::{CONTENT_TYPE_JSON, CONTENT_TYPE_PROTO},
-    service::metrics,
+    service::{metrics, quotas::quota_exceeded_response},
 };
 
 /// _json ingestion API
@@ -45,11 +45,17 @@ pub async fn json(org_id: web::Path<String>, body: web::Bytes) -> Result<HttpRes
     let org_id = org_id.into_inner();
     Ok(match metrics::json::ingest(&org_id, body).await {
         Ok(v) => HttpResponse::Ok().json(v),
-        Err(e) => {
-            log::error!("Error processing request {org_id}/metrics/_json: {e}");
-            HttpResponse::BadRequest()
-                .json(MetaHttpResponse::error(http::StatusCode::BAD_REQUEST, e))
-        }
+        Err(e) => match e.downcast_ref::<infra::errors::Error>() {
+            Some(infra::errors::Error::QuotaExceeded(exceeded)) => {
+                log::error!("Error processing request {org_id}/metrics/_json: {exceeded}");
+                quota_exceeded_response(exceeded)
+            }
+            _ => {
+                log::error!("Error processing request {org_id}/metrics/_json: {e}");
+                HttpResponse::BadRequest()
+                    .json(MetaHttpResponse::error(http::StatusCode::BAD_REQUEST, e))
+            }
+        },
     })
 }
 
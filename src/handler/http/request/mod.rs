@@ -17,6 +17,7 @@ pub mod actions;
 #[cfg(feature = "enterprise")]
 pub mod ai;
 pub mod alerts;
+pub mod api_keys;
 pub mod authz;
 #[cfg(feature = "cloud")]
 pub mod billings;
@@ -0,0 +1,138 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use actix_web::{HttpResponse, Responder, post, web};
+
+use crate::{
+    common::{
+        meta::{
+            http::HttpResponse as MetaHttpResponse,
+            org_bundle::{ConflictStrategy, OrgBundleExportRequest},
+        },
+        utils::auth::UserEmail,
+    },
+    service::org_bundle::{self, BundleError},
+};
+
+fn bundle_error_response(e: BundleError) -> HttpResponse {
+    match e {
+        BundleError::Internal(e) => MetaHttpResponse::internal_error(e),
+        e => MetaHttpResponse::bad_request(e),
+    }
+}
+
+/// ExportOrganizationBundle
+///
+/// Exports the dashboards, alerts, destinations, templates, functions, pipelines, saved views,
+/// enrichment tables, folders and stream settings of the org, and optionally ranges of its logs,
+/// into one zip archive.
+///
+/// #{"ratelimit_module":"Organizations", "ratelimit_module_operation":"get"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Organizations",
+    operation_id = "OrganizationBundleExport",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    request_body(
+        content = OrgBundleExportRequest,
+        description = "Objects and stream data to export",
+        content_type = "application/json",
+        example = json!({
+            "kinds": ["folders", "dashboards", "alerts", "destinations", "templates"],
+            "data": [{
+                "stream_name": "default",
+                "stream_type": "logs",
+                "start_time": 1749110162176000_i64,
+                "end_time": 1749111062176000_i64,
+            }],
+        }),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/zip"),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 500, description = "Failure", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/bundle/export")]
+async fn export(
+    path: web::Path<String>,
+    req: Option<web::Json<OrgBundleExportRequest>>,
+    user_email: UserEmail,
+) -> impl Responder {
+    let org_id = path.into_inner();
+    let req = req.map(|req| req.into_inner()).unwrap_or_default();
+    match org_bundle::export::export(&org_id, &user_email.user_id, req).await {
+        Ok(content) => HttpResponse::Ok()
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{org_id}-bundle.zip\""),
+            ))
+            .content_type("application/zip")
+            .body(content),
+        Err(e) => bundle_error_response(e),
+    }
+}
+
+/// ImportOrganizationBundle
+///
+/// Imports a bundle exported by any org into this org. Ids are generated anew and the outcome of
+/// every object is reported along with its old and new id.
+///
+/// #{"ratelimit_module":"Organizations", "ratelimit_module_operation":"create"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Organizations",
+    operation_id = "OrganizationBundleImport",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("on_conflict" = Option<String>, Query, description = "What to do with objects whose name is taken: skip (default), overwrite or rename"),
+    ),
+    request_body(content = String, description = "Bundle archive", content_type = "application/zip"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = OrgBundleImportResponse),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 500, description = "Failure", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/bundle/import")]
+async fn import(
+    path: web::Path<String>,
+    query: web::Query<HashMap<String, String>>,
+    body: web::Bytes,
+    user_email: UserEmail,
+) -> impl Responder {
+    let org_id = path.into_inner();
+    let strategy = match query.get("on_conflict") {
+        Some(on_conflict) => match on_conflict.parse::<ConflictStrategy>() {
+            Ok(strategy) => strategy,
+            Err(e) => return MetaHttpResponse::bad_request(e),
+        },
+        None => ConflictStrategy::default(),
+    };
+    match org_bundle::import::import(&org_id, &user_email.user_id, &body, strategy).await {
+        Ok(resp) => HttpResponse::Ok().json(resp),
+        Err(e) => bundle_error_response(e),
+    }
+}
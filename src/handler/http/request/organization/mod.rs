@@ -12,6 +12,9 @@
 //
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
+pub mod bundle;
 pub mod es;
 pub mod org;
+pub mod quota;
+pub mod secrets;
 pub mod settings;
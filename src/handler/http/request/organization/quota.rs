@@ -0,0 +1,137 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use actix_web::{HttpResponse, Responder, delete, get, put, web};
+use config::meta::quota::OrgQuota;
+
+use crate::{
+    common::{
+        meta::http::HttpResponse as MetaHttpResponse,
+        utils::auth::{UserEmail, is_root_user},
+    },
+    service::quotas,
+};
+
+/// GetOrganizationQuota
+///
+/// #{"ratelimit_module":"Settings", "ratelimit_module_operation":"get"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Organizations",
+    operation_id = "OrganizationQuotaGet",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = OrgQuotaResponse),
+    )
+)]
+#[get("/{org_id}/quota")]
+async fn get(path: web::Path<String>) -> impl Responder {
+    let org_id = path.into_inner();
+    HttpResponse::Ok().json(quotas::get(&org_id).await)
+}
+
+/// SetOrganizationQuota
+///
+/// Only the root user can change the quota of an org.
+///
+/// #{"ratelimit_module":"Settings", "ratelimit_module_operation":"update"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Organizations",
+    operation_id = "OrganizationQuotaSet",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    request_body(
+        content = OrgQuota,
+        description = "Organization quota",
+        content_type = "application/json",
+        example = json!({
+            "max_daily_ingest_bytes": 107374182400_u64,
+            "max_storage_bytes": 10995116277760_u64,
+            "max_streams": 500,
+            "soft_limit_percent": 80,
+            "search": {
+                "max_concurrent_queries_per_node": 10,
+                "max_hourly_scan_bytes": 1099511627776_u64,
+                "queue_when_busy": true,
+            },
+        }),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 403, description = "Forbidden", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[put("/{org_id}/quota")]
+async fn set(
+    path: web::Path<String>,
+    quota: web::Json<OrgQuota>,
+    user_email: UserEmail,
+) -> impl Responder {
+    let org_id = path.into_inner();
+    if !is_root_user(&user_email.user_id) {
+        return MetaHttpResponse::forbidden("Only the root user can change quotas");
+    }
+    let quota = quota.into_inner();
+    if let Err(e) = quota.validate() {
+        return MetaHttpResponse::bad_request(e);
+    }
+    match quotas::set(&org_id, quota).await {
+        Ok(()) => MetaHttpResponse::ok("Organization quota updated"),
+        Err(e) => MetaHttpResponse::internal_error(e),
+    }
+}
+
+/// DeleteOrganizationQuota
+///
+/// Only the root user can remove the quota of an org.
+///
+/// #{"ratelimit_module":"Settings", "ratelimit_module_operation":"delete"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Organizations",
+    operation_id = "OrganizationQuotaDelete",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 403, description = "Forbidden", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[delete("/{org_id}/quota")]
+async fn delete(path: web::Path<String>, user_email: UserEmail) -> impl Responder {
+    let org_id = path.into_inner();
+    if !is_root_user(&user_email.user_id) {
+        return MetaHttpResponse::forbidden("Only the root user can change quotas");
+    }
+    match quotas::delete(&org_id).await {
+        Ok(()) => MetaHttpResponse::ok("Organization quota removed"),
+        Err(e) => MetaHttpResponse::internal_error(e),
+    }
+}
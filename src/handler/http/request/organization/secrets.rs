@@ -0,0 +1,210 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use actix_web::{HttpResponse, Responder, delete, get, post, put, web};
+use config::meta::{
+    secrets::{SecretRequest, SecretUpdateRequest},
+    user::UserRole,
+};
+
+use crate::{
+    common::{
+        meta::http::HttpResponse as MetaHttpResponse,
+        utils::auth::{UserEmail, is_root_user},
+    },
+    service::{
+        secrets::{self, SecretError},
+        users,
+    },
+};
+
+impl From<SecretError> for HttpResponse {
+    fn from(value: SecretError) -> Self {
+        match &value {
+            SecretError::InfraError(err) => MetaHttpResponse::internal_error(err),
+            SecretError::NotFound(_) => MetaHttpResponse::not_found(value),
+            SecretError::AlreadyExists | SecretError::UsedByDestination(_) => {
+                MetaHttpResponse::conflict(value)
+            }
+            other_err => MetaHttpResponse::bad_request(other_err),
+        }
+    }
+}
+
+/// Secrets can only be managed by the admins of the org.
+async fn is_org_admin(org_id: &str, user_id: &str) -> bool {
+    is_root_user(user_id)
+        || users::get_user(Some(org_id), user_id)
+            .await
+            .is_some_and(|user| user.role == UserRole::Admin)
+}
+
+/// CreateOrganizationSecret
+///
+/// The value is stored encrypted and can't be read back, destinations reference it as
+/// `${secret:name}` in their url and header values.
+///
+/// #{"ratelimit_module":"Organizations", "ratelimit_module_operation":"create"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Organizations",
+    operation_id = "OrganizationSecretCreate",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    request_body(
+        content = SecretRequest,
+        description = "Secret name and value",
+        content_type = "application/json",
+        example = json!({
+            "name": "pagerduty_key",
+            "value": "R0ut1ngK3y",
+        }),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 403, description = "Forbidden", content_type = "application/json", body = HttpResponse),
+        (status = 409, description = "Conflict", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/secrets")]
+async fn create(
+    path: web::Path<String>,
+    req: web::Json<SecretRequest>,
+    user_email: UserEmail,
+) -> impl Responder {
+    let org_id = path.into_inner();
+    if !is_org_admin(&org_id, &user_email.user_id).await {
+        return MetaHttpResponse::forbidden("Only org admins can manage secrets");
+    }
+    let req = req.into_inner();
+    match secrets::save(&org_id, &req.name, &req.value, &user_email.user_id, true).await {
+        Ok(()) => MetaHttpResponse::ok("Secret saved"),
+        Err(e) => e.into(),
+    }
+}
+
+/// UpdateOrganizationSecret
+///
+/// #{"ratelimit_module":"Organizations", "ratelimit_module_operation":"update"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Organizations",
+    operation_id = "OrganizationSecretUpdate",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("name" = String, Path, description = "Secret name"),
+    ),
+    request_body(
+        content = SecretUpdateRequest,
+        description = "New value of the secret",
+        content_type = "application/json",
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+        (status = 403, description = "Forbidden", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[put("/{org_id}/secrets/{name}")]
+async fn update(
+    path: web::Path<(String, String)>,
+    req: web::Json<SecretUpdateRequest>,
+    user_email: UserEmail,
+) -> impl Responder {
+    let (org_id, name) = path.into_inner();
+    if !is_org_admin(&org_id, &user_email.user_id).await {
+        return MetaHttpResponse::forbidden("Only org admins can manage secrets");
+    }
+    match secrets::save(&org_id, &name, &req.value, &user_email.user_id, false).await {
+        Ok(()) => MetaHttpResponse::ok("Secret updated"),
+        Err(e) => e.into(),
+    }
+}
+
+/// ListOrganizationSecrets
+///
+/// Lists the names of the secrets, their values are never returned.
+///
+/// #{"ratelimit_module":"Organizations", "ratelimit_module_operation":"list"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Organizations",
+    operation_id = "OrganizationSecretList",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = Vec<SecretInfo>),
+        (status = 403, description = "Forbidden", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/secrets")]
+async fn list(path: web::Path<String>, user_email: UserEmail) -> impl Responder {
+    let org_id = path.into_inner();
+    if !is_org_admin(&org_id, &user_email.user_id).await {
+        return MetaHttpResponse::forbidden("Only org admins can manage secrets");
+    }
+    match secrets::list(&org_id).await {
+        Ok(list) => MetaHttpResponse::json(list),
+        Err(e) => e.into(),
+    }
+}
+
+/// DeleteOrganizationSecret
+///
+/// Secrets referenced by a destination can't be deleted.
+///
+/// #{"ratelimit_module":"Organizations", "ratelimit_module_operation":"delete"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Organizations",
+    operation_id = "OrganizationSecretDelete",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("name" = String, Path, description = "Secret name"),
+    ),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = HttpResponse),
+        (status = 403, description = "Forbidden", content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+        (status = 409, description = "Conflict", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[delete("/{org_id}/secrets/{name}")]
+async fn delete(path: web::Path<(String, String)>, user_email: UserEmail) -> impl Responder {
+    let (org_id, name) = path.into_inner();
+    if !is_org_admin(&org_id, &user_email.user_id).await {
+        return MetaHttpResponse::forbidden("Only org admins can manage secrets");
+    }
+    match secrets::delete(&org_id, &name).await {
+        Ok(()) => MetaHttpResponse::ok("Secret deleted"),
+        Err(e) => e.into(),
+    }
+}
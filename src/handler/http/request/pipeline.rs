@@ -17,10 +17,15 @@ use std::io::Error;
 
 use actix_web::{HttpRequest, HttpResponse, delete, get, http, post, put, web};
 use ahash::HashMap;
-use config::{ider, meta::pipeline::Pipeline};
+use config::{
+    ider,
+    meta::pipeline::{
+        DeadLetterReplayRequest, Pipeline, PipelineRollbackRequest, PipelineTestRequest,
+    },
+};
 
 use crate::{
-    common::meta::http::HttpResponse as MetaHttpResponse,
+    common::{meta::http::HttpResponse as MetaHttpResponse, utils::auth::UserEmail},
     service::{db::pipeline::PipelineError, pipeline},
 };
 
@@ -28,7 +33,9 @@ impl From<PipelineError> for HttpResponse {
     fn from(value: PipelineError) -> Self {
         match value {
             PipelineError::InfraError(err) => MetaHttpResponse::internal_error(err),
-            PipelineError::NotFound(_) => MetaHttpResponse::not_found(value),
+            PipelineError::NotFound(_) | PipelineError::VersionNotFound(..) => {
+                MetaHttpResponse::not_found(value)
+            }
             PipelineError::Modified(_) => MetaHttpResponse::conflict(value),
             error => MetaHttpResponse::bad_request(error),
         }
@@ -47,6 +54,7 @@ impl From<PipelineError> for HttpResponse {
     ),
     params(
         ("org_id" = String, Path, description = "Organization name"),
+        ("comment" = Option<String>, Query, description = "Comment stored with this version of the pipeline"),
     ),
     request_body(content = Pipeline, description = "Pipeline data", content_type = "application/json"),
     responses(
@@ -58,14 +66,16 @@ impl From<PipelineError> for HttpResponse {
 pub async fn save_pipeline(
     path: web::Path<String>,
     pipeline: web::Json<Pipeline>,
-    _req: HttpRequest,
+    req: HttpRequest,
+    user_email: UserEmail,
 ) -> Result<HttpResponse, Error> {
     let org_id = path.into_inner();
     let mut pipeline = pipeline.into_inner();
     pipeline.name = pipeline.name.trim().to_lowercase();
     pipeline.org = org_id;
     pipeline.id = ider::generate();
-    match pipeline::save_pipeline(pipeline).await {
+    let comment = get_version_comment(&req);
+    match pipeline::save_pipeline(pipeline, &user_email.user_id, comment).await {
         Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
             http::StatusCode::OK,
             "Pipeline created successfully",
@@ -74,6 +84,38 @@ pub async fn save_pipeline(
     }
 }
 
+/// TestPipeline
+///
+/// #{"ratelimit_module":"Pipeline", "ratelimit_module_operation":"create"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "testPipeline",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+    ),
+    request_body(content = PipelineTestRequest, description = "Pipeline and sample records to dry run", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success", content_type = "application/json", body = PipelineTestResponse),
+        (status = 400, description = "Failure", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/pipelines/test")]
+pub async fn test_pipeline(
+    path: web::Path<String>,
+    req_body: web::Json<PipelineTestRequest>,
+) -> Result<HttpResponse, Error> {
+    let org_id = path.into_inner();
+    let PipelineTestRequest { pipeline, records } = req_body.into_inner();
+    match pipeline::test_pipeline(&org_id, pipeline, records).await {
+        Ok(resp) => Ok(HttpResponse::Ok().json(resp)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
 /// ListPipelines
 ///
 /// #{"ratelimit_module":"Pipeline", "ratelimit_module_operation":"list"}#
@@ -206,6 +248,7 @@ async fn delete_pipeline(
     ),
     params(
         ("org_id" = String, Path, description = "Organization name"),
+        ("comment" = Option<String>, Query, description = "Comment stored with this version of the pipeline"),
     ),
     request_body(content = Pipeline, description = "Pipeline data", content_type = "application/json"),
     responses(
@@ -216,10 +259,12 @@ async fn delete_pipeline(
 #[put("/{org_id}/pipelines")]
 pub async fn update_pipeline(
     pipeline: web::Json<Pipeline>,
-    _req: HttpRequest,
+    req: HttpRequest,
+    user_email: UserEmail,
 ) -> Result<HttpResponse, Error> {
     let pipeline = pipeline.into_inner();
-    match pipeline::update_pipeline(pipeline).await {
+    let comment = get_version_comment(&req);
+    match pipeline::update_pipeline(pipeline, &user_email.user_id, comment).await {
         Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
             http::StatusCode::OK,
             "Pipeline updated successfully",
@@ -242,6 +287,7 @@ pub async fn update_pipeline(
         ("org_id" = String, Path, description = "Organization name"),
         ("pipeline_id" = String, Path, description = "Pipeline ID"),
         ("value" = bool, Query, description = "Enable or disable pipeline"),
+        ("comment" = Option<String>, Query, description = "Comment stored with this version of the pipeline"),
     ),
     responses(
         (status = 200, description = "Success",  content_type = "application/json", body = HttpResponse),
@@ -253,6 +299,7 @@ pub async fn update_pipeline(
 pub async fn enable_pipeline(
     path: web::Path<(String, String)>,
     req: HttpRequest,
+    user_email: UserEmail,
 ) -> Result<HttpResponse, Error> {
     let (org_id, pipeline_id) = path.into_inner();
     let query = web::Query::<HashMap<String, String>>::from_query(req.query_string()).unwrap();
@@ -262,10 +309,181 @@ pub async fn enable_pipeline(
     };
     let resp_msg =
         "Pipeline successfully ".to_string() + if enable { "enabled" } else { "disabled" };
-    match pipeline::enable_pipeline(&org_id, &pipeline_id, enable).await {
+    let comment = get_version_comment(&req);
+    match pipeline::enable_pipeline(&org_id, &pipeline_id, enable, &user_email.user_id, comment)
+        .await
+    {
         Ok(()) => {
             Ok(HttpResponse::Ok().json(MetaHttpResponse::message(http::StatusCode::OK, resp_msg)))
         }
         Err(e) => Ok(e.into()),
     }
 }
+
+/// ReplayPipelineDeadLetter
+///
+/// Ingests the original records of the failures captured in the dead letter stream of the
+/// pipeline into its source stream again. The records go through the whole pipeline, so the
+/// branches which didn't fail for them get them twice: `allow_duplicates` must be set to replay.
+///
+/// #{"ratelimit_module":"Pipeline", "ratelimit_module_operation":"update"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "replayPipelineDeadLetter",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+    ),
+    request_body(content = DeadLetterReplayRequest, description = "Time range of the dead letter records to replay", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = DeadLetterReplayResponse),
+        (status = 400, description = "Failure",  content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/pipelines/{pipeline_id}/dead_letter/replay")]
+pub async fn replay_dead_letter(
+    path: web::Path<(String, String)>,
+    req_body: web::Json<DeadLetterReplayRequest>,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    let DeadLetterReplayRequest {
+        start_time,
+        end_time,
+        allow_duplicates,
+    } = req_body.into_inner();
+    if !allow_duplicates {
+        return Ok(MetaHttpResponse::bad_request(
+            "Replayed records run through the whole pipeline again, so the destinations which \
+             already received them get them twice. Set allow_duplicates to replay them",
+        ));
+    }
+    match pipeline::dead_letter::replay(&org_id, &pipeline_id, start_time, end_time).await {
+        Ok(resp) => Ok(HttpResponse::Ok().json(resp)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// ListPipelineVersions
+///
+/// #{"ratelimit_module":"Pipeline", "ratelimit_module_operation":"get"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "listPipelineVersions",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+    ),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = PipelineVersionList),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/pipelines/{pipeline_id}/versions")]
+pub async fn list_pipeline_versions(
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    match pipeline::list_pipeline_versions(&org_id, &pipeline_id).await {
+        Ok(versions) => Ok(HttpResponse::Ok().json(versions)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// DiffPipelineVersions
+///
+/// #{"ratelimit_module":"Pipeline", "ratelimit_module_operation":"get"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "diffPipelineVersions",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+        ("from" = i32, Query, description = "Version to compare from"),
+        ("to" = i32, Query, description = "Version to compare to"),
+    ),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = PipelineVersionDiff),
+        (status = 400, description = "Failure",  content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[get("/{org_id}/pipelines/{pipeline_id}/versions/diff")]
+pub async fn diff_pipeline_versions(
+    path: web::Path<(String, String)>,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string()).unwrap();
+    let (Some(from), Some(to)) = (
+        query.get("from").and_then(|v| v.parse::<i32>().ok()),
+        query.get("to").and_then(|v| v.parse::<i32>().ok()),
+    ) else {
+        return Ok(MetaHttpResponse::bad_request(
+            "Please provide the versions to compare as `from` and `to`",
+        ));
+    };
+    match pipeline::diff_pipeline_versions(&org_id, &pipeline_id, from, to).await {
+        Ok(diff) => Ok(HttpResponse::Ok().json(diff)),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+/// RollbackPipeline
+///
+/// #{"ratelimit_module":"Pipeline", "ratelimit_module_operation":"update"}#
+#[utoipa::path(
+    context_path = "/api",
+    tag = "Pipelines",
+    operation_id = "rollbackPipeline",
+    security(
+        ("Authorization"= [])
+    ),
+    params(
+        ("org_id" = String, Path, description = "Organization name"),
+        ("pipeline_id" = String, Path, description = "Pipeline ID"),
+    ),
+    request_body(content = PipelineRollbackRequest, description = "Version to roll the pipeline back to", content_type = "application/json"),
+    responses(
+        (status = 200, description = "Success",  content_type = "application/json", body = HttpResponse),
+        (status = 400, description = "Failure",  content_type = "application/json", body = HttpResponse),
+        (status = 404, description = "NotFound", content_type = "application/json", body = HttpResponse),
+    )
+)]
+#[post("/{org_id}/pipelines/{pipeline_id}/rollback")]
+pub async fn rollback_pipeline(
+    path: web::Path<(String, String)>,
+    req_body: web::Json<PipelineRollbackRequest>,
+    user_email: UserEmail,
+) -> Result<HttpResponse, Error> {
+    let (org_id, pipeline_id) = path.into_inner();
+    let PipelineRollbackRequest { version, comment } = req_body.into_inner();
+    match pipeline::rollback_pipeline(&org_id, &pipeline_id, version, &user_email.user_id, comment)
+        .await
+    {
+        Ok(()) => Ok(HttpResponse::Ok().json(MetaHttpResponse::message(
+            http::StatusCode::OK,
+            format!("Pipeline rolled back to version {version}"),
+        ))),
+        Err(e) => Ok(e.into()),
+    }
+}
+
+fn get_version_comment(req: &HttpRequest) -> Option<String> {
+    web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .ok()
+        .and_then(|query| query.get("comment").cloned())
+        .filter(|comment| !comment.trim().is_empty())
+}
@@ -24,7 +24,7 @@ use {config::meta::stream::StreamType, o2_openfga::meta::mapping::OFGA_MODELS};
 
 use crate::{
     common::{meta::http::HttpResponse as MetaHttpResponse, utils::http::get_or_create_trace_id},
-    service::{metrics, promql},
+    service::{metrics, promql, quotas::quota_exceeded_response},
 };
 
 /// prometheus remote-write endpoint for metrics
@@ -55,8 +55,11 @@ pub async fn remote_write(
     if content_type == "application/x-protobuf" {
         Ok(match metrics::prom::remote_write(&org_id, body).await {
             Ok(_) => HttpResponse::Ok().into(),
-            Err(e) => HttpResponse::BadRequest()
-                .json(MetaHttpResponse::error(http::StatusCode::BAD_REQUEST, e)),
+            Err(e) => match e.downcast_ref::<errors::Error>() {
+                Some(errors::Error::QuotaExceeded(exceeded)) => quota_exceeded_response(exceeded),
+                _ => HttpResponse::BadRequest()
+                    .json(MetaHttpResponse::error(http::StatusCode::BAD_REQUEST, e)),
+            },
         })
     } else {
         Ok(HttpResponse::BadRequest().json(MetaHttpResponse::error(
@@ -18,6 +18,7 @@ use infra::errors;
 
 use crate::{
     common::meta::http::HttpResponse as MetaHttpResponse, handler::http::router::ERROR_HEADER,
+    service::quotas::quota_exceeded_response,
 };
 
 pub fn map_error_to_http_response(err: &errors::Error, trace_id: Option<String>) -> HttpResponse {
@@ -47,6 +48,7 @@ pub fn map_error_to_http_response(err: &errors::Error, trace_id: Option<String>)
                 .append_header((ERROR_HEADER, code.to_json()))
                 .json(MetaHttpResponse::error_code_with_trace_id(code, trace_id)),
         },
+        errors::Error::QuotaExceeded(exceeded) => quota_exceeded_response(exceeded),
         errors::Error::ResourceError(_) => HttpResponse::ServiceUnavailable()
             .append_header((ERROR_HEADER, err.to_string()))
             .json(MetaHttpResponse::error(
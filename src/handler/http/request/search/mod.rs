@@ -24,7 +24,7 @@ use config::{
         search::{SearchEventType, SearchHistoryHitResponse, default_use_cache},
         self_reporting::usage::{RequestStats, USAGE_STREAM, UsageType},
         sql::resolve_stream_names,
-        stream::StreamType,
+        stream::{StreamSettings, StreamType},
     },
     utils::{base64, json, time::now_micros},
 };
@@ -67,6 +67,12 @@ pub mod search_job;
 pub mod search_stream;
 pub(crate) mod utils;
 
+/// The distinct stream doesn't know about field policies nor row filters, so the values of
+/// streams which have any must come from the stream itself.
+fn distinct_stream_allowed(stream_settings: &StreamSettings) -> bool {
+    stream_settings.field_policies.is_empty() && stream_settings.row_filters.is_empty()
+}
+
 async fn can_use_distinct_stream(
     org: &str,
     stream_name: &str,
@@ -83,6 +89,10 @@ async fn can_use_distinct_stream(
         .await
         .unwrap_or_default();
 
+    if !distinct_stream_allowed(&stream_settings) {
+        return false;
+    }
+
     // all fields which are requested must be in the distinct stream
     let all_fields_distinct = fields.iter().all(|f| {
         if DISTINCT_FIELDS.contains(f) {
@@ -1582,3 +1592,27 @@ pub async fn search_history(
 
     Ok(HttpResponse::Ok().json(search_res))
 }
+
+#[cfg(test)]
+mod tests {
+    use config::meta::stream::RowFilter;
+
+    use super::*;
+
+    #[test]
+    fn test_distinct_stream_allowed() {
+        assert!(distinct_stream_allowed(&StreamSettings::default()));
+
+        // values of a stream with row filters must be read from the stream, filtered
+        let settings = StreamSettings {
+            row_filters: vec![RowFilter {
+                name: "payments".to_string(),
+                filter: "team = 'payments'".to_string(),
+                roles: vec![],
+                groups: vec!["payments".to_string()],
+            }],
+            ..Default::default()
+        };
+        assert!(!distinct_stream_allowed(&settings));
+    }
+}
@@ -20,7 +20,7 @@ use chrono::Utc;
 use config::{
     TIMESTAMP_COL_NAME, get_config,
     meta::{
-        function::{RESULT_ARRAY, VRLResultResolver},
+        function::{RESULT_ARRAY, VRLResultResolver, get_wasm_function_name},
         search::{self, PARTIAL_ERROR_RESPONSE_MESSAGE},
         self_reporting::usage::{RequestStats, UsageType},
         sql::resolve_stream_names,
@@ -166,6 +166,11 @@ pub async fn search_multi(
             query_fn = Some(format!("{} \n .", vrl_function));
         }
     }
+    // wasm functions are applied to the hits of each query
+    let is_wasm_fn = query_fn
+        .as_deref()
+        .and_then(get_wasm_function_name)
+        .is_some();
 
     let mut range_error = String::new();
 
@@ -302,7 +307,7 @@ pub async fn search_multi(
             }
         }
 
-        if !per_query_resp {
+        if !per_query_resp || is_wasm_fn {
             req.query.query_fn = query_fn.clone();
         }
         for fn_name in functions::get_all_transform_keys(&org_id).await {
@@ -465,6 +470,8 @@ pub async fn search_multi(
     }
 
     let mut report_function_usage = false;
+    // wasm functions were already applied to the hits of each query
+    let query_fn = query_fn.filter(|_| !is_wasm_fn);
     multi_res.hits = if query_fn.is_some() && per_query_resp {
         // compile vrl function & apply the same before returning the response
         let mut input_fn = query_fn.unwrap().trim().to_string();
@@ -22,6 +22,8 @@ use {
     o2_enterprise::enterprise::common::config::get_config as get_o2_config,
 };
 
+use crate::service::search::org_limits;
+
 #[delete("/{org_id}/query_manager/{trace_id}")]
 pub async fn cancel_query(params: web::Path<(String, String)>) -> Result<HttpResponse, Error> {
     #[cfg(feature = "enterprise")]
@@ -64,20 +66,31 @@ pub async fn cancel_multiple_query(
     }
 }
 
+/// Returns the running searches and, when the org has search limits, how its searches are
+/// queued or rejected by them on this node.
 #[get("/{org_id}/query_manager/status")]
-pub async fn query_status(_params: web::Path<String>) -> Result<HttpResponse, Error> {
+pub async fn query_status(params: web::Path<String>) -> Result<HttpResponse, Error> {
+    let org_id = params.into_inner();
+
     #[cfg(feature = "enterprise")]
     {
         let res = crate::service::search::query_status().await;
         match res {
-            Ok(query_status) => Ok(HttpResponse::Ok().json(query_status)),
+            Ok(mut query_status) => {
+                query_status.org_limits = org_limits::status(&org_id);
+                Ok(HttpResponse::Ok().json(query_status))
+            }
             Err(e) => Ok(MetaHttpResponse::bad_request(e)),
         }
     }
 
     #[cfg(not(feature = "enterprise"))]
     {
-        Ok(HttpResponse::Forbidden().json("Not Supported"))
+        let query_status = config::meta::search::QueryStatusResponse {
+            status: vec![],
+            org_limits: org_limits::status(&org_id),
+        };
+        Ok(HttpResponse::Ok().json(query_status))
     }
 }
 
@@ -213,7 +213,7 @@ pub async fn zo_config() -> Result<HttpResponse, Error> {
     #[cfg(feature = "enterprise")]
     let sso_enabled = dex_cfg.dex_enabled;
     #[cfg(not(feature = "enterprise"))]
-    let sso_enabled = false;
+    let sso_enabled = get_config().oidc.enabled;
     #[cfg(feature = "enterprise")]
     let native_login_enabled = dex_cfg.native_login_enabled;
     #[cfg(not(feature = "enterprise"))]
@@ -744,6 +744,113 @@ async fn refresh_token_with_dex(req: actix_web::HttpRequest) -> HttpResponse {
     }
 }
 
+#[cfg(not(feature = "enterprise"))]
+#[get("/redirect")]
+pub async fn redirect(req: HttpRequest) -> Result<HttpResponse, Error> {
+    use crate::handler::http::auth::validator::ID_TOKEN_HEADER;
+
+    let cfg = get_config();
+    if !cfg.oidc.enabled {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string()).unwrap();
+    if let Some(error) = query.get("error") {
+        return Ok(HttpResponse::Unauthorized().json(error.to_string()));
+    }
+    let (Some(code), Some(state)) = (query.get("code"), query.get("state")) else {
+        return Ok(HttpResponse::BadRequest().json("no code or state in request".to_string()));
+    };
+
+    match crate::service::oidc::exchange(code, state).await {
+        Ok((claims, tokens)) => {
+            let id_token = json::to_string(&json::json!({
+                "email": claims.email,
+                "name": claims.name,
+                "family_name": claims.family_name,
+                "given_name": claims.given_name,
+                "is_valid": true,
+            }))
+            .unwrap();
+            let login_url = format!(
+                "{}#id_token={}.{}",
+                cfg.oidc.ui_redirect_url,
+                ID_TOKEN_HEADER,
+                base64::encode(&id_token)
+            );
+            let auth_cookie = prepare_empty_cookie(
+                "auth_tokens",
+                &AuthTokens {
+                    access_token: tokens.access_token,
+                    refresh_token: tokens.refresh_token,
+                },
+                &cfg,
+            );
+            Ok(HttpResponse::Found()
+                .append_header((header::LOCATION, login_url))
+                .cookie(auth_cookie)
+                .finish())
+        }
+        Err(e) => {
+            log::error!("[OIDC] login failed: {e}");
+            Ok(HttpResponse::Unauthorized().json(e.to_string()))
+        }
+    }
+}
+
+#[cfg(not(feature = "enterprise"))]
+#[get("/dex_login")]
+pub async fn dex_login() -> Result<HttpResponse, Error> {
+    if !get_config().oidc.enabled {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    match crate::service::oidc::login().await {
+        Ok(url) => Ok(HttpResponse::Ok().json(url)),
+        Err(e) => {
+            log::error!("[OIDC] failed to start login: {e}");
+            Ok(HttpResponse::InternalServerError().json(e.to_string()))
+        }
+    }
+}
+
+#[cfg(not(feature = "enterprise"))]
+#[get("/dex_refresh")]
+async fn refresh_token_with_dex(req: actix_web::HttpRequest) -> HttpResponse {
+    let conf = get_config();
+    if !conf.oidc.enabled {
+        return HttpResponse::NotFound().finish();
+    }
+    let Some(cookie) = req.cookie("auth_tokens") else {
+        return HttpResponse::Unauthorized().finish();
+    };
+    let decoded_cookie = config::utils::base64::decode(cookie.value()).unwrap_or_default();
+    let auth_tokens: AuthTokens = json::from_str(&decoded_cookie).unwrap_or_default();
+    if let Some(session_id) = auth_tokens.access_token.strip_prefix("session ") {
+        crate::service::session::remove_session(session_id).await;
+    }
+
+    match crate::service::oidc::refresh(&auth_tokens.refresh_token).await {
+        Ok(tokens) => {
+            let auth_cookie = prepare_empty_cookie(
+                "auth_tokens",
+                &AuthTokens {
+                    access_token: tokens.access_token,
+                    refresh_token: tokens.refresh_token,
+                },
+                &conf,
+            );
+            HttpResponse::Ok().cookie(auth_cookie).finish()
+        }
+        Err(e) => {
+            log::info!("[OIDC] refresh failed: {e}");
+            let auth_cookie = prepare_empty_cookie("auth_tokens", &AuthTokens::default(), &conf);
+            HttpResponse::Unauthorized()
+                .append_header((header::LOCATION, "/"))
+                .cookie(auth_cookie)
+                .finish()
+        }
+    }
+}
+
 fn prepare_empty_cookie<'a, T: Serialize + ?Sized>(
     cookie_name: &'a str,
     token_struct: &T,
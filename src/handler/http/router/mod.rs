@@ -16,37 +16,42 @@
 use std::{rc::Rc, str::FromStr};
 
 use actix_cors::Cors;
+use actix_http::h1::Payload;
 use actix_web::{
-    HttpRequest, HttpResponse,
+    HttpMessage, HttpRequest, HttpResponse,
     body::MessageBody,
     dev::{Service, ServiceRequest, ServiceResponse},
     get,
     http::header,
-    middleware, web,
+    middleware,
+    web::{self, BytesMut},
 };
 use actix_web_httpauth::middleware::HttpAuthentication;
 use actix_web_lab::middleware::{Next, from_fn};
-use config::get_config;
-use futures::FutureExt;
+use config::{get_config, utils::time::now_micros};
+use futures::{FutureExt, StreamExt};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 #[cfg(feature = "enterprise")]
 use {
-    crate::{common::meta::ingestion::INGESTION_EP, service::self_reporting::audit},
-    actix_http::h1::Payload,
-    actix_web::{HttpMessage, web::BytesMut},
+    crate::service::self_reporting::audit,
     base64::{Engine as _, engine::general_purpose},
-    config::utils::time::now_micros,
-    futures::StreamExt,
     o2_enterprise::enterprise::common::{
         auditor::{AuditMessage, Protocol, ResponseMeta},
         config::get_config as get_o2_config,
     },
 };
+#[cfg(not(feature = "enterprise"))]
+use {
+    crate::service::self_reporting::publish_audit_data,
+    config::meta::self_reporting::audit::{AuditData, redact_body},
+};
 
 use super::request::*;
 use crate::{
-    common::meta::{middleware_data::RumExtraData, proxy::PathParamProxyURL},
+    common::meta::{
+        ingestion::INGESTION_EP, middleware_data::RumExtraData, proxy::PathParamProxyURL,
+    },
     handler::http::request::search::search_inspector,
 };
 
@@ -162,13 +167,89 @@ async fn audit_middleware(
     }
 }
 
+/// Records every call which changes something, that is every non-GET call besides ingestion,
+/// in the `_audit` stream of its org.
 #[cfg(not(feature = "enterprise"))]
 async fn audit_middleware(
-    req: ServiceRequest,
+    mut req: ServiceRequest,
     next: Next<impl MessageBody>,
 ) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let cfg = get_config();
+    let method = req.method().to_string();
+    let prefix = format!("{}/api/", cfg.common.base_uri);
+    let path = req
+        .path()
+        .strip_prefix(&prefix)
+        .unwrap_or(req.path())
+        .to_string();
+    let path_columns = path.split('/').collect::<Vec<&str>>();
+    let is_mutation = !matches!(method.as_str(), "GET" | "HEAD" | "OPTIONS");
+    if !cfg.common.audit_enabled
+        || !is_mutation
+        || path_columns.get(1).is_some_and(|v| v.eq(&"ws"))
+        || path_columns.get(1).is_some_and(|v| v.ends_with("_stream")) // skip for http2 streams
+        || path_columns
+            .last()
+            .is_some_and(|v| INGESTION_EP.contains(v))
+    {
+        let mut res = next.call(req).await?;
+        res.headers_mut().remove(ERROR_HEADER);
+        return Ok(res);
+    }
+
+    let org_id = match path_columns[0] {
+        "organizations" => "",
+        "v2" => path_columns.get(1).copied().unwrap_or_default(),
+        org => org,
+    }
+    .to_string();
+    let query_params = req.query_string().to_string();
+    let user_email = req
+        .headers()
+        .get("user_id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    let mut request_body = BytesMut::new();
+    let mut payload_stream = req.take_payload();
+    while let Some(chunk) = payload_stream.next().await {
+        request_body.extend_from_slice(&chunk?);
+    }
+    // Put the payload back into the req
+    let (_, mut payload) = Payload::create(true);
+    payload.unread_data(request_body.clone().into());
+    req.set_payload(payload.into());
+
     let mut res = next.call(req).await?;
+
+    let error_msg = match res.response().error() {
+        Some(e) => Some(e.to_string()),
+        None => res
+            .response()
+            .headers()
+            .get(ERROR_HEADER)
+            .map(|v| v.to_str().unwrap_or_default().to_string()),
+    };
     res.headers_mut().remove(ERROR_HEADER);
+
+    let redact_fields = cfg.common.audit_redact_fields.to_lowercase();
+    let redact_fields = redact_fields
+        .split(',')
+        .map(|v| v.trim())
+        .collect::<Vec<_>>();
+    publish_audit_data(AuditData {
+        _timestamp: now_micros(),
+        org_id,
+        user_email,
+        http_method: method,
+        http_path: path,
+        http_query_params: query_params,
+        http_body: redact_body(&request_body, &redact_fields),
+        http_response_code: res.response().status().as_u16(),
+        error_msg,
+    })
+    .await;
     Ok(res)
 }
 
@@ -331,6 +412,9 @@ pub fn get_config_routes(svc: &mut web::ServiceConfig) {
         web::scope("/config")
             .wrap(cors.clone())
             .service(status::zo_config)
+            .service(status::redirect)
+            .service(status::dex_login)
+            .service(status::refresh_token_with_dex)
             .service(status::logout)
             .service(web::scope("/reload").service(status::config_reload)),
     );
@@ -386,6 +470,15 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(organization::settings::delete_logo)
         .service(organization::settings::set_logo_text)
         .service(organization::settings::delete_logo_text)
+        .service(organization::quota::get)
+        .service(organization::quota::set)
+        .service(organization::quota::delete)
+        .service(organization::bundle::export)
+        .service(organization::bundle::import)
+        .service(organization::secrets::create)
+        .service(organization::secrets::update)
+        .service(organization::secrets::list)
+        .service(organization::secrets::delete)
         .service(organization::org::org_summary)
         .service(organization::org::get_user_passcode)
         .service(organization::org::update_user_passcode)
@@ -457,12 +550,17 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(functions::update_function)
         .service(functions::list_pipeline_dependencies)
         .service(dashboards::create_dashboard)
+        .service(dashboards::import_grafana_dashboard)
         .service(dashboards::update_dashboard)
         .service(dashboards::list_dashboards)
         .service(dashboards::get_dashboard)
         .service(dashboards::delete_dashboard)
         .service(dashboards::move_dashboard)
         .service(dashboards::move_dashboards)
+        .service(dashboards::list_dashboard_versions)
+        .service(dashboards::diff_dashboard_versions)
+        .service(dashboards::get_dashboard_version)
+        .service(dashboards::restore_dashboard_version)
         .service(dashboards::reports::create_report)
         .service(dashboards::reports::update_report)
         .service(dashboards::reports::get_report)
@@ -495,6 +593,7 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(alerts::enable_alert)
         .service(alerts::trigger_alert)
         .service(alerts::move_alerts)
+        .service(alerts::backtest_alert)
         .service(alerts::deprecated::save_alert)
         .service(alerts::deprecated::update_alert)
         .service(alerts::deprecated::get_alert)
@@ -503,6 +602,7 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(alerts::deprecated::delete_alert)
         .service(alerts::deprecated::enable_alert)
         .service(alerts::deprecated::trigger_alert)
+        .service(alerts::templates::test_template)
         .service(alerts::templates::save_template)
         .service(alerts::templates::update_template)
         .service(alerts::templates::get_template)
@@ -513,6 +613,11 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(alerts::destinations::get_destination)
         .service(alerts::destinations::list_destinations)
         .service(alerts::destinations::delete_destination)
+        .service(alerts::maintenance_windows::create_maintenance_window)
+        .service(alerts::maintenance_windows::update_maintenance_window)
+        .service(alerts::maintenance_windows::get_maintenance_window)
+        .service(alerts::maintenance_windows::list_maintenance_windows)
+        .service(alerts::maintenance_windows::delete_maintenance_window)
         .service(kv::get)
         .service(kv::set)
         .service(kv::delete)
@@ -543,11 +648,16 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(authz::fga::delete_group)
         .service(clusters::list_clusters)
         .service(pipeline::save_pipeline)
+        .service(pipeline::test_pipeline)
         .service(pipeline::update_pipeline)
         .service(pipeline::list_pipelines)
         .service(pipeline::list_streams_with_pipeline)
         .service(pipeline::delete_pipeline)
         .service(pipeline::enable_pipeline)
+        .service(pipeline::replay_dead_letter)
+        .service(pipeline::list_pipeline_versions)
+        .service(pipeline::diff_pipeline_versions)
+        .service(pipeline::rollback_pipeline)
         .service(search::multi_streams::search_multi)
         .service(search::multi_streams::_search_partition_multi)
         .service(search::multi_streams::around_multi)
@@ -559,6 +669,9 @@ pub fn get_service_routes(svc: &mut web::ServiceConfig) {
         .service(service_accounts::delete)
         .service(service_accounts::update)
         .service(service_accounts::get_api_token)
+        .service(api_keys::list)
+        .service(api_keys::create)
+        .service(api_keys::delete)
         .service(ws::websocket);
 
     #[cfg(feature = "enterprise")]
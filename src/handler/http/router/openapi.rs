@@ -40,6 +40,15 @@ use crate::{common::meta, handler::http::request};
         request::organization::org::create_user_rumtoken,
         request::organization::settings::get,
         request::organization::settings::create,
+        request::organization::quota::get,
+        request::organization::quota::set,
+        request::organization::quota::delete,
+        request::organization::bundle::export,
+        request::organization::bundle::import,
+        request::organization::secrets::create,
+        request::organization::secrets::update,
+        request::organization::secrets::list,
+        request::organization::secrets::delete,
         request::stream::list,
         request::stream::schema,
         request::stream::settings,
@@ -95,12 +104,17 @@ use crate::{common::meta, handler::http::request};
         request::functions::list_pipeline_dependencies,
         request::functions::test_function,
         request::dashboards::create_dashboard,
+        request::dashboards::import_grafana_dashboard,
         request::dashboards::update_dashboard,
         request::dashboards::list_dashboards,
         request::dashboards::get_dashboard,
         request::dashboards::delete_dashboard,
         request::dashboards::move_dashboard,
         request::dashboards::move_dashboards,
+        request::dashboards::list_dashboard_versions,
+        request::dashboards::diff_dashboard_versions,
+        request::dashboards::get_dashboard_version,
+        request::dashboards::restore_dashboard_version,
         request::dashboards::timed_annotations::create_annotations,
         request::dashboards::timed_annotations::get_annotations,
         request::dashboards::timed_annotations::delete_annotations,
@@ -122,16 +136,23 @@ use crate::{common::meta, handler::http::request};
         request::alerts::enable_alert,
         request::alerts::trigger_alert,
         request::alerts::move_alerts,
+        request::alerts::backtest_alert,
         request::alerts::templates::list_templates,
         request::alerts::templates::get_template,
         request::alerts::templates::save_template,
         request::alerts::templates::update_template,
         request::alerts::templates::delete_template,
+        request::alerts::templates::test_template,
         request::alerts::destinations::list_destinations,
         request::alerts::destinations::get_destination,
         request::alerts::destinations::save_destination,
         request::alerts::destinations::update_destination,
         request::alerts::destinations::delete_destination,
+        request::alerts::maintenance_windows::list_maintenance_windows,
+        request::alerts::maintenance_windows::get_maintenance_window,
+        request::alerts::maintenance_windows::create_maintenance_window,
+        request::alerts::maintenance_windows::update_maintenance_window,
+        request::alerts::maintenance_windows::delete_maintenance_window,
         request::kv::get,
         request::kv::set,
         request::kv::delete,
@@ -151,12 +172,20 @@ use crate::{common::meta, handler::http::request};
         request::service_accounts::update,
         request::service_accounts::delete,
         request::service_accounts::get_api_token,
+        request::api_keys::list,
+        request::api_keys::create,
+        request::api_keys::delete,
         request::pipeline::save_pipeline,
         request::pipeline::list_pipelines,
         request::pipeline::list_streams_with_pipeline,
         request::pipeline::delete_pipeline,
         request::pipeline::update_pipeline,
         request::pipeline::enable_pipeline,
+        request::pipeline::replay_dead_letter,
+        request::pipeline::test_pipeline,
+        request::pipeline::list_pipeline_versions,
+        request::pipeline::diff_pipeline_versions,
+        request::pipeline::rollback_pipeline,
         request::dashboards::reports::create_report,
         request::dashboards::reports::update_report,
         request::dashboards::reports::list_reports,
@@ -210,6 +239,9 @@ use crate::{common::meta, handler::http::request};
             config::meta::stream::StreamStats,
             config::meta::stream::PartitionTimeLevel,
             config::meta::stream::UpdateStreamSettings,
+            config::meta::stream::FieldPolicy,
+            config::meta::stream::FieldPolicyAction,
+            config::meta::stream::RowFilter,
             config::meta::dashboards::Dashboard,
             config::meta::dashboards::v1::AxisItem,
             config::meta::dashboards::v1::Dashboard,
@@ -228,6 +260,9 @@ use crate::{common::meta, handler::http::request};
             config::meta::alerts::AggFunction,
             config::meta::alerts::Condition,
             config::meta::alerts::CompareHistoricData,
+            config::meta::alerts::AnomalyCondition,
+            config::meta::alerts::AnomalyMethod,
+            config::meta::alerts::CompositeCondition,
             config::meta::alerts::FrequencyType,
             config::meta::alerts::Operator,
             config::meta::alerts::QueryType,
@@ -244,22 +279,43 @@ use crate::{common::meta, handler::http::request};
             crate::handler::http::models::dashboards::GetDashboardResponseBody,
             crate::handler::http::models::dashboards::UpdateDashboardRequestBody,
             crate::handler::http::models::dashboards::UpdateDashboardResponseBody,
+            crate::handler::http::models::dashboards::ImportGrafanaDashboardRequestBody,
+            crate::handler::http::models::dashboards::ImportGrafanaDashboardResponseBody,
+            crate::service::dashboards::grafana::ConversionReport,
+            crate::service::dashboards::grafana::UnmappedItem,
             crate::handler::http::models::dashboards::ListDashboardsResponseBody,
             crate::handler::http::models::dashboards::ListDashboardsResponseBodyItem,
             crate::handler::http::models::dashboards::MoveDashboardRequestBody,
             crate::handler::http::models::dashboards::MoveDashboardsRequestBody,
+            crate::handler::http::models::dashboards::GetDashboardVersionResponseBody,
+            config::meta::dashboards::versions::SavedVersion,
+            config::meta::dashboards::versions::SavedVersionList,
+            config::meta::dashboards::versions::SavedVersionDiff,
+            config::meta::dashboards::versions::DiffChange,
             // Destinations
             crate::handler::http::models::destinations::Destination,
             crate::handler::http::models::destinations::DestinationType,
             crate::handler::http::models::destinations::Template,
+            crate::handler::http::models::destinations::TemplateTestRequest,
+            crate::handler::http::models::destinations::TemplateTestResponse,
+            config::meta::destinations::TemplateEngine,
+            config::meta::destinations::TemplateContext,
+            config::meta::destinations::TemplateAlert,
+            config::meta::destinations::TemplateLinks,
             // Alerts
             crate::handler::http::models::alerts::requests::CreateAlertRequestBody,
             crate::handler::http::models::alerts::requests::UpdateAlertRequestBody,
             crate::handler::http::models::alerts::requests::MoveAlertsRequestBody,
+            crate::handler::http::models::alerts::requests::BacktestAlertRequestBody,
             crate::handler::http::models::alerts::responses::GetAlertResponseBody,
             crate::handler::http::models::alerts::responses::ListAlertsResponseBody,
             crate::handler::http::models::alerts::responses::ListAlertsResponseBodyItem,
             crate::handler::http::models::alerts::responses::EnableAlertResponseBody,
+            crate::handler::http::models::alerts::responses::BacktestAlertResponseBody,
+            config::meta::alerts::backtest::BacktestResult,
+            config::meta::alerts::backtest::BacktestEvaluation,
+            config::meta::alerts::maintenance_window::MaintenanceWindow,
+            config::meta::alerts::maintenance_window::MaintenanceTargets,
             crate::handler::http::models::alerts::Alert,
             crate::handler::http::models::alerts::TriggerCondition,
             crate::handler::http::models::alerts::CompareHistoricData,
@@ -281,6 +337,19 @@ use crate::{common::meta, handler::http::request};
             config::meta::function::FunctionList,
             config::meta::function::StreamOrder,
             config::meta::function::TestVRLRequest,
+            config::meta::pipeline::DeadLetterReplayRequest,
+            config::meta::pipeline::DeadLetterReplayResponse,
+            config::meta::pipeline::PipelineTestRequest,
+            config::meta::pipeline::PipelineTestResponse,
+            config::meta::pipeline::PipelineTestNodeResult,
+            config::meta::pipeline::PipelineTestRecord,
+            config::meta::pipeline::PipelineTestError,
+            config::meta::pipeline::PipelineVersion,
+            config::meta::pipeline::PipelineVersionList,
+            config::meta::pipeline::PipelineVersionDiff,
+            config::meta::pipeline::PipelineRollbackRequest,
+            config::meta::pipeline::NodeChange,
+            config::meta::pipeline::EdgeChange,
             config::meta::sql::OrderBy,
             config::meta::search::Query,
             config::meta::search::Request,
@@ -321,6 +390,11 @@ use crate::{common::meta, handler::http::request};
             meta::user::UserList,
             meta::user::UserResponse,
             meta::user::SignInResponse,
+            config::meta::api_key::ApiKey,
+            config::meta::api_key::ApiKeyScope,
+            config::meta::api_key::ApiKeyRequest,
+            config::meta::api_key::ApiKeyCreateResponse,
+            config::meta::api_key::ApiKeyList,
             meta::organization::OrgSummary,
             meta::organization::StreamSummary,
             meta::organization::PipelineSummary,
@@ -334,6 +408,22 @@ use crate::{common::meta, handler::http::request};
             meta::organization::OrgRenameBody,
             meta::organization::OrganizationSetting,
             meta::organization::OrganizationSettingResponse,
+            config::meta::quota::OrgQuota,
+            config::meta::quota::OrgQuotaResponse,
+            config::meta::quota::QuotaUsage,
+            config::meta::quota::OrgSearchLimits,
+            config::meta::quota::OrgSearchStatus,
+            meta::org_bundle::OrgBundleExportRequest,
+            meta::org_bundle::StreamDataRange,
+            meta::org_bundle::BundleKind,
+            meta::org_bundle::ConflictStrategy,
+            meta::org_bundle::ImportAction,
+            meta::org_bundle::ImportItem,
+            meta::org_bundle::DataImportResult,
+            meta::org_bundle::OrgBundleImportResponse,
+            config::meta::secrets::SecretRequest,
+            config::meta::secrets::SecretUpdateRequest,
+            config::meta::secrets::SecretInfo,
             meta::organization::RumIngestionResponse,
             meta::organization::RumIngestionToken,
             request::status::HealthzResponse,
@@ -361,6 +451,7 @@ use crate::{common::meta, handler::http::request};
         (name = "Organizations", description = "Organizations retrieval & management operations"),
         (name = "Streams", description = "Stream retrieval & management operations"),
         (name = "Users", description = "Users retrieval & management operations"),
+        (name = "ApiKeys", description = "Scoped API keys of users and service accounts"),
         (name = "KV", description = "Key Value retrieval & management operations"),
         (name = "Metrics", description = "Metrics data ingestion operations"),
         (name = "Traces", description = "Traces data ingestion operations"),
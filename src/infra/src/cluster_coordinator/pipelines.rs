@@ -13,6 +13,8 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use config::{meta::pipeline::Pipeline, utils::json};
+
 use crate::errors::Error;
 
 pub const PIPELINES_WATCH_PREFIX: &str = "/pipelines/";
@@ -28,6 +30,19 @@ pub async fn emit_put_event(pipeline_id: &str) -> Result<(), Error> {
     Ok(())
 }
 
+/// Sends event to the cluster coordinator indicating that a pipeline has been rolled back to a
+/// previous version. The rolled back pipeline is pushed along with the event so that all nodes
+/// load exactly that version.
+pub async fn emit_rollback_event(pipeline: &Pipeline) -> Result<(), Error> {
+    let key = format!("{PIPELINES_WATCH_PREFIX}{}", pipeline.id);
+    let value = json::to_vec(pipeline)?;
+    let cluster_coordinator = super::get_coordinator().await;
+    cluster_coordinator
+        .put(&key, bytes::Bytes::from(value), true, None)
+        .await?;
+    Ok(())
+}
+
 /// Sends event to the cluster coordinator indicating that a pipeline has been
 /// deleted from the database.
 pub async fn emit_delete_event(pipeline_id: &str) -> Result<(), Error> {
@@ -85,6 +85,8 @@ pub enum Error {
     #[error("Error# {0}")]
     IngestionError(String),
     #[error("Error# {0}")]
+    QuotaExceeded(config::meta::quota::QuotaExceeded),
+    #[error("Error# {0}")]
     WalFileError(String),
     #[error("Error# {0}")]
     OtherError(#[from] anyhow::Error),
@@ -146,6 +148,8 @@ pub enum PutDashboardError {
     MissingOwner,
     #[error("error putting dashboard with missing inner data for version {0}")]
     MissingInnerData(i32),
+    #[error("error putting dashboard changed since the given hash")]
+    ConflictingHash,
 }
 
 #[derive(ThisError, Debug)]
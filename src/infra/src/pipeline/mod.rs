@@ -14,10 +14,13 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use async_trait::async_trait;
-use config::meta::{
-    meta_store::MetaStore,
-    pipeline::{Pipeline, components::PipelineSource},
-    stream::StreamParams,
+use config::{
+    meta::{
+        meta_store::MetaStore,
+        pipeline::{Pipeline, PipelineVersion, components::PipelineSource},
+        stream::StreamParams,
+    },
+    utils::json,
 };
 use once_cell::sync::Lazy;
 
@@ -29,6 +32,9 @@ pub mod sqlite;
 
 static CLIENT: Lazy<Box<dyn PipelineTable>> = Lazy::new(connect);
 
+/// (pipeline_id, version, author, comment, created_at, data) of a `pipeline_history` row
+type PipelineVersionRow = (String, i32, String, Option<String>, i64, String);
+
 pub fn connect() -> Box<dyn PipelineTable> {
     match config::get_config().common.meta_store.as_str().into() {
         MetaStore::MySQL => Box::<mysql::MySqlPipelineTable>::default(),
@@ -42,14 +48,16 @@ pub trait PipelineTable: Sync + Send + 'static {
     async fn create_table(&self) -> Result<()>;
     async fn create_table_index(&self) -> Result<()>;
     async fn drop_table(&self) -> Result<()>;
-    async fn put(&self, pipeline: &Pipeline) -> Result<()>;
-    async fn update(&self, pipeline: &Pipeline) -> Result<()>;
+    async fn put(&self, pipeline: &Pipeline, version: Option<&PipelineVersion>) -> Result<()>;
+    async fn update(&self, pipeline: &Pipeline, version: Option<&PipelineVersion>) -> Result<()>;
     async fn get_by_stream(&self, stream_params: &StreamParams) -> Result<Pipeline>;
     async fn get_by_id(&self, pipeline_id: &str) -> Result<Pipeline>;
     async fn get_with_same_source_stream(&self, pipeline: &Pipeline) -> Result<Pipeline>;
     async fn list(&self) -> Result<Vec<Pipeline>>;
     async fn list_by_org(&self, org: &str) -> Result<Vec<Pipeline>>;
     async fn list_streams_with_pipeline(&self, org: &str) -> Result<Vec<Pipeline>>;
+    async fn list_versions(&self, pipeline_id: &str) -> Result<Vec<PipelineVersion>>;
+    async fn get_version(&self, pipeline_id: &str, version: i32) -> Result<PipelineVersion>;
     async fn delete(&self, pipeline_id: &str) -> Result<Pipeline>;
 }
 
@@ -63,13 +71,35 @@ pub async fn init() -> Result<()> {
 /// Creates a pipeline entry in the table
 #[inline]
 pub async fn put(pipeline: &Pipeline) -> Result<()> {
+    put_with_version(pipeline, None).await
+}
+
+/// Creates a pipeline entry in the table, recording the given version in the pipeline history
+/// within the same transaction
+#[inline]
+pub async fn put_with_version(
+    pipeline: &Pipeline,
+    version: Option<&PipelineVersion>,
+) -> Result<()> {
     if CLIENT.get_by_id(&pipeline.id).await.is_ok() {
-        CLIENT.update(pipeline).await
+        CLIENT.update(pipeline, version).await
     } else {
-        CLIENT.put(pipeline).await
+        CLIENT.put(pipeline, version).await
     }
 }
 
+/// Lists all the stored versions of the pipeline, latest first
+#[inline]
+pub async fn list_versions(pipeline_id: &str) -> Result<Vec<PipelineVersion>> {
+    CLIENT.list_versions(pipeline_id).await
+}
+
+/// Finds the given version of the pipeline
+#[inline]
+pub async fn get_version(pipeline_id: &str, version: i32) -> Result<PipelineVersion> {
+    CLIENT.get_version(pipeline_id, version).await
+}
+
 /// Finds the pipeline associated with the StreamParams within an organization
 #[inline]
 pub async fn get_by_stream(stream_params: &StreamParams) -> Result<Pipeline> {
@@ -129,3 +159,16 @@ pub async fn drop_table() -> Result<()> {
     CLIENT.drop_table().await?;
     Ok(())
 }
+
+fn parse_version_row(
+    (pipeline_id, version, author, comment, created_at, data): PipelineVersionRow,
+) -> Result<PipelineVersion> {
+    Ok(PipelineVersion {
+        pipeline_id,
+        version,
+        author,
+        comment,
+        created_at,
+        pipeline: json::from_str(&data)?,
+    })
+}
@@ -16,11 +16,12 @@
 use async_trait::async_trait;
 use config::{
     meta::{
-        pipeline::{Pipeline, components::PipelineSource},
+        pipeline::{Pipeline, PipelineVersion, components::PipelineSource},
         stream::StreamParams,
     },
     utils::json,
 };
+use sqlx::{MySql, Transaction};
 
 use crate::{
     db::mysql::{CLIENT, CLIENT_DDL, CLIENT_RO},
@@ -63,6 +64,7 @@ CREATE TABLE IF NOT EXISTS pipeline
     derived_stream  TEXT,
     nodes           TEXT,
     edges           TEXT,
+    dead_letter     TEXT,
     created_at      TIMESTAMP DEFAULT CURRENT_TIMESTAMP
 );
             "#,
@@ -70,6 +72,24 @@ CREATE TABLE IF NOT EXISTS pipeline
         .execute(&pool)
         .await?;
 
+        sqlx::query(
+            r#"
+CREATE TABLE IF NOT EXISTS pipeline_history
+(
+    id              BIGINT not null primary key AUTO_INCREMENT,
+    pipeline_id     VARCHAR(256) not null,
+    version         INT not null,
+    org             VARCHAR(100) not null,
+    author          VARCHAR(256) not null,
+    comment         TEXT,
+    created_at      BIGINT not null,
+    data            LONGTEXT not null
+);
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
         Ok(())
     }
 
@@ -79,6 +99,7 @@ CREATE TABLE IF NOT EXISTS pipeline
         let queries = vec![
             "CREATE INDEX pipeline_org_idx ON pipeline (org);",
             "CREATE INDEX pipeline_org_src_type_stream_params_idx ON pipeline (org, source_type, stream_org, stream_name, stream_type);",
+            "CREATE UNIQUE INDEX pipeline_history_pipeline_id_version_idx ON pipeline_history (pipeline_id, version);",
         ];
 
         for query in queries {
@@ -100,11 +121,18 @@ CREATE TABLE IF NOT EXISTS pipeline
         sqlx::query("DROP TABLE IF EXISTS pipeline;")
             .execute(&pool)
             .await?;
+        sqlx::query("DROP TABLE IF EXISTS pipeline_history;")
+            .execute(&pool)
+            .await?;
 
         Ok(())
     }
 
-    async fn put(&self, pipeline: &Pipeline) -> Result<()> {
+    async fn put(&self, pipeline: &Pipeline, version: Option<&PipelineVersion>) -> Result<()> {
+        let dead_letter = pipeline
+            .dead_letter
+            .as_ref()
+            .map(|dl| json::to_string(dl).expect("Serializing pipeline dead letter error"));
         let pool = CLIENT.clone();
         let mut tx = pool.begin().await?;
 
@@ -118,8 +146,8 @@ CREATE TABLE IF NOT EXISTS pipeline
                 );
                 sqlx::query(
                     r#"
-INSERT IGNORE INTO pipeline (id, version, enabled, name, description, org, source_type, stream_org, stream_name, stream_type, nodes, edges)
-    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?);
+INSERT IGNORE INTO pipeline (id, version, enabled, name, description, org, source_type, stream_org, stream_name, stream_type, nodes, edges, dead_letter)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?);
                     "#,
                 )
                 .bind(&pipeline.id)
@@ -134,6 +162,7 @@ INSERT IGNORE INTO pipeline (id, version, enabled, name, description, org, sourc
                 .bind(stream_type)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .execute(&mut *tx)
                 .await
             }
@@ -145,8 +174,8 @@ INSERT IGNORE INTO pipeline (id, version, enabled, name, description, org, sourc
                 );
                 sqlx::query(
                     r#"
-INSERT IGNORE INTO pipeline (id, version, enabled, name, description, org, source_type, derived_stream, nodes, edges)
-    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?);
+INSERT IGNORE INTO pipeline (id, version, enabled, name, description, org, source_type, derived_stream, nodes, edges, dead_letter)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?);
                     "#,
                 )
                 .bind(&pipeline.id)
@@ -159,6 +188,7 @@ INSERT IGNORE INTO pipeline (id, version, enabled, name, description, org, sourc
                 .bind(derived_stream_str)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .execute(&mut *tx)
                 .await
             }
@@ -169,6 +199,15 @@ INSERT IGNORE INTO pipeline (id, version, enabled, name, description, org, sourc
             return Err(e.into());
         }
 
+        if let Some(version) = version {
+            if let Err(e) = insert_version(&mut tx, version).await {
+                if let Err(e) = tx.rollback().await {
+                    log::error!("[MYSQL] rollback push pipeline version error: {}", e);
+                }
+                return Err(e.into());
+            }
+        }
+
         if let Err(e) = tx.commit().await {
             log::error!("[MYSQL] commit push pipeline error: {}", e);
             return Err(e.into());
@@ -177,7 +216,11 @@ INSERT IGNORE INTO pipeline (id, version, enabled, name, description, org, sourc
         Ok(())
     }
 
-    async fn update(&self, pipeline: &Pipeline) -> Result<()> {
+    async fn update(&self, pipeline: &Pipeline, version: Option<&PipelineVersion>) -> Result<()> {
+        let dead_letter = pipeline
+            .dead_letter
+            .as_ref()
+            .map(|dl| json::to_string(dl).expect("Serializing pipeline dead letter error"));
         let pool = CLIENT.clone();
         let mut tx = pool.begin().await?;
 
@@ -192,7 +235,7 @@ INSERT IGNORE INTO pipeline (id, version, enabled, name, description, org, sourc
                 sqlx::query(
                     r#"
 UPDATE pipeline
-    SET version = ?, enabled = ?, name = ?, description = ?, org = ?, source_type = ?, stream_org = ?, stream_name = ?, stream_type = ?, nodes = ?, edges = ?
+    SET version = ?, enabled = ?, name = ?, description = ?, org = ?, source_type = ?, stream_org = ?, stream_name = ?, stream_type = ?, nodes = ?, edges = ?, dead_letter = ?
     WHERE id =?;
                     "#,
                 )
@@ -207,6 +250,7 @@ UPDATE pipeline
                 .bind(stream_type)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .bind(&pipeline.id)
                 .execute(&mut *tx)
                 .await
@@ -220,7 +264,7 @@ UPDATE pipeline
                 sqlx::query(
                     r#"
 UPDATE pipeline
-    SET version = ?, enabled = ?, name = ?, description = ?, org = ?, source_type = ?, derived_stream = ?, nodes = ?, edges = ?
+    SET version = ?, enabled = ?, name = ?, description = ?, org = ?, source_type = ?, derived_stream = ?, nodes = ?, edges = ?, dead_letter = ?
     WHERE id = ?;
                     "#,
                 )
@@ -233,6 +277,7 @@ UPDATE pipeline
                 .bind(derived_stream_str)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .bind(&pipeline.id)
                 .execute(&mut *tx)
                 .await
@@ -244,6 +289,15 @@ UPDATE pipeline
             return Err(e.into());
         }
 
+        if let Some(version) = version {
+            if let Err(e) = insert_version(&mut tx, version).await {
+                if let Err(e) = tx.rollback().await {
+                    log::error!("[MYSQL] rollback push pipeline version error: {}", e);
+                }
+                return Err(e.into());
+            }
+        }
+
         if let Err(e) = tx.commit().await {
             log::error!("[MYSQL] commit push pipeline error: {}", e);
             return Err(e.into());
@@ -351,6 +405,32 @@ SELECT * FROM pipeline WHERE org = ? AND source_type = ? ORDER BY id;
         }
     }
 
+    async fn list_versions(&self, pipeline_id: &str) -> Result<Vec<PipelineVersion>> {
+        let pool = CLIENT_RO.clone();
+        let query = r#"
+SELECT pipeline_id, version, author, comment, created_at, data FROM pipeline_history WHERE pipeline_id = ? ORDER BY version DESC;
+        "#;
+        let rows = sqlx::query_as::<_, super::PipelineVersionRow>(query)
+            .bind(pipeline_id)
+            .fetch_all(&pool)
+            .await?;
+        rows.into_iter().map(super::parse_version_row).collect()
+    }
+
+    async fn get_version(&self, pipeline_id: &str, version: i32) -> Result<PipelineVersion> {
+        let pool = CLIENT_RO.clone();
+        let query = r#"
+SELECT pipeline_id, version, author, comment, created_at, data FROM pipeline_history WHERE pipeline_id = ? AND version = ?;
+        "#;
+        let row = sqlx::query_as::<_, super::PipelineVersionRow>(query)
+            .bind(pipeline_id)
+            .bind(version)
+            .fetch_one(&pool)
+            .await
+            .map_err(|_| Error::from(DbError::KeyNotExists(format!("{pipeline_id}/{version}"))))?;
+        super::parse_version_row(row)
+    }
+
     async fn delete(&self, pipeline_id: &str) -> Result<Pipeline> {
         let pool = CLIENT.clone();
         let pipeline = sqlx::query_as::<_, Pipeline>("SELECT * FROM pipeline WHERE id = ?;")
@@ -363,6 +443,33 @@ SELECT * FROM pipeline WHERE org = ? AND source_type = ? ORDER BY id;
             .execute(&pool)
             .await?;
 
+        sqlx::query(r#"DELETE FROM pipeline_history WHERE pipeline_id = ?;"#)
+            .bind(pipeline_id)
+            .execute(&pool)
+            .await?;
+
         Ok(pipeline)
     }
 }
+
+async fn insert_version(
+    tx: &mut Transaction<'_, MySql>,
+    version: &PipelineVersion,
+) -> std::result::Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+INSERT INTO pipeline_history (pipeline_id, version, org, author, comment, created_at, data)
+    VALUES (?, ?, ?, ?, ?, ?, ?);
+        "#,
+    )
+    .bind(&version.pipeline_id)
+    .bind(version.version)
+    .bind(&version.pipeline.org)
+    .bind(&version.author)
+    .bind(version.comment.as_deref())
+    .bind(version.created_at)
+    .bind(json::to_string(&version.pipeline).expect("Serializing pipeline version error"))
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
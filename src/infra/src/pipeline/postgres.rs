@@ -16,11 +16,12 @@
 use async_trait::async_trait;
 use config::{
     meta::{
-        pipeline::{Pipeline, components::PipelineSource},
+        pipeline::{Pipeline, PipelineVersion, components::PipelineSource},
         stream::StreamParams,
     },
     utils::json,
 };
+use sqlx::{Postgres, Transaction};
 
 use crate::{
     db::postgres::{CLIENT, CLIENT_DDL, CLIENT_RO},
@@ -62,12 +63,31 @@ CREATE TABLE IF NOT EXISTS pipeline
     derived_stream  TEXT,
     nodes           TEXT,
     edges           TEXT,
+    dead_letter     TEXT,
     created_at      TIMESTAMP default CURRENT_TIMESTAMP
 );
             "#,
         )
         .execute(&pool)
         .await?;
+
+        sqlx::query(
+            r#"
+CREATE TABLE IF NOT EXISTS pipeline_history
+(
+    id              BIGSERIAL PRIMARY KEY,
+    pipeline_id     VARCHAR(256) not null,
+    version         INT not null,
+    org             VARCHAR(100) not null,
+    author          VARCHAR(256) not null,
+    comment         TEXT,
+    created_at      BIGINT not null,
+    data            TEXT not null
+);
+            "#,
+        )
+        .execute(&pool)
+        .await?;
         Ok(())
     }
 
@@ -77,6 +97,7 @@ CREATE TABLE IF NOT EXISTS pipeline
         let queries = vec![
             "CREATE INDEX IF NOT EXISTS pipeline_org_idx ON pipeline (org);",
             "CREATE INDEX IF NOT EXISTS pipeline_org_src_type_stream_params_idx ON pipeline (org, source_type, stream_org, stream_name, stream_type);",
+            "CREATE UNIQUE INDEX IF NOT EXISTS pipeline_history_pipeline_id_version_idx ON pipeline_history (pipeline_id, version);",
         ];
 
         for query in queries {
@@ -96,10 +117,17 @@ CREATE TABLE IF NOT EXISTS pipeline
         sqlx::query("DROP TABLE IF EXISTS pipeline;")
             .execute(&pool)
             .await?;
+        sqlx::query("DROP TABLE IF EXISTS pipeline_history;")
+            .execute(&pool)
+            .await?;
         Ok(())
     }
 
-    async fn put(&self, pipeline: &Pipeline) -> Result<()> {
+    async fn put(&self, pipeline: &Pipeline, version: Option<&PipelineVersion>) -> Result<()> {
+        let dead_letter = pipeline
+            .dead_letter
+            .as_ref()
+            .map(|dl| json::to_string(dl).expect("Serializing pipeline dead letter error"));
         let pool = CLIENT.clone();
         let mut tx = pool.begin().await?;
 
@@ -113,8 +141,8 @@ CREATE TABLE IF NOT EXISTS pipeline
                 );
                 sqlx::query(
                     r#"
-INSERT INTO pipeline (id, version, enabled, name, description, org, source_type, stream_org, stream_name, stream_type, nodes, edges)
-    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+INSERT INTO pipeline (id, version, enabled, name, description, org, source_type, stream_org, stream_name, stream_type, nodes, edges, dead_letter)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
     ON CONFLICT DO NOTHING;
                     "#,
                 )
@@ -130,6 +158,7 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
                 .bind(stream_type)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .execute(&mut *tx)
                 .await
             }
@@ -142,8 +171,8 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
 
                 sqlx::query(
                     r#"
-INSERT INTO pipeline (id, version, enabled, name, description, org, source_type, derived_stream, nodes, edges)
-    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+INSERT INTO pipeline (id, version, enabled, name, description, org, source_type, derived_stream, nodes, edges, dead_letter)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
     ON CONFLICT DO NOTHING;
                     "#,
                 )
@@ -157,6 +186,7 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
                 .bind(derived_stream_str)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .execute(&mut *tx)
                 .await
             }
@@ -167,6 +197,15 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
             return Err(e.into());
         }
 
+        if let Some(version) = version {
+            if let Err(e) = insert_version(&mut tx, version).await {
+                if let Err(e) = tx.rollback().await {
+                    log::error!("[POSTGRES] rollback push pipeline version error: {}", e);
+                }
+                return Err(e.into());
+            }
+        }
+
         if let Err(e) = tx.commit().await {
             log::error!("[POSTGRES] commit push pipeline error: {}", e);
             return Err(e.into());
@@ -174,7 +213,11 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
         Ok(())
     }
 
-    async fn update(&self, pipeline: &Pipeline) -> Result<()> {
+    async fn update(&self, pipeline: &Pipeline, version: Option<&PipelineVersion>) -> Result<()> {
+        let dead_letter = pipeline
+            .dead_letter
+            .as_ref()
+            .map(|dl| json::to_string(dl).expect("Serializing pipeline dead letter error"));
         let pool = CLIENT.clone();
         let mut tx = pool.begin().await?;
 
@@ -189,8 +232,8 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
                 sqlx::query(
                     r#"
 UPDATE pipeline
-    SET version = $1, enabled = $2, name = $3, description = $4, org = $5, source_type = $6, stream_org = $7, stream_name = $8, stream_type = $9, nodes = $10, edges = $11
-    WHERE id = $12;
+    SET version = $1, enabled = $2, name = $3, description = $4, org = $5, source_type = $6, stream_org = $7, stream_name = $8, stream_type = $9, nodes = $10, edges = $11, dead_letter = $12
+    WHERE id = $13;
                     "#,
                 )
                 .bind(pipeline.version)
@@ -204,6 +247,7 @@ UPDATE pipeline
                 .bind(stream_type)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .bind(&pipeline.id)
                 .execute(&mut *tx)
                 .await
@@ -218,8 +262,8 @@ UPDATE pipeline
                 sqlx::query(
                     r#"
 UPDATE pipeline
-    SET version = $1, enabled = $2, name = $3, description = $4, org = $5, source_type = $6, derived_stream = $7, nodes = $8, edges = $9
-    WHERE id = $10;
+    SET version = $1, enabled = $2, name = $3, description = $4, org = $5, source_type = $6, derived_stream = $7, nodes = $8, edges = $9, dead_letter = $10
+    WHERE id = $11;
                     "#,
                 )
                 .bind(pipeline.version)
@@ -231,6 +275,7 @@ UPDATE pipeline
                 .bind(derived_stream_str)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .bind(&pipeline.id)
                 .execute(&mut *tx)
                 .await
@@ -242,6 +287,15 @@ UPDATE pipeline
             return Err(e.into());
         }
 
+        if let Some(version) = version {
+            if let Err(e) = insert_version(&mut tx, version).await {
+                if let Err(e) = tx.rollback().await {
+                    log::error!("[POSTGRES] rollback push pipeline version error: {}", e);
+                }
+                return Err(e.into());
+            }
+        }
+
         if let Err(e) = tx.commit().await {
             log::error!("[POSTGRES] commit push pipeline error: {}", e);
             return Err(e.into());
@@ -350,6 +404,32 @@ SELECT * FROM pipeline WHERE org = $1 AND source_type = $2 ORDER BY id;
         }
     }
 
+    async fn list_versions(&self, pipeline_id: &str) -> Result<Vec<PipelineVersion>> {
+        let pool = CLIENT_RO.clone();
+        let query = r#"
+SELECT pipeline_id, version, author, comment, created_at, data FROM pipeline_history WHERE pipeline_id = $1 ORDER BY version DESC;
+        "#;
+        let rows = sqlx::query_as::<_, super::PipelineVersionRow>(query)
+            .bind(pipeline_id)
+            .fetch_all(&pool)
+            .await?;
+        rows.into_iter().map(super::parse_version_row).collect()
+    }
+
+    async fn get_version(&self, pipeline_id: &str, version: i32) -> Result<PipelineVersion> {
+        let pool = CLIENT_RO.clone();
+        let query = r#"
+SELECT pipeline_id, version, author, comment, created_at, data FROM pipeline_history WHERE pipeline_id = $1 AND version = $2;
+        "#;
+        let row = sqlx::query_as::<_, super::PipelineVersionRow>(query)
+            .bind(pipeline_id)
+            .bind(version)
+            .fetch_one(&pool)
+            .await
+            .map_err(|_| Error::from(DbError::KeyNotExists(format!("{pipeline_id}/{version}"))))?;
+        super::parse_version_row(row)
+    }
+
     async fn delete(&self, pipeline_id: &str) -> Result<Pipeline> {
         let pool = CLIENT.clone();
 
@@ -363,6 +443,33 @@ SELECT * FROM pipeline WHERE org = $1 AND source_type = $2 ORDER BY id;
             .execute(&pool)
             .await?;
 
+        sqlx::query(r#"DELETE FROM pipeline_history WHERE pipeline_id = $1;"#)
+            .bind(pipeline_id)
+            .execute(&pool)
+            .await?;
+
         Ok(pipeline)
     }
 }
+
+async fn insert_version(
+    tx: &mut Transaction<'_, Postgres>,
+    version: &PipelineVersion,
+) -> std::result::Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+INSERT INTO pipeline_history (pipeline_id, version, org, author, comment, created_at, data)
+    VALUES ($1, $2, $3, $4, $5, $6, $7);
+        "#,
+    )
+    .bind(&version.pipeline_id)
+    .bind(version.version)
+    .bind(&version.pipeline.org)
+    .bind(&version.author)
+    .bind(version.comment.as_deref())
+    .bind(version.created_at)
+    .bind(json::to_string(&version.pipeline).expect("Serializing pipeline version error"))
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
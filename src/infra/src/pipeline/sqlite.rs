@@ -16,11 +16,12 @@
 use async_trait::async_trait;
 use config::{
     meta::{
-        pipeline::{Pipeline, components::PipelineSource},
+        pipeline::{Pipeline, PipelineVersion, components::PipelineSource},
         stream::StreamParams,
     },
     utils::json,
 };
+use sqlx::{Sqlite, Transaction};
 
 use crate::{
     db::sqlite::{CLIENT_RO, CLIENT_RW},
@@ -63,12 +64,31 @@ CREATE TABLE IF NOT EXISTS pipeline
     derived_stream  TEXT,
     nodes           TEXT,
     edges           TEXT,
+    dead_letter     TEXT,
     created_at      TIMESTAMP default CURRENT_TIMESTAMP
 );
             "#,
         )
         .execute(&*client)
         .await?;
+
+        sqlx::query(
+            r#"
+CREATE TABLE IF NOT EXISTS pipeline_history
+(
+    id              INTEGER not null primary key autoincrement,
+    pipeline_id     VARCHAR(256) not null,
+    version         INT not null,
+    org             VARCHAR(100) not null,
+    author          VARCHAR(256) not null,
+    comment         TEXT,
+    created_at      BIGINT not null,
+    data            TEXT not null
+);
+            "#,
+        )
+        .execute(&*client)
+        .await?;
         Ok(())
     }
 
@@ -78,6 +98,7 @@ CREATE TABLE IF NOT EXISTS pipeline
         let queries = vec![
             "CREATE INDEX IF NOT EXISTS pipeline_org_idx ON pipeline (org);",
             "CREATE INDEX IF NOT EXISTS pipeline_org_src_type_stream_params_idx ON pipeline (org, source_type, stream_org, stream_name, stream_type);",
+            "CREATE UNIQUE INDEX IF NOT EXISTS pipeline_history_pipeline_id_version_idx ON pipeline_history (pipeline_id, version);",
         ];
 
         for query in queries {
@@ -92,10 +113,17 @@ CREATE TABLE IF NOT EXISTS pipeline
         sqlx::query("DROP TABLE IF EXISTS pipeline;")
             .execute(&*client)
             .await?;
+        sqlx::query("DROP TABLE IF EXISTS pipeline_history;")
+            .execute(&*client)
+            .await?;
         Ok(())
     }
 
-    async fn put(&self, pipeline: &Pipeline) -> Result<()> {
+    async fn put(&self, pipeline: &Pipeline, version: Option<&PipelineVersion>) -> Result<()> {
+        let dead_letter = pipeline
+            .dead_letter
+            .as_ref()
+            .map(|dl| json::to_string(dl).expect("Serializing pipeline dead letter error"));
         let client = CLIENT_RW.clone();
         let client = client.lock().await;
         let mut tx = client.begin().await?;
@@ -110,8 +138,8 @@ CREATE TABLE IF NOT EXISTS pipeline
                 );
                 sqlx::query(
                     r#"
-INSERT INTO pipeline (id, version, enabled, name, description, org, source_type, stream_org, stream_name, stream_type, nodes, edges)
-    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+INSERT INTO pipeline (id, version, enabled, name, description, org, source_type, stream_org, stream_name, stream_type, nodes, edges, dead_letter)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
     ON CONFLICT DO NOTHING;
                     "#,
                 )
@@ -127,6 +155,7 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
                 .bind(stream_type)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .execute(&mut *tx)
                 .await
             }
@@ -138,8 +167,8 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
                 );
                 sqlx::query(
                     r#"
-INSERT INTO pipeline (id, version, enabled, name, description, org, source_type, derived_stream, nodes, edges)
-    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+INSERT INTO pipeline (id, version, enabled, name, description, org, source_type, derived_stream, nodes, edges, dead_letter)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
     ON CONFLICT DO NOTHING;
                     "#,
                 )
@@ -153,6 +182,7 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
                 .bind(derived_stream_str)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .execute(&mut *tx)
                 .await
             }
@@ -163,6 +193,15 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
             return Err(e.into());
         }
 
+        if let Some(version) = version {
+            if let Err(e) = insert_version(&mut tx, version).await {
+                if let Err(e) = tx.rollback().await {
+                    log::error!("[SQLITE] rollback push pipeline version error: {}", e);
+                }
+                return Err(e.into());
+            }
+        }
+
         if let Err(e) = tx.commit().await {
             log::error!("[SQLITE] commit push pipeline error: {}", e);
             return Err(e.into());
@@ -173,7 +212,11 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
         Ok(())
     }
 
-    async fn update(&self, pipeline: &Pipeline) -> Result<()> {
+    async fn update(&self, pipeline: &Pipeline, version: Option<&PipelineVersion>) -> Result<()> {
+        let dead_letter = pipeline
+            .dead_letter
+            .as_ref()
+            .map(|dl| json::to_string(dl).expect("Serializing pipeline dead letter error"));
         let client = CLIENT_RW.clone();
         let client = client.lock().await;
         let mut tx = client.begin().await?;
@@ -189,8 +232,8 @@ INSERT INTO pipeline (id, version, enabled, name, description, org, source_type,
                 sqlx::query(
                     r#"
 UPDATE pipeline
-    SET version = $1, enabled = $2, name = $3, description = $4, org = $5, source_type = $6, stream_org = $7, stream_name = $8, stream_type = $9, nodes = $10, edges = $11
-    WHERE id = $12;
+    SET version = $1, enabled = $2, name = $3, description = $4, org = $5, source_type = $6, stream_org = $7, stream_name = $8, stream_type = $9, nodes = $10, edges = $11, dead_letter = $12
+    WHERE id = $13;
                     "#,
                 )
                 .bind(pipeline.version)
@@ -204,6 +247,7 @@ UPDATE pipeline
                 .bind(stream_type)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .bind(&pipeline.id)
                 .execute(&mut *tx)
                 .await
@@ -217,8 +261,8 @@ UPDATE pipeline
                 sqlx::query(
                     r#"
 UPDATE pipeline
-    SET version = $1, enabled = $2, name = $3, description = $4, org = $5, source_type = $6, derived_stream = $7, nodes = $8, edges = $9
-    WHERE id = $10;
+    SET version = $1, enabled = $2, name = $3, description = $4, org = $5, source_type = $6, derived_stream = $7, nodes = $8, edges = $9, dead_letter = $10
+    WHERE id = $11;
                     "#,
                 )
                 .bind(pipeline.version)
@@ -230,6 +274,7 @@ UPDATE pipeline
                 .bind(derived_stream_str)
                 .bind(json::to_string(&pipeline.nodes).expect("Serializing pipeline nodes error"))
                 .bind(json::to_string(&pipeline.edges).expect("Serializing pipeline edges error"))
+                .bind(dead_letter.as_deref())
                 .bind(&pipeline.id)
                 .execute(&mut *tx)
                 .await
@@ -241,6 +286,15 @@ UPDATE pipeline
             return Err(e.into());
         }
 
+        if let Some(version) = version {
+            if let Err(e) = insert_version(&mut tx, version).await {
+                if let Err(e) = tx.rollback().await {
+                    log::error!("[SQLITE] rollback push pipeline version error: {}", e);
+                }
+                return Err(e.into());
+            }
+        }
+
         if let Err(e) = tx.commit().await {
             log::error!("[SQLITE] commit push pipeline error: {}", e);
             return Err(e.into());
@@ -353,6 +407,32 @@ SELECT * FROM pipeline WHERE org = $1 AND source_type = $2 ORDER BY id;
         }
     }
 
+    async fn list_versions(&self, pipeline_id: &str) -> Result<Vec<PipelineVersion>> {
+        let pool = CLIENT_RO.clone();
+        let query = r#"
+SELECT pipeline_id, version, author, comment, created_at, data FROM pipeline_history WHERE pipeline_id = $1 ORDER BY version DESC;
+        "#;
+        let rows = sqlx::query_as::<_, super::PipelineVersionRow>(query)
+            .bind(pipeline_id)
+            .fetch_all(&pool)
+            .await?;
+        rows.into_iter().map(super::parse_version_row).collect()
+    }
+
+    async fn get_version(&self, pipeline_id: &str, version: i32) -> Result<PipelineVersion> {
+        let pool = CLIENT_RO.clone();
+        let query = r#"
+SELECT pipeline_id, version, author, comment, created_at, data FROM pipeline_history WHERE pipeline_id = $1 AND version = $2;
+        "#;
+        let row = sqlx::query_as::<_, super::PipelineVersionRow>(query)
+            .bind(pipeline_id)
+            .bind(version)
+            .fetch_one(&pool)
+            .await
+            .map_err(|_| Error::from(DbError::KeyNotExists(format!("{pipeline_id}/{version}"))))?;
+        super::parse_version_row(row)
+    }
+
     async fn delete(&self, pipeline_id: &str) -> Result<Pipeline> {
         let client = CLIENT_RW.clone();
         let client = client.lock().await;
@@ -368,6 +448,11 @@ SELECT * FROM pipeline WHERE org = $1 AND source_type = $2 ORDER BY id;
             .execute(&mut *tx)
             .await?;
 
+        sqlx::query(r#"DELETE FROM pipeline_history WHERE pipeline_id = $1;"#)
+            .bind(pipeline_id)
+            .execute(&mut *tx)
+            .await?;
+
         if let Err(e) = tx.commit().await {
             log::error!("[SQLITE] commit delete pipeline error: {}", e);
             return Err(e.into());
@@ -378,3 +463,25 @@ SELECT * FROM pipeline WHERE org = $1 AND source_type = $2 ORDER BY id;
         Ok(pipeline)
     }
 }
+
+async fn insert_version(
+    tx: &mut Transaction<'_, Sqlite>,
+    version: &PipelineVersion,
+) -> std::result::Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+INSERT INTO pipeline_history (pipeline_id, version, org, author, comment, created_at, data)
+    VALUES ($1, $2, $3, $4, $5, $6, $7);
+        "#,
+    )
+    .bind(&version.pipeline_id)
+    .bind(version.version)
+    .bind(&version.pipeline.org)
+    .bind(&version.author)
+    .bind(version.comment.as_deref())
+    .bind(version.created_at)
+    .bind(json::to_string(&version.pipeline).expect("Serializing pipeline version error"))
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
@@ -13,7 +13,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::ops::Range;
+use std::{ops::Range, sync::Arc};
 
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -22,12 +22,12 @@ use futures::stream::BoxStream;
 use hashbrown::{HashMap, HashSet};
 use object_store::{
     GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOpts,
-    PutOptions, PutPayload, PutResult, Result, path::Path,
+    PutOptions, PutPayload, PutResult, Result, path::Path, signer::Signer,
 };
 
 use crate::storage::{ObjectStoreExt, get_stream_from_file, remote::StorageConfig};
 
-const DEFAULT_ACCOUNT: &str = "default";
+pub(crate) const DEFAULT_ACCOUNT: &str = "default";
 
 pub struct StorageClientFactory {
     accounts: HashMap<String, Box<dyn ObjectStore>>,
@@ -120,6 +120,25 @@ impl StorageClientFactory {
     }
 }
 
+/// Builds the signers of the accounts, which make presigned urls for their objects. The local
+/// disk storage has none.
+pub(crate) fn signers() -> HashMap<String, Arc<dyn Signer>> {
+    if is_local_disk_storage() {
+        return HashMap::new();
+    }
+    let (_, accounts) = parse_storage_config(&get_config().s3);
+    accounts
+        .into_iter()
+        .filter_map(|(name, config)| match super::remote::init_signer(config) {
+            Ok(signer) => Some((name, signer)),
+            Err(e) => {
+                log::warn!("[STORAGE] init signer for account {name} error: {e}");
+                None
+            }
+        })
+        .collect()
+}
+
 pub fn parse_storage_config(
     config: &config::S3,
 ) -> (StreamStrategy, HashMap<String, StorageConfig>) {
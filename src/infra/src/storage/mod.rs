@@ -13,7 +13,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{fmt::Debug, ops::Range, sync::Arc};
+use std::{fmt::Debug, ops::Range, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use bytes::{Bytes, buf::Buf};
@@ -23,7 +23,7 @@ use futures::{StreamExt, TryStreamExt, stream::BoxStream};
 use hashbrown::HashMap;
 use object_store::{
     GetOptions, GetRange, GetResult, ListResult, MultipartUpload, ObjectMeta, PutMultipartOpts,
-    PutOptions, PutPayload, PutResult, Result, WriteMultipart, path::Path,
+    PutOptions, PutPayload, PutResult, Result, WriteMultipart, path::Path, signer::Signer,
 };
 use once_cell::sync::Lazy;
 use parquet::file::metadata::ParquetMetaDataReader;
@@ -39,6 +39,8 @@ pub const CONCURRENT_REQUESTS: usize = 1000;
 
 static MULTI_ACCOUNTS: Lazy<Box<dyn ObjectStoreExt>> = Lazy::new(accounts::default);
 
+static SIGNERS: Lazy<HashMap<String, Arc<dyn Signer>>> = Lazy::new(accounts::signers);
+
 // Create a wrapper trait that extends ObjectStore
 #[async_trait]
 pub trait ObjectStoreExt: std::fmt::Display + Send + Sync + Debug + 'static {
@@ -166,6 +168,36 @@ pub async fn put_multipart(account: &str, file: &str, data: bytes::Bytes) -> Res
     Ok(())
 }
 
+/// Whether the account is one of the configured accounts, an empty name is the default
+/// account.
+pub fn is_account_configured(account: &str) -> bool {
+    if account.is_empty() || account == accounts::DEFAULT_ACCOUNT {
+        return true;
+    }
+    if is_local_disk_storage() {
+        return false;
+    }
+    let (_, accounts) = accounts::parse_storage_config(&get_config().s3);
+    accounts.contains_key(account)
+}
+
+/// Returns a presigned url to download the file for the given duration. The local disk storage
+/// doesn't support presigned urls.
+pub async fn signed_url(account: &str, file: &str, expires_in: Duration) -> Result<String> {
+    let signer = SIGNERS
+        .get(account)
+        .or_else(|| SIGNERS.get(accounts::DEFAULT_ACCOUNT))
+        .ok_or(object_store::Error::NotImplemented)?;
+    let url = signer
+        .signed_url(
+            reqwest::Method::GET,
+            &format_key(file, true).into(),
+            expires_in,
+        )
+        .await?;
+    Ok(url.to_string())
+}
+
 /// Delete files from the object store.
 /// params: account, file
 pub async fn del(files: Vec<(&str, &str)>) -> Result<()> {
@@ -13,7 +13,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{ops::Range, time::Duration};
+use std::{ops::Range, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -22,6 +22,7 @@ use futures::stream::BoxStream;
 use object_store::{
     Error, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
     PutMultipartOpts, PutOptions, PutPayload, PutResult, Result, limit::LimitStore, path::Path,
+    signer::Signer,
 };
 
 use crate::storage::{CONCURRENT_REQUESTS, format_key};
@@ -389,6 +390,16 @@ fn init_client(config: StorageConfig) -> Box<dyn object_store::ObjectStore> {
     }
 }
 
+/// Builds a signer making presigned urls for the objects of the account.
+pub(crate) fn init_signer(config: StorageConfig) -> Result<Arc<dyn Signer>> {
+    let provider = config.provider.to_string();
+    match provider.as_str() {
+        "azure" => Ok(Arc::new(init_azure_config(config)?)),
+        "gcs" | "gcp" => Ok(Arc::new(init_gcp_config(config)?)),
+        _ => Ok(Arc::new(init_aws_config(config)?)),
+    }
+}
+
 pub async fn test_config() -> Result<(), anyhow::Error> {
     // Test download
     match super::get("", TEST_FILE).await {
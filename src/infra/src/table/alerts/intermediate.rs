@@ -205,12 +205,16 @@ pub enum QueryType {
     Custom,
     Sql,
     Promql,
+    Anomaly,
+    Composite,
 }
 
 impl QueryType {
     const CUSTOM: i16 = 0;
     const SQL: i16 = 1;
     const PROMQL: i16 = 2;
+    const ANOMALY: i16 = 3;
+    const COMPOSITE: i16 = 4;
 }
 
 impl From<QueryType> for i16 {
@@ -219,6 +223,8 @@ impl From<QueryType> for i16 {
             QueryType::Custom => QueryType::CUSTOM,
             QueryType::Sql => QueryType::SQL,
             QueryType::Promql => QueryType::PROMQL,
+            QueryType::Anomaly => QueryType::ANOMALY,
+            QueryType::Composite => QueryType::COMPOSITE,
         }
     }
 }
@@ -231,6 +237,8 @@ impl TryFrom<i16> for QueryType {
             Self::CUSTOM => Ok(QueryType::Custom),
             Self::SQL => Ok(QueryType::Sql),
             Self::PROMQL => Ok(QueryType::Promql),
+            Self::ANOMALY => Ok(QueryType::Anomaly),
+            Self::COMPOSITE => Ok(QueryType::Composite),
             _ => Err(FromI16Error {
                 value,
                 ty: "QueryType".to_string(),
@@ -245,6 +253,8 @@ impl From<MetaQueryType> for QueryType {
             MetaQueryType::Custom => QueryType::Custom,
             MetaQueryType::SQL => QueryType::Sql,
             MetaQueryType::PromQL => QueryType::Promql,
+            MetaQueryType::Anomaly => QueryType::Anomaly,
+            MetaQueryType::Composite => QueryType::Composite,
         }
     }
 }
@@ -255,6 +265,8 @@ impl From<QueryType> for MetaQueryType {
             QueryType::Custom => MetaQueryType::Custom,
             QueryType::Sql => MetaQueryType::SQL,
             QueryType::Promql => MetaQueryType::PromQL,
+            QueryType::Anomaly => MetaQueryType::Anomaly,
+            QueryType::Composite => MetaQueryType::Composite,
         }
     }
 }
@@ -311,6 +323,26 @@ pub enum ConditionOperator {
     Contains,
     #[serde(rename = "not_contains")]
     NotContains,
+    #[serde(rename = "regex")]
+    Regex,
+    #[serde(rename = "in")]
+    In,
+    #[serde(rename = "not_in")]
+    NotIn,
+    #[serde(rename = "exists")]
+    Exists,
+    #[serde(rename = "not_exists")]
+    NotExists,
+    #[serde(rename = "starts_with")]
+    StartsWith,
+    #[serde(rename = "ends_with")]
+    EndsWith,
+    #[serde(rename = "cidr_match")]
+    CidrMatch,
+    #[serde(rename = "between")]
+    Between,
+    #[serde(rename = "not_between")]
+    NotBetween,
 }
 
 impl From<MetaOperator> for ConditionOperator {
@@ -324,6 +356,16 @@ impl From<MetaOperator> for ConditionOperator {
             MetaOperator::LessThanEquals => Self::LessThanEquals,
             MetaOperator::Contains => Self::Contains,
             MetaOperator::NotContains => Self::NotContains,
+            MetaOperator::Regex => Self::Regex,
+            MetaOperator::In => Self::In,
+            MetaOperator::NotIn => Self::NotIn,
+            MetaOperator::Exists => Self::Exists,
+            MetaOperator::NotExists => Self::NotExists,
+            MetaOperator::StartsWith => Self::StartsWith,
+            MetaOperator::EndsWith => Self::EndsWith,
+            MetaOperator::CidrMatch => Self::CidrMatch,
+            MetaOperator::Between => Self::Between,
+            MetaOperator::NotBetween => Self::NotBetween,
         }
     }
 }
@@ -339,6 +381,16 @@ impl From<ConditionOperator> for MetaOperator {
             ConditionOperator::LessThanEquals => Self::LessThanEquals,
             ConditionOperator::Contains => Self::Contains,
             ConditionOperator::NotContains => Self::NotContains,
+            ConditionOperator::Regex => Self::Regex,
+            ConditionOperator::In => Self::In,
+            ConditionOperator::NotIn => Self::NotIn,
+            ConditionOperator::Exists => Self::Exists,
+            ConditionOperator::NotExists => Self::NotExists,
+            ConditionOperator::StartsWith => Self::StartsWith,
+            ConditionOperator::EndsWith => Self::EndsWith,
+            ConditionOperator::CidrMatch => Self::CidrMatch,
+            ConditionOperator::Between => Self::Between,
+            ConditionOperator::NotBetween => Self::NotBetween,
         }
     }
 }
@@ -18,7 +18,7 @@ use std::str::FromStr;
 use chrono::{DateTime, FixedOffset, TimeZone, Utc};
 use config::meta::{
     alerts::{
-        ConditionList, QueryCondition as MetaQueryCondition,
+        AnomalyCondition, CompositeCondition, ConditionList, QueryCondition as MetaQueryCondition,
         TriggerCondition as MetaTriggerCondition,
         alert::{Alert as MetaAlert, ListAlertsParams},
     },
@@ -90,6 +90,16 @@ impl TryFrom<alerts::Model> for MetaAlert {
             .query_multi_time_range
             .map(serde_json::from_value)
             .transpose()?;
+        let query_anomaly: Option<AnomalyCondition> = value
+            .query_anomaly
+            .map(serde_json::from_value)
+            .transpose()?;
+        let query_composite: Option<CompositeCondition> = value
+            .query_composite
+            .map(serde_json::from_value)
+            .transpose()?;
+        let depends_on: Option<Vec<String>> =
+            value.depends_on.map(serde_json::from_value).transpose()?;
 
         // Transform the Unix timestamp into a date time that will always use
         // the UTC timezone.
@@ -114,6 +124,7 @@ impl TryFrom<alerts::Model> for MetaAlert {
         alert.owner = value.owner;
         alert.last_edited_by = value.last_edited_by;
         alert.updated_at = updated_at_utc;
+        alert.depends_on = depends_on.unwrap_or_default();
         alert.query_condition = MetaQueryCondition {
             query_type: query_type.into(),
             conditions: query_conditions,
@@ -125,6 +136,8 @@ impl TryFrom<alerts::Model> for MetaAlert {
             search_event_type: query_search_event_type.map(|t| t.into()),
             multi_time_range: query_multi_time_range
                 .map(|ds| ds.into_iter().map(|d| d.into()).collect()),
+            anomaly: query_anomaly,
+            composite: query_composite,
         };
         alert.trigger_condition = MetaTriggerCondition {
             align_time: value.align_time,
@@ -626,6 +639,20 @@ fn update_mutable_fields(
         })
         .map(serde_json::to_value)
         .transpose()?;
+    let query_anomaly = alert
+        .query_condition
+        .anomaly
+        .map(serde_json::to_value)
+        .transpose()?;
+    let query_composite = alert
+        .query_condition
+        .composite
+        .map(serde_json::to_value)
+        .transpose()?;
+    let depends_on = Some(alert.depends_on)
+        .filter(|ids| !ids.is_empty())
+        .map(serde_json::to_value)
+        .transpose()?;
     let trigger_threshold_operator: String =
         intermediate::TriggerThresholdOperator::try_from(alert.trigger_condition.operator)
             .map_err(|_| {
@@ -667,6 +694,9 @@ fn update_mutable_fields(
     alert_am.query_vrl_function = Set(query_vrl_function);
     alert_am.query_search_event_type = Set(query_search_event_type);
     alert_am.query_multi_time_range = Set(query_multi_time_range);
+    alert_am.query_anomaly = Set(query_anomaly);
+    alert_am.query_composite = Set(query_composite);
+    alert_am.depends_on = Set(depends_on);
     alert_am.trigger_threshold_operator = Set(trigger_threshold_operator);
     alert_am.trigger_period_seconds = Set(trigger_period_seconds);
     alert_am.trigger_threshold_count = Set(trigger_threshold_count);
@@ -0,0 +1,167 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use config::{meta::api_key::ApiKey, utils::json};
+use sea_orm::{ColumnTrait, EntityTrait, Order, QueryFilter, QueryOrder, Set, SqlErr};
+
+use super::{entity::api_keys::*, get_lock};
+use crate::{
+    db::{ORM_CLIENT, connect_to_orm},
+    errors,
+};
+
+/// An API key along with the hash of the key it was issued for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiKeyRecord {
+    pub key_hash: String,
+    pub api_key: ApiKey,
+}
+
+impl TryFrom<Model> for ApiKeyRecord {
+    type Error = errors::Error;
+
+    fn try_from(model: Model) -> Result<Self, Self::Error> {
+        Ok(ApiKeyRecord {
+            key_hash: model.key_hash,
+            api_key: ApiKey {
+                id: model.id,
+                org_id: model.org,
+                user_email: model.user_email,
+                name: model.name,
+                key_prefix: model.key_prefix,
+                // scopes that can't be read must not turn into an unrestricted key
+                scopes: json::from_value(model.scopes)?,
+                streams: json::from_value(model.streams)?,
+                expires_at: model.expires_at,
+                last_used_at: model.last_used_at,
+                created_at: model.created_at,
+            },
+        })
+    }
+}
+
+pub async fn add(record: &ApiKeyRecord) -> Result<(), errors::Error> {
+    let api_key = &record.api_key;
+    let model = ActiveModel {
+        id: Set(api_key.id.clone()),
+        org: Set(api_key.org_id.clone()),
+        user_email: Set(api_key.user_email.clone()),
+        name: Set(api_key.name.clone()),
+        key_hash: Set(record.key_hash.clone()),
+        key_prefix: Set(api_key.key_prefix.clone()),
+        scopes: Set(json::to_value(&api_key.scopes)?),
+        streams: Set(json::to_value(&api_key.streams)?),
+        expires_at: Set(api_key.expires_at),
+        last_used_at: Set(api_key.last_used_at),
+        created_at: Set(api_key.created_at),
+    };
+
+    // make sure only one client is writing to the database(only for sqlite)
+    let _lock = get_lock().await;
+
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    if let Err(e) = Entity::insert(model).exec(client).await {
+        drop(_lock);
+        return match e.sql_err() {
+            Some(SqlErr::UniqueConstraintViolation(_)) => {
+                Err(errors::Error::DbError(errors::DbError::UniqueViolation))
+            }
+            _ => Err(e.into()),
+        };
+    }
+    drop(_lock);
+
+    Ok(())
+}
+
+pub async fn update_last_used(id: &str, last_used_at: i64) -> Result<(), errors::Error> {
+    // make sure only one client is writing to the database(only for sqlite)
+    let _lock = get_lock().await;
+
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    Entity::update_many()
+        .col_expr(
+            Column::LastUsedAt,
+            sea_orm::sea_query::Expr::value(last_used_at),
+        )
+        .filter(Column::Id.eq(id))
+        .exec(client)
+        .await?;
+    drop(_lock);
+
+    Ok(())
+}
+
+pub async fn remove(org: &str, id: &str) -> Result<(), errors::Error> {
+    // make sure only one client is writing to the database(only for sqlite)
+    let _lock = get_lock().await;
+
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    Entity::delete_many()
+        .filter(Column::Org.eq(org))
+        .filter(Column::Id.eq(id))
+        .exec(client)
+        .await?;
+    drop(_lock);
+
+    Ok(())
+}
+
+pub async fn get(org: &str, id: &str) -> Result<Option<ApiKeyRecord>, errors::Error> {
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    Entity::find()
+        .filter(Column::Org.eq(org))
+        .filter(Column::Id.eq(id))
+        .one(client)
+        .await?
+        .map(ApiKeyRecord::try_from)
+        .transpose()
+}
+
+pub async fn get_by_hash(key_hash: &str) -> Result<Option<ApiKeyRecord>, errors::Error> {
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    Entity::find()
+        .filter(Column::KeyHash.eq(key_hash))
+        .one(client)
+        .await?
+        .map(ApiKeyRecord::try_from)
+        .transpose()
+}
+
+/// Lists the API keys of the org, only the ones of the given user when set.
+pub async fn list(org: &str, user_email: Option<&str>) -> Result<Vec<ApiKey>, errors::Error> {
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    let mut res = Entity::find()
+        .filter(Column::Org.eq(org))
+        .order_by(Column::CreatedAt, Order::Desc);
+    if let Some(user_email) = user_email {
+        res = res.filter(Column::UserEmail.eq(user_email));
+    }
+    res.all(client)
+        .await?
+        .into_iter()
+        .map(|model| ApiKeyRecord::try_from(model).map(|record| record.api_key))
+        .collect()
+}
+
+pub async fn list_all() -> Result<Vec<ApiKeyRecord>, errors::Error> {
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    Entity::find()
+        .all(client)
+        .await?
+        .into_iter()
+        .map(ApiKeyRecord::try_from)
+        .collect()
+}
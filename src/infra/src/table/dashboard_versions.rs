@@ -0,0 +1,244 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Saved versions of the dashboards. A version is recorded in the same transaction as the save
+//! of the dashboard, see [super::dashboards::put_with_version].
+
+use config::{
+    get_config,
+    meta::dashboards::{Dashboard, versions::SavedVersion},
+    utils::time::now_micros,
+};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use svix_ksuid::KsuidLike;
+
+use super::{dashboards::get_model_by_id, entity::dashboard_versions::*};
+use crate::{
+    db::{ORM_CLIENT, connect_to_orm},
+    errors,
+};
+
+/// Lists the saved versions of the dashboard, latest first. Returns `None` when the dashboard
+/// does not exist.
+pub async fn list(
+    org_id: &str,
+    dashboard_id: &str,
+) -> Result<Option<Vec<SavedVersion>>, errors::Error> {
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    let Some((_folder, dashboard_m)) = get_model_by_id(client, org_id, dashboard_id).await? else {
+        return Ok(None);
+    };
+    let versions = Entity::find()
+        .select_only()
+        .column(Column::Version)
+        .column(Column::Author)
+        .column(Column::Title)
+        .column(Column::CreatedAt)
+        .filter(Column::DashboardId.eq(dashboard_m.id))
+        .order_by_desc(Column::Version)
+        .into_tuple::<(i32, String, String, i64)>()
+        .all(client)
+        .await?
+        .into_iter()
+        .map(|(version, author, title, created_at)| SavedVersion {
+            version,
+            author,
+            title,
+            created_at,
+        })
+        .collect();
+    Ok(Some(versions))
+}
+
+/// Gets a saved version of the dashboard. Returns `None` when either the dashboard or the
+/// version does not exist.
+pub async fn get(
+    org_id: &str,
+    dashboard_id: &str,
+    version: i32,
+) -> Result<Option<(SavedVersion, Dashboard)>, errors::Error> {
+    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    let Some((_folder, dashboard_m)) = get_model_by_id(client, org_id, dashboard_id).await? else {
+        return Ok(None);
+    };
+    let Some(model) = Entity::find()
+        .filter(Column::DashboardId.eq(dashboard_m.id))
+        .filter(Column::Version.eq(version))
+        .one(client)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let dashboard = serde_json::from_value(model.data)?;
+    let saved = SavedVersion {
+        version: model.version,
+        author: model.author,
+        title: model.title,
+        created_at: model.created_at,
+    };
+    Ok(Some((saved, dashboard)))
+}
+
+/// Returns the latest saved version of the dashboard.
+pub async fn latest(
+    org_id: &str,
+    dashboard_id: &str,
+) -> Result<Option<SavedVersion>, errors::Error> {
+    Ok(list(org_id, dashboard_id)
+        .await?
+        .and_then(|versions| versions.into_iter().next()))
+}
+
+/// Records the state of a dashboard saved before versions were kept as its first version, so
+/// that the next save of the dashboard can be reverted. Does nothing if the dashboard already
+/// has versions.
+pub(super) async fn record_baseline<C: ConnectionTrait>(
+    db: &C,
+    dashboard_m: &super::entity::dashboards::Model,
+) -> Result<(), errors::Error> {
+    if latest_version(db, &dashboard_m.id).await?.is_none() {
+        insert(db, dashboard_m, &dashboard_m.owner, 1).await?;
+    }
+    Ok(())
+}
+
+/// Records the saved dashboard as its next version, then removes the versions beyond the
+/// configured retention.
+pub(super) async fn record<C: ConnectionTrait>(
+    db: &C,
+    dashboard_m: &super::entity::dashboards::Model,
+    author: &str,
+) -> Result<(), errors::Error> {
+    let version = latest_version(db, &dashboard_m.id)
+        .await?
+        .unwrap_or_default()
+        + 1;
+    insert(db, dashboard_m, author, version).await?;
+    prune(db, &dashboard_m.id).await
+}
+
+async fn latest_version<C: ConnectionTrait>(
+    db: &C,
+    dashboard_pk: &str,
+) -> Result<Option<i32>, errors::Error> {
+    let version = Entity::find()
+        .select_only()
+        .column(Column::Version)
+        .filter(Column::DashboardId.eq(dashboard_pk))
+        .order_by_desc(Column::Version)
+        .into_tuple::<i32>()
+        .one(db)
+        .await?;
+    Ok(version)
+}
+
+async fn insert<C: ConnectionTrait>(
+    db: &C,
+    dashboard_m: &super::entity::dashboards::Model,
+    author: &str,
+    version: i32,
+) -> Result<(), errors::Error> {
+    let dashboard = Dashboard::try_from(dashboard_m.clone())?;
+    let data = serde_json::to_value(&dashboard)?;
+    let version_am = ActiveModel {
+        id: Set(svix_ksuid::Ksuid::new(None, None).to_string()),
+        dashboard_id: Set(dashboard_m.id.clone()),
+        version: Set(version),
+        author: Set(author.to_owned()),
+        title: Set(dashboard_m.title.clone()),
+        data: Set(data),
+        created_at: Set(now_micros()),
+    };
+    version_am.insert(db).await?;
+    Ok(())
+}
+
+/// Removes the versions of the dashboard beyond the configured number of versions or age.
+async fn prune<C: ConnectionTrait>(db: &C, dashboard_pk: &str) -> Result<(), errors::Error> {
+    let cfg = get_config();
+    let max_versions = cfg.limit.dashboard_max_versions;
+    let retention_days = cfg.limit.dashboard_versions_retention_days;
+    if max_versions == 0 && retention_days <= 0 {
+        return Ok(());
+    }
+    let min_created_at =
+        (retention_days > 0).then(|| now_micros() - retention_days * 24 * 3600 * 1_000_000);
+
+    let versions = Entity::find()
+        .select_only()
+        .column(Column::Id)
+        .column(Column::CreatedAt)
+        .filter(Column::DashboardId.eq(dashboard_pk))
+        .order_by_desc(Column::Version)
+        .into_tuple::<(String, i64)>()
+        .all(db)
+        .await?;
+    let expired = expired_versions(versions, max_versions, min_created_at);
+    if !expired.is_empty() {
+        Entity::delete_many()
+            .filter(Column::Id.is_in(expired))
+            .exec(db)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Returns the ids of the expired versions, given the `(id, created_at)` of the versions
+/// latest first. The latest version is always kept.
+fn expired_versions(
+    versions: Vec<(String, i64)>,
+    max_versions: usize,
+    min_created_at: Option<i64>,
+) -> Vec<String> {
+    versions
+        .into_iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(i, (_, created_at))| {
+            (max_versions > 0 && *i >= max_versions)
+                || min_created_at.is_some_and(|min| *created_at < min)
+        })
+        .map(|(_, (id, _))| id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expired_versions() {
+        let versions = || {
+            vec![
+                ("v4".to_string(), 400),
+                ("v3".to_string(), 300),
+                ("v2".to_string(), 200),
+                ("v1".to_string(), 100),
+            ]
+        };
+        assert!(expired_versions(versions(), 0, None).is_empty());
+        assert_eq!(expired_versions(versions(), 2, None), vec!["v2", "v1"]);
+        assert_eq!(expired_versions(versions(), 0, Some(250)), vec!["v2", "v1"]);
+        assert_eq!(expired_versions(versions(), 3, Some(150)), vec!["v1"]);
+        // the latest version is kept even when expired
+        assert_eq!(
+            expired_versions(versions(), 1, Some(500)),
+            vec!["v3", "v2", "v1"]
+        );
+    }
+}
@@ -23,16 +23,18 @@ use config::meta::{
 };
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
-    IntoActiveModel, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
-    TryIntoModel, prelude::Expr, sea_query::Func,
+    IntoActiveModel, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set,
+    TransactionTrait, TryIntoModel, prelude::Expr, sea_query::Func,
 };
 use serde_json::Value as JsonValue;
 use svix_ksuid::KsuidLike;
 
 use super::{
+    dashboard_versions,
     distinct_values::{self, OriginType},
     entity::{dashboards, folders, reports},
     folders::folder_type_into_i16,
+    get_lock,
 };
 use crate::{
     db::{ORM_CLIENT, connect_to_orm},
@@ -165,13 +167,43 @@ pub async fn list_all() -> Result<Vec<(String, Dashboard)>, errors::Error> {
 /// if `clone` is true, the given dashboard will be used as it is when saving to db.
 /// `clone` should be always true for super cluster.
 pub async fn put(
+    org_id: &str,
+    folder_id: &str,
+    new_folder_id: Option<&str>,
+    dashboard: Dashboard,
+    clone: bool,
+) -> Result<Dashboard, errors::Error> {
+    put_with_version(
+        org_id,
+        folder_id,
+        new_folder_id,
+        dashboard,
+        clone,
+        None,
+        None,
+    )
+    .await
+}
+
+/// Creates a new dashboard or updates an existing dashboard in the database
+/// like [put]. Returns the new or updated dashboard.
+///
+/// If `hash` is given, an existing dashboard is only updated if its hash is
+/// still the given hash. If `author` is given, the saved dashboard is recorded
+/// as a new version of the dashboard within the same transaction.
+pub async fn put_with_version(
     org_id: &str,
     folder_id: &str,
     new_folder_id: Option<&str>,
     mut dashboard: Dashboard,
     clone: bool,
+    hash: Option<&str>,
+    author: Option<&str>,
 ) -> Result<Dashboard, errors::Error> {
-    let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
+    // make sure only one client is writing to the database (only for SQLite)
+    let _lock = get_lock().await;
+
+    let txn = ORM_CLIENT.get_or_init(connect_to_orm).await.begin().await?;
 
     // Get the fields that will be inserted into or updated in the database.
     let dashboard_id = dashboard
@@ -211,7 +243,7 @@ pub async fn put(
             .filter(folders::Column::Org.eq(org_id))
             .filter(folders::Column::Type.eq::<i16>(folder_type_into_i16(FolderType::Dashboards)))
             .filter(folders::Column::FolderId.eq(new_folder_id))
-            .one(client)
+            .one(&txn)
             .await?
             .ok_or(errors::PutDashboardError::FolderDoesNotExist)?;
         Some(new_folder_model)
@@ -220,7 +252,7 @@ pub async fn put(
     };
 
     let dashboard_model =
-        match get_model_from_folder(client, org_id, folder_id, &dashboard_id).await? {
+        match get_model_from_folder(&txn, org_id, folder_id, &dashboard_id).await? {
             None => {
                 // Destination folder does not exist so the dashboard can neither be
                 // created nor updated.
@@ -238,6 +270,23 @@ pub async fn put(
                     folder_m.id
                 };
 
+                // Check the dashboard wasn't changed since the given hash was
+                // computed, holding the row until the transaction completes.
+                if let Some(hash) = hash {
+                    let current = dashboards::Entity::find_by_id(dash_m.id.clone())
+                        .lock_exclusive()
+                        .one(&txn)
+                        .await?
+                        .map(Dashboard::try_from)
+                        .transpose()?;
+                    if current.is_none_or(|current| current.hash != hash) {
+                        return Err(errors::PutDashboardError::ConflictingHash.into());
+                    }
+                }
+                if author.is_some() {
+                    dashboard_versions::record_baseline(&txn, &dash_m).await?;
+                }
+
                 let mut dash_am = dash_m.into_active_model();
                 dash_am.folder_id = Set(folder_id);
                 dash_am.owner = Set(owner);
@@ -247,7 +296,7 @@ pub async fn put(
                 dash_am.data = Set(data);
                 dash_am.version = Set(version);
                 dash_am.updated_at = Set(updated_at);
-                let model: dashboards::Model = dash_am.update(client).await?.try_into_model()?;
+                let model: dashboards::Model = dash_am.update(&txn).await?.try_into_model()?;
                 Ok(model)
             }
             Some((folder_m, None)) => {
@@ -273,11 +322,16 @@ pub async fn put(
                     created_at: Set(created_at_unix),
                     updated_at: Set(updated_at),
                 };
-                let model: dashboards::Model = dash_am.insert(client).await?.try_into_model()?;
+                let model: dashboards::Model = dash_am.insert(&txn).await?.try_into_model()?;
                 Ok(model)
             }
         }?;
 
+    if let Some(author) = author {
+        dashboard_versions::record(&txn, &dashboard_model, author).await?;
+    }
+    txn.commit().await?;
+
     let dash = dashboard_model.try_into()?;
     Ok(dash)
 }
@@ -357,8 +411,8 @@ pub async fn get_model_from_folder<C: ConnectionTrait>(
 
 /// Tries to get a dashboard ORM entity and its parent folder ORM entity by the
 /// dashboard ID.
-async fn get_model_by_id(
-    db: &DatabaseConnection,
+pub(super) async fn get_model_by_id<C: ConnectionTrait>(
+    db: &C,
     org_id: &str,
     dashboard_id: &str,
 ) -> Result<Option<(folders::Model, dashboards::Model)>, sea_orm::DbErr> {
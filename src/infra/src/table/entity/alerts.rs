@@ -43,6 +43,9 @@ pub struct Model {
     pub last_edited_by: Option<String>,
     pub updated_at: Option<i64>,
     pub align_time: bool,
+    pub query_anomaly: Option<Json>,
+    pub query_composite: Option<Json>,
+    pub depends_on: Option<Json>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
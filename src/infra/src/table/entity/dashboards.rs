@@ -29,6 +29,8 @@ pub enum Relation {
         on_delete = "NoAction"
     )]
     Folders,
+    #[sea_orm(has_many = "super::dashboard_versions::Entity")]
+    DashboardVersions,
     #[sea_orm(has_many = "super::report_dashboards::Entity")]
     ReportDashboards,
     #[sea_orm(has_many = "super::timed_annotations::Entity")]
@@ -41,6 +43,12 @@ impl Related<super::folders::Entity> for Entity {
     }
 }
 
+impl Related<super::dashboard_versions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::DashboardVersions.def()
+    }
+}
+
 impl Related<super::report_dashboards::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ReportDashboards.def()
@@ -4,7 +4,9 @@ pub mod prelude;
 
 pub mod action_scripts;
 pub mod alerts;
+pub mod api_keys;
 pub mod cipher_keys;
+pub mod dashboard_versions;
 pub mod dashboards;
 pub mod destinations;
 pub mod distinct_value_fields;
@@ -1,12 +1,13 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
 pub use super::{
-    action_scripts::Entity as ActionScripts, alerts::Entity as Alerts,
-    cipher_keys::Entity as CipherKeys, dashboards::Entity as Dashboards,
-    destinations::Entity as Destinations, distinct_value_fields::Entity as DistinctValueFields,
-    folders::Entity as Folders, org_users::Entity as OrgUsers,
-    organizations::Entity as Organizations, report_dashboards::Entity as ReportDashboards,
-    reports::Entity as Reports, search_job_partitions::Entity as SearchJobPartitions,
+    action_scripts::Entity as ActionScripts, alerts::Entity as Alerts, api_keys::Entity as ApiKeys,
+    cipher_keys::Entity as CipherKeys, dashboard_versions::Entity as DashboardVersions,
+    dashboards::Entity as Dashboards, destinations::Entity as Destinations,
+    distinct_value_fields::Entity as DistinctValueFields, folders::Entity as Folders,
+    org_users::Entity as OrgUsers, organizations::Entity as Organizations,
+    report_dashboards::Entity as ReportDashboards, reports::Entity as Reports,
+    search_job_partitions::Entity as SearchJobPartitions,
     search_job_results::Entity as SearchJobResults, search_jobs::Entity as SearchJobs,
     search_queue::Entity as SearchQueue, templates::Entity as Templates,
     timed_annotation_panels::Entity as TimedAnnotationPanels,
@@ -0,0 +1,94 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Records the current version of the pipelines saved before the pipeline history existed, so
+//! that they can be diffed against and rolled back to.
+
+use config::{meta::pipeline::PipelineVersion, utils::json};
+use sea_orm_migration::prelude::*;
+
+/// Author of the versions recorded by this migration.
+const AUTHOR: &str = "system";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if !manager.has_table("pipeline_history").await? {
+            return Ok(());
+        }
+        let pipelines = crate::pipeline::list()
+            .await
+            .map_err(|e| DbErr::Migration(e.to_string()))?;
+        for pipeline in pipelines {
+            let versions = crate::pipeline::list_versions(&pipeline.id)
+                .await
+                .map_err(|e| DbErr::Migration(e.to_string()))?;
+            if !versions.is_empty() {
+                continue;
+            }
+            let version = PipelineVersion::new(
+                &pipeline,
+                AUTHOR,
+                Some("Saved before the version history".to_string()),
+            );
+            let data =
+                json::to_string(&version.pipeline).map_err(|e| DbErr::Migration(e.to_string()))?;
+            let stmt = Query::insert()
+                .into_table(PipelineHistory::Table)
+                .columns([
+                    PipelineHistory::PipelineId,
+                    PipelineHistory::Version,
+                    PipelineHistory::Org,
+                    PipelineHistory::Author,
+                    PipelineHistory::Comment,
+                    PipelineHistory::CreatedAt,
+                    PipelineHistory::Data,
+                ])
+                .values_panic([
+                    version.pipeline_id.into(),
+                    version.version.into(),
+                    version.pipeline.org.clone().into(),
+                    version.author.into(),
+                    version.comment.into(),
+                    version.created_at.into(),
+                    data.into(),
+                ])
+                .to_owned();
+            manager.exec_stmt(stmt).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // Reversing this migration is not supported.
+        Ok(())
+    }
+}
+
+/// Identifiers used in queries on the pipeline_history table.
+#[derive(DeriveIden)]
+enum PipelineHistory {
+    Table,
+    PipelineId,
+    Version,
+    Org,
+    Author,
+    Comment,
+    CreatedAt,
+    Data,
+}
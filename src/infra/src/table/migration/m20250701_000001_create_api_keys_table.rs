@@ -0,0 +1,200 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const API_KEYS_KEY_HASH_IDX: &str = "api_keys_key_hash_idx";
+const API_KEYS_ORG_USER_NAME_IDX: &str = "api_keys_org_user_email_name_idx";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(create_api_keys_table_statement())
+            .await?;
+        manager
+            .create_index(create_api_keys_key_hash_idx_stmnt())
+            .await?;
+        manager
+            .create_index(create_api_keys_org_user_name_idx_stmnt())
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name(API_KEYS_ORG_USER_NAME_IDX).to_owned())
+            .await?;
+        manager
+            .drop_index(Index::drop().name(API_KEYS_KEY_HASH_IDX).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ApiKeys::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+/// Statement to create the api keys table.
+fn create_api_keys_table_statement() -> TableCreateStatement {
+    Table::create()
+        .table(ApiKeys::Table)
+        .if_not_exists()
+        // The ID is 27-character human readable KSUID.
+        .col(
+            ColumnDef::new(ApiKeys::Id)
+                .char_len(27)
+                .not_null()
+                .primary_key(),
+        )
+        .col(ColumnDef::new(ApiKeys::Org).string_len(100).not_null())
+        .col(ColumnDef::new(ApiKeys::UserEmail).string_len(256).not_null())
+        .col(ColumnDef::new(ApiKeys::Name).string_len(256).not_null())
+        // hex encoded sha256 of the key, the key itself is never stored
+        .col(ColumnDef::new(ApiKeys::KeyHash).char_len(64).not_null())
+        .col(ColumnDef::new(ApiKeys::KeyPrefix).string_len(16).not_null())
+        .col(ColumnDef::new(ApiKeys::Scopes).json().not_null())
+        .col(ColumnDef::new(ApiKeys::Streams).json().not_null())
+        .col(ColumnDef::new(ApiKeys::ExpiresAt).big_integer().null())
+        .col(ColumnDef::new(ApiKeys::LastUsedAt).big_integer().null())
+        .col(ColumnDef::new(ApiKeys::CreatedAt).big_integer().not_null())
+        .to_owned()
+}
+
+/// Statement to create the unique index on the key hash, used to look keys up.
+fn create_api_keys_key_hash_idx_stmnt() -> IndexCreateStatement {
+    sea_query::Index::create()
+        .if_not_exists()
+        .name(API_KEYS_KEY_HASH_IDX)
+        .table(ApiKeys::Table)
+        .col(ApiKeys::KeyHash)
+        .unique()
+        .to_owned()
+}
+
+/// Statement to create the unique index on the key name of a user in an org.
+fn create_api_keys_org_user_name_idx_stmnt() -> IndexCreateStatement {
+    sea_query::Index::create()
+        .if_not_exists()
+        .name(API_KEYS_ORG_USER_NAME_IDX)
+        .table(ApiKeys::Table)
+        .col(ApiKeys::Org)
+        .col(ApiKeys::UserEmail)
+        .col(ApiKeys::Name)
+        .unique()
+        .to_owned()
+}
+
+/// Identifiers used in queries on the api keys table.
+#[derive(DeriveIden)]
+enum ApiKeys {
+    Table,
+    Id,
+    Org,
+    UserEmail,
+    Name,
+    KeyHash,
+    KeyPrefix,
+    Scopes,
+    Streams,
+    ExpiresAt,
+    LastUsedAt,
+    CreatedAt,
+}
+
+#[cfg(test)]
+mod tests {
+    use collapse::*;
+
+    use super::*;
+
+    #[test]
+    fn postgres() {
+        collapsed_eq!(
+            &create_api_keys_table_statement().to_string(PostgresQueryBuilder),
+            r#"
+                CREATE TABLE IF NOT EXISTS "api_keys" (
+                "id" char(27) NOT NULL PRIMARY KEY,
+                "org" varchar(100) NOT NULL,
+                "user_email" varchar(256) NOT NULL,
+                "name" varchar(256) NOT NULL,
+                "key_hash" char(64) NOT NULL,
+                "key_prefix" varchar(16) NOT NULL,
+                "scopes" json NOT NULL,
+                "streams" json NOT NULL,
+                "expires_at" bigint NULL,
+                "last_used_at" bigint NULL,
+                "created_at" bigint NOT NULL
+            )"#
+        );
+        assert_eq!(
+            &create_api_keys_org_user_name_idx_stmnt().to_string(PostgresQueryBuilder),
+            r#"CREATE UNIQUE INDEX IF NOT EXISTS "api_keys_org_user_email_name_idx" ON "api_keys" ("org", "user_email", "name")"#
+        );
+    }
+
+    #[test]
+    fn mysql() {
+        collapsed_eq!(
+            &create_api_keys_table_statement().to_string(MysqlQueryBuilder),
+            r#"
+                CREATE TABLE IF NOT EXISTS `api_keys` (
+                `id` char(27) NOT NULL PRIMARY KEY,
+                `org` varchar(100) NOT NULL,
+                `user_email` varchar(256) NOT NULL,
+                `name` varchar(256) NOT NULL,
+                `key_hash` char(64) NOT NULL,
+                `key_prefix` varchar(16) NOT NULL,
+                `scopes` json NOT NULL,
+                `streams` json NOT NULL,
+                `expires_at` bigint NULL,
+                `last_used_at` bigint NULL,
+                `created_at` bigint NOT NULL
+            )"#
+        );
+        assert_eq!(
+            &create_api_keys_org_user_name_idx_stmnt().to_string(MysqlQueryBuilder),
+            r#"CREATE UNIQUE INDEX `api_keys_org_user_email_name_idx` ON `api_keys` (`org`, `user_email`, `name`)"#
+        );
+    }
+
+    #[test]
+    fn sqlite() {
+        collapsed_eq!(
+            &create_api_keys_table_statement().to_string(SqliteQueryBuilder),
+            r#"
+                CREATE TABLE IF NOT EXISTS "api_keys" (
+                "id" char(27) NOT NULL PRIMARY KEY,
+                "org" varchar(100) NOT NULL,
+                "user_email" varchar(256) NOT NULL,
+                "name" varchar(256) NOT NULL,
+                "key_hash" char(64) NOT NULL,
+                "key_prefix" varchar(16) NOT NULL,
+                "scopes" json_text NOT NULL,
+                "streams" json_text NOT NULL,
+                "expires_at" bigint NULL,
+                "last_used_at" bigint NULL,
+                "created_at" bigint NOT NULL
+            )"#
+        );
+        assert_eq!(
+            &create_api_keys_org_user_name_idx_stmnt().to_string(SqliteQueryBuilder),
+            r#"CREATE UNIQUE INDEX IF NOT EXISTS "api_keys_org_user_email_name_idx" ON "api_keys" ("org", "user_email", "name")"#
+        );
+    }
+}
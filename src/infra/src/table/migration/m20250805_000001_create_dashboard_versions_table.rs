@@ -0,0 +1,208 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const DASHBOARD_VERSIONS_DASHBOARD_ID_VERSION_IDX: &str =
+    "dashboard_versions_dashboard_id_version_idx";
+const DASHBOARD_VERSIONS_DASHBOARDS_FK: &str = "fk_dashboard_versions_dashboard_id";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(create_dashboard_versions_table_statement())
+            .await?;
+        manager
+            .create_index(create_dashboard_versions_dashboard_id_version_idx_stmnt())
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(DASHBOARD_VERSIONS_DASHBOARD_ID_VERSION_IDX)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(DashboardVersions::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+/// Statement to create the dashboard versions table.
+fn create_dashboard_versions_table_statement() -> TableCreateStatement {
+    Table::create()
+        .table(DashboardVersions::Table)
+        .if_not_exists()
+        // The ID is 27-character human readable KSUID.
+        .col(
+            ColumnDef::new(DashboardVersions::Id)
+                .char_len(27)
+                .not_null()
+                .primary_key(),
+        )
+        // KSUID of the dashboard in the dashboards table.
+        .col(
+            ColumnDef::new(DashboardVersions::DashboardId)
+                .char_len(27)
+                .not_null(),
+        )
+        .col(
+            ColumnDef::new(DashboardVersions::Version)
+                .integer()
+                .not_null(),
+        )
+        .col(
+            ColumnDef::new(DashboardVersions::Author)
+                .string_len(256)
+                .not_null(),
+        )
+        .col(
+            ColumnDef::new(DashboardVersions::Title)
+                .string_len(256)
+                .not_null(),
+        )
+        // The whole dashboard, including the schema version of its definition.
+        .col(ColumnDef::new(DashboardVersions::Data).json().not_null())
+        .col(
+            ColumnDef::new(DashboardVersions::CreatedAt)
+                .big_integer()
+                .not_null(),
+        )
+        .foreign_key(
+            sea_query::ForeignKey::create()
+                .name(DASHBOARD_VERSIONS_DASHBOARDS_FK)
+                .from(DashboardVersions::Table, DashboardVersions::DashboardId)
+                .to(Dashboards::Table, Dashboards::Id)
+                .on_delete(ForeignKeyAction::Cascade),
+        )
+        .to_owned()
+}
+
+/// Statement to create the unique index on the version numbers of a dashboard.
+fn create_dashboard_versions_dashboard_id_version_idx_stmnt() -> IndexCreateStatement {
+    sea_query::Index::create()
+        .if_not_exists()
+        .name(DASHBOARD_VERSIONS_DASHBOARD_ID_VERSION_IDX)
+        .table(DashboardVersions::Table)
+        .col(DashboardVersions::DashboardId)
+        .col(DashboardVersions::Version)
+        .unique()
+        .to_owned()
+}
+
+/// Identifiers used in queries on the dashboard versions table.
+#[derive(DeriveIden)]
+enum DashboardVersions {
+    Table,
+    Id,
+    DashboardId,
+    Version,
+    Author,
+    Title,
+    Data,
+    CreatedAt,
+}
+
+/// Identifiers used in queries on the dashboards table.
+#[derive(DeriveIden)]
+enum Dashboards {
+    Table,
+    Id,
+}
+
+#[cfg(test)]
+mod tests {
+    use collapse::*;
+
+    use super::*;
+
+    #[test]
+    fn postgres() {
+        collapsed_eq!(
+            &create_dashboard_versions_table_statement().to_string(PostgresQueryBuilder),
+            r#"
+                CREATE TABLE IF NOT EXISTS "dashboard_versions" (
+                "id" char(27) NOT NULL PRIMARY KEY,
+                "dashboard_id" char(27) NOT NULL,
+                "version" integer NOT NULL,
+                "author" varchar(256) NOT NULL,
+                "title" varchar(256) NOT NULL,
+                "data" json NOT NULL,
+                "created_at" bigint NOT NULL,
+                CONSTRAINT "fk_dashboard_versions_dashboard_id" FOREIGN KEY ("dashboard_id") REFERENCES "dashboards" ("id") ON DELETE CASCADE
+            )"#
+        );
+        assert_eq!(
+            &create_dashboard_versions_dashboard_id_version_idx_stmnt()
+                .to_string(PostgresQueryBuilder),
+            r#"CREATE UNIQUE INDEX IF NOT EXISTS "dashboard_versions_dashboard_id_version_idx" ON "dashboard_versions" ("dashboard_id", "version")"#
+        );
+    }
+
+    #[test]
+    fn mysql() {
+        collapsed_eq!(
+            &create_dashboard_versions_table_statement().to_string(MysqlQueryBuilder),
+            r#"
+                CREATE TABLE IF NOT EXISTS `dashboard_versions` (
+                `id` char(27) NOT NULL PRIMARY KEY,
+                `dashboard_id` char(27) NOT NULL,
+                `version` int NOT NULL,
+                `author` varchar(256) NOT NULL,
+                `title` varchar(256) NOT NULL,
+                `data` json NOT NULL,
+                `created_at` bigint NOT NULL,
+                CONSTRAINT `fk_dashboard_versions_dashboard_id` FOREIGN KEY (`dashboard_id`) REFERENCES `dashboards` (`id`) ON DELETE CASCADE
+            )"#
+        );
+        assert_eq!(
+            &create_dashboard_versions_dashboard_id_version_idx_stmnt()
+                .to_string(MysqlQueryBuilder),
+            r#"CREATE UNIQUE INDEX `dashboard_versions_dashboard_id_version_idx` ON `dashboard_versions` (`dashboard_id`, `version`)"#
+        );
+    }
+
+    #[test]
+    fn sqlite() {
+        collapsed_eq!(
+            &create_dashboard_versions_table_statement().to_string(SqliteQueryBuilder),
+            r#"
+                CREATE TABLE IF NOT EXISTS "dashboard_versions" (
+                "id" char(27) NOT NULL PRIMARY KEY,
+                "dashboard_id" char(27) NOT NULL,
+                "version" integer NOT NULL,
+                "author" varchar(256) NOT NULL,
+                "title" varchar(256) NOT NULL,
+                "data" json_text NOT NULL,
+                "created_at" bigint NOT NULL,
+                FOREIGN KEY ("dashboard_id") REFERENCES "dashboards" ("id") ON DELETE CASCADE
+            )"#
+        );
+        assert_eq!(
+            &create_dashboard_versions_dashboard_id_version_idx_stmnt()
+                .to_string(SqliteQueryBuilder),
+            r#"CREATE UNIQUE INDEX IF NOT EXISTS "dashboard_versions_dashboard_id_version_idx" ON "dashboard_versions" ("dashboard_id", "version")"#
+        );
+    }
+}
@@ -57,6 +57,14 @@ mod m20250422_000001_add_alert_align_time;
 mod m20250611_000001_create_reports_table;
 mod m20250611_000002_populate_reports_table;
 mod m20250611_000003_populate_reports_scheduled_jobs;
+mod m20250620_000001_add_pipeline_dead_letter;
+mod m20250625_000001_seed_pipeline_history;
+mod m20250701_000001_create_api_keys_table;
+mod m20250801_000001_add_template_engine;
+mod m20250802_000001_add_alert_query_anomaly;
+mod m20250803_000001_add_alert_dependencies;
+mod m20250804_000001_add_report_media_type;
+mod m20250805_000001_create_dashboard_versions_table;
 
 pub struct Migrator;
 
@@ -106,6 +114,14 @@ impl MigratorTrait for Migrator {
             Box::new(m20250611_000001_create_reports_table::Migration),
             Box::new(m20250611_000002_populate_reports_table::Migration),
             Box::new(m20250611_000003_populate_reports_scheduled_jobs::Migration),
+            Box::new(m20250620_000001_add_pipeline_dead_letter::Migration),
+            Box::new(m20250625_000001_seed_pipeline_history::Migration),
+            Box::new(m20250701_000001_create_api_keys_table::Migration),
+            Box::new(m20250801_000001_add_template_engine::Migration),
+            Box::new(m20250802_000001_add_alert_query_anomaly::Migration),
+            Box::new(m20250803_000001_add_alert_dependencies::Migration),
+            Box::new(m20250804_000001_add_report_media_type::Migration),
+            Box::new(m20250805_000001_create_dashboard_versions_table::Migration),
         ]
     }
 }
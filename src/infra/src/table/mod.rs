@@ -24,7 +24,9 @@ use crate::{
 
 pub mod action_scripts;
 pub mod alerts;
+pub mod api_keys;
 pub mod cipher;
+pub mod dashboard_versions;
 pub mod dashboards;
 pub mod destinations;
 pub mod distinct_values;
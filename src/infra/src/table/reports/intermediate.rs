@@ -24,8 +24,12 @@ use config::{
         dashboards::reports::{
             ReportDashboardVariable as MetaReportDashboardVariable,
             ReportDestination as MetaReportDestination, ReportFrequency as MetaReportFrequency,
-            ReportFrequencyType as MetaReportFrequencyType, ReportTimerange as MetaReportTimeRange,
-            ReportTimerangeType as MetaReportTimeRangeType,
+            ReportFrequencyType as MetaReportFrequencyType,
+            ReportHttpDelivery as MetaReportHttpDelivery,
+            ReportHttpDestination as MetaReportHttpDestination,
+            ReportStorageDestination as MetaReportStorageDestination,
+            ReportTimerange as MetaReportTimeRange, ReportTimerangeType as MetaReportTimeRangeType,
+            default_storage_pattern,
         },
     },
     utils::json,
@@ -51,12 +55,16 @@ impl From<Vec<MetaReportDestination>> for ReportDestinations {
 #[serde(rename_all = "snake_case")]
 pub enum ReportDestination {
     Email(String),
+    Http(ReportHttpDestination),
+    Storage(ReportStorageDestination),
 }
 
 impl From<ReportDestination> for MetaReportDestination {
     fn from(value: ReportDestination) -> Self {
         match value {
             ReportDestination::Email(email) => Self::Email(email),
+            ReportDestination::Http(http) => Self::Http(http.into()),
+            ReportDestination::Storage(storage) => Self::Storage(storage.into()),
         }
     }
 }
@@ -65,6 +73,92 @@ impl From<MetaReportDestination> for ReportDestination {
     fn from(value: MetaReportDestination) -> Self {
         match value {
             MetaReportDestination::Email(email) => Self::Email(email),
+            MetaReportDestination::Http(http) => Self::Http(http.into()),
+            MetaReportDestination::Storage(storage) => Self::Storage(storage.into()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReportHttpDestination {
+    pub destination: String,
+    #[serde(default)]
+    pub delivery: ReportHttpDelivery,
+    pub link_expiry_secs: u64,
+}
+
+impl From<ReportHttpDestination> for MetaReportHttpDestination {
+    fn from(value: ReportHttpDestination) -> Self {
+        Self {
+            destination: value.destination,
+            delivery: value.delivery.into(),
+            link_expiry_secs: value.link_expiry_secs,
+        }
+    }
+}
+
+impl From<MetaReportHttpDestination> for ReportHttpDestination {
+    fn from(value: MetaReportHttpDestination) -> Self {
+        Self {
+            destination: value.destination,
+            delivery: value.delivery.into(),
+            link_expiry_secs: value.link_expiry_secs,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportHttpDelivery {
+    #[default]
+    Multipart,
+    Link,
+}
+
+impl From<ReportHttpDelivery> for MetaReportHttpDelivery {
+    fn from(value: ReportHttpDelivery) -> Self {
+        match value {
+            ReportHttpDelivery::Multipart => Self::Multipart,
+            ReportHttpDelivery::Link => Self::Link,
+        }
+    }
+}
+
+impl From<MetaReportHttpDelivery> for ReportHttpDelivery {
+    fn from(value: MetaReportHttpDelivery) -> Self {
+        match value {
+            MetaReportHttpDelivery::Multipart => Self::Multipart,
+            MetaReportHttpDelivery::Link => Self::Link,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReportStorageDestination {
+    #[serde(default)]
+    pub account: String,
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default = "default_storage_pattern")]
+    pub pattern: String,
+}
+
+impl From<ReportStorageDestination> for MetaReportStorageDestination {
+    fn from(value: ReportStorageDestination) -> Self {
+        Self {
+            account: value.account,
+            prefix: value.prefix,
+            pattern: value.pattern,
+        }
+    }
+}
+
+impl From<MetaReportStorageDestination> for ReportStorageDestination {
+    fn from(value: MetaReportStorageDestination) -> Self {
+        Self {
+            account: value.account,
+            prefix: value.prefix,
+            pattern: value.pattern,
         }
     }
 }
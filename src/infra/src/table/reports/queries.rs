@@ -253,7 +253,7 @@ impl TryFrom<SelectReportAndJoinRelationsResult> for (MetaFolder, MetaReport) {
             description: report_model.description.unwrap_or_default(),
             message: report_model.message.unwrap_or_default(),
             enabled: report_model.enabled,
-            media_type: config::meta::dashboards::reports::ReportMediaType::Pdf,
+            media_type: report_model.media_type.as_str().into(),
             timezone: report_model.timezone,
             tz_offset: report_model.tz_offset,
             created_at: created_at_utc,
@@ -522,6 +522,7 @@ mod tests {
                 "reports"."created_at",
                 "reports"."updated_at",
                 "reports"."start_at",
+                "reports"."media_type",
                 "reports"."id" AS "report_id",
                 "reports"."name" AS "report_name",
                 "reports"."owner" AS "report_owner",
@@ -571,6 +572,7 @@ mod tests {
                 `reports`.`created_at`,
                 `reports`.`updated_at`,
                 `reports`.`start_at`,
+                `reports`.`media_type`,
                 `reports`.`id` AS `report_id`,
                 `reports`.`name` AS `report_name`,
                 `reports`.`owner` AS `report_owner`,
@@ -621,6 +623,7 @@ mod tests {
                 "reports"."created_at",
                 "reports"."updated_at",
                 "reports"."start_at",
+                "reports"."media_type",
                 "reports"."id" AS "report_id",
                 "reports"."name" AS "report_name",
                 "reports"."owner" AS "report_owner",
@@ -684,6 +687,7 @@ mod tests {
                 "reports"."created_at",
                 "reports"."updated_at",
                 "reports"."start_at",
+                "reports"."media_type",
                 "reports"."id" AS "report_id",
                 "reports"."name" AS "report_name",
                 "reports"."owner" AS "report_owner",
@@ -739,6 +743,7 @@ mod tests {
                 `reports`.`created_at`,
                 `reports`.`updated_at`,
                 `reports`.`start_at`,
+                `reports`.`media_type`,
                 `reports`.`id` AS `report_id`,
                 `reports`.`name` AS `report_name`,
                 `reports`.`owner` AS `report_owner`,
@@ -793,6 +798,7 @@ mod tests {
                 "reports"."created_at",
                 "reports"."updated_at",
                 "reports"."start_at",
+                "reports"."media_type",
                 "reports"."id" AS "report_id",
                 "reports"."name" AS "report_name",
                 "reports"."owner" AS "report_owner",
@@ -55,6 +55,7 @@ impl TryFrom<Model> for Template {
             is_default: value.is_default,
             template_type,
             body: value.body,
+            engine: value.engine.as_str().into(),
         })
     }
 }
@@ -76,6 +77,7 @@ pub async fn put(template: Template) -> Result<Template, Error> {
         r#type: Set(template.template_type.to_string()),
         body: Set(template.body),
         title: Set(title),
+        engine: Set(template.engine.to_string()),
     };
     let model: Model = match get_model(client, &template.org_id, &template.name).await? {
         Some(model) => {
@@ -105,6 +105,8 @@ pub async fn init() -> Result<(), anyhow::Error> {
         .await
         .expect("organizations cache failed");
     db::org_users::cache().await.expect("org user cache failed");
+    db::api_keys::cache().await.expect("api keys cache failed");
+    db::quotas::cache().await.expect("org quotas cache failed");
 
     db::organization::org_settings_cache()
         .await
@@ -113,6 +115,8 @@ pub async fn init() -> Result<(), anyhow::Error> {
     // watch org users
     tokio::task::spawn(async move { db::user::watch().await });
     tokio::task::spawn(async move { db::org_users::watch().await });
+    tokio::task::spawn(async move { db::api_keys::watch().await });
+    tokio::task::spawn(async move { db::quotas::watch().await });
     tokio::task::spawn(async move { db::organization::watch().await });
 
     // check version
@@ -167,6 +171,8 @@ pub async fn init() -> Result<(), anyhow::Error> {
     tokio::task::spawn(async move { db::alerts::destinations::watch().await });
     tokio::task::spawn(async move { db::alerts::realtime_triggers::watch().await });
     tokio::task::spawn(async move { db::alerts::alert::watch().await });
+    tokio::task::spawn(async move { db::alerts::maintenance_windows::watch().await });
+    tokio::task::spawn(async move { db::alerts::firing_states::watch().await });
     tokio::task::spawn(async move { db::organization::org_settings_watch().await });
 
     // pipeline not used on compactors
@@ -209,6 +215,12 @@ pub async fn init() -> Result<(), anyhow::Error> {
     db::alerts::alert::cache()
         .await
         .expect("alerts cache failed");
+    db::alerts::maintenance_windows::cache()
+        .await
+        .expect("alerts maintenance windows cache failed");
+    db::alerts::firing_states::cache()
+        .await
+        .expect("alerts firing states cache failed");
     db::syslog::cache().await.expect("syslog cache failed");
     db::syslog::cache_syslog_settings()
         .await
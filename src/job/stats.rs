@@ -16,12 +16,13 @@
 use config::{cluster::LOCAL_NODE, get_config};
 use tokio::time;
 
-use crate::service::{compact::stats::update_stats_from_file_list, db};
+use crate::service::{compact::stats::update_stats_from_file_list, db, quotas, search::org_limits};
 
 pub async fn run() -> Result<(), anyhow::Error> {
     tokio::task::spawn(async move { update_node_memory_usage().await });
     tokio::task::spawn(async move { file_list_update_stats().await });
     tokio::task::spawn(async move { cache_stream_stats().await });
+    tokio::task::spawn(async move { refresh_quota_usage().await });
     Ok(())
 }
 
@@ -99,3 +100,28 @@ async fn update_node_memory_usage() -> Result<(), anyhow::Error> {
         tokio::time::sleep(time::Duration::from_secs(1)).await;
     }
 }
+
+// refresh the usage counted against org quotas and search limits
+async fn refresh_quota_usage() -> Result<(), anyhow::Error> {
+    if !LOCAL_NODE.is_ingester() && !LOCAL_NODE.is_querier() {
+        return Ok(());
+    }
+
+    let mut interval = time::interval(time::Duration::from_secs(std::cmp::max(
+        10,
+        get_config().limit.quota_refresh_interval,
+    )));
+    loop {
+        interval.tick().await;
+        if LOCAL_NODE.is_ingester() {
+            if let Err(e) = quotas::refresh_usage().await {
+                log::error!("[QUOTA] run refresh quota usage error: {}", e);
+            }
+        }
+        if LOCAL_NODE.is_querier() {
+            if let Err(e) = org_limits::refresh_usage().await {
+                log::error!("[QUOTA] run refresh search usage error: {}", e);
+            }
+        }
+    }
+}
@@ -45,7 +45,7 @@ use openobserve::{
     handler::{
         self,
         grpc::{
-            auth::check_auth,
+            auth::{check_auth, check_ingest_auth},
             flight::FlightServiceImpl,
             request::{
                 event::Eventer,
@@ -61,8 +61,12 @@ use openobserve::{
     },
     job, migration, router,
     service::{
-        cluster_info::ClusterInfoService, db, metadata, node::NodeService, search::SEARCH_SERVER,
-        self_reporting, tls::http_tls_config,
+        cluster_info::ClusterInfoService,
+        db, metadata,
+        node::NodeService,
+        search::SEARCH_SERVER,
+        self_reporting,
+        tls::{http_on_connect, http_tls_config},
     },
 };
 use opentelemetry::{KeyValue, global, trace::TracerProvider};
@@ -87,7 +91,8 @@ use tokio::sync::oneshot;
 use tonic::{
     codec::CompressionEncoding,
     metadata::{MetadataKey, MetadataMap, MetadataValue},
-    transport::{Identity, ServerTlsConfig},
+    service::interceptor::InterceptedService,
+    transport::{Certificate, Identity, ServerTlsConfig},
 };
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_opentelemetry::OpenTelemetryLayer;
@@ -571,28 +576,30 @@ async fn init_common_grpc_server(
     );
     init_tx.send(()).ok();
 
-    let builder = if cfg.grpc.tls_enabled {
+    let mut builder = if cfg.grpc.tls_enabled {
         let cert = std::fs::read_to_string(&cfg.grpc.tls_cert_path)?;
         let key = std::fs::read_to_string(&cfg.grpc.tls_key_path)?;
         let identity = Identity::from_pem(cert, key);
-        tonic::transport::Server::builder().tls_config(ServerTlsConfig::new().identity(identity))?
+        tonic::transport::Server::builder().tls_config(grpc_tls_config(identity)?)?
     } else {
         tonic::transport::Server::builder()
     };
     let ret = builder
-        .layer(tonic::service::interceptor(check_auth))
-        .add_service(event_svc)
-        .add_service(search_svc)
-        .add_service(metrics_svc)
-        .add_service(metrics_ingest_svc)
-        .add_service(trace_svc)
-        .add_service(logs_svc)
-        .add_service(query_cache_svc)
-        .add_service(ingest_svc)
-        .add_service(streams_svc)
-        .add_service(flight_svc)
-        .add_service(node_svc)
-        .add_service(cluster_info_svc)
+        .add_service(InterceptedService::new(event_svc, check_auth))
+        .add_service(InterceptedService::new(search_svc, check_auth))
+        .add_service(InterceptedService::new(metrics_svc, check_auth))
+        .add_service(InterceptedService::new(
+            metrics_ingest_svc,
+            check_ingest_auth,
+        ))
+        .add_service(InterceptedService::new(trace_svc, check_ingest_auth))
+        .add_service(InterceptedService::new(logs_svc, check_ingest_auth))
+        .add_service(InterceptedService::new(query_cache_svc, check_auth))
+        .add_service(InterceptedService::new(ingest_svc, check_auth))
+        .add_service(InterceptedService::new(streams_svc, check_auth))
+        .add_service(InterceptedService::new(flight_svc, check_auth))
+        .add_service(InterceptedService::new(node_svc, check_auth))
+        .add_service(InterceptedService::new(cluster_info_svc, check_auth))
         .serve_with_shutdown(gaddr, async {
             shutdown_rx.await.ok();
             log::info!("gRPC server starts shutting down");
@@ -606,6 +613,19 @@ async fn init_common_grpc_server(
     Ok(())
 }
 
+/// TLS config of the gRPC servers, with optional mutual TLS when a client CA is configured.
+fn grpc_tls_config(identity: Identity) -> Result<ServerTlsConfig, anyhow::Error> {
+    let cfg = get_config();
+    let tls_config = ServerTlsConfig::new().identity(identity);
+    if cfg.grpc.tls_client_ca_path.is_empty() {
+        return Ok(tls_config);
+    }
+    let ca = std::fs::read_to_string(&cfg.grpc.tls_client_ca_path)?;
+    Ok(tls_config
+        .client_ca_root(Certificate::from_pem(ca))
+        .client_auth_optional(true))
+}
+
 async fn init_router_grpc_server(
     init_tx: oneshot::Sender<()>,
     shutdown_rx: oneshot::Receiver<()>,
@@ -640,12 +660,12 @@ async fn init_router_grpc_server(
         let cert = std::fs::read_to_string(&cfg.grpc.tls_cert_path)?;
         let key = std::fs::read_to_string(&cfg.grpc.tls_key_path)?;
         let identity = Identity::from_pem(cert, key);
-        tonic::transport::Server::builder().tls_config(ServerTlsConfig::new().identity(identity))?
+        tonic::transport::Server::builder().tls_config(grpc_tls_config(identity)?)?
     } else {
         tonic::transport::Server::builder()
     };
     let ret = builder
-        .layer(tonic::service::interceptor(check_auth))
+        .layer(tonic::service::interceptor(check_ingest_auth))
         .add_service(logs_svc)
         .add_service(metrics_svc)
         .add_service(traces_svc)
@@ -748,6 +768,7 @@ async fn init_http_server() -> Result<(), anyhow::Error> {
             ))
             .wrap(RequestTracing::new())
     })
+    .on_connect(http_on_connect)
     .keep_alive(if cfg.limit.http_keep_alive_disabled {
         KeepAlive::Disabled
     } else {
@@ -861,6 +882,7 @@ async fn init_http_server_without_tracing() -> Result<(), anyhow::Error> {
                 r#"%a "%r" %s %b "%{Content-Length}i" "%{Referer}i" "%{User-Agent}i" %T"#,
             ))
     })
+    .on_connect(http_on_connect)
     .keep_alive(if cfg.limit.http_keep_alive_disabled {
         KeepAlive::Disabled
     } else {
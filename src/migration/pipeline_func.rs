@@ -109,6 +109,7 @@ async fn migrate_pipelines() -> Result<(), anyhow::Error> {
                     tz_offset: old_derived_stream.tz_offset,
                     start_at: None,
                     delay: None,
+                    last_edited_by: None,
                 };
 
                 let pipeline_source = PipelineSource::Scheduled(new_derived_stream.clone());
@@ -152,6 +153,7 @@ async fn migrate_pipelines() -> Result<(), anyhow::Error> {
                     source: pipeline_source,
                     nodes,
                     edges,
+                    dead_letter: None,
                 };
                 new_pipeline_by_source.insert(
                     StreamParams::new(
@@ -192,6 +194,7 @@ async fn migrate_pipelines() -> Result<(), anyhow::Error> {
                     source: pipeline_source,
                     nodes: vec![source_node],
                     edges: vec![],
+                    dead_letter: None,
                 }
             });
 
@@ -323,6 +326,7 @@ async fn migrate_pipelines() -> Result<(), anyhow::Error> {
                 source: pipeline_source,
                 nodes: vec![source_node],
                 edges: vec![],
+                dead_letter: None,
             }
         });
 
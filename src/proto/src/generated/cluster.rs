@@ -513,6 +513,8 @@ pub struct MetricsQueryRequest {
     pub timeout: i64,
     #[prost(bool, tag = "9")]
     pub no_cache: bool,
+    #[prost(string, tag = "10")]
+    pub user_email: ::prost::alloc::string::String,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -30,7 +30,8 @@ use config::{
             alert::{Alert, AlertListFilter, ListAlertsParams},
         },
         destinations::{
-            AwsSns, DestinationType, Email, Endpoint, HTTPType, Module, Template, TemplateType,
+            AwsSns, DestinationType, Email, Endpoint, HTTPType, Module, Template, TemplateAlert,
+            TemplateContext, TemplateLinks, TemplateType,
         },
         folder::{DEFAULT_FOLDER, Folder, FolderType},
         search::{SearchEventContext, SearchEventType},
@@ -73,10 +74,10 @@ use crate::{
         utils::auth::{is_ofga_unsupported, remove_ownership, set_ownership},
     },
     service::{
-        alerts::{QueryConditionExt, build_sql, destinations},
+        alerts::{QueryConditionExt, build_sql, dependencies, destinations, templates},
         db, folders,
         search::sql::RE_ONLY_SELECT,
-        short_url,
+        secrets, short_url,
     },
 };
 
@@ -146,6 +147,30 @@ pub enum AlertError {
     #[error("Alert with PromQL mode should have a query")]
     PromqlMissingQuery,
 
+    #[error("Alert with anomaly mode should have either a SQL or a PromQL query")]
+    AnomalyMissingQuery,
+
+    #[error("Invalid anomaly condition: {0}")]
+    AnomalyInvalidCondition(String),
+
+    #[error("Invalid alert condition: {0}")]
+    InvalidCondition(String),
+
+    #[error("Alert with composite mode should have a composite condition")]
+    CompositeMissingCondition,
+
+    #[error("Alert {0} referenced by the alert is not found")]
+    DependencyNotFound(String),
+
+    #[error("Alert {0} is a realtime alert, only scheduled alerts can be referenced")]
+    DependencyRealtime(String),
+
+    #[error("Alert references make a cycle: {0}")]
+    DependencyCycle(String),
+
+    #[error("Alert is referenced by the alerts: {0}")]
+    AlertReferenced(String),
+
     #[error("{error_message}")]
     SendNotificationError { error_message: String },
 
@@ -182,6 +207,18 @@ pub enum AlertError {
     /// Not support save destination remote pipeline for alert so far
     #[error("Not support save destination {0} type for alert so far")]
     NotSupportedAlertDestinationType(Module),
+
+    #[error("Backtesting is only supported for scheduled alerts")]
+    BacktestRealtime,
+
+    #[error("Backtesting is not supported for composite alerts")]
+    BacktestComposite,
+
+    #[error("Invalid backtest time range: {0}")]
+    BacktestInvalidRange(String),
+
+    #[error("Alert backtest failed: {0}")]
+    BacktestFailed(String),
 }
 
 pub async fn save(
@@ -250,7 +287,6 @@ async fn prepare_alert(
         return Err(AlertError::AlertNameOfgaUnsupported);
     }
     alert.org_id = org_id.to_string();
-    let stream_type = alert.stream_type;
     alert.stream_name = stream_name.to_string();
     alert.row_template = alert.row_template.trim().to_string();
 
@@ -275,22 +311,7 @@ async fn prepare_alert(
         }
     }
 
-    if alert.trigger_condition.frequency_type == FrequencyType::Cron {
-        let now = Utc::now().second();
-        alert.trigger_condition.cron = update_cron_expression(&alert.trigger_condition.cron, now);
-        // Check the cron expression
-        Schedule::from_str(&alert.trigger_condition.cron).map_err(AlertError::ParseCron)?;
-    } else {
-        // if cron is not empty, set it to empty string
-        if !alert.trigger_condition.cron.is_empty() {
-            alert.trigger_condition.cron = "".to_string();
-        }
-        if alert.trigger_condition.frequency == 0 {
-            // default frequency is 60 seconds
-            alert.trigger_condition.frequency =
-                std::cmp::max(60, get_config().limit.alert_schedule_interval);
-        }
-    }
+    prepare_trigger_condition(alert)?;
 
     if alert.name.is_empty() || alert.stream_name.is_empty() {
         return Err(AlertError::AlertNameMissing);
@@ -299,23 +320,7 @@ async fn prepare_alert(
         return Err(AlertError::AlertNameContainsForwardSlash);
     }
 
-    if let Some(vrl) = alert.query_condition.vrl_function.as_ref() {
-        match base64::decode_url(vrl) {
-            Ok(vrl) => {
-                let vrl = vrl.trim().to_owned();
-                if !vrl.is_empty() && !vrl.ends_with('.') {
-                    let vrl = base64::encode_url(&format!("{vrl}\n."));
-                    alert.query_condition.vrl_function = Some(vrl);
-                } else if vrl.is_empty() || vrl.eq(".") {
-                    // In case the vrl contains only ".", no need to save it
-                    alert.query_condition.vrl_function = None;
-                }
-            }
-            Err(e) => {
-                return Err(AlertError::DecodeVrl(e));
-            }
-        }
-    }
+    prepare_vrl_function(alert)?;
 
     // before saving alert check alert destination
     if alert.destinations.is_empty() {
@@ -349,6 +354,73 @@ async fn prepare_alert(
         alert.context_attributes = Some(new_attrs);
     }
 
+    validate_query_condition(org_id, stream_name, alert).await?;
+    // drop the blank and duplicated parents
+    alert.depends_on.retain(|id| !id.trim().is_empty());
+    alert.depends_on.sort();
+    alert.depends_on.dedup();
+    dependencies::validate(org_id, alert).await?;
+
+    // Commented intentionally - in case the alert period is big and there
+    // is huge amount of data within the time period, the below can timeout and return error.
+    // // test the alert
+    // if let Err(e) = &alert.evaluate(None).await {
+    //     return Err(anyhow::anyhow!("Alert test failed: {}", e));
+    // }
+
+    Ok(())
+}
+
+/// Checks the cron expression of the alert, or defaults its frequency.
+pub(super) fn prepare_trigger_condition(alert: &mut Alert) -> Result<(), AlertError> {
+    if alert.trigger_condition.frequency_type == FrequencyType::Cron {
+        let now = Utc::now().second();
+        alert.trigger_condition.cron = update_cron_expression(&alert.trigger_condition.cron, now);
+        // Check the cron expression
+        Schedule::from_str(&alert.trigger_condition.cron).map_err(AlertError::ParseCron)?;
+    } else {
+        // if cron is not empty, set it to empty string
+        if !alert.trigger_condition.cron.is_empty() {
+            alert.trigger_condition.cron = "".to_string();
+        }
+        if alert.trigger_condition.frequency == 0 {
+            // default frequency is 60 seconds
+            alert.trigger_condition.frequency =
+                std::cmp::max(60, get_config().limit.alert_schedule_interval);
+        }
+    }
+    Ok(())
+}
+
+/// Makes sure the vrl function of the alert ends with `.`, or removes it when it's empty.
+pub(super) fn prepare_vrl_function(alert: &mut Alert) -> Result<(), AlertError> {
+    if let Some(vrl) = alert.query_condition.vrl_function.as_ref() {
+        match base64::decode_url(vrl) {
+            Ok(vrl) => {
+                let vrl = vrl.trim().to_owned();
+                if !vrl.is_empty() && !vrl.ends_with('.') {
+                    let vrl = base64::encode_url(&format!("{vrl}\n."));
+                    alert.query_condition.vrl_function = Some(vrl);
+                } else if vrl.is_empty() || vrl.eq(".") {
+                    // In case the vrl contains only ".", no need to save it
+                    alert.query_condition.vrl_function = None;
+                }
+            }
+            Err(e) => {
+                return Err(AlertError::DecodeVrl(e));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks the stream and the query of the alert.
+pub(super) async fn validate_query_condition(
+    org_id: &str,
+    stream_name: &str,
+    alert: &mut Alert,
+) -> Result<(), AlertError> {
+    let stream_type = alert.stream_type;
     // before saving alert check column type to decide numeric condition
     let schema = infra::schema::get(org_id, stream_name, stream_type).await?;
     if stream_name.is_empty() || schema.fields().is_empty() {
@@ -375,6 +447,38 @@ async fn prepare_alert(
         return Err(AlertError::RealtimeMissingCustomQuery);
     }
 
+    if let Some(conditions) = alert.query_condition.conditions.as_ref() {
+        conditions
+            .validate()
+            .map_err(AlertError::InvalidCondition)?;
+        // scheduled alerts evaluate the conditions in SQL, which has no cidr matching
+        if !alert.is_real_time
+            && conditions.any_condition(&|condition| condition.operator == Operator::CidrMatch)
+        {
+            return Err(AlertError::InvalidCondition(format!(
+                "Operator [{}] is only supported by real-time alerts",
+                Operator::CidrMatch
+            )));
+        }
+    }
+    if let Some(agg) = alert.query_condition.aggregation.as_ref() {
+        agg.having
+            .validate()
+            .map_err(AlertError::InvalidCondition)?;
+        if agg.having.operator == Operator::CidrMatch {
+            return Err(AlertError::InvalidCondition(format!(
+                "Operator [{}] is not supported by the aggregation",
+                Operator::CidrMatch
+            )));
+        }
+    }
+    if !alert.trigger_condition.operator.is_comparison() {
+        return Err(AlertError::InvalidCondition(format!(
+            "Trigger condition does not support operator [{}]",
+            alert.trigger_condition.operator
+        )));
+    }
+
     match alert.query_condition.query_type {
         QueryType::Custom => {
             if alert.query_condition.aggregation.is_some() {
@@ -431,16 +535,63 @@ async fn prepare_alert(
             {
                 return Err(AlertError::PromqlMissingQuery);
             }
+            let operator = alert
+                .query_condition
+                .promql_condition
+                .as_ref()
+                .unwrap()
+                .operator;
+            if !operator.is_comparison() {
+                return Err(AlertError::InvalidCondition(format!(
+                    "PromQL condition does not support operator [{operator}]"
+                )));
+            }
+        }
+        QueryType::Composite => {
+            let Some(composite) = alert.query_condition.composite.as_ref() else {
+                return Err(AlertError::CompositeMissingCondition);
+            };
+            composite.validate().map_err(AlertError::InvalidCondition)?;
+        }
+        QueryType::Anomaly => {
+            let has_sql = alert
+                .query_condition
+                .sql
+                .as_ref()
+                .is_some_and(|sql| !sql.is_empty());
+            let has_promql = alert
+                .query_condition
+                .promql
+                .as_ref()
+                .is_some_and(|promql| !promql.is_empty());
+            if has_sql == has_promql {
+                return Err(AlertError::AnomalyMissingQuery);
+            }
+            if has_sql && RE_ONLY_SELECT.is_match(alert.query_condition.sql.as_ref().unwrap()) {
+                return Err(AlertError::SqlContainsSelectStar);
+            }
+            let anomaly = alert
+                .query_condition
+                .anomaly
+                .get_or_insert_with(Default::default);
+            if !(1..=52).contains(&anomaly.baseline_weeks) {
+                return Err(AlertError::AnomalyInvalidCondition(
+                    "baseline_weeks should be between 1 and 52".to_string(),
+                ));
+            }
+            if anomaly.sensitivity <= 0.0 {
+                return Err(AlertError::AnomalyInvalidCondition(
+                    "sensitivity should be greater than 0".to_string(),
+                ));
+            }
+            if anomaly.min_consecutive == 0 {
+                return Err(AlertError::AnomalyInvalidCondition(
+                    "min_consecutive should be at least 1".to_string(),
+                ));
+            }
         }
     }
 
-    // Commented intentionally - in case the alert period is big and there
-    // is huge amount of data within the time period, the below can timeout and return error.
-    // // test the alert
-    // if let Err(e) = &alert.evaluate(None).await {
-    //     return Err(anyhow::anyhow!("Alert test failed: {}", e));
-    // }
-
     Ok(())
 }
 
@@ -738,6 +889,10 @@ pub async fn delete_by_id<C: ConnectionTrait>(
     };
 
     let alert_id_str = alert_id.to_string();
+    let dependents = dependencies::dependent_alerts(org_id, &alert_id_str).await;
+    if !dependents.is_empty() {
+        return Err(AlertError::AlertReferenced(dependents.join(", ")));
+    }
     match db::alerts::alert::delete_by_id(conn, org_id, alert_id).await {
         Ok(_) => {
             remove_ownership(org_id, "alerts", Authz::new(&alert_id_str)).await;
@@ -880,6 +1035,8 @@ impl AlertExt for Alert {
                     Some(SearchEventType::Alerts),
                     Some(search_event_ctx),
                     trace_id,
+                    // the query runs with the row filters of whoever last edited the alert
+                    self.last_edited_by.clone().or_else(|| self.owner.clone()),
                 )
                 .await
         }
@@ -968,48 +1125,40 @@ async fn send_notification(
         process_row_template(&org_name, &alert.row_template, alert, rows)
     };
     let is_email = matches!(dest_type, DestinationType::Email(_));
-    let msg: String = process_dest_template(
+    let ctx = build_template_context(
         &org_name,
-        &template.body,
         alert,
         rows,
-        &rows_tpl_val,
+        rows_tpl_val,
         ProcessTemplateOptions {
             rows_end_time,
             start_time,
             evaluation_timestamp,
-            is_email,
         },
     )
     .await;
+    let msg = templates::render(template.engine, &template.body, &ctx, is_email)?;
 
     let email_subject = if let TemplateType::Email { title } = &template.template_type {
-        process_dest_template(
-            &org_name,
-            title,
-            alert,
-            rows,
-            &rows_tpl_val,
-            ProcessTemplateOptions {
-                rows_end_time,
-                start_time,
-                evaluation_timestamp,
-                is_email,
-            },
-        )
-        .await
+        templates::render(template.engine, title, &ctx, is_email)?
     } else {
         template.name.clone()
     };
 
     match dest_type {
-        DestinationType::Http(endpoint) => send_http_notification(endpoint, msg).await,
+        DestinationType::Http(endpoint) => {
+            send_http_notification(&alert.org_id, endpoint, msg).await
+        }
         DestinationType::Email(email) => send_email_notification(&email_subject, email, msg).await,
         DestinationType::Sns(aws_sns) => send_sns_notification(&alert.name, aws_sns, msg).await,
     }
 }
 
-async fn send_http_notification(endpoint: &Endpoint, msg: String) -> Result<String, anyhow::Error> {
+async fn send_http_notification(
+    org_id: &str,
+    endpoint: &Endpoint,
+    msg: String,
+) -> Result<String, anyhow::Error> {
     #[cfg(feature = "enterprise")]
     let msg = if endpoint.action_id.is_some() {
         let incoming_msg = serde_json::from_str::<serde_json::Value>(&msg)
@@ -1041,6 +1190,8 @@ async fn send_http_notification(endpoint: &Endpoint, msg: String) -> Result<Stri
         msg
     };
 
+    // resolve the `${secret:name}` references, the unresolved endpoint is the one logged
+    let resolved = secrets::resolve_endpoint(org_id, endpoint).await?;
     let client = if endpoint.skip_tls_verify {
         reqwest::Client::builder()
             .danger_accept_invalid_certs(true)
@@ -1048,7 +1199,7 @@ async fn send_http_notification(endpoint: &Endpoint, msg: String) -> Result<Stri
     } else {
         reqwest::Client::new()
     };
-    let url = url::Url::parse(&endpoint.url)?;
+    let url = url::Url::parse(&resolved.url)?;
     let mut req = match endpoint.method {
         HTTPType::POST => client.post(url),
         HTTPType::PUT => client.put(url),
@@ -1057,7 +1208,7 @@ async fn send_http_notification(endpoint: &Endpoint, msg: String) -> Result<Stri
 
     // Add additional headers if any from destination description
     let mut has_context_type = false;
-    if let Some(headers) = &endpoint.headers {
+    if let Some(headers) = &resolved.headers {
         for (key, value) in headers.iter() {
             if !key.is_empty() && !value.is_empty() {
                 if key.to_lowercase().trim() == "content-type" {
@@ -1275,23 +1426,21 @@ struct ProcessTemplateOptions {
     pub rows_end_time: i64,
     pub start_time: Option<i64>,
     pub evaluation_timestamp: i64,
-    pub is_email: bool,
 }
 
-async fn process_dest_template(
+/// Builds the variables the templates of the alert destinations are rendered with.
+async fn build_template_context(
     org_name: &str,
-    tpl: &str,
     alert: &Alert,
     rows: &[Map<String, Value>],
-    rows_tpl_val: &[String],
+    rows_tpl_val: Vec<String>,
     options: ProcessTemplateOptions,
-) -> String {
+) -> TemplateContext {
     let cfg = get_config();
     let ProcessTemplateOptions {
         rows_end_time,
         start_time,
         evaluation_timestamp,
-        is_email,
     } = options;
     // format values
     let alert_count = rows.len();
@@ -1325,31 +1474,6 @@ async fn process_dest_template(
         use_given_time,
     );
 
-    let alert_start_time_str = if alert_start_time > 0 {
-        Local
-            .timestamp_nanos(alert_start_time * 1000)
-            .format("%Y-%m-%dT%H:%M:%S")
-            .to_string()
-    } else {
-        String::from("N/A")
-    };
-    let alert_end_time_str = if alert_end_time > 0 {
-        Local
-            .timestamp_nanos(alert_end_time * 1000)
-            .format("%Y-%m-%dT%H:%M:%S")
-            .to_string()
-    } else {
-        String::from("N/A")
-    };
-    let evaluation_timestamp_str = if evaluation_timestamp > 0 {
-        Local
-            .timestamp_nanos(evaluation_timestamp * 1000)
-            .format("%Y-%m-%dT%H:%M:%S")
-            .to_string()
-    } else {
-        String::from("N/A")
-    };
-
     let alert_type = if alert.is_real_time {
         "realtime"
     } else {
@@ -1370,18 +1494,30 @@ async fn process_dest_template(
                 .replace('+', "%2B")
         )
     };
-    let alert_url = if alert.query_condition.query_type == QueryType::PromQL {
+    let is_promql = match alert.query_condition.query_type {
+        QueryType::PromQL => true,
+        // anomaly alerts evaluate either a SQL or a PromQL series
+        QueryType::Anomaly => alert
+            .query_condition
+            .promql
+            .as_ref()
+            .is_some_and(|promql| !promql.is_empty()),
+        _ => false,
+    };
+    let alert_url = if is_promql {
         if let Some(promql) = &alert.query_condition.promql {
-            let condition = alert.query_condition.promql_condition.as_ref().unwrap();
-            alert_query = format!(
-                "({}) {} {}",
-                promql,
-                match condition.operator {
-                    Operator::EqualTo => "==".to_string(),
-                    _ => condition.operator.to_string(),
-                },
-                to_float(&condition.value)
-            );
+            alert_query = match alert.query_condition.promql_condition.as_ref() {
+                Some(condition) => format!(
+                    "({}) {} {}",
+                    promql,
+                    match condition.operator {
+                        Operator::EqualTo => "==".to_string(),
+                        _ => condition.operator.to_string(),
+                    },
+                    to_float(&condition.value)
+                ),
+                None => promql.clone(),
+            };
         }
         // http://localhost:5080/web/metrics?stream=zo_http_response_time_bucket&from=1705248000000000&to=1705334340000000&query=em9faHR0cF9yZXNwb25zZV90aW1lX2J1Y2tldHt9&org_identifier=default
         format!(
@@ -1400,7 +1536,7 @@ async fn process_dest_template(
         )
     } else {
         match alert.query_condition.query_type {
-            QueryType::SQL => {
+            QueryType::SQL | QueryType::Anomaly => {
                 if let Some(sql) = &alert.query_condition.sql {
                     alert_query = sql.clone();
                 }
@@ -1420,6 +1556,8 @@ async fn process_dest_template(
                     }
                 }
             }
+            // composite alerts have no query of their own
+            QueryType::Composite => {}
             _ => unreachable!(),
         };
         // http://localhost:5080/web/logs?stream_type=logs&stream=test&from=1708416534519324&to=1708416597898186&sql_mode=true&query=U0VMRUNUICogRlJPTSAidGVzdCIgd2hlcmUgbGV2ZWwgPSAnaW5mbyc=&org_identifier=default
@@ -1448,31 +1586,79 @@ async fn process_dest_template(
         }
     };
 
-    let evaluation_timestamp_millis = evaluation_timestamp / 1000;
+    TemplateContext {
+        org_name: org_name.to_string(),
+        alert: TemplateAlert {
+            name: alert.name.clone(),
+            alert_type: alert_type.to_string(),
+            stream_type: alert.stream_type.to_string(),
+            stream_name: alert.stream_name.clone(),
+            period: alert.trigger_condition.period,
+            operator: alert.trigger_condition.operator.to_string(),
+            threshold: alert.trigger_condition.threshold,
+            count: alert_count,
+            start_time: alert_start_time,
+            end_time: alert_end_time,
+            trigger_time: evaluation_timestamp,
+            promql_operator: alert
+                .query_condition
+                .promql_condition
+                .as_ref()
+                .map(|condition| condition.operator.to_string()),
+            promql_value: alert
+                .query_condition
+                .promql_condition
+                .as_ref()
+                .map(|condition| condition.value.to_string()),
+            context_attributes: alert
+                .context_attributes
+                .as_ref()
+                .map(|attrs| attrs.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+                .unwrap_or_default(),
+        },
+        rows: rows.to_vec(),
+        rendered_rows: rows_tpl_val,
+        values: vars
+            .into_iter()
+            .map(|(key, values)| (key, values.into_iter().sorted().collect()))
+            .collect(),
+        links: TemplateLinks { alert_url },
+    }
+}
+
+fn format_template_time(micros: i64) -> String {
+    if micros > 0 {
+        Local
+            .timestamp_nanos(micros * 1000)
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string()
+    } else {
+        String::from("N/A")
+    }
+}
+
+/// Renders a template of the legacy engine, replacing its `{var}` placeholders.
+pub(super) fn render_legacy_template(tpl: &str, ctx: &TemplateContext, is_email: bool) -> String {
+    let alert = &ctx.alert;
+    let evaluation_timestamp_millis = alert.trigger_time / 1000;
     let evaluation_timestamp_seconds = evaluation_timestamp_millis / 1000;
     let mut resp = tpl
-        .replace("{org_name}", org_name)
-        .replace("{stream_type}", alert.stream_type.as_str())
+        .replace("{org_name}", &ctx.org_name)
+        .replace("{stream_type}", &alert.stream_type)
         .replace("{stream_name}", &alert.stream_name)
         .replace("{alert_name}", &alert.name)
-        .replace("{alert_type}", alert_type)
-        .replace(
-            "{alert_period}",
-            &alert.trigger_condition.period.to_string(),
-        )
+        .replace("{alert_type}", &alert.alert_type)
+        .replace("{alert_period}", &alert.period.to_string())
+        .replace("{alert_operator}", &alert.operator)
+        .replace("{alert_threshold}", &alert.threshold.to_string())
+        .replace("{alert_count}", &alert.count.to_string())
         .replace(
-            "{alert_operator}",
-            &alert.trigger_condition.operator.to_string(),
+            "{alert_start_time}",
+            &format_template_time(alert.start_time),
         )
-        .replace(
-            "{alert_threshold}",
-            &alert.trigger_condition.threshold.to_string(),
-        )
-        .replace("{alert_count}", &alert_count.to_string())
-        .replace("{alert_start_time}", &alert_start_time_str)
-        .replace("{alert_end_time}", &alert_end_time_str)
-        .replace("{alert_url}", &alert_url)
-        .replace("{alert_trigger_time}", &evaluation_timestamp.to_string())
+        .replace("{alert_end_time}", &format_template_time(alert.end_time))
+        .replace("{alert_url}", &ctx.links.alert_url)
+        .replace("{alert_trigger_time}", &alert.trigger_time.to_string())
         .replace(
             "{alert_trigger_time_millis}",
             &evaluation_timestamp_millis.to_string(),
@@ -1481,25 +1667,31 @@ async fn process_dest_template(
             "{alert_trigger_time_seconds}",
             &evaluation_timestamp_seconds.to_string(),
         )
-        .replace("{alert_trigger_time_str}", &evaluation_timestamp_str);
+        .replace(
+            "{alert_trigger_time_str}",
+            &format_template_time(alert.trigger_time),
+        );
 
-    if let Some(contidion) = &alert.query_condition.promql_condition {
-        resp = resp
-            .replace("{alert_promql_operator}", &contidion.operator.to_string())
-            .replace("{alert_promql_value}", &contidion.value.to_string());
+    if let Some(operator) = &alert.promql_operator {
+        resp = resp.replace("{alert_promql_operator}", operator);
+    }
+    if let Some(value) = &alert.promql_value {
+        resp = resp.replace("{alert_promql_value}", value);
     }
 
-    process_variable_replace(&mut resp, "rows", &VarValue::Vector(rows_tpl_val), is_email);
-    for (key, value) in vars.iter() {
+    process_variable_replace(
+        &mut resp,
+        "rows",
+        &VarValue::Vector(&ctx.rendered_rows),
+        is_email,
+    );
+    for (key, value) in ctx.values.iter() {
         if resp.contains(&format!("{{{key}}}")) {
-            let val = value.iter().cloned().collect::<Vec<_>>();
-            process_variable_replace(&mut resp, key, &VarValue::Str(&val.join(", ")), is_email);
+            process_variable_replace(&mut resp, key, &VarValue::Str(&value.join(", ")), is_email);
         }
     }
-    if let Some(attrs) = &alert.context_attributes {
-        for (key, value) in attrs.iter() {
-            process_variable_replace(&mut resp, key, &VarValue::Str(value), is_email);
-        }
+    for (key, value) in alert.context_attributes.iter() {
+        process_variable_replace(&mut resp, key, &VarValue::Str(value), is_email);
     }
 
     resp
@@ -0,0 +1,441 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Evaluates the anomaly query type. The series of the alert window is compared against a
+//! baseline computed from the same window, so the same weekday and hour, of the previous weeks.
+
+use chrono::Duration;
+use config::{
+    TIMESTAMP_COL_NAME,
+    meta::{
+        alerts::{AnomalyCondition, AnomalyMethod, QueryCondition, TriggerEvalResults},
+        cluster::RoleGroup,
+        search::{SearchEventContext, SearchEventType},
+        stream::StreamType,
+    },
+    utils::{
+        base64,
+        json::{Map, Value},
+        time::parse_timestamp_micro_from_value,
+    },
+};
+use hashbrown::HashMap;
+
+use super::alert::to_float;
+use crate::service::{
+    promql::{self, holt_winters_calculation, value::Sample},
+    search as SearchService,
+    self_reporting::http_report_metrics,
+};
+
+/// Makes the median absolute deviation comparable to a standard deviation.
+const MAD_SCALE: f64 = 1.4826;
+/// Smallest spread relative to the expected value, so a flat baseline doesn't make any small
+/// change anomalous.
+const MIN_RELATIVE_SPREAD: f64 = 0.01;
+const HOLT_WINTERS_SMOOTHING_FACTOR: f64 = 0.5;
+const HOLT_WINTERS_TREND_FACTOR: f64 = 0.5;
+
+/// One series of the query result, identified by its labels or its group by columns.
+struct Series {
+    labels: Map<String, Value>,
+    points: Vec<(i64, f64)>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Baseline {
+    expected: f64,
+    spread: f64,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn evaluate(
+    query_condition: &QueryCondition,
+    org_id: &str,
+    stream_type: StreamType,
+    (start_time, end_time): (i64, i64),
+    search_type: Option<SearchEventType>,
+    search_event_context: Option<SearchEventContext>,
+    trace_id: &str,
+    user_id: Option<String>,
+) -> Result<TriggerEvalResults, anyhow::Error> {
+    let anomaly = query_condition.anomaly.clone().unwrap_or_default();
+    let mut eval_results = TriggerEvalResults {
+        end_time,
+        ..Default::default()
+    };
+    // the current window first, then the same window of each previous week, oldest first, so
+    // the values of the baseline are ordered oldest first
+    let week = Duration::try_weeks(1).unwrap().num_microseconds().unwrap();
+    let req_start = std::time::Instant::now();
+    let mut current = HashMap::new();
+    // values of the previous weeks keyed by the series and by the time of the point of the
+    // current window they are the baseline of
+    let mut history: HashMap<String, HashMap<i64, Vec<f64>>> = HashMap::new();
+    for weeks_ago in std::iter::once(0).chain((1..=anomaly.baseline_weeks).rev()) {
+        let offset = week * weeks_ago;
+        let series = fetch_series(
+            query_condition,
+            &anomaly,
+            org_id,
+            stream_type,
+            (start_time - offset, end_time - offset),
+            search_type,
+            search_event_context.clone(),
+            trace_id,
+            user_id.clone(),
+        )
+        .await?;
+        if weeks_ago == 0 {
+            if series.is_empty() {
+                return Ok(eval_results);
+            }
+            current = series;
+            continue;
+        }
+        for (key, series) in series {
+            let points = history.entry(key).or_default();
+            for (timestamp, value) in series.points {
+                points.entry(timestamp + offset).or_default().push(value);
+            }
+        }
+    }
+    eval_results.query_took = Some(req_start.elapsed().as_millis() as i64);
+
+    let mut rows = vec![];
+    for (key, series) in current {
+        let Some(series_history) = history.get(&key) else {
+            continue;
+        };
+        let anomalies = series
+            .points
+            .iter()
+            .map(|(timestamp, actual)| {
+                series_history
+                    .get(timestamp)
+                    .and_then(|values| compute_baseline(&anomaly.method, values))
+                    .filter(|baseline| is_anomalous(baseline, *actual, anomaly.sensitivity))
+            })
+            .collect::<Vec<_>>();
+        let flags = anomalies.iter().map(Option::is_some).collect::<Vec<_>>();
+        for idx in sustained(&flags, anomaly.min_consecutive) {
+            let (timestamp, actual) = series.points[idx];
+            let baseline = anomalies[idx].as_ref().unwrap();
+            let bound = anomaly.sensitivity * baseline.spread;
+            let mut row = series.labels.clone();
+            row.insert(TIMESTAMP_COL_NAME.to_string(), timestamp.into());
+            row.insert("actual".to_string(), actual.into());
+            row.insert("expected".to_string(), baseline.expected.into());
+            row.insert(
+                "lower_bound".to_string(),
+                (baseline.expected - bound).into(),
+            );
+            row.insert(
+                "upper_bound".to_string(),
+                (baseline.expected + bound).into(),
+            );
+            rows.push(row);
+        }
+    }
+    if !rows.is_empty() {
+        eval_results.data = Some(rows);
+    }
+    Ok(eval_results)
+}
+
+/// Gets the expected value and the spread around it from the values of the previous weeks,
+/// ordered oldest first.
+fn compute_baseline(method: &AnomalyMethod, values: &[f64]) -> Option<Baseline> {
+    if values.len() < 2 {
+        return None;
+    }
+    let center = median(values);
+    let deviations = values
+        .iter()
+        .map(|value| (value - center).abs())
+        .collect::<Vec<_>>();
+    let mad = MAD_SCALE * median(&deviations);
+    let expected = match method {
+        AnomalyMethod::MedianMad => center,
+        AnomalyMethod::HoltWinters => {
+            let samples = values
+                .iter()
+                .enumerate()
+                .map(|(idx, value)| Sample::new(idx as i64, *value))
+                .collect::<Vec<_>>();
+            holt_winters_calculation(
+                &samples,
+                HOLT_WINTERS_SMOOTHING_FACTOR,
+                HOLT_WINTERS_TREND_FACTOR,
+            )?
+        }
+    };
+    let spread = mad.max(expected.abs() * MIN_RELATIVE_SPREAD);
+    Some(Baseline {
+        expected,
+        spread: if spread > 0.0 { spread } else { f64::EPSILON },
+    })
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut values = values.to_vec();
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+fn is_anomalous(baseline: &Baseline, actual: f64, sensitivity: f64) -> bool {
+    (actual - baseline.expected).abs() > sensitivity * baseline.spread
+}
+
+/// Gets the indexes of the anomalous points that are part of a run of at least `min_consecutive`
+/// anomalous points.
+fn sustained(anomalous: &[bool], min_consecutive: usize) -> Vec<usize> {
+    let min_consecutive = min_consecutive.max(1);
+    let mut indexes = vec![];
+    let mut run_start = 0;
+    for idx in 0..=anomalous.len() {
+        if idx < anomalous.len() && anomalous[idx] {
+            continue;
+        }
+        if idx - run_start >= min_consecutive {
+            indexes.extend(run_start..idx);
+        }
+        run_start = idx + 1;
+    }
+    indexes
+}
+
+fn series_key(labels: &Map<String, Value>) -> String {
+    let mut pairs = labels
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>();
+    pairs.sort();
+    pairs.join(",")
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn fetch_series(
+    query_condition: &QueryCondition,
+    anomaly: &AnomalyCondition,
+    org_id: &str,
+    stream_type: StreamType,
+    (start_time, end_time): (i64, i64),
+    search_type: Option<SearchEventType>,
+    search_event_context: Option<SearchEventContext>,
+    trace_id: &str,
+    user_id: Option<String>,
+) -> Result<HashMap<String, Series>, anyhow::Error> {
+    let mut series = HashMap::new();
+    if let Some(query) = query_condition.promql.as_ref().filter(|q| !q.is_empty()) {
+        let req = promql::MetricsQueryRequest {
+            query: query.to_string(),
+            start: start_time,
+            end: end_time,
+            // whole seconds, so the points of the previous weeks line up with the current ones
+            step: promql::round_step(std::cmp::max(
+                promql::micros(promql::MINIMAL_INTERVAL),
+                (end_time - start_time) / promql::MAX_DATA_POINTS,
+            )),
+            query_exemplars: false,
+            no_cache: None,
+        };
+        let user_email = user_id.as_deref().unwrap_or_default();
+        let resp = promql::search::search(trace_id, org_id, &req, user_email, 0)
+            .await
+            .map_err(|e| anyhow::anyhow!("Error evaluating PromQL query: {e}"))?;
+        let promql::value::Value::Matrix(matrix) = resp else {
+            return Ok(series);
+        };
+        for metric in matrix {
+            let labels = metric
+                .labels
+                .iter()
+                .map(|label| (label.name.to_string(), label.value.to_string().into()))
+                .collect::<Map<_, _>>();
+            let points = metric
+                .samples
+                .iter()
+                .map(|sample| (sample.timestamp, sample.value))
+                .collect();
+            series.insert(series_key(&labels), Series { labels, points });
+        }
+        return Ok(series);
+    }
+
+    let Some(sql) = query_condition.sql.as_ref().filter(|q| !q.is_empty()) else {
+        return Ok(series);
+    };
+    let query_fn = match query_condition.vrl_function.as_ref() {
+        Some(vrl) => Some(
+            base64::decode_url(vrl)
+                .map_err(|e| anyhow::anyhow!("Error decoding alert vrl query function: {e}"))?,
+        ),
+        None => None,
+    };
+    let req = config::meta::search::Request {
+        query: config::meta::search::Query {
+            sql: sql.to_string(),
+            from: 0,
+            size: -1,
+            start_time,
+            end_time,
+            quick_mode: false,
+            query_type: "".to_string(),
+            track_total_hits: false,
+            action_id: None,
+            uses_zo_fn: false,
+            query_fn,
+            skip_wal: false,
+            streaming_output: false,
+            streaming_id: None,
+            histogram_interval: 0,
+        },
+        encoding: config::meta::search::RequestEncoding::Empty,
+        regions: vec![],
+        clusters: vec![],
+        timeout: 0,
+        search_type,
+        search_event_context,
+        use_cache: false,
+        local_mode: None,
+    };
+    let req_start = std::time::Instant::now();
+    let resp = SearchService::grpc_search::grpc_search(
+        trace_id,
+        org_id,
+        stream_type,
+        user_id,
+        &req,
+        Some(RoleGroup::Background),
+    )
+    .await
+    .map_err(|e| match e {
+        infra::errors::Error::ErrorCode(e) => {
+            anyhow::anyhow!("{} {}", e.get_message(), e.get_inner_message())
+        }
+        e => anyhow::anyhow!("{e}"),
+    })?;
+    http_report_metrics(
+        req_start,
+        org_id,
+        stream_type,
+        "200",
+        "_search",
+        &SearchEventType::Alerts.to_string(),
+        "",
+    );
+    if resp.is_partial {
+        return Err(anyhow::anyhow!(
+            "Partial response: {}",
+            resp.function_error.join(", ")
+        ));
+    }
+
+    for hit in resp.hits {
+        let Value::Object(mut hit) = hit else {
+            continue;
+        };
+        let (Some(timestamp), Some(value)) = (
+            hit.remove(&anomaly.timestamp_column),
+            hit.remove(&anomaly.value_column),
+        ) else {
+            continue;
+        };
+        let Ok(timestamp) = parse_timestamp_micro_from_value(&timestamp) else {
+            continue;
+        };
+        // the remaining columns are the group by columns of the series
+        series
+            .entry(series_key(&hit))
+            .or_insert_with(|| Series {
+                labels: hit,
+                points: vec![],
+            })
+            .points
+            .push((timestamp, to_float(&value)));
+    }
+    for s in series.values_mut() {
+        s.points.sort_by_key(|(timestamp, _)| *timestamp);
+    }
+    Ok(series)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median_mad_baseline() {
+        let baseline =
+            compute_baseline(&AnomalyMethod::MedianMad, &[10.0, 12.0, 11.0, 13.0]).unwrap();
+        assert_eq!(baseline.expected, 11.5);
+        assert!((baseline.spread - MAD_SCALE).abs() < 1e-9);
+        assert!(!is_anomalous(&baseline, 14.0, 3.0));
+        assert!(is_anomalous(&baseline, 20.0, 3.0));
+        assert!(is_anomalous(&baseline, 5.0, 3.0));
+    }
+
+    #[test]
+    fn test_baseline_needs_history() {
+        assert!(compute_baseline(&AnomalyMethod::MedianMad, &[]).is_none());
+        assert!(compute_baseline(&AnomalyMethod::HoltWinters, &[1.0]).is_none());
+    }
+
+    #[test]
+    fn test_flat_baseline_spread() {
+        let baseline = compute_baseline(&AnomalyMethod::MedianMad, &[100.0, 100.0, 100.0]).unwrap();
+        assert_eq!(baseline.spread, 1.0);
+        assert!(!is_anomalous(&baseline, 102.0, 3.0));
+        assert!(is_anomalous(&baseline, 104.0, 3.0));
+
+        let baseline = compute_baseline(&AnomalyMethod::MedianMad, &[0.0, 0.0]).unwrap();
+        assert!(is_anomalous(&baseline, 1.0, 3.0));
+    }
+
+    #[test]
+    fn test_holt_winters_baseline_follows_trend() {
+        let baseline =
+            compute_baseline(&AnomalyMethod::HoltWinters, &[10.0, 20.0, 30.0, 40.0]).unwrap();
+        assert!(baseline.expected > 30.0);
+        assert!(!is_anomalous(&baseline, 45.0, 3.0));
+    }
+
+    #[test]
+    fn test_sustained() {
+        let flags = [true, false, true, true, true, false, true, true];
+        assert_eq!(sustained(&flags, 3), vec![2, 3, 4]);
+        assert_eq!(sustained(&flags, 2), vec![2, 3, 4, 6, 7]);
+        assert_eq!(sustained(&flags, 1), vec![0, 2, 3, 4, 6, 7]);
+        assert!(sustained(&flags, 4).is_empty());
+        assert!(sustained(&[], 1).is_empty());
+    }
+
+    #[test]
+    fn test_series_key_ignores_column_order() {
+        let mut a = Map::new();
+        a.insert("host".to_string(), "a".into());
+        a.insert("region".to_string(), "eu".into());
+        let mut b = Map::new();
+        b.insert("region".to_string(), "eu".into());
+        b.insert("host".to_string(), "a".into());
+        assert_eq!(series_key(&a), series_key(&b));
+    }
+}
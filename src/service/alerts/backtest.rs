@@ -0,0 +1,124 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Replays the evaluations the scheduler would have run for an alert over a past time range,
+//! without saving the alert or sending any notification.
+
+use chrono::Duration;
+use config::{
+    get_config,
+    meta::alerts::{
+        QueryType,
+        alert::Alert,
+        backtest::{BacktestEvaluation, BacktestResult},
+    },
+    utils::time::now_micros,
+};
+
+use super::alert::{
+    AlertError, AlertExt, prepare_trigger_condition, prepare_vrl_function, validate_query_condition,
+};
+
+pub async fn backtest(
+    org_id: &str,
+    mut alert: Alert,
+    start_time: i64,
+    end_time: i64,
+    max_rows: usize,
+) -> Result<BacktestResult, AlertError> {
+    if alert.is_real_time {
+        return Err(AlertError::BacktestRealtime);
+    }
+    // composite alerts read the current states of other alerts, which can't be replayed
+    if alert.query_condition.query_type == QueryType::Composite {
+        return Err(AlertError::BacktestComposite);
+    }
+    if start_time >= end_time {
+        return Err(AlertError::BacktestInvalidRange(
+            "start_time should be less than end_time".to_string(),
+        ));
+    }
+    if end_time > now_micros() {
+        return Err(AlertError::BacktestInvalidRange(
+            "end_time can't be in the future".to_string(),
+        ));
+    }
+
+    alert.org_id = org_id.to_string();
+    prepare_trigger_condition(&mut alert)?;
+    prepare_vrl_function(&mut alert)?;
+    let stream_name = alert.stream_name.clone();
+    validate_query_condition(org_id, &stream_name, &mut alert).await?;
+    // the scheduler delays each run by a random tolerance, replay the exact schedule instead
+    alert.trigger_condition.tolerance_in_secs = None;
+
+    let max_evaluations = get_config().limit.alert_backtest_max_evaluations;
+    let period = Duration::try_minutes(alert.trigger_condition.period)
+        .unwrap()
+        .num_microseconds()
+        .unwrap();
+    let mut result = BacktestResult::default();
+    let mut run_at = next_run_at(&alert, false, start_time)?;
+    while run_at <= end_time {
+        if result.evaluations.len() >= max_evaluations {
+            result.truncated = true;
+            break;
+        }
+
+        let mut evaluation = BacktestEvaluation {
+            start_time: run_at - period,
+            end_time: run_at,
+            ..Default::default()
+        };
+        match alert
+            .evaluate(None, (Some(evaluation.start_time), run_at), None)
+            .await
+        {
+            Ok(eval_results) => {
+                if let Some(mut rows) = eval_results.data {
+                    evaluation.fired = true;
+                    evaluation.row_count = rows.len();
+                    rows.truncate(max_rows);
+                    evaluation.rows = rows;
+                }
+            }
+            Err(e) => evaluation.error = Some(e.to_string()),
+        }
+
+        // same as the scheduler, the alert is silenced after it fires
+        let silenced = evaluation.fired && alert.trigger_condition.silence > 0;
+        let next = next_run_at(&alert, silenced, run_at)?;
+        if silenced {
+            evaluation.silenced_until = Some(next);
+        }
+        if evaluation.fired {
+            result.fired += 1;
+        }
+        result.evaluations.push(evaluation);
+
+        if next <= run_at {
+            break;
+        }
+        run_at = next;
+    }
+    Ok(result)
+}
+
+fn next_run_at(alert: &Alert, apply_silence: bool, start_from: i64) -> Result<i64, AlertError> {
+    alert
+        .trigger_condition
+        .get_next_trigger_time(true, alert.tz_offset, apply_silence, Some(start_from))
+        .map_err(|e| AlertError::BacktestFailed(e.to_string()))
+}
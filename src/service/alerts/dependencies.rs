@@ -0,0 +1,370 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Alerts reading the state of other alerts: composite alerts combine the firing states of
+//! other alerts, and dependent alerts are suppressed while one of their parents is firing.
+//! The state of an alert is the `firing` field of its trigger data, set at each scheduled
+//! evaluation and cached in `ALERT_FIRING_STATES`.
+
+use config::{
+    TIMESTAMP_COL_NAME,
+    meta::{
+        alerts::{CompositeCondition, TriggerEvalResults, alert::Alert},
+        triggers::{Trigger, TriggerModule},
+    },
+    utils::json::{Map, Value},
+};
+use hashbrown::{HashMap, HashSet};
+
+use super::alert::AlertError;
+use crate::{
+    common::infra::config::ALERTS,
+    service::db::{
+        self,
+        alerts::alert::{cache_alert_key, scheduler_key},
+    },
+};
+
+/// Checks whether the alert is enabled and its conditions were satisfied at its last
+/// evaluation.
+pub async fn is_firing(org_id: &str, alert_id: &str) -> bool {
+    let enabled = ALERTS
+        .read()
+        .await
+        .get(&cache_alert_key(org_id, alert_id))
+        .is_some_and(|(_, alert)| alert.enabled);
+    if !enabled {
+        return false;
+    }
+    db::alerts::firing_states::is_firing(org_id, alert_id)
+}
+
+/// Returns the name of the first parent of the alert that is firing.
+pub async fn firing_parent(org_id: &str, alert: &Alert) -> Option<String> {
+    for parent_id in alert.depends_on.iter() {
+        if is_firing(org_id, parent_id).await {
+            let cacher = ALERTS.read().await;
+            let name = cacher
+                .get(&cache_alert_key(org_id, parent_id))
+                .map_or(parent_id.to_string(), |(_, parent)| parent.name.clone());
+            return Some(name);
+        }
+    }
+    None
+}
+
+/// Evaluates the composite query type. When the condition is satisfied, the result has one
+/// row per referenced alert with its state.
+pub(super) async fn evaluate_composite(
+    org_id: &str,
+    composite: &CompositeCondition,
+    end_time: i64,
+) -> Result<TriggerEvalResults, anyhow::Error> {
+    let mut eval_results = TriggerEvalResults {
+        end_time,
+        ..Default::default()
+    };
+    let alert_ids = composite.alert_ids();
+    let mut firing = HashSet::with_capacity(alert_ids.len());
+    for alert_id in alert_ids.iter() {
+        if is_firing(org_id, alert_id).await {
+            firing.insert(alert_id.to_string());
+        }
+    }
+    if !composite.evaluate(&|alert_id| firing.contains(alert_id)) {
+        return Ok(eval_results);
+    }
+
+    let cacher = ALERTS.read().await;
+    let rows = alert_ids
+        .iter()
+        .map(|alert_id| {
+            let name = cacher
+                .get(&cache_alert_key(org_id, alert_id))
+                .map_or(String::new(), |(_, alert)| alert.name.clone());
+            let mut row = Map::with_capacity(4);
+            row.insert(TIMESTAMP_COL_NAME.to_string(), end_time.into());
+            row.insert("alert_id".to_string(), alert_id.to_string().into());
+            row.insert("alert_name".to_string(), name.into());
+            row.insert(
+                "firing".to_string(),
+                Value::Bool(firing.contains(*alert_id)),
+            );
+            row
+        })
+        .collect();
+    eval_results.data = Some(rows);
+    Ok(eval_results)
+}
+
+/// Checks the alerts referenced by the alert exist and are scheduled, and that its
+/// references don't make a cycle.
+pub(super) async fn validate(org_id: &str, alert: &Alert) -> Result<(), AlertError> {
+    let upstream_ids = alert.upstream_alert_ids();
+    if upstream_ids.is_empty() {
+        return Ok(());
+    }
+    {
+        let cacher = ALERTS.read().await;
+        for upstream_id in upstream_ids.iter() {
+            let Some((_, upstream)) = cacher.get(&cache_alert_key(org_id, upstream_id)) else {
+                return Err(AlertError::DependencyNotFound(upstream_id.to_string()));
+            };
+            if upstream.is_real_time {
+                return Err(AlertError::DependencyRealtime(upstream.name.clone()));
+            }
+        }
+    }
+    // a new alert isn't referenced by any alert yet
+    if alert.id.is_none() {
+        return Ok(());
+    }
+
+    let key = cache_alert_key(org_id, &scheduler_key(alert.id));
+    let mut graph = dependency_graph().await;
+    graph.insert(
+        key.clone(),
+        upstream_ids
+            .iter()
+            .map(|id| cache_alert_key(org_id, id))
+            .collect(),
+    );
+    if let Some(cycle) = find_cycle(&graph, &key) {
+        let cacher = ALERTS.read().await;
+        let names = std::iter::once(&key)
+            .chain(cycle.iter())
+            .map(|node| {
+                if *node == key {
+                    alert.name.clone()
+                } else {
+                    cacher
+                        .get(node)
+                        .map_or(node.to_string(), |(_, upstream)| upstream.name.clone())
+                }
+            })
+            .collect::<Vec<_>>();
+        return Err(AlertError::DependencyCycle(names.join(" -> ")));
+    }
+    Ok(())
+}
+
+/// Returns the names of the alerts referencing the given alert.
+pub(super) async fn dependent_alerts(org_id: &str, alert_id: &str) -> Vec<String> {
+    let prefix = format!("{org_id}/");
+    ALERTS
+        .read()
+        .await
+        .iter()
+        .filter(|(key, _)| key.starts_with(&prefix))
+        .filter(|(_, (_, alert))| alert.upstream_alert_ids().contains(&alert_id))
+        .map(|(_, (_, alert))| alert.name.clone())
+        .collect()
+}
+
+/// Returns, for each trigger, the positions of the triggers of the same batch whose alerts it
+/// references, and which are evaluated first.
+pub async fn batch_parents(triggers: &[Trigger]) -> Vec<Vec<usize>> {
+    let graph = dependency_graph().await;
+    if graph.is_empty() {
+        return vec![vec![]; triggers.len()];
+    }
+    let keys = triggers
+        .iter()
+        .map(|trigger| {
+            (trigger.module == TriggerModule::Alert)
+                .then(|| cache_alert_key(&trigger.org, &trigger.module_key))
+        })
+        .collect::<Vec<_>>();
+    parents_in_batch(&graph, &keys)
+}
+
+/// See [batch_parents], `keys` are the alert cache keys of the triggers. A reference only
+/// counts when it has a lower level than the alert, see [dependency_levels], so the alerts of
+/// a cycle don't wait for each other.
+fn parents_in_batch(
+    graph: &HashMap<String, Vec<String>>,
+    keys: &[Option<String>],
+) -> Vec<Vec<usize>> {
+    let levels = dependency_levels(graph);
+    let level = |key: &str| levels.get(key).copied().unwrap_or_default();
+    let positions = keys
+        .iter()
+        .enumerate()
+        .filter_map(|(i, key)| key.as_deref().map(|key| (key, i)))
+        .collect::<HashMap<_, _>>();
+    keys.iter()
+        .map(|key| {
+            let Some(key) = key.as_deref() else {
+                return vec![];
+            };
+            graph
+                .get(key)
+                .into_iter()
+                .flatten()
+                .filter(|upstream| level(upstream) < level(key))
+                .filter_map(|upstream| positions.get(upstream.as_str()).copied())
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the ids of the alerts referenced by each alert, only for the alerts with
+/// references. The ids are the `org/alert_id` keys of the alerts cache.
+async fn dependency_graph() -> HashMap<String, Vec<String>> {
+    ALERTS
+        .read()
+        .await
+        .iter()
+        .filter_map(|(key, (_, alert))| {
+            let upstream_ids = alert.upstream_alert_ids();
+            (!upstream_ids.is_empty()).then(|| {
+                let upstream_keys = upstream_ids
+                    .iter()
+                    .map(|id| cache_alert_key(&alert.org_id, id))
+                    .collect();
+                (key.to_string(), upstream_keys)
+            })
+        })
+        .collect()
+}
+
+/// Returns a path from the references of `start` back to `start`, if any.
+fn find_cycle(graph: &HashMap<String, Vec<String>>, start: &str) -> Option<Vec<String>> {
+    fn visit<'a>(
+        graph: &'a HashMap<String, Vec<String>>,
+        node: &str,
+        start: &str,
+        visited: &mut HashSet<&'a str>,
+        path: &mut Vec<String>,
+    ) -> bool {
+        for next in graph.get(node).into_iter().flatten() {
+            path.push(next.to_string());
+            if next == start {
+                return true;
+            }
+            if visited.insert(next.as_str()) && visit(graph, next, start, visited, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    let mut visited = HashSet::new();
+    let mut path = Vec::new();
+    visit(graph, start, start, &mut visited, &mut path).then_some(path)
+}
+
+/// Computes the level of each node of the graph: a node without references has level 0, and
+/// the other nodes have one more level than the highest level of their references. The nodes
+/// of a cycle, which can't be saved, are treated as having no references.
+fn dependency_levels(graph: &HashMap<String, Vec<String>>) -> HashMap<&str, usize> {
+    fn visit<'a>(
+        graph: &'a HashMap<String, Vec<String>>,
+        node: &'a str,
+        levels: &mut HashMap<&'a str, usize>,
+        visiting: &mut HashSet<&'a str>,
+    ) -> usize {
+        if let Some(level) = levels.get(node) {
+            return *level;
+        }
+        let Some(upstream) = graph.get(node) else {
+            return 0;
+        };
+        if !visiting.insert(node) {
+            return 0;
+        }
+        let level = upstream
+            .iter()
+            .map(|next| visit(graph, next, levels, visiting) + 1)
+            .max()
+            .unwrap_or_default();
+        visiting.remove(node);
+        levels.insert(node, level);
+        level
+    }
+
+    let mut levels = HashMap::with_capacity(graph.len());
+    let mut visiting = HashSet::new();
+    for node in graph.keys() {
+        visit(graph, node, &mut levels, &mut visiting);
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(edges: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        edges
+            .iter()
+            .map(|(node, upstream)| {
+                (
+                    node.to_string(),
+                    upstream.iter().map(|id| id.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let g = graph(&[("c", &["a", "b"]), ("d", &["c"]), ("b", &["a"])]);
+        assert_eq!(find_cycle(&g, "d"), None);
+        assert_eq!(find_cycle(&g, "c"), None);
+
+        let g = graph(&[("c", &["a", "b"]), ("d", &["c"]), ("b", &["d"])]);
+        assert_eq!(
+            find_cycle(&g, "d"),
+            Some(vec!["c".to_string(), "b".to_string(), "d".to_string()])
+        );
+
+        let g = graph(&[("a", &["a"])]);
+        assert_eq!(find_cycle(&g, "a"), Some(vec!["a".to_string()]));
+    }
+
+    #[test]
+    fn test_dependency_levels() {
+        let g = graph(&[("c", &["a", "b"]), ("d", &["c"]), ("b", &["a"])]);
+        let levels = dependency_levels(&g);
+        assert_eq!(levels.get("a"), None);
+        assert_eq!(levels.get("b"), Some(&1));
+        assert_eq!(levels.get("c"), Some(&2));
+        assert_eq!(levels.get("d"), Some(&3));
+
+        // cycles don't loop forever
+        let g = graph(&[("a", &["b"]), ("b", &["a"])]);
+        let levels = dependency_levels(&g);
+        assert_eq!(levels.len(), 2);
+    }
+
+    #[test]
+    fn test_parents_in_batch() {
+        let g = graph(&[("c", &["a", "b"]), ("d", &["c"]), ("b", &["a"])]);
+        let keys =
+            [Some("d"), Some("a"), None, Some("c"), Some("x")].map(|key| key.map(String::from));
+        assert_eq!(
+            parents_in_batch(&g, &keys),
+            vec![vec![3], vec![], vec![], vec![1], vec![]]
+        );
+
+        // only one alert of a cycle waits for the other
+        let g = graph(&[("a", &["b"]), ("b", &["a"])]);
+        let keys = [Some("a"), Some("b")].map(|key| key.map(String::from));
+        let parents = parents_in_batch(&g, &keys);
+        assert_eq!(parents.iter().map(Vec::len).sum::<usize>(), 1);
+    }
+}
@@ -99,6 +99,16 @@ pub async fn save(
                 ));
             }
         }
+        QueryType::Anomaly => {
+            return Err(anyhow::anyhow!(
+                "Scheduled pipeline doesn't support the anomaly query type"
+            ));
+        }
+        QueryType::Composite => {
+            return Err(anyhow::anyhow!(
+                "Scheduled pipeline doesn't support the composite query type"
+            ));
+        }
         _ => {}
     };
     // End input validation
@@ -218,6 +228,7 @@ impl DerivedStreamExt for DerivedStream {
                     module_key.to_string(),
                 ))),
                 trace_id,
+                self.last_edited_by.clone(),
             )
             .await
     }
@@ -21,7 +21,10 @@ use crate::{
         meta::authz::Authz,
         utils::auth::{is_ofga_unsupported, remove_ownership, set_ownership},
     },
-    service::db::{self, alerts::destinations::DestinationError, user},
+    service::{
+        db::{self, alerts::destinations::DestinationError, user},
+        secrets::{self, SecretError},
+    },
 };
 
 pub async fn save(
@@ -82,17 +85,39 @@ pub async fn save(
         return Err(DestinationError::InvalidName);
     }
 
-    match db::alerts::destinations::get(&destination.org_id, &destination.name).await {
-        Ok(_) => {
+    let existing = db::alerts::destinations::get(&destination.org_id, &destination.name).await;
+    let existing = match existing {
+        Ok(existing) => {
             if create {
                 return Err(DestinationError::AlreadyExists);
             }
+            Some(existing)
         }
         Err(_) => {
             if !create {
                 return Err(DestinationError::NotFound);
             }
+            None
+        }
+    };
+
+    // credentials are redacted when read back, so updates send back the redacted values
+    if let Some(endpoint) = destination.endpoint_mut() {
+        let saved = existing.as_ref().and_then(|dest| dest.endpoint());
+        if let Some(header) = endpoint.restore_redacted(saved).into_iter().next() {
+            return Err(DestinationError::RedactedValue(header));
         }
+        if let Some(part) = endpoint.plaintext_url_credential() {
+            return Err(DestinationError::PlaintextUrlCredential(part));
+        }
+    }
+    if let Some(endpoint) = destination.endpoint() {
+        secrets::check_refs(&destination.org_id, &endpoint.secret_refs())
+            .await
+            .map_err(|e| match e {
+                SecretError::NotFound(name) => DestinationError::SecretNotFound(name),
+                e => DestinationError::InfraError(infra::errors::Error::Message(e.to_string())),
+            })?;
     }
 
     let saved = db::alerts::destinations::set(destination).await?;
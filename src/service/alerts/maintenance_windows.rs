@@ -0,0 +1,132 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use config::{
+    ider,
+    meta::alerts::{alert::Alert, maintenance_window::MaintenanceWindow},
+    utils::time::now_micros,
+};
+
+use crate::service::db::{self, alerts::alert::scheduler_key};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MaintenanceWindowError {
+    #[error("InfraError# {0}")]
+    InfraError(#[from] infra::errors::Error),
+    #[error("{0}")]
+    Invalid(String),
+    #[error("Maintenance window with the same name already exists")]
+    AlreadyExists,
+    #[error("Maintenance window not found")]
+    NotFound,
+}
+
+pub fn list(org_id: &str) -> Vec<MaintenanceWindow> {
+    let mut windows = db::alerts::maintenance_windows::list(org_id);
+    windows.sort_by(|a, b| a.name.cmp(&b.name));
+    windows
+}
+
+pub fn get(org_id: &str, id: &str) -> Result<MaintenanceWindow, MaintenanceWindowError> {
+    db::alerts::maintenance_windows::get(org_id, id).ok_or(MaintenanceWindowError::NotFound)
+}
+
+pub async fn save(
+    org_id: &str,
+    id: Option<&str>,
+    mut window: MaintenanceWindow,
+    user_id: &str,
+) -> Result<MaintenanceWindow, MaintenanceWindowError> {
+    window.name = window.name.trim().to_string();
+    window.validate().map_err(MaintenanceWindowError::Invalid)?;
+    match id {
+        Some(id) => {
+            let Some(existing) = db::alerts::maintenance_windows::get(org_id, id) else {
+                return Err(MaintenanceWindowError::NotFound);
+            };
+            window.id = id.to_string();
+            window.created_by = existing.created_by;
+        }
+        None => {
+            window.id = ider::generate();
+            window.created_by = user_id.to_string();
+        }
+    }
+    if db::alerts::maintenance_windows::list(org_id)
+        .iter()
+        .any(|w| w.name == window.name && w.id != window.id)
+    {
+        return Err(MaintenanceWindowError::AlreadyExists);
+    }
+    window.updated_at = now_micros();
+    db::alerts::maintenance_windows::set(org_id, &window).await?;
+    Ok(window)
+}
+
+pub async fn delete(org_id: &str, id: &str) -> Result<(), MaintenanceWindowError> {
+    if db::alerts::maintenance_windows::get(org_id, id).is_none() {
+        return Err(MaintenanceWindowError::NotFound);
+    }
+    db::alerts::maintenance_windows::delete(org_id, id).await?;
+    Ok(())
+}
+
+/// Returns the maintenance window of the org suppressing the notifications of the alert at
+/// `now`, with the end of its current occurrence.
+pub fn active_window(
+    org_id: &str,
+    folder_id: &str,
+    alert: &Alert,
+    now: i64,
+) -> Option<(MaintenanceWindow, i64)> {
+    select_window(
+        db::alerts::maintenance_windows::list(org_id),
+        folder_id,
+        alert,
+        now,
+    )
+}
+
+/// Returns the maintenance window suppressing the notifications of the realtime alert at `now`.
+/// The folder of the alert is only taken from the alerts cache when the org has maintenance
+/// windows, and windows without a folder selector still apply when the alert isn't cached.
+pub async fn active_window_of_realtime(
+    alert: &Alert,
+    now: i64,
+) -> Option<(MaintenanceWindow, i64)> {
+    let windows = db::alerts::maintenance_windows::list(&alert.org_id);
+    if windows.is_empty() {
+        return None;
+    }
+    let folder_id =
+        db::alerts::alert::get_alert_from_cache(&alert.org_id, &scheduler_key(alert.id))
+            .await
+            .map(|(folder, _)| folder.folder_id)
+            .unwrap_or_default();
+    select_window(windows, &folder_id, alert, now)
+}
+
+fn select_window(
+    windows: Vec<MaintenanceWindow>,
+    folder_id: &str,
+    alert: &Alert,
+    now: i64,
+) -> Option<(MaintenanceWindow, i64)> {
+    windows
+        .into_iter()
+        .filter(|window| window.targets(folder_id, alert))
+        .filter_map(|window| window.active_until(now).map(|until| (window, until)))
+        .max_by_key(|(_, until)| *until)
+}
@@ -42,8 +42,12 @@ use crate::service::{
 };
 
 pub mod alert;
+pub mod anomaly;
+pub mod backtest;
+pub mod dependencies;
 pub mod derived_streams;
 pub mod destinations;
+pub mod maintenance_windows;
 pub mod scheduler;
 pub mod templates;
 
@@ -65,6 +69,7 @@ pub trait QueryConditionExt: Sync + Send + 'static {
         search_type: Option<SearchEventType>,
         search_event_context: Option<SearchEventContext>,
         trace_id: Option<String>,
+        user_id: Option<String>,
     ) -> Result<TriggerEvalResults, anyhow::Error>;
 }
 
@@ -106,6 +111,7 @@ impl QueryConditionExt for QueryCondition {
         search_type: Option<SearchEventType>,
         search_event_context: Option<SearchEventContext>,
         trace_id: Option<String>,
+        user_id: Option<String>,
     ) -> Result<TriggerEvalResults, anyhow::Error> {
         let trace_id = trace_id.unwrap_or_else(ider::generate_trace_id);
         // create context with trace_id
@@ -175,12 +181,14 @@ impl QueryConditionExt for QueryCondition {
                     query_exemplars: false,
                     no_cache: None,
                 };
-                let resp = match promql::search::search(&trace_id, org_id, &req, "", 0).await {
-                    Ok(v) => v,
-                    Err(_) => {
-                        return Ok(eval_results);
-                    }
-                };
+                let user_email = user_id.as_deref().unwrap_or_default();
+                let resp =
+                    match promql::search::search(&trace_id, org_id, &req, user_email, 0).await {
+                        Ok(v) => v,
+                        Err(_) => {
+                            return Ok(eval_results);
+                        }
+                    };
                 let promql::value::Value::Matrix(value) = resp else {
                     log::warn!(
                         "Alert evaluate: trace_id: {trace_id}, PromQL query {} returned unexpected response: {:?}",
@@ -214,6 +222,35 @@ impl QueryConditionExt for QueryCondition {
                 }
                 return Ok(eval_results);
             }
+            QueryType::Anomaly => {
+                let start = start_time.unwrap_or_else(|| {
+                    end_time
+                        - Duration::try_minutes(trigger_condition.period)
+                            .unwrap()
+                            .num_microseconds()
+                            .unwrap()
+                });
+                return anomaly::evaluate(
+                    self,
+                    org_id,
+                    stream_type,
+                    (start, end_time),
+                    search_type,
+                    search_event_context,
+                    &trace_id,
+                    user_id,
+                )
+                .instrument(eval_span)
+                .await;
+            }
+            QueryType::Composite => {
+                let Some(composite) = self.composite.as_ref() else {
+                    return Ok(eval_results);
+                };
+                return dependencies::evaluate_composite(org_id, composite, end_time)
+                    .instrument(eval_span)
+                    .await;
+            }
         };
 
         let stream_names = resolve_stream_names(&sql)
@@ -345,7 +382,7 @@ impl QueryConditionExt for QueryCondition {
                 &trace_id,
                 org_id,
                 stream_type,
-                None,
+                user_id,
                 &req,
                 Some(RoleGroup::Background),
             )
@@ -402,7 +439,7 @@ impl QueryConditionExt for QueryCondition {
                 &trace_id,
                 org_id,
                 stream_type,
-                None,
+                user_id,
                 &req,
                 Some(RoleGroup::Background),
             )
@@ -598,7 +635,7 @@ impl ConditionExt for ConditionList {
             ConditionList::LegacyConditions(conditions) => {
                 let mut eval = true;
                 for condition in conditions {
-                    eval = eval && condition.evaluate(row).await
+                    eval = eval && condition.evaluate(row)
                 }
                 eval
             }
@@ -610,7 +647,7 @@ impl ConditionExt for ConditionList {
                 eval
             }
             ConditionList::NotNode { not: conditions } => !conditions.evaluate(row).await,
-            ConditionList::EndCondition(condition) => condition.evaluate(row).await,
+            ConditionList::EndCondition(condition) => condition.evaluate(row),
         }
     }
 }
@@ -618,67 +655,7 @@ impl ConditionExt for ConditionList {
 #[async_trait]
 impl ConditionExt for Condition {
     async fn evaluate(&self, row: &Map<String, Value>) -> bool {
-        let val = match row.get(&self.column) {
-            Some(val) => val,
-            None => {
-                return false;
-            }
-        };
-        match val {
-            Value::String(v) => {
-                let val = v.as_str();
-                let con_val = self.value.as_str().unwrap_or_default();
-                match self.operator {
-                    Operator::EqualTo => val == con_val,
-                    Operator::NotEqualTo => val != con_val,
-                    Operator::GreaterThan => val > con_val,
-                    Operator::GreaterThanEquals => val >= con_val,
-                    Operator::LessThan => val < con_val,
-                    Operator::LessThanEquals => val <= con_val,
-                    Operator::Contains => val.contains(con_val),
-                    Operator::NotContains => !val.contains(con_val),
-                }
-            }
-            Value::Number(_) => {
-                let val = val.as_f64().unwrap_or_default();
-                let con_val = if self.value.is_number() {
-                    self.value.as_f64().unwrap_or_default()
-                } else {
-                    self.value
-                        .as_str()
-                        .unwrap_or_default()
-                        .parse()
-                        .unwrap_or_default()
-                };
-                match self.operator {
-                    Operator::EqualTo => val == con_val,
-                    Operator::NotEqualTo => val != con_val,
-                    Operator::GreaterThan => val > con_val,
-                    Operator::GreaterThanEquals => val >= con_val,
-                    Operator::LessThan => val < con_val,
-                    Operator::LessThanEquals => val <= con_val,
-                    _ => false,
-                }
-            }
-            Value::Bool(v) => {
-                let val = v.to_owned();
-                let con_val = if self.value.is_boolean() {
-                    self.value.as_bool().unwrap_or_default()
-                } else {
-                    self.value
-                        .as_str()
-                        .unwrap_or_default()
-                        .parse()
-                        .unwrap_or_default()
-                };
-                match self.operator {
-                    Operator::EqualTo => val == con_val,
-                    Operator::NotEqualTo => val != con_val,
-                    _ => false,
-                }
-            }
-            _ => false,
-        }
+        Condition::evaluate(self, row)
     }
 }
 
@@ -766,6 +743,72 @@ fn build_expr(
     } else {
         cond.column.as_str()
     };
+    // operators whose value isn't a single literal
+    match cond.operator {
+        Operator::Exists => return Ok(format!("\"{}\" IS NOT NULL", field_alias)),
+        Operator::NotExists => return Ok(format!("\"{}\" IS NULL", field_alias)),
+        Operator::In | Operator::NotIn => {
+            let values = cond
+                .values()
+                .iter()
+                .map(|val| build_literal(cond, val, field_type))
+                .collect::<Result<Vec<_>, _>>()?;
+            if values.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Operator [{}] needs a list of values for column {}",
+                    cond.operator,
+                    cond.column
+                ));
+            }
+            let op = if cond.operator == Operator::In {
+                "IN"
+            } else {
+                "NOT IN"
+            };
+            if cond.ignore_case && *field_type == DataType::Utf8 {
+                let values = values
+                    .iter()
+                    .map(|val| format!("LOWER({val})"))
+                    .collect::<Vec<_>>();
+                return Ok(format!(
+                    "LOWER(\"{}\") {} ({})",
+                    field_alias,
+                    op,
+                    values.join(", ")
+                ));
+            }
+            return Ok(format!(
+                "\"{}\" {} ({})",
+                field_alias,
+                op,
+                values.join(", ")
+            ));
+        }
+        Operator::Between | Operator::NotBetween => {
+            let values = cond
+                .values()
+                .iter()
+                .map(|val| build_literal(cond, val, field_type))
+                .collect::<Result<Vec<_>, _>>()?;
+            let [lower, upper] = values.as_slice() else {
+                return Err(anyhow::anyhow!(
+                    "Operator [{}] needs the lower and upper bounds for column {}",
+                    cond.operator,
+                    cond.column
+                ));
+            };
+            let op = if cond.operator == Operator::Between {
+                "BETWEEN"
+            } else {
+                "NOT BETWEEN"
+            };
+            return Ok(format!(
+                "\"{}\" {} {} AND {}",
+                field_alias, op, lower, upper
+            ));
+        }
+        _ => {}
+    }
     let expr = match field_type {
         DataType::Utf8 => {
             let val = if cond.value.is_string() {
@@ -786,6 +829,30 @@ fn build_expr(
                 Operator::NotContains => {
                     format!("\"{}\" {} '%{}%'", field_alias, "NOT LIKE", val)
                 }
+                Operator::StartsWith | Operator::EndsWith => {
+                    let pattern = if cond.operator == Operator::StartsWith {
+                        format!("{}%", like_literal(&val))
+                    } else {
+                        format!("%{}", like_literal(&val))
+                    };
+                    if cond.ignore_case {
+                        format!("LOWER(\"{}\") LIKE LOWER('{}')", field_alias, pattern)
+                    } else {
+                        format!("\"{}\" LIKE '{}'", field_alias, pattern)
+                    }
+                }
+                Operator::Regex => {
+                    let op = if cond.ignore_case { "~*" } else { "~" };
+                    format!("\"{}\" {} '{}'", field_alias, op, val.replace('\'', "''"))
+                }
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Column {} has data_type [{}] and it does not supported operator [{:?}]",
+                        cond.column,
+                        field_type,
+                        cond.operator
+                    ));
+                }
             }
         }
         DataType::Int16 | DataType::Int32 | DataType::Int64 => {
@@ -907,3 +974,128 @@ fn build_expr(
     };
     Ok(expr)
 }
+
+/// Escapes the value to match it literally in a LIKE pattern, backslash being the escape
+/// character of DataFusion.
+fn like_literal(val: &str) -> String {
+    val.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+        .replace('\'', "''")
+}
+
+/// Formats one item of the value of a list operator as a SQL literal of the column data type.
+fn build_literal(
+    cond: &Condition,
+    val: &Value,
+    field_type: &DataType,
+) -> Result<String, anyhow::Error> {
+    let text = match val {
+        Value::String(v) => v.trim_matches('"').to_string(),
+        v => v.to_string(),
+    };
+    let invalid = |e: &dyn std::fmt::Display| {
+        anyhow::anyhow!(
+            "Column [{}] dataType is [{}] but value is [{}], err: {}",
+            cond.column,
+            field_type,
+            val,
+            e
+        )
+    };
+    match field_type {
+        DataType::Utf8 => Ok(format!("'{}'", text.replace('\'', "''"))),
+        DataType::Int16 | DataType::Int32 | DataType::Int64 => text
+            .trim()
+            .parse::<i64>()
+            .map(|v| v.to_string())
+            .map_err(|e| invalid(&e)),
+        DataType::Float32 | DataType::Float64 => text
+            .trim()
+            .parse::<f64>()
+            .map(|v| v.to_string())
+            .map_err(|e| invalid(&e)),
+        DataType::Boolean => text
+            .trim()
+            .parse::<bool>()
+            .map(|v| v.to_string())
+            .map_err(|e| invalid(&e)),
+        _ => Err(anyhow::anyhow!(
+            "Column {} has data_type [{}] and it does not supported operator [{:?}]",
+            cond.column,
+            field_type,
+            cond.operator
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use config::utils::json::json;
+
+    use super::*;
+
+    fn expr(operator: Operator, value: Value, ignore_case: bool, field_type: DataType) -> String {
+        let cond = Condition {
+            column: "host".to_string(),
+            operator,
+            value,
+            ignore_case,
+        };
+        build_expr(&cond, "", &field_type).unwrap()
+    }
+
+    #[test]
+    fn test_build_expr_like() {
+        assert_eq!(
+            expr(Operator::StartsWith, json!("web-"), false, DataType::Utf8),
+            r#""host" LIKE 'web-%'"#
+        );
+        assert_eq!(
+            expr(Operator::EndsWith, json!("o'k"), false, DataType::Utf8),
+            r#""host" LIKE '%o''k'"#
+        );
+        // wildcards of the value are matched literally
+        assert_eq!(
+            expr(
+                Operator::StartsWith,
+                json!("100%_a\\b"),
+                false,
+                DataType::Utf8
+            ),
+            r#""host" LIKE '100\%\_a\\b%'"#
+        );
+        assert_eq!(
+            expr(Operator::EndsWith, json!("Prod"), true, DataType::Utf8),
+            r#"LOWER("host") LIKE LOWER('%Prod')"#
+        );
+    }
+
+    #[test]
+    fn test_build_expr_in() {
+        let hosts = json!(["Web-1", "web-2"]);
+        assert_eq!(
+            expr(Operator::In, hosts.clone(), false, DataType::Utf8),
+            r#""host" IN ('Web-1', 'web-2')"#
+        );
+        assert_eq!(
+            expr(Operator::NotIn, hosts, true, DataType::Utf8),
+            r#"LOWER("host") NOT IN (LOWER('Web-1'), LOWER('web-2'))"#
+        );
+        assert_eq!(
+            expr(Operator::In, json!("1,2"), true, DataType::Int64),
+            r#""host" IN (1, 2)"#
+        );
+    }
+
+    #[test]
+    fn test_build_expr_cidr_match() {
+        let cond = Condition {
+            column: "ip".to_string(),
+            operator: Operator::CidrMatch,
+            value: json!("10.0.0.0/8"),
+            ignore_case: false,
+        };
+        assert!(build_expr(&cond, "", &DataType::Utf8).is_err());
+    }
+}
@@ -45,7 +45,9 @@ use proto::cluster_rpc;
 use crate::service::{
     alerts::{
         alert::{AlertExt, get_alert_start_end_time, get_by_id_db, get_row_column_map},
+        dependencies,
         derived_streams::DerivedStreamExt,
+        maintenance_windows,
     },
     dashboards::reports::SendReport,
     db::{self, alerts::alert::set_without_updating_trigger},
@@ -161,10 +163,10 @@ async fn handle_alert_triggers(
     );
 
     // here it can be alert id or alert name
-    let alert = if let Ok(alert_id) = svix_ksuid::Ksuid::from_str(&trigger.module_key) {
+    let (folder, alert) = if let Ok(alert_id) = svix_ksuid::Ksuid::from_str(&trigger.module_key) {
         let client = ORM_CLIENT.get_or_init(connect_to_orm).await;
         match db::alerts::alert::get_by_id(client, &trigger.org, alert_id).await {
-            Ok(Some((_, alert))) => alert,
+            Ok(Some(folder_alert)) => folder_alert,
             Ok(None) => {
                 log::error!(
                     "[SCHEDULER trace_id {scheduler_trace_id}] Alert not found for module_key: {}",
@@ -242,6 +244,7 @@ async fn handle_alert_triggers(
             period_end_time: None,
             tolerance: 0,
             last_satisfied_at: None,
+            firing: false,
         }
     };
 
@@ -463,7 +466,29 @@ async fn handle_alert_triggers(
             }
         }
     }
-    if trigger_results.data.is_some() && alert.trigger_condition.silence > 0 {
+    // the alert is still evaluated during a maintenance window or while one of its parents is
+    // firing, only its notification is suppressed
+    let suppressed_by = match trigger_results.data.as_ref() {
+        None => None,
+        Some(_) => match maintenance_windows::active_window(
+            &new_trigger.org,
+            &folder.folder_id,
+            &alert,
+            now,
+        ) {
+            Some((window, until)) => Some(format!(
+                "maintenance window {} active until {until}",
+                window.name
+            )),
+            None => dependencies::firing_parent(&new_trigger.org, &alert)
+                .await
+                .map(|parent| format!("firing parent alert {parent}")),
+        },
+    };
+    if trigger_results.data.is_some()
+        && suppressed_by.is_none()
+        && alert.trigger_condition.silence > 0
+    {
         new_trigger.next_run_at =
             alert
                 .trigger_condition
@@ -482,9 +507,24 @@ async fn handle_alert_triggers(
     if trigger_results.data.is_some() {
         trigger_data.last_satisfied_at = Some(triggered_at);
     }
+    trigger_data.firing = trigger_results.data.is_some();
+    if let Some(alert_id) = alert.id {
+        if let Err(e) = db::alerts::firing_states::set(
+            &new_trigger.org,
+            &alert_id.to_string(),
+            trigger_data.firing,
+        )
+        .await
+        {
+            log::error!(
+                "[SCHEDULER trace_id {scheduler_trace_id}] Error saving firing state of alert {}/{alert_id}: {e}",
+                &new_trigger.org
+            );
+        }
+    }
 
     // send notification
-    if let Some(data) = trigger_results.data {
+    if let Some(data) = trigger_results.data.filter(|_| suppressed_by.is_none()) {
         let vars = get_row_column_map(&data);
         // Multi-time range alerts can have multiple time ranges, hence only
         // use the main start_time (now - period) and end_time (now) for the alert evaluation.
@@ -588,6 +628,25 @@ async fn handle_alert_triggers(
                     Some(format!("error sending notification for alert: {e}"));
             }
         }
+    } else if let Some(suppressed_by) = suppressed_by {
+        log::info!(
+            "[SCHEDULER trace_id {scheduler_trace_id}] Alert notification suppressed by {}, org: {}, module_key: {}",
+            &suppressed_by,
+            &new_trigger.org,
+            &new_trigger.module_key
+        );
+        trigger_data.period_end_time = if should_store_last_end_time {
+            Some(trigger_results.end_time)
+        } else {
+            None
+        };
+        new_trigger.data = json::to_string(&trigger_data).unwrap();
+        db::scheduler::update_trigger(new_trigger).await?;
+        trigger_data_stream.start_time = start_time;
+        trigger_data_stream.end_time = trigger_results.end_time;
+        trigger_data_stream.status = TriggerDataStatus::Suppressed;
+        trigger_data_stream.success_response =
+            Some(format!("Notification suppressed by {suppressed_by}"));
     } else {
         log::info!(
             "[SCHEDULER trace_id {scheduler_trace_id}] Alert conditions not satisfied, org: {}, module_key: {}",
@@ -1350,6 +1409,7 @@ async fn handle_derived_stream_triggers(
             period_end_time: Some(start_time),
             tolerance: 0,
             last_satisfied_at: None,
+            firing: false,
         })
         .unwrap();
     }
@@ -15,13 +15,17 @@
 use std::{collections::HashMap, sync::Arc};
 
 use anyhow::Result;
+use futures::FutureExt;
 use tokio::{
     sync::{Mutex, mpsc},
     time,
 };
 
 use super::handlers::handle_triggers;
-use crate::service::db::scheduler::{Trigger, TriggerModule, pull as scheduler_pull};
+use crate::service::{
+    alerts::dependencies,
+    db::scheduler::{Trigger, TriggerModule, pull as scheduler_pull},
+};
 
 #[derive(Debug, Clone)]
 pub struct ScheduledJob {
@@ -211,9 +215,13 @@ impl SchedulerJobPuller {
                 }
             }
 
+            // alerts reading the state of other alerts of the batch are evaluated after them
+            let parents = dependencies::batch_parents(&triggers).await;
+
             // keep alive the jobs before sending them to the workers
             // but need to release the thread after the job is sent to the worker
             let mut jobs = Vec::with_capacity(triggers.len());
+            let mut handled = Vec::with_capacity(triggers.len());
             for trigger in triggers {
                 let job_id = trigger.id;
                 let job_key = trigger.module_key.clone();
@@ -223,13 +231,12 @@ impl SchedulerJobPuller {
                     trigger,
                     stop_keep_alive_tx: tx,
                 };
-                jobs.push(scheduled_job);
 
                 let trace_id_keep_alive = trace_id.clone();
                 let ttl = self.config.keep_alive_interval_secs;
                 let alert_timeout = self.config.alert_schedule_timeout;
                 let report_timeout = self.config.report_schedule_timeout;
-                tokio::task::spawn(async move {
+                let keep_alive = tokio::task::spawn(async move {
                     loop {
                         tokio::select! {
                             _ = tokio::time::sleep(tokio::time::Duration::from_secs(ttl)) => {}
@@ -257,10 +264,37 @@ impl SchedulerJobPuller {
                         }
                     }
                 });
+                jobs.push(scheduled_job);
+                handled.push(keep_alive.map(|_| ()).boxed().shared());
             }
 
-            // Send all jobs to be processed
-            for job in jobs {
+            // Send all jobs to be processed. The keep alive of a job stops once it's handled, so
+            // a job with parents is sent from its own task once their keep alives ended, or
+            // after the alert timeout, without holding back the other jobs.
+            for (job, parents) in jobs.into_iter().zip(parents) {
+                if !parents.is_empty() {
+                    let parents_handled = parents
+                        .into_iter()
+                        .map(|i| handled[i].clone())
+                        .collect::<Vec<_>>();
+                    let max_wait =
+                        time::Duration::from_secs(self.config.alert_schedule_timeout.max(0) as u64);
+                    let tx = self.tx.clone();
+                    tokio::task::spawn(async move {
+                        let wait = futures::future::join_all(parents_handled);
+                        if time::timeout(max_wait, wait).await.is_err() {
+                            log::warn!(
+                                "[SCHEDULER][JobPuller-{}] trigger[{}] evaluated before its parent alerts were handled",
+                                job.trace_id,
+                                job.trigger.module_key
+                            );
+                        }
+                        if tx.send(job).await.is_err() {
+                            log::error!("[SCHEDULER][JobPuller] Channel closed, dropping job");
+                        }
+                    });
+                    continue;
+                }
                 if self.tx.send(job).await.is_err() {
                     log::error!(
                         "[SCHEDULER][JobPuller-{}] Channel closed, exiting job puller",
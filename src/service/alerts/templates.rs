@@ -13,16 +13,126 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use config::meta::destinations::{Template, TemplateType};
+use chrono::{
+    Local, TimeZone,
+    format::{Item, StrftimeItems},
+};
+use config::{
+    meta::destinations::{
+        Template, TemplateAlert, TemplateContext, TemplateEngine, TemplateLinks, TemplateType,
+    },
+    utils::{json, time::now_micros},
+};
+use minijinja::Environment;
+use once_cell::sync::Lazy;
 
 use crate::{
     common::{
         meta::{authz::Authz, organization::DEFAULT_ORG},
         utils::auth::{is_ofga_unsupported, remove_ownership, set_ownership},
     },
-    service::db::{self, alerts::templates::TemplateError},
+    service::{
+        alerts::alert::render_legacy_template,
+        db::{self, alerts::templates::TemplateError},
+    },
 };
 
+/// Bounds the work a template can do, e.g. loops over many rows, before its rendering fails.
+const TEMPLATE_FUEL: u64 = 1_000_000;
+
+/// Environment the `Jinja` templates are rendered in. It has no loader, so templates can't
+/// include or import anything, and only the filters below on top of the builtin ones.
+static JINJA_ENV: Lazy<Environment<'static>> = Lazy::new(|| {
+    let mut env = Environment::new();
+    env.set_fuel(Some(TEMPLATE_FUEL));
+    env.add_filter("datetime", datetime_filter);
+    env.add_filter("number", number_filter);
+    env.add_filter("truncate", truncate_filter);
+    env
+});
+
+/// `{{ alert.start_time | datetime }}` formats a timestamp in microseconds as local time,
+/// `%Y-%m-%dT%H:%M:%S` unless another format is given.
+fn datetime_filter(micros: i64, format: Option<&str>) -> Result<String, minijinja::Error> {
+    if micros <= 0 {
+        return Ok("N/A".to_string());
+    }
+    let format = format.unwrap_or("%Y-%m-%dT%H:%M:%S");
+    let items = StrftimeItems::new(format).collect::<Vec<_>>();
+    if items.contains(&Item::Error) {
+        return Err(minijinja::Error::new(
+            minijinja::ErrorKind::InvalidOperation,
+            format!("invalid datetime format: {format}"),
+        ));
+    }
+    Ok(Local
+        .timestamp_nanos(micros * 1000)
+        .format_with_items(items.into_iter())
+        .to_string())
+}
+
+/// `{{ row.latency | number(3) }}` formats a number with 2 decimals unless another precision
+/// is given.
+fn number_filter(value: f64, decimals: Option<usize>) -> String {
+    format!("{:.*}", decimals.unwrap_or(2), value)
+}
+
+/// `{{ row.message | truncate(100) }}` keeps the first characters of a string, like the `{var:100}`
+/// placeholders of the legacy engine.
+fn truncate_filter(value: &str, length: usize) -> String {
+    value.chars().take(length).collect()
+}
+
+/// Renders the body or title of a template. `is_email` only matters to the legacy engine, the
+/// `Jinja` templates escape values themselves with the `escape` and `tojson` filters.
+pub fn render(
+    engine: TemplateEngine,
+    tpl: &str,
+    ctx: &TemplateContext,
+    is_email: bool,
+) -> Result<String, TemplateError> {
+    match engine {
+        TemplateEngine::Legacy => Ok(render_legacy_template(tpl, ctx, is_email)),
+        TemplateEngine::Jinja => {
+            let ctx = json::to_value(ctx).map_err(|e| TemplateError::Render(e.to_string()))?;
+            JINJA_ENV
+                .render_str(tpl, jinja_value(&ctx))
+                .map_err(|e| TemplateError::Render(e.to_string()))
+        }
+    }
+}
+
+/// Converts the variables for `Jinja`. Serialized as is, the json numbers of the rows would be
+/// maps to it because of `arbitrary_precision`.
+fn jinja_value(value: &json::Value) -> minijinja::Value {
+    match value {
+        json::Value::Null => minijinja::Value::from(()),
+        json::Value::Bool(v) => minijinja::Value::from(*v),
+        json::Value::Number(v) => match (v.as_i64(), v.as_u64()) {
+            (Some(v), _) => minijinja::Value::from(v),
+            (None, Some(v)) => minijinja::Value::from(v),
+            _ => minijinja::Value::from(v.as_f64().unwrap_or_default()),
+        },
+        json::Value::String(v) => minijinja::Value::from(v.as_str()),
+        json::Value::Array(v) => v.iter().map(jinja_value).collect(),
+        json::Value::Object(v) => v
+            .iter()
+            .map(|(k, v)| (k.as_str(), jinja_value(v)))
+            .collect(),
+    }
+}
+
+/// Checks the `Jinja` templates parse, the legacy ones always do.
+fn check_syntax(engine: TemplateEngine, tpl: &str) -> Result<(), TemplateError> {
+    if engine == TemplateEngine::Jinja {
+        // filters are only looked up when rendering, so any environment can parse the template
+        Environment::new()
+            .template_from_str(tpl)
+            .map_err(|e| TemplateError::Render(e.to_string()))?;
+    }
+    Ok(())
+}
+
 pub async fn save(
     name: &str,
     mut template: Template,
@@ -46,7 +156,9 @@ pub async fn save(
         if title.is_empty() {
             return Err(TemplateError::EmptyTitle);
         }
+        check_syntax(template.engine, title)?;
     }
+    check_syntax(template.engine, &template.body)?;
 
     match db::alerts::templates::get(&template.org_id, &template.name).await {
         Ok(existing) => {
@@ -101,3 +213,148 @@ pub async fn delete(org_id: &str, name: &str) -> Result<(), TemplateError> {
     remove_ownership(org_id, "templates", Authz::new(name)).await;
     Ok(())
 }
+
+/// Renders the body, and the title of email templates, without saving the template. Sample
+/// alert data is used when no variables are given.
+pub fn test(
+    org_id: &str,
+    template: &Template,
+    ctx: Option<TemplateContext>,
+) -> Result<(String, Option<String>), TemplateError> {
+    let ctx = ctx.unwrap_or_else(|| sample_context(org_id));
+    let is_email = matches!(template.template_type, TemplateType::Email { .. });
+    let body = render(template.engine, &template.body, &ctx, is_email)?;
+    let title = match &template.template_type {
+        TemplateType::Email { title } => Some(render(template.engine, title, &ctx, is_email)?),
+        _ => None,
+    };
+    Ok((body, title))
+}
+
+/// Variables of a scheduled alert which matched two rows.
+fn sample_context(org_name: &str) -> TemplateContext {
+    let end_time = now_micros();
+    let start_time = end_time - 10 * 60 * 1_000_000;
+    let rows = vec![
+        json::json!({"_timestamp": start_time, "host": "web-1", "status": 500, "latency": 1.25}),
+        json::json!({"_timestamp": end_time, "host": "web-2", "status": 503, "latency": 3.5}),
+    ]
+    .into_iter()
+    .filter_map(|row| row.as_object().cloned())
+    .collect::<Vec<_>>();
+    let values = [
+        (
+            "_timestamp",
+            vec![start_time.to_string(), end_time.to_string()],
+        ),
+        ("host", vec!["web-1".to_string(), "web-2".to_string()]),
+        ("status", vec!["500".to_string(), "503".to_string()]),
+        ("latency", vec!["1.25".to_string(), "3.50".to_string()]),
+    ]
+    .into_iter()
+    .map(|(key, values)| (key.to_string(), values))
+    .collect();
+    TemplateContext {
+        org_name: org_name.to_string(),
+        alert: TemplateAlert {
+            name: "high_error_rate".to_string(),
+            alert_type: "scheduled".to_string(),
+            stream_type: "logs".to_string(),
+            stream_name: "default".to_string(),
+            period: 10,
+            operator: ">=".to_string(),
+            threshold: 1,
+            count: rows.len(),
+            start_time,
+            end_time,
+            trigger_time: end_time,
+            promql_operator: None,
+            promql_value: None,
+            context_attributes: Default::default(),
+        },
+        rendered_rows: vec![
+            "host: web-1, status: 500".to_string(),
+            "host: web-2, status: 503".to_string(),
+        ],
+        rows,
+        values,
+        links: TemplateLinks {
+            alert_url: "https://openobserve.example.com/web/logs".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(engine: TemplateEngine, body: &str) -> Template {
+        Template {
+            id: None,
+            org_id: "default".to_string(),
+            name: "test".to_string(),
+            is_default: false,
+            template_type: TemplateType::Http,
+            body: body.to_string(),
+            engine,
+        }
+    }
+
+    #[test]
+    fn test_render_legacy() {
+        let tpl = template(
+            TemplateEngine::Legacy,
+            r#"{"text": "{alert_name} on {stream_name}: {host}, {alert_count} rows"}"#,
+        );
+        let (body, title) = test("default", &tpl, None).unwrap();
+        assert_eq!(
+            body,
+            r#"{"text": "high_error_rate on default: web-1, web-2, 2 rows"}"#
+        );
+        assert!(title.is_none());
+    }
+
+    #[test]
+    fn test_render_jinja() {
+        let tpl = template(
+            TemplateEngine::Jinja,
+            "{% for row in rows %}{{ row.host }}={{ row.latency | number(2) }}\
+             {% if not loop.last %},{% endif %}{% endfor %} {{ alert.name | tojson }}",
+        );
+        let (body, _) = test("default", &tpl, None).unwrap();
+        assert_eq!(body, r#"web-1=1.25,web-2=3.50 "high_error_rate""#);
+
+        let tpl = template(
+            TemplateEngine::Jinja,
+            "{{ alert.trigger_time | datetime('%Y') | length }} {{ 'abcdef' | truncate(3) }}",
+        );
+        let (body, _) = test("default", &tpl, None).unwrap();
+        assert_eq!(body, "4 abc");
+    }
+
+    #[test]
+    fn test_render_jinja_errors() {
+        assert!(check_syntax(TemplateEngine::Jinja, "{% for row in rows %}").is_err());
+        assert!(check_syntax(TemplateEngine::Legacy, "{% for row in rows %}").is_ok());
+
+        // templates can't loop forever
+        let tpl = template(
+            TemplateEngine::Jinja,
+            "{% for i in range(10000) %}{% for j in range(10000) %}{% endfor %}{% endfor %}",
+        );
+        assert!(matches!(
+            test("default", &tpl, None),
+            Err(TemplateError::Render(_))
+        ));
+
+        // invalid datetime formats fail the rendering instead of panicking
+        let tpl = template(
+            TemplateEngine::Jinja,
+            "{{ alert.trigger_time | datetime('%Q') }}",
+        );
+        assert!(matches!(
+            test("default", &tpl, None),
+            Err(TemplateError::Render(_))
+        ));
+    }
+}
@@ -0,0 +1,318 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use actix_web::http::Method;
+use chrono::Utc;
+use config::{
+    ider,
+    meta::{
+        api_key::{
+            API_KEY_DENIED_EP, API_KEY_PREFIX, API_KEY_RANDOM_LEN, ApiKey, ApiKeyCreateResponse,
+            ApiKeyRequest, ApiRequestKind, SEARCH_EP,
+        },
+        user::UserRole,
+    },
+    utils::rand::generate_random_string,
+};
+use infra::{
+    errors::{DbError, Error as InfraError},
+    table::api_keys::ApiKeyRecord,
+};
+
+use crate::{
+    common::meta::ingestion::INGESTION_EP,
+    service::{db, users},
+};
+
+/// Number of characters of the key kept to help users recognize it.
+const KEY_PREFIX_LEN: usize = 12;
+
+/// The last used time of a key is written at most once per this many microseconds.
+const LAST_USED_UPDATE_INTERVAL: i64 = 60 * 1_000_000;
+
+/// Errors that can occur when managing API keys.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiKeyError {
+    #[error("InfraError# {0}")]
+    InfraError(#[from] InfraError),
+
+    #[error("{0}")]
+    Internal(#[from] anyhow::Error),
+
+    #[error("API key name cannot be empty")]
+    MissingName,
+
+    #[error("API key expiry time must be in the future")]
+    ExpiresInPast,
+
+    #[error("API key with this name already exists for this user")]
+    NameAlreadyExists,
+
+    #[error("Only admins can manage API keys of other users")]
+    Forbidden,
+
+    #[error("User {0} not found in this organization")]
+    UserNotFound(String),
+
+    #[error("API key not found")]
+    NotFound,
+}
+
+/// Hashes the key the way it is stored at rest.
+pub fn hash_key(key: &str) -> String {
+    sha256::digest(key)
+}
+
+async fn is_admin(org_id: &str, user_email: &str) -> bool {
+    users::get_user(Some(org_id), user_email)
+        .await
+        .is_some_and(|user| matches!(user.role, UserRole::Admin | UserRole::Root))
+}
+
+/// Creates an API key, returning the key itself along with its details. The key can't be
+/// retrieved afterwards.
+pub async fn create(
+    org_id: &str,
+    initiator: &str,
+    req: ApiKeyRequest,
+) -> Result<ApiKeyCreateResponse, ApiKeyError> {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ApiKeyError::MissingName);
+    }
+    let now = Utc::now().timestamp_micros();
+    if req.expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err(ApiKeyError::ExpiresInPast);
+    }
+
+    let user_email = match req.user_email {
+        Some(user_email) => user_email.trim().to_lowercase(),
+        None => initiator.to_lowercase(),
+    };
+    if user_email != initiator.to_lowercase() && !is_admin(org_id, initiator).await {
+        return Err(ApiKeyError::Forbidden);
+    }
+    if users::get_user(Some(org_id), &user_email).await.is_none() {
+        return Err(ApiKeyError::UserNotFound(user_email));
+    }
+
+    let key = format!(
+        "{API_KEY_PREFIX}{}",
+        generate_random_string(API_KEY_RANDOM_LEN)
+    );
+    let api_key = ApiKey {
+        id: ider::uuid(),
+        org_id: org_id.to_string(),
+        user_email,
+        name: name.to_string(),
+        key_prefix: key[..KEY_PREFIX_LEN].to_string(),
+        scopes: req.scopes,
+        streams: req.streams,
+        expires_at: req.expires_at,
+        last_used_at: None,
+        created_at: now,
+    };
+    let record = ApiKeyRecord {
+        key_hash: hash_key(&key),
+        api_key,
+    };
+    match db::api_keys::add(&record).await {
+        Ok(()) => Ok(ApiKeyCreateResponse {
+            api_key: record.api_key,
+            key,
+        }),
+        Err(InfraError::DbError(DbError::UniqueViolation)) => Err(ApiKeyError::NameAlreadyExists),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Lists the API keys of the org, admins see the keys of every user.
+pub async fn list(org_id: &str, initiator: &str) -> Result<Vec<ApiKey>, ApiKeyError> {
+    let keys = if is_admin(org_id, initiator).await {
+        db::api_keys::list(org_id, None).await?
+    } else {
+        db::api_keys::list(org_id, Some(&initiator.to_lowercase())).await?
+    };
+    Ok(keys)
+}
+
+pub async fn delete(org_id: &str, id: &str, initiator: &str) -> Result<(), ApiKeyError> {
+    let Some(api_key) = db::api_keys::get(org_id, id).await? else {
+        return Err(ApiKeyError::NotFound);
+    };
+    if api_key.user_email != initiator.to_lowercase() && !is_admin(org_id, initiator).await {
+        return Err(ApiKeyError::Forbidden);
+    }
+    db::api_keys::remove(org_id, id).await?;
+    Ok(())
+}
+
+/// Classifies the request for scope checks, returning the stream it targets when the path names
+/// one. The path columns start after the org, or after `v2/{org_id}` for v2 APIs.
+///
+/// Only the exact ingestion and search routes, named by their last path segment, count as such:
+/// ingestion is always a `POST`, apart from the `_license` and `_xpack` checks of the
+/// Elasticsearch clients.
+pub fn request_kind<'a>(
+    method: &Method,
+    path_columns: &[&'a str],
+) -> (ApiRequestKind, Option<&'a str>) {
+    let first = path_columns.first().copied().unwrap_or_default();
+    let last = path_columns.last().copied().unwrap_or_default();
+    let kind = if (*method == Method::POST && INGESTION_EP.contains(&last))
+        || (*method == Method::GET && (last == "_license" || last == "_xpack"))
+    {
+        ApiRequestKind::Ingest
+    } else if (*method == Method::GET || *method == Method::POST) && SEARCH_EP.contains(&last) {
+        ApiRequestKind::Search
+    } else if API_KEY_DENIED_EP.contains(&first) {
+        ApiRequestKind::Denied
+    } else if *method == Method::GET || *method == Method::HEAD {
+        ApiRequestKind::Read
+    } else {
+        ApiRequestKind::Write
+    };
+    // only `{stream_name}/{endpoint}` routes name a stream, `v1/logs` and the like are OTLP
+    let stream_name = match (kind, path_columns) {
+        (ApiRequestKind::Ingest | ApiRequestKind::Search, [stream_name, _])
+            if *stream_name != "v1" =>
+        {
+            Some(*stream_name)
+        }
+        _ => None,
+    };
+    (kind, stream_name)
+}
+
+/// Checks the API key presented by `user_email` for a request to the org, returning the key
+/// when it allows the request.
+pub async fn validate(
+    org_id: &str,
+    user_email: &str,
+    key: &str,
+    kind: ApiRequestKind,
+    stream_name: Option<&str>,
+) -> Option<ApiKey> {
+    let key_hash = hash_key(key);
+    let record = match db::api_keys::get_by_hash(&key_hash).await {
+        Ok(Some(record)) => record,
+        Ok(None) => return None,
+        Err(e) => {
+            log::error!("Error getting api key: {}", e);
+            return None;
+        }
+    };
+    let api_key = &record.api_key;
+    let now = Utc::now().timestamp_micros();
+    if api_key.org_id != org_id
+        || !api_key.user_email.eq_ignore_ascii_case(user_email)
+        || api_key.is_expired(now)
+        || !api_key.allows(kind, stream_name)
+    {
+        return None;
+    }
+
+    if api_key
+        .last_used_at
+        .is_none_or(|last_used_at| now - last_used_at >= LAST_USED_UPDATE_INTERVAL)
+    {
+        let id = api_key.id.clone();
+        tokio::task::spawn(async move {
+            db::api_keys::update_last_used(&key_hash, &id, now).await;
+        });
+    }
+    Some(record.api_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_kind() {
+        assert_eq!(
+            request_kind(&Method::POST, &["k8s_logs", "_json"]),
+            (ApiRequestKind::Ingest, Some("k8s_logs"))
+        );
+        assert_eq!(
+            request_kind(&Method::POST, &["_bulk"]),
+            (ApiRequestKind::Ingest, None)
+        );
+        assert_eq!(
+            request_kind(&Method::POST, &["_search"]),
+            (ApiRequestKind::Search, None)
+        );
+        assert_eq!(
+            request_kind(&Method::GET, &["k8s_logs", "_values"]),
+            (ApiRequestKind::Search, Some("k8s_logs"))
+        );
+        assert_eq!(
+            request_kind(&Method::GET, &["dashboards"]),
+            (ApiRequestKind::Read, None)
+        );
+        assert_eq!(
+            request_kind(&Method::DELETE, &["streams", "k8s_logs"]),
+            (ApiRequestKind::Write, None)
+        );
+        assert_eq!(
+            request_kind(&Method::POST, &["v1", "logs"]),
+            (ApiRequestKind::Ingest, None)
+        );
+        assert_eq!(
+            request_kind(&Method::GET, &["_license"]),
+            (ApiRequestKind::Ingest, None)
+        );
+        // ingestion endpoint names elsewhere in the path don't make it an ingestion request
+        assert_eq!(
+            request_kind(&Method::GET, &["streams", "logs", "schema"]),
+            (ApiRequestKind::Read, None)
+        );
+        assert_eq!(
+            request_kind(&Method::PUT, &["_json", "settings"]),
+            (ApiRequestKind::Write, None)
+        );
+        assert_eq!(
+            request_kind(&Method::GET, &["k8s_logs", "_json"]),
+            (ApiRequestKind::Read, None)
+        );
+    }
+
+    #[test]
+    fn test_request_kind_denied() {
+        for path_columns in [
+            vec!["passcode"],
+            vec!["rumtoken"],
+            vec!["users", "root@example.com"],
+            vec!["service_accounts"],
+            vec!["api_keys", "id"],
+            vec!["roles", "admin", "users"],
+        ] {
+            for method in [Method::GET, Method::POST, Method::PUT, Method::DELETE] {
+                assert_eq!(
+                    request_kind(&method, &path_columns),
+                    (ApiRequestKind::Denied, None)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_key() {
+        let key = format!("{API_KEY_PREFIX}abc");
+        assert_eq!(hash_key(&key).len(), 64);
+        assert_eq!(hash_key(&key), hash_key(&key));
+        assert_ne!(hash_key(&key), hash_key("o2ak_abd"));
+    }
+}
@@ -0,0 +1,1000 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Conversion of Grafana dashboard JSON into the v5 dashboard model. Rows become tabs, the
+//! panels keep their grid position, and only the PromQL queries are converted. Everything that
+//! can't be mapped is listed in the [`ConversionReport`] instead of failing the import.
+
+use config::{
+    ider,
+    meta::dashboards::v5,
+    utils::json::{self, Map, Value, json},
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Grafana uses a 24 columns grid, the dashboards use 48 columns.
+const COLUMNS_FACTOR: i64 = 2;
+
+/// Height of the panels of the legacy rows, which only have a width.
+const LEGACY_PANEL_HEIGHT: i64 = 9;
+
+static RE_VARIABLE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[\[(\w+)(?::\w+)?\]\]|\$\{(\w+)(?::[^}]*)?\}").unwrap());
+static RE_LEGEND: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*([^{}\s]+)\s*\}\}").unwrap());
+static RE_LABEL_VALUES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^\s*label_values\(\s*([a-zA-Z_:][a-zA-Z0-9_:]*)\s*(\{[^}]*\})?\s*,\s*([a-zA-Z_][a-zA-Z0-9_]*)\s*\)\s*$",
+    )
+    .unwrap()
+});
+static RE_RELATIVE_TIME: Lazy<Regex> = Lazy::new(|| Regex::new(r"^now-(\d+[smhdwM])$").unwrap());
+
+/// Grafana built-in variables without an equivalent.
+const UNSUPPORTED_BUILTINS: [&str; 3] = ["__range", "__from", "__to"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum GrafanaImportError {
+    #[error("Grafana dashboard must be a JSON object")]
+    NotAnObject,
+
+    #[error("Grafana dashboard must have a title")]
+    MissingTitle,
+
+    #[error("Error building the dashboard: {0}")]
+    InvalidDashboard(#[from] json::Error),
+}
+
+/// Lists what could not be converted from a Grafana dashboard.
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ConversionReport {
+    /// Number of Grafana panels converted.
+    pub converted_panels: usize,
+    /// Number of Grafana panels left out of the dashboard.
+    pub skipped_panels: usize,
+    /// Parts of the Grafana dashboard that were left out or only partially converted.
+    pub unmapped: Vec<UnmappedItem>,
+}
+
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct UnmappedItem {
+    /// The Grafana panel, query or variable, e.g. `panel "CPU usage"`.
+    pub item: String,
+    pub reason: String,
+}
+
+/// Converts a Grafana dashboard, either the dashboard JSON model or the response of the
+/// Grafana dashboard api which wraps it with its metadata.
+pub fn convert(value: &Value) -> Result<(v5::Dashboard, ConversionReport), GrafanaImportError> {
+    let grafana = match value.get("dashboard") {
+        Some(dashboard) if dashboard.is_object() => dashboard,
+        _ => value,
+    };
+    if !grafana.is_object() {
+        return Err(GrafanaImportError::NotAnObject);
+    }
+    let title = str_field(grafana, "title");
+    if title.trim().is_empty() {
+        return Err(GrafanaImportError::MissingTitle);
+    }
+
+    let mut converter = Converter::default();
+    let tabs = match grafana.get("rows").and_then(Value::as_array) {
+        Some(rows) if !rows.is_empty() => converter.legacy_rows(rows),
+        _ => converter.grid_panels(array_field(grafana, "panels")),
+    };
+    let tabs = tabs
+        .into_iter()
+        .map(|(tab_id, name, panels)| json!({"tabId": tab_id, "name": name, "panels": panels}))
+        .collect::<Vec<_>>();
+    let variables = converter.variables(
+        grafana
+            .pointer("/templating/list")
+            .and_then(Value::as_array)
+            .map_or(&[], Vec::as_slice),
+    );
+    let datetime = converter.time_range(grafana.get("time"));
+
+    let dashboard = json!({
+        "version": 5,
+        "title": title,
+        "description": str_field(grafana, "description"),
+        "tabs": tabs,
+        "variables": variables,
+        "defaultDatetimeDuration": datetime,
+    });
+    Ok((json::from_value(dashboard)?, converter.report))
+}
+
+/// A tab being built, as tab id, name and panels.
+type TabPanels = (String, String, Vec<Value>);
+
+#[derive(Default)]
+struct Converter {
+    report: ConversionReport,
+    next_panel_id: usize,
+}
+
+impl Converter {
+    fn unmapped(&mut self, item: &str, reason: impl Into<String>) {
+        self.report.unmapped.push(UnmappedItem {
+            item: item.to_string(),
+            reason: reason.into(),
+        });
+    }
+
+    /// Converts the panels of the dashboards using the grid layout. Each row starts a new tab,
+    /// the panels before the first row go to the default tab.
+    fn grid_panels(&mut self, panels: &[Value]) -> Vec<TabPanels> {
+        let mut tabs = vec![("default".to_string(), "Default".to_string(), vec![])];
+        let mut row_y = 0;
+        for panel in panels.iter() {
+            if str_field(panel, "type") == "row" {
+                let name = match str_field(panel, "title") {
+                    "" => format!("Row {}", tabs.len()),
+                    title => title.to_string(),
+                };
+                tabs.push((ider::generate(), name, vec![]));
+                row_y = int_field(panel.get("gridPos"), "y") + 1;
+                // the panels of collapsed rows are nested in the row
+                for nested in array_field(panel, "panels").iter() {
+                    self.grid_panel(nested, row_y, &mut tabs);
+                }
+                continue;
+            }
+            self.grid_panel(panel, row_y, &mut tabs);
+        }
+        if tabs.len() > 1 && tabs[0].2.is_empty() {
+            tabs.remove(0);
+        }
+        tabs
+    }
+
+    fn grid_panel(&mut self, panel: &Value, row_y: i64, tabs: &mut [TabPanels]) {
+        let grid = panel.get("gridPos");
+        let (_, _, panels) = tabs.last_mut().unwrap();
+        let layout = json!({
+            "x": int_field(grid, "x") * COLUMNS_FACTOR,
+            "y": (int_field(grid, "y") - row_y).max(0),
+            "w": int_field(grid, "w").max(1) * COLUMNS_FACTOR,
+            "h": int_field(grid, "h").max(1),
+            "i": panels.len() + 1,
+        });
+        if let Some(converted) = self.panel(panel, layout) {
+            panels.push(converted);
+        }
+    }
+
+    /// Converts the rows of the dashboards from before the grid layout. The panels of each row
+    /// are laid out from left to right using their span out of 12 columns.
+    fn legacy_rows(&mut self, rows: &[Value]) -> Vec<TabPanels> {
+        let mut tabs = Vec::with_capacity(rows.len());
+        for (idx, row) in rows.iter().enumerate() {
+            let name = match str_field(row, "title") {
+                "" => format!("Row {}", idx + 1),
+                title => title.to_string(),
+            };
+            let tab_id = if idx == 0 {
+                "default".to_string()
+            } else {
+                ider::generate()
+            };
+            let mut panels = vec![];
+            let (mut x, mut y) = (0, 0);
+            for panel in array_field(row, "panels").iter() {
+                let span = panel.get("span").and_then(Value::as_f64).unwrap_or(12.0);
+                let w = ((span * 4.0).round() as i64).clamp(1, 48);
+                if x + w > 48 {
+                    x = 0;
+                    y += LEGACY_PANEL_HEIGHT;
+                }
+                let layout = json!({
+                    "x": x,
+                    "y": y,
+                    "w": w,
+                    "h": LEGACY_PANEL_HEIGHT,
+                    "i": panels.len() + 1,
+                });
+                x += w;
+                if let Some(converted) = self.panel(panel, layout) {
+                    panels.push(converted);
+                }
+            }
+            tabs.push((tab_id, name, panels));
+        }
+        tabs
+    }
+
+    fn panel(&mut self, panel: &Value, layout: Value) -> Option<Value> {
+        let item = format!("panel \"{}\"", str_field(panel, "title"));
+        if panel.get("libraryPanel").is_some() {
+            self.unmapped(&item, "library panels are not part of the dashboard JSON");
+            self.report.skipped_panels += 1;
+            return None;
+        }
+        let grafana_type = str_field(panel, "type");
+        let Some(typ) = panel_type(panel) else {
+            self.unmapped(&item, format!("panel type {grafana_type} is not supported"));
+            self.report.skipped_panels += 1;
+            return None;
+        };
+        if panel
+            .get("repeat")
+            .is_some_and(|r| !str_value(r).is_empty())
+        {
+            self.unmapped(&item, "repeating panels by variable is not supported");
+        }
+        if !array_field(panel, "transformations").is_empty() {
+            self.unmapped(&item, "transformations are not supported");
+        }
+        if panel
+            .pointer("/fieldConfig/overrides")
+            .and_then(Value::as_array)
+            .is_some_and(|overrides| !overrides.is_empty())
+        {
+            self.unmapped(&item, "field overrides are not supported");
+        }
+
+        let mut queries = if grafana_type == "text" {
+            vec![query("", "", false)]
+        } else {
+            self.queries(panel, &item)
+        };
+        if queries.is_empty() {
+            self.unmapped(&item, "the panel has no PromQL query to convert");
+            self.report.skipped_panels += 1;
+            return None;
+        }
+        if typ == "gauge" {
+            let defaults = panel.pointer("/fieldConfig/defaults");
+            let min = defaults.and_then(|d| d.get("min")).and_then(Value::as_f64);
+            let max = defaults.and_then(|d| d.get("max")).and_then(Value::as_f64);
+            for query in queries.iter_mut() {
+                if let Some(config) = query.pointer_mut("/config").and_then(|c| c.as_object_mut()) {
+                    config.insert("min".to_string(), min.unwrap_or(0.0).into());
+                    config.insert("max".to_string(), max.unwrap_or(100.0).into());
+                }
+            }
+        }
+
+        self.next_panel_id += 1;
+        let mut converted = json!({
+            "id": format!("Panel_ID{}", self.next_panel_id),
+            "type": typ,
+            "title": str_field(panel, "title"),
+            "description": str_field(panel, "description"),
+            "config": self.panel_config(panel, typ, &item),
+            "queryType": "promql",
+            "queries": queries,
+            "layout": layout,
+        });
+        if grafana_type == "text" {
+            let content = panel
+                .pointer("/options/content")
+                .or_else(|| panel.get("content"))
+                .map_or("", str_value);
+            let key = if typ == "html" {
+                "htmlContent"
+            } else {
+                "markdownContent"
+            };
+            let object = converted.as_object_mut().unwrap();
+            object.insert(key.to_string(), content.into());
+            object.insert("queryType".to_string(), "".into());
+        }
+        self.report.converted_panels += 1;
+        Some(converted)
+    }
+
+    fn queries(&mut self, panel: &Value, item: &str) -> Vec<Value> {
+        let panel_datasource = datasource_type(panel.get("datasource"));
+        let mut queries = vec![];
+        for target in array_field(panel, "targets").iter() {
+            let ref_id = str_field(target, "refId");
+            let query_item = format!("{item} query {ref_id}");
+            if target.get("hide").and_then(Value::as_bool).unwrap_or(false) {
+                self.unmapped(&query_item, "hidden queries are not imported");
+                continue;
+            }
+            let datasource = datasource_type(target.get("datasource")).or(panel_datasource);
+            if let Some(datasource) = datasource.filter(|ds| *ds != "prometheus") {
+                self.unmapped(
+                    &query_item,
+                    format!("{datasource} queries are not supported, only PromQL"),
+                );
+                continue;
+            }
+            let expr = str_field(target, "expr");
+            if expr.trim().is_empty() {
+                self.unmapped(&query_item, "only PromQL queries are supported");
+                continue;
+            }
+            let expr = rewrite_variables(expr);
+            for builtin in UNSUPPORTED_BUILTINS {
+                if expr.contains(&format!("${builtin}")) || expr.contains(&format!("${{{builtin}"))
+                {
+                    self.unmapped(
+                        &query_item,
+                        format!("the ${builtin} variable is not supported"),
+                    );
+                }
+            }
+            let legend = match str_field(target, "legendFormat") {
+                "__auto" => String::new(),
+                legend => RE_LEGEND.replace_all(legend, "{$1}").into_owned(),
+            };
+            queries.push(query(&expr, &legend, true));
+        }
+        queries
+    }
+
+    fn panel_config(&mut self, panel: &Value, typ: &str, item: &str) -> Value {
+        let defaults = panel.pointer("/fieldConfig/defaults");
+        let mut config = Map::new();
+
+        let legend = panel.pointer("/options/legend");
+        let show_legends = legend
+            .and_then(|l| l.get("showLegend"))
+            .and_then(Value::as_bool)
+            .or_else(|| panel.pointer("/legend/show").and_then(Value::as_bool))
+            .unwrap_or(true)
+            && legend.is_none_or(|l| str_field(l, "displayMode") != "hidden");
+        config.insert("show_legends".to_string(), show_legends.into());
+        let legends_position = match legend.map_or("", |l| str_field(l, "placement")) {
+            "right" => Value::from("right"),
+            "bottom" => Value::from("bottom"),
+            _ => Value::Null,
+        };
+        config.insert("legends_position".to_string(), legends_position);
+
+        let unit = defaults
+            .and_then(|d| d.get("unit"))
+            .or_else(|| panel.pointer("/yaxes/0/format"))
+            .map_or("", str_value);
+        match unit_of(unit) {
+            Some(Some(unit)) => {
+                config.insert("unit".to_string(), unit.into());
+            }
+            Some(None) => {}
+            None => {
+                config.insert("unit".to_string(), "custom".into());
+                config.insert("unit_custom".to_string(), unit.into());
+                self.unmapped(item, format!("unit {unit} is kept as a custom unit"));
+            }
+        }
+        if let Some(decimals) = defaults
+            .and_then(|d| d.get("decimals"))
+            .and_then(Value::as_f64)
+        {
+            config.insert("decimals".to_string(), decimals.into());
+        }
+        if typ != "gauge" {
+            for (from, to) in [("min", "y_axis_min"), ("max", "y_axis_max")] {
+                if let Some(value) = defaults.and_then(|d| d.get(from)).and_then(Value::as_f64) {
+                    config.insert(to.to_string(), value.into());
+                }
+            }
+        }
+
+        let thresholds = thresholds(panel);
+        if !thresholds.is_empty() {
+            if has_axis(typ) {
+                let mark_lines = thresholds
+                    .into_iter()
+                    .map(|value| json!({"name": "", "type": "yAxis", "value": value}))
+                    .collect::<Vec<_>>();
+                config.insert("mark_line".to_string(), mark_lines.into());
+            } else {
+                self.unmapped(
+                    item,
+                    format!("thresholds are only mapped on charts with axes, not {typ}"),
+                );
+            }
+        }
+
+        let mappings = defaults
+            .and_then(|d| d.get("mappings"))
+            .or_else(|| panel.get("valueMaps"))
+            .and_then(Value::as_array)
+            .map_or(&[][..], Vec::as_slice);
+        let mappings = self.mappings(mappings, item);
+        if !mappings.is_empty() {
+            config.insert("mappings".to_string(), mappings.into());
+        }
+        Value::Object(config)
+    }
+
+    fn mappings(&mut self, mappings: &[Value], item: &str) -> Vec<Value> {
+        let mut converted = vec![];
+        for mapping in mappings.iter() {
+            let options = mapping.get("options");
+            match mapping.get("type") {
+                Some(Value::String(typ)) if typ == "value" => {
+                    let mut values = options
+                        .and_then(Value::as_object)
+                        .map(|options| options.iter().collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    values.sort_by_key(|(_, result)| int_field(Some(*result), "index"));
+                    for (value, result) in values {
+                        converted.push(self.mapping(
+                            "value",
+                            json!({"value": value}),
+                            result,
+                            item,
+                        ));
+                    }
+                }
+                Some(Value::String(typ)) if typ == "range" => {
+                    let options = options.unwrap_or(&Value::Null);
+                    let fields = json!({
+                        "from": options.get("from").map(number_string),
+                        "to": options.get("to").map(number_string),
+                    });
+                    let result = options.get("result").unwrap_or(&Value::Null);
+                    converted.push(self.mapping("range", fields, result, item));
+                }
+                Some(Value::String(typ)) if typ == "regex" => {
+                    let options = options.unwrap_or(&Value::Null);
+                    let fields = json!({"pattern": str_field(options, "pattern")});
+                    let result = options.get("result").unwrap_or(&Value::Null);
+                    converted.push(self.mapping("regex", fields, result, item));
+                }
+                // the mappings of the dashboards before Grafana 8: 1 is value, 2 is range
+                Some(Value::Number(typ)) if typ.as_i64() == Some(1) => {
+                    let fields = json!({"value": number_string(mapping.get("value").unwrap_or(&Value::Null))});
+                    converted.push(self.mapping("value", fields, mapping, item));
+                }
+                Some(Value::Number(typ)) if typ.as_i64() == Some(2) => {
+                    let fields = json!({
+                        "from": mapping.get("from").map(number_string),
+                        "to": mapping.get("to").map(number_string),
+                    });
+                    converted.push(self.mapping("range", fields, mapping, item));
+                }
+                typ => {
+                    let typ = typ.map_or(String::new(), number_string);
+                    self.unmapped(
+                        item,
+                        format!("value mappings of type {typ} are not supported"),
+                    );
+                }
+            }
+        }
+        converted
+    }
+
+    fn mapping(&mut self, typ: &str, fields: Value, result: &Value, item: &str) -> Value {
+        let mut mapping = fields;
+        let object = mapping.as_object_mut().unwrap();
+        object.insert("type".to_string(), typ.into());
+        object.insert("text".to_string(), str_field(result, "text").into());
+        let color = str_field(result, "color");
+        let color = if color.is_empty() {
+            Value::Null
+        } else if color.starts_with('#') || color.chars().all(|c| c.is_ascii_alphabetic()) {
+            color.into()
+        } else {
+            self.unmapped(item, format!("mapping color {color} is not supported"));
+            Value::Null
+        };
+        object.insert("color".to_string(), color);
+        mapping
+    }
+
+    fn variables(&mut self, list: &[Value]) -> Option<Value> {
+        let mut variables = vec![];
+        let mut dynamic_filters = false;
+        for var in list.iter() {
+            let name = str_field(var, "name");
+            let item = format!("variable \"{name}\"");
+            let label = match str_field(var, "label") {
+                "" => name,
+                label => label,
+            };
+            let query = var
+                .get("query")
+                .map(|q| q.get("query").unwrap_or(q))
+                .map_or("", str_value);
+            let mut variable = json!({
+                "type": "",
+                "name": name,
+                "label": label,
+            });
+            let object = variable.as_object_mut().unwrap();
+            match str_field(var, "type") {
+                "query" => {
+                    let Some(caps) = RE_LABEL_VALUES.captures(query) else {
+                        self.unmapped(
+                            &item,
+                            "only label_values(metric, label) queries are supported",
+                        );
+                        continue;
+                    };
+                    if caps.get(2).is_some() {
+                        self.unmapped(&item, "the label selector of the query is not supported");
+                    }
+                    object.insert("type".to_string(), "query_values".into());
+                    object.insert(
+                        "query_data".to_string(),
+                        json!({
+                            "stream_type": "metrics",
+                            "stream": &caps[1],
+                            "field": &caps[3],
+                            "max_record_size": null,
+                        }),
+                    );
+                }
+                "custom" => {
+                    let current = current_values(var);
+                    let options = custom_options(query)
+                        .into_iter()
+                        .map(|(label, value)| {
+                            let selected = current.contains(&value);
+                            json!({"label": label, "value": value, "selected": selected})
+                        })
+                        .collect::<Vec<_>>();
+                    object.insert("type".to_string(), "custom".into());
+                    object.insert("options".to_string(), options.into());
+                }
+                "constant" => {
+                    object.insert("type".to_string(), "constant".into());
+                    object.insert("value".to_string(), query.into());
+                }
+                "textbox" => {
+                    let value = current_values(var).into_iter().next();
+                    object.insert("type".to_string(), "textbox".into());
+                    object.insert(
+                        "value".to_string(),
+                        value.unwrap_or_else(|| query.to_string()).into(),
+                    );
+                }
+                "adhoc" => {
+                    dynamic_filters = true;
+                    continue;
+                }
+                "interval" => {
+                    self.unmapped(&item, "use the built-in $__interval variable instead");
+                    continue;
+                }
+                "datasource" => {
+                    self.unmapped(&item, "datasource variables are not needed");
+                    continue;
+                }
+                typ => {
+                    self.unmapped(&item, format!("variables of type {typ} are not supported"));
+                    continue;
+                }
+            }
+            if var.get("multi").and_then(Value::as_bool).unwrap_or(false) {
+                object.insert("multiSelect".to_string(), true.into());
+                if var
+                    .get("includeAll")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false)
+                {
+                    object.insert("selectAllValueForMultiSelect".to_string(), "all".into());
+                }
+            }
+            if var.get("hide").and_then(Value::as_i64) == Some(2) {
+                object.insert("hideOnDashboard".to_string(), true.into());
+            }
+            variables.push(variable);
+        }
+        if variables.is_empty() && !dynamic_filters {
+            return None;
+        }
+        Some(json!({"list": variables, "showDynamicFilters": dynamic_filters}))
+    }
+
+    fn time_range(&mut self, time: Option<&Value>) -> Option<Value> {
+        let time = time?;
+        let (from, to) = (str_field(time, "from"), str_field(time, "to"));
+        match RE_RELATIVE_TIME.captures(from) {
+            Some(caps) if to == "now" => Some(json!({
+                "type": "relative",
+                "relativeTimePeriod": &caps[1],
+            })),
+            _ => {
+                self.unmapped(
+                    "time range",
+                    format!(
+                        "time range {from} to {to} is not supported, only now-<duration> to now"
+                    ),
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Returns the panel type for the Grafana panel, if supported.
+fn panel_type(panel: &Value) -> Option<&'static str> {
+    let custom = panel.pointer("/fieldConfig/defaults/custom");
+    let stacked = custom
+        .and_then(|c| c.pointer("/stacking/mode"))
+        .is_some_and(|mode| matches!(str_value(mode), "normal" | "percent"))
+        || panel.get("stack").and_then(Value::as_bool).unwrap_or(false);
+    let typ = match str_field(panel, "type") {
+        "timeseries" => match custom.map_or("", |c| str_field(c, "drawStyle")) {
+            "bars" if stacked => "stacked",
+            "bars" => "bar",
+            "points" => "scatter",
+            _ if stacked => "area-stacked",
+            _ => "line",
+        },
+        "graph" => match panel.get("bars").and_then(Value::as_bool) {
+            Some(true) if stacked => "stacked",
+            Some(true) => "bar",
+            _ if stacked => "area-stacked",
+            _ => "line",
+        },
+        "barchart" => match panel.pointer("/options/orientation").map_or("", str_value) {
+            "horizontal" if stacked => "h-stacked",
+            "horizontal" => "h-bar",
+            _ if stacked => "stacked",
+            _ => "bar",
+        },
+        "bargauge" => "h-bar",
+        "stat" | "singlestat" => "metric",
+        "gauge" => "gauge",
+        "piechart" => match panel.pointer("/options/pieType").map_or("", str_value) {
+            "donut" => "donut",
+            _ => "pie",
+        },
+        "table" | "table-old" => "table",
+        "heatmap" => "heatmap",
+        "text" => "markdown",
+        _ => return None,
+    };
+    // text panels in html mode are html panels
+    if typ == "markdown"
+        && panel
+            .pointer("/options/mode")
+            .or_else(|| panel.get("mode"))
+            .is_some_and(|mode| str_value(mode) == "html")
+    {
+        return Some("html");
+    }
+    Some(typ)
+}
+
+/// Whether the panel type has a value axis to draw the thresholds on.
+fn has_axis(typ: &str) -> bool {
+    matches!(
+        typ,
+        "line" | "area" | "area-stacked" | "bar" | "h-bar" | "stacked" | "h-stacked" | "scatter"
+    )
+}
+
+/// Returns the unit for the Grafana unit, `Some(None)` for the default unit and `None` when
+/// the unit has no equivalent.
+fn unit_of(unit: &str) -> Option<Option<&'static str>> {
+    let unit = match unit {
+        "" | "short" => return Some(None),
+        "none" => "numbers",
+        "bytes" | "decbytes" => "bytes",
+        "kbytes" | "deckbytes" => "kilobytes",
+        "mbytes" | "decmbytes" => "megabytes",
+        "bps" => "bps",
+        "s" => "seconds",
+        "ms" => "milliseconds",
+        "µs" | "us" => "microseconds",
+        "ns" => "nanoseconds",
+        "percent" => "percent",
+        "percentunit" => "percent-1",
+        "currencyUSD" => "currency-dollar",
+        "currencyEUR" => "currency-euro",
+        "currencyGBP" => "currency-pound",
+        "currencyJPY" => "currency-yen",
+        "currencyINR" => "currency-rupee",
+        _ => return None,
+    };
+    Some(Some(unit))
+}
+
+/// Returns the values of the thresholds of the panel. The first step of the thresholds is the
+/// base color, without a value.
+fn thresholds(panel: &Value) -> Vec<String> {
+    if let Some(steps) = panel
+        .pointer("/fieldConfig/defaults/thresholds/steps")
+        .and_then(Value::as_array)
+    {
+        return steps
+            .iter()
+            .filter_map(|step| step.get("value"))
+            .filter(|value| value.is_number())
+            .map(number_string)
+            .collect();
+    }
+    // the graph panels had their own thresholds
+    array_field(panel, "thresholds")
+        .iter()
+        .filter_map(|threshold| threshold.get("value"))
+        .filter(|value| value.is_number())
+        .map(number_string)
+        .collect()
+}
+
+fn query(expr: &str, legend: &str, custom_query: bool) -> Value {
+    json!({
+        "query": expr,
+        "vrlFunctionQuery": "",
+        "customQuery": custom_query,
+        "fields": {
+            "stream": "",
+            "stream_type": "metrics",
+            "x": [],
+            "y": [],
+            "filter": {
+                "filterType": "group",
+                "logicalOperator": "AND",
+                "conditions": [],
+            },
+        },
+        "config": {
+            "promql_legend": legend,
+        },
+    })
+}
+
+/// Rewrites the `[[name]]` and `${name:format}` variable references to `${name}`.
+fn rewrite_variables(expr: &str) -> String {
+    RE_VARIABLE
+        .replace_all(expr, |caps: &regex::Captures| {
+            let name = caps.get(1).or_else(|| caps.get(2)).unwrap().as_str();
+            format!("${{{name}}}")
+        })
+        .into_owned()
+}
+
+/// Parses the options of a custom variable, separated by commas, each as `value` or
+/// `label : value`.
+fn custom_options(query: &str) -> Vec<(String, String)> {
+    query
+        .split(',')
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .map(|option| match option.split_once(" : ") {
+            Some((label, value)) => (label.trim().to_string(), value.trim().to_string()),
+            None => (option.to_string(), option.to_string()),
+        })
+        .collect()
+}
+
+fn current_values(var: &Value) -> Vec<String> {
+    match var.pointer("/current/value") {
+        Some(Value::Array(values)) => values.iter().map(|v| str_value(v).to_string()).collect(),
+        Some(value) => vec![str_value(value).to_string()],
+        None => vec![],
+    }
+}
+
+/// Returns the datasource type, unknown for the datasources given by name.
+fn datasource_type(datasource: Option<&Value>) -> Option<&str> {
+    datasource
+        .and_then(|ds| ds.get("type"))
+        .and_then(Value::as_str)
+        // the panel datasource is a placeholder when the queries have their own
+        .filter(|typ| *typ != "datasource")
+}
+
+fn str_value(value: &Value) -> &str {
+    value.as_str().unwrap_or_default()
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).map_or("", str_value)
+}
+
+fn int_field(value: Option<&Value>, key: &str) -> i64 {
+    value
+        .and_then(|v| v.get(key))
+        .and_then(|v| v.as_i64().or_else(|| v.as_f64().map(|f| f as i64)))
+        .unwrap_or_default()
+}
+
+fn array_field<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+fn number_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        let grafana = json!({
+            "dashboard": {
+                "title": "Node",
+                "description": "Node metrics",
+                "time": {"from": "now-6h", "to": "now"},
+                "panels": [
+                    {
+                        "type": "timeseries",
+                        "title": "CPU",
+                        "gridPos": {"x": 0, "y": 0, "w": 12, "h": 8},
+                        "datasource": {"type": "prometheus", "uid": "prom"},
+                        "fieldConfig": {
+                            "defaults": {
+                                "unit": "percent",
+                                "thresholds": {"steps": [
+                                    {"color": "green", "value": null},
+                                    {"color": "red", "value": 80}
+                                ]},
+                                "mappings": [
+                                    {"type": "value", "options": {"1": {"text": "Up", "color": "green", "index": 0}}},
+                                    {"type": "special", "options": {"match": "null", "result": {"text": "-"}}}
+                                ]
+                            },
+                            "overrides": []
+                        },
+                        "targets": [
+                            {"refId": "A", "expr": "rate(cpu{job=\"[[job]]\"}[$__rate_interval])", "legendFormat": "{{instance}}"},
+                            {"refId": "B", "expr": "up", "hide": true}
+                        ]
+                    },
+                    {"type": "row", "title": "Logs", "gridPos": {"x": 0, "y": 8, "w": 24, "h": 1}},
+                    {
+                        "type": "logs",
+                        "title": "Errors",
+                        "gridPos": {"x": 0, "y": 9, "w": 24, "h": 8},
+                        "datasource": {"type": "loki"},
+                        "targets": [{"refId": "A", "expr": "{app=\"x\"}"}]
+                    },
+                    {
+                        "type": "stat",
+                        "title": "Up",
+                        "gridPos": {"x": 0, "y": 17, "w": 6, "h": 4},
+                        "targets": [{"refId": "A", "expr": "sum(up)"}]
+                    }
+                ],
+                "templating": {"list": [
+                    {"type": "query", "name": "job", "query": {"query": "label_values(up, job)"}, "multi": true, "includeAll": true},
+                    {"type": "custom", "name": "env", "query": "prod,Staging : stage", "current": {"value": "stage"}},
+                    {"type": "datasource", "name": "ds", "query": "prometheus"}
+                ]}
+            }
+        });
+        let (dashboard, report) = convert(&grafana).unwrap();
+        assert_eq!(dashboard.title, "Node");
+        assert_eq!(dashboard.tabs.len(), 2);
+        assert_eq!(dashboard.tabs[0].tab_id, "default");
+        assert_eq!(dashboard.tabs[1].name, "Logs");
+
+        let cpu = &dashboard.tabs[0].panels[0];
+        assert_eq!(cpu.typ, "line");
+        assert_eq!(cpu.query_type, "promql");
+        assert_eq!((cpu.layout.w, cpu.layout.h), (24, 8));
+        assert_eq!(cpu.queries.len(), 1);
+        assert_eq!(
+            cpu.queries[0].query.as_deref(),
+            Some("rate(cpu{job=\"${job}\"}[$__rate_interval])")
+        );
+        let cpu = json::to_value(cpu).unwrap();
+        assert_eq!(
+            cpu.pointer("/queries/0/config/promql_legend"),
+            Some(&json!("{instance}"))
+        );
+        assert_eq!(cpu.pointer("/config/unit"), Some(&json!("percent")));
+        assert_eq!(
+            cpu.pointer("/config/mark_line"),
+            Some(&json!([{"name": "", "type": "yAxis", "value": "80"}]))
+        );
+        assert_eq!(
+            cpu.pointer("/config/mappings"),
+            Some(&json!([{"type": "value", "value": "1", "text": "Up", "color": "green"}]))
+        );
+
+        // the stat panel is placed relative to its row
+        let up = &dashboard.tabs[1].panels[0];
+        assert_eq!(up.typ, "metric");
+        assert_eq!((up.layout.x, up.layout.y, up.layout.w), (0, 8, 12));
+
+        let variables = dashboard.variables.unwrap().list;
+        assert_eq!(variables.len(), 2);
+        assert_eq!(variables[0].type_field, "query_values");
+        assert_eq!(variables[0].query_data.as_ref().unwrap().stream, "up");
+        assert_eq!(variables[0].query_data.as_ref().unwrap().field, "job");
+        assert_eq!(variables[0].multi_select, Some(true));
+        let options = variables[1].options.as_ref().unwrap();
+        assert_eq!(options[1].label, "Staging");
+        assert_eq!(options[1].value, "stage");
+        assert_eq!(options[1].selected, Some(true));
+
+        assert_eq!(
+            dashboard
+                .default_datetime_duration
+                .unwrap()
+                .relative_time_period
+                .as_deref(),
+            Some("6h")
+        );
+
+        assert_eq!(report.converted_panels, 2);
+        assert_eq!(report.skipped_panels, 1);
+        let items = report
+            .unmapped
+            .iter()
+            .map(|u| u.item.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![
+                "panel \"CPU\" query B",
+                "panel \"CPU\"",
+                "panel \"Errors\"",
+                "variable \"ds\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_errors() {
+        assert!(matches!(
+            convert(&json!([])),
+            Err(GrafanaImportError::NotAnObject)
+        ));
+        assert!(matches!(
+            convert(&json!({"panels": []})),
+            Err(GrafanaImportError::MissingTitle)
+        ));
+    }
+
+    #[test]
+    fn test_legacy_rows() {
+        let grafana = json!({
+            "title": "Old",
+            "rows": [{
+                "title": "Overview",
+                "panels": [
+                    {"type": "graph", "title": "A", "span": 6, "targets": [{"expr": "a"}]},
+                    {"type": "graph", "title": "B", "span": 6, "targets": [{"expr": "b"}]},
+                    {"type": "singlestat", "title": "C", "span": 4, "targets": [{"expr": "c"}]}
+                ]
+            }]
+        });
+        let (dashboard, report) = convert(&grafana).unwrap();
+        assert_eq!(dashboard.tabs.len(), 1);
+        let layouts = dashboard.tabs[0]
+            .panels
+            .iter()
+            .map(|p| (p.layout.x, p.layout.y, p.layout.w))
+            .collect::<Vec<_>>();
+        assert_eq!(layouts, vec![(0, 0, 24), (24, 0, 24), (0, 9, 16)]);
+        assert_eq!(report.converted_panels, 3);
+        assert!(report.unmapped.is_empty());
+    }
+
+    #[test]
+    fn test_rewrite_variables() {
+        assert_eq!(
+            rewrite_variables("up{job=~\"$job\", env=\"[[env]]\", x=\"${x:regex}\", y=\"${y}\"}"),
+            "up{job=~\"$job\", env=\"${env}\", x=\"${x}\", y=\"${y}\"}"
+        );
+    }
+}
@@ -16,7 +16,10 @@
 use config::{
     TIMESTAMP_COL_NAME, ider,
     meta::{
-        dashboards::{Dashboard, ListDashboardsParams},
+        dashboards::{
+            Dashboard, ListDashboardsParams,
+            versions::{SavedVersion, SavedVersionDiff, SavedVersionList},
+        },
         folder::{DEFAULT_FOLDER, Folder, FolderType},
         stream::{DistinctField, StreamType},
     },
@@ -34,6 +37,9 @@ use crate::common::{
     meta::authz::Authz,
     utils::auth::{remove_ownership, set_ownership},
 };
+pub mod grafana;
+pub mod report_data;
+pub mod report_delivery;
 pub mod reports;
 pub mod timed_annotations;
 
@@ -68,9 +74,14 @@ pub enum DashboardError {
 
     /// Error that occurs when trying to update a dashboard using a stale hash.
     /// This occurs when the two clients attempt to update the dashboard
-    /// concurrently.
+    /// concurrently. Holds the latest saved version of the dashboard, if any.
     #[error("tried to update dashboard using conflicting hash")]
-    UpdateConflictingHash,
+    UpdateConflictingHash(Option<SavedVersion>),
+
+    /// Error that occurs when trying to access a saved version of a dashboard
+    /// that cannot be found.
+    #[error("version {0} of the dashboard not found")]
+    VersionNotFound(i32),
 
     /// Error that occurs when trying to create an update a dashboard but not
     /// title is provided.
@@ -300,13 +311,23 @@ pub async fn create_dashboard(
     org_id: &str,
     folder_id: &str,
     dashboard: Dashboard,
+    author: &str,
 ) -> Result<Dashboard, DashboardError> {
     // NOTE: Overwrite whatever `dashboard_id` the client has sent us
     // If folder is default folder & doesn't exist then create it
 
     let dashboard = if table::folders::exists(org_id, folder_id, FolderType::Dashboards).await? {
         let dashboard_id = ider::generate();
-        let saved = put(org_id, &dashboard_id, folder_id, None, dashboard, None).await?;
+        let saved = put(
+            org_id,
+            &dashboard_id,
+            folder_id,
+            None,
+            dashboard,
+            None,
+            Some(author),
+        )
+        .await?;
         set_ownership(
             org_id,
             "dashboards",
@@ -328,7 +349,16 @@ pub async fn create_dashboard(
             .await
             .map_err(|_| DashboardError::CreateDefaultFolder)?;
         let dashboard_id = ider::generate();
-        let saved = put(org_id, &dashboard_id, folder_id, None, dashboard, None).await?;
+        let saved = put(
+            org_id,
+            &dashboard_id,
+            folder_id,
+            None,
+            dashboard,
+            None,
+            Some(author),
+        )
+        .await?;
         set_ownership(
             org_id,
             "dashboards",
@@ -364,8 +394,18 @@ pub async fn update_dashboard(
     folder_id: &str,
     dashboard: Dashboard,
     hash: Option<&str>,
+    author: &str,
 ) -> Result<Dashboard, DashboardError> {
-    let dashboard = put(org_id, dashboard_id, folder_id, None, dashboard, hash).await?;
+    let dashboard = put(
+        org_id,
+        dashboard_id,
+        folder_id,
+        None,
+        dashboard,
+        hash,
+        Some(author),
+    )
+    .await?;
 
     #[cfg(feature = "enterprise")]
     if get_o2_config().super_cluster.enabled {
@@ -473,6 +513,7 @@ pub async fn move_dashboard(
         Some(to_folder),
         dashboard,
         Some(&hash),
+        None,
     )
     .await?;
 
@@ -531,6 +572,79 @@ pub async fn move_dashboards(
     Ok(())
 }
 
+/// Lists the saved versions of the dashboard, latest first.
+#[tracing::instrument]
+pub async fn list_dashboard_versions(
+    org_id: &str,
+    dashboard_id: &str,
+) -> Result<SavedVersionList, DashboardError> {
+    let list = table::dashboard_versions::list(org_id, dashboard_id)
+        .await?
+        .ok_or(DashboardError::DashboardNotFound)?;
+    Ok(SavedVersionList { list })
+}
+
+/// Gets a saved version of the dashboard.
+#[tracing::instrument]
+pub async fn get_dashboard_version(
+    org_id: &str,
+    dashboard_id: &str,
+    version: i32,
+) -> Result<(SavedVersion, Dashboard), DashboardError> {
+    if table::dashboards::get_by_id(org_id, dashboard_id)
+        .await?
+        .is_none()
+    {
+        return Err(DashboardError::DashboardNotFound);
+    }
+    table::dashboard_versions::get(org_id, dashboard_id, version)
+        .await?
+        .ok_or(DashboardError::VersionNotFound(version))
+}
+
+/// Compares two saved versions of the dashboard.
+#[tracing::instrument]
+pub async fn diff_dashboard_versions(
+    org_id: &str,
+    dashboard_id: &str,
+    from_version: i32,
+    to_version: i32,
+) -> Result<SavedVersionDiff, DashboardError> {
+    let (_, from) = get_dashboard_version(org_id, dashboard_id, from_version).await?;
+    let (_, to) = get_dashboard_version(org_id, dashboard_id, to_version).await?;
+    Ok(SavedVersionDiff::new(
+        (from_version, &from),
+        (to_version, &to),
+    ))
+}
+
+/// Restores a saved version of the dashboard. The restored dashboard is saved
+/// as a new version, so the restore itself can be reverted.
+#[tracing::instrument]
+pub async fn restore_dashboard_version(
+    org_id: &str,
+    dashboard_id: &str,
+    version: i32,
+    hash: Option<&str>,
+    author: &str,
+) -> Result<Dashboard, DashboardError> {
+    let (folder, _) = get_folder_and_dashboard(org_id, dashboard_id).await?;
+    let (_, mut dashboard) = get_dashboard_version(org_id, dashboard_id, version).await?;
+    dashboard.set_updated_at();
+    update_dashboard(
+        org_id,
+        dashboard_id,
+        &folder.folder_id,
+        dashboard,
+        hash,
+        author,
+    )
+    .await
+}
+
+/// Saves the dashboard. An existing dashboard is only updated if it is still
+/// at the given `hash`. If `author` is given, the saved dashboard is recorded
+/// as a new version of the dashboard.
 #[tracing::instrument(skip(dashboard))]
 async fn put(
     org_id: &str,
@@ -539,8 +653,10 @@ async fn put(
     new_folder_id: Option<&str>,
     mut dashboard: Dashboard,
     hash: Option<&str>,
+    author: Option<&str>,
 ) -> Result<Dashboard, DashboardError> {
     let old_version = table::dashboards::get_from_folder(org_id, folder_id, dashboard_id).await?;
+    let mut checked_hash = None;
     if let Some(existing_dash) = &old_version {
         let existing_dash_hash = &existing_dash.hash;
 
@@ -548,8 +664,9 @@ async fn put(
             return Err(DashboardError::UpdateMissingHash);
         };
         if hash_val.to_string() != *existing_dash_hash {
-            return Err(DashboardError::UpdateConflictingHash);
+            return Err(conflicting_hash(org_id, dashboard_id).await);
         }
+        checked_hash = Some(existing_dash_hash.clone());
     };
 
     match update_distinct_variables(org_id, old_version, &dashboard).await {
@@ -568,10 +685,36 @@ async fn put(
     dashboard.set_title(title);
 
     dashboard.set_dashboard_id(dashboard_id.to_owned());
-    let dash = table::dashboards::put(org_id, folder_id, new_folder_id, dashboard, false).await?;
+    // the hash is checked again while saving, in case of a concurrent update
+    let dash = match table::dashboards::put_with_version(
+        org_id,
+        folder_id,
+        new_folder_id,
+        dashboard,
+        false,
+        checked_hash.as_deref(),
+        author,
+    )
+    .await
+    {
+        Ok(dash) => dash,
+        Err(infra::errors::Error::DbError(infra::errors::DbError::PutDashboard(
+            infra::errors::PutDashboardError::ConflictingHash,
+        ))) => return Err(conflicting_hash(org_id, dashboard_id).await),
+        Err(e) => return Err(e.into()),
+    };
     Ok(dash)
 }
 
+/// Returns the error for an update of the dashboard with a stale hash, along
+/// with the latest saved version of the dashboard.
+async fn conflicting_hash(org_id: &str, dashboard_id: &str) -> DashboardError {
+    let latest = table::dashboard_versions::latest(org_id, dashboard_id)
+        .await
+        .unwrap_or_default();
+    DashboardError::UpdateConflictingHash(latest)
+}
+
 /// Internal helper function find dashboard and its folder by id.
 ///
 /// Used by self_reporting to enrich dashboard SearchEventContext
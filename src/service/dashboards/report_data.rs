@@ -0,0 +1,741 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Data reports carry the results of the panel queries of a dashboard tab, as csv files or as
+//! an xlsx workbook, instead of a capture of the dashboard. The queries run through the search
+//! service, so these reports don't need a headless browser.
+
+use std::{
+    fmt::Write as _,
+    io::{Cursor, Write},
+};
+
+use config::{
+    TIMESTAMP_COL_NAME, get_config, ider,
+    meta::{
+        cluster::RoleGroup,
+        dashboards::{Dashboard, reports::ReportDashboard},
+        search::{SearchEventContext, SearchEventType},
+        stream::StreamType,
+    },
+    utils::json::{Map, Value},
+};
+use hashbrown::HashSet;
+use infra::table;
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::service::{promql, search as SearchService, self_reporting::http_report_metrics};
+
+/// Maximum length of the name of a sheet.
+const MAX_SHEET_NAME_LEN: usize = 31;
+
+/// Characters not allowed in the name of a sheet.
+const INVALID_SHEET_NAME_CHARS: [char; 7] = ['[', ']', ':', '*', '?', '/', '\\'];
+
+/// First characters which make spreadsheet applications read a csv cell as a formula.
+const CSV_FORMULA_CHARS: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+const XML_HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DataReportError {
+    #[error("Atleast one tab is required")]
+    NoDashboardTabs,
+
+    #[error("Dashboard not found")]
+    DashboardNotFound,
+
+    #[error("Dashboard tab has no panel queries")]
+    NoPanelQueries,
+
+    #[error("Error running the query of panel {panel}: {e}")]
+    QueryError { panel: String, e: String },
+
+    #[error(transparent)]
+    DbError(#[from] infra::errors::Error),
+
+    #[error(transparent)]
+    CsvError(#[from] csv::Error),
+
+    #[error(transparent)]
+    ZipError(#[from] zip::result::ZipError),
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+/// The results of the query of a panel.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReportTable {
+    /// The panel title, suffixed with the query number for panels with several queries.
+    pub name: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    /// Whether the query returned more rows than the report can carry.
+    pub truncated: bool,
+}
+
+impl ReportTable {
+    /// Builds the table from query records, the columns are in the order they first appear.
+    pub fn new(name: String, records: Vec<Map<String, Value>>) -> Self {
+        let mut columns: Vec<String> = vec![];
+        {
+            let mut seen = HashSet::new();
+            for record in records.iter() {
+                for key in record.keys() {
+                    if seen.insert(key.as_str()) {
+                        columns.push(key.to_string());
+                    }
+                }
+            }
+        }
+        let rows = records
+            .into_iter()
+            .map(|mut record| {
+                columns
+                    .iter()
+                    .map(|column| record.remove(column).unwrap_or(Value::Null))
+                    .collect()
+            })
+            .collect();
+        Self {
+            name,
+            columns,
+            rows,
+            truncated: false,
+        }
+    }
+}
+
+/// A query of a panel of the report tab.
+struct PanelQuery {
+    name: String,
+    query_type: String,
+    stream_type: StreamType,
+    query: String,
+    vrl_function: Option<String>,
+}
+
+/// Collects the queries of the panels of the tab and the default values of the dashboard
+/// variables. The vrl function field is only given for the versions that have it.
+macro_rules! _get_panel_queries {
+    ($dash:ident, $tab_id:ident $(, $vrl:ident)?) => {{
+        let variables = $dash
+            .variables
+            .iter()
+            .flat_map(|vars| vars.list.iter())
+            .filter_map(|var| Some((var.name.clone(), var.value.clone()?)))
+            .collect::<Vec<_>>();
+        let mut queries = vec![];
+        for tab in $dash.tabs.iter().filter(|tab| tab.tab_id == *$tab_id) {
+            for panel in tab.panels.iter() {
+                let panel_queries = panel
+                    .queries
+                    .iter()
+                    .filter(|query| query.query.as_ref().is_some_and(|q| !q.trim().is_empty()))
+                    .collect::<Vec<_>>();
+                for (idx, query) in panel_queries.iter().enumerate() {
+                    let name = if panel_queries.len() > 1 {
+                        format!("{} ({})", panel.title, idx + 1)
+                    } else {
+                        panel.title.clone()
+                    };
+                    queries.push(PanelQuery {
+                        name,
+                        query_type: panel.query_type.clone(),
+                        stream_type: query.fields.stream_type,
+                        query: query.query.clone().unwrap_or_default(),
+                        vrl_function: None$(.or_else(|| query.$vrl.clone()))?
+                            .filter(|f: &String| !f.trim().is_empty()),
+                    });
+                }
+            }
+        }
+        (queries, variables)
+    }};
+}
+
+/// Runs the queries of the panels of the report tab over the given time range, in
+/// microseconds, and returns one table per query.
+pub async fn query_panels(
+    org_id: &str,
+    report_name: &str,
+    report_dashboard: &ReportDashboard,
+    user_id: Option<&str>,
+    (start_time, end_time): (i64, i64),
+) -> Result<Vec<ReportTable>, DataReportError> {
+    // Only one tab is supported for now
+    let Some(tab_id) = report_dashboard.tabs.first() else {
+        return Err(DataReportError::NoDashboardTabs);
+    };
+    let Some(dashboard) = table::dashboards::get_from_folder(
+        org_id,
+        &report_dashboard.folder,
+        &report_dashboard.dashboard,
+    )
+    .await?
+    else {
+        return Err(DataReportError::DashboardNotFound);
+    };
+    let (queries, mut variables) = panel_queries(&dashboard, tab_id);
+    if queries.is_empty() {
+        return Err(DataReportError::NoPanelQueries);
+    }
+
+    // The report variables override the default values of the dashboard variables
+    for var in report_dashboard.variables.iter() {
+        match variables.iter_mut().find(|(name, _)| *name == var.key) {
+            Some((_, value)) => *value = var.value.clone(),
+            None => variables.push((var.key.clone(), var.value.clone())),
+        }
+    }
+    let step = std::cmp::max(
+        promql::micros(promql::MINIMAL_INTERVAL),
+        (end_time - start_time) / promql::MAX_DATA_POINTS,
+    );
+    let step_secs = step / 1_000_000;
+    variables.extend([
+        ("__interval".to_string(), format!("{step_secs}s")),
+        ("__interval_ms".to_string(), (step / 1_000).to_string()),
+        // a few points per window, so the rate has samples to work with
+        ("__rate_interval".to_string(), format!("{}s", 4 * step_secs)),
+    ]);
+    // longer names first, so a variable doesn't replace the start of another one
+    variables.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+
+    let max_rows = get_config().limit.report_data_max_rows;
+    let trace_id = ider::generate_trace_id();
+    let report_key = format!("{org_id}-{report_name}");
+    let mut tables = Vec::with_capacity(queries.len());
+    for query in queries.iter() {
+        let query_str = substitute_variables(&query.query, &variables);
+        let mut records = if query.query_type == "promql" {
+            let req = promql::MetricsQueryRequest {
+                query: query_str,
+                start: start_time,
+                end: end_time,
+                step,
+                query_exemplars: false,
+                no_cache: None,
+            };
+            let resp =
+                promql::search::search(&trace_id, org_id, &req, user_id.unwrap_or_default(), 0)
+                    .await
+                    .map_err(|e| search_error(&query.name, e))?;
+            promql_records(resp)
+        } else {
+            let req = config::meta::search::Request {
+                query: config::meta::search::Query {
+                    sql: query_str,
+                    from: 0,
+                    // one more row than the limit tells whether the results are truncated
+                    size: if max_rows > 0 {
+                        max_rows as i64 + 1
+                    } else {
+                        -1
+                    },
+                    start_time,
+                    end_time,
+                    quick_mode: false,
+                    query_type: "".to_string(),
+                    track_total_hits: false,
+                    action_id: None,
+                    uses_zo_fn: false,
+                    query_fn: query.vrl_function.clone(),
+                    skip_wal: false,
+                    streaming_output: false,
+                    streaming_id: None,
+                    histogram_interval: 0,
+                },
+                encoding: config::meta::search::RequestEncoding::Empty,
+                regions: vec![],
+                clusters: vec![],
+                timeout: 0,
+                search_type: Some(SearchEventType::Reports),
+                search_event_context: Some(SearchEventContext::with_report(Some(
+                    report_key.clone(),
+                ))),
+                use_cache: false,
+                local_mode: None,
+            };
+            let req_start = std::time::Instant::now();
+            let resp = SearchService::grpc_search::grpc_search(
+                &trace_id,
+                org_id,
+                query.stream_type,
+                user_id.map(|id| id.to_string()),
+                &req,
+                Some(RoleGroup::Background),
+            )
+            .await
+            .map_err(|e| search_error(&query.name, e))?;
+            // the search request doesn't via cache layer, so need report usage separately
+            http_report_metrics(
+                req_start,
+                org_id,
+                query.stream_type,
+                "200",
+                "_search",
+                &SearchEventType::Reports.to_string(),
+                "",
+            );
+            if resp.is_partial {
+                log::warn!(
+                    "[REPORT] partial results for panel {} of report {report_name}: {}",
+                    query.name,
+                    resp.function_error.join(", ")
+                );
+            }
+            resp.hits
+                .into_iter()
+                .filter_map(|hit| match hit {
+                    Value::Object(hit) => Some(hit),
+                    _ => None,
+                })
+                .collect()
+        };
+        let truncated = max_rows > 0 && records.len() > max_rows;
+        if truncated {
+            log::warn!(
+                "[REPORT] results of panel {} of report {report_name} truncated to {max_rows} rows",
+                query.name
+            );
+            records.truncate(max_rows);
+        }
+        let mut table = ReportTable::new(query.name.clone(), records);
+        table.truncated = truncated;
+        tables.push(table);
+    }
+    Ok(tables)
+}
+
+/// Returns the html note telling the report recipients which panels have truncated results.
+pub fn truncation_note(tables: &[ReportTable]) -> Option<String> {
+    let panels = tables
+        .iter()
+        .filter(|table| table.truncated)
+        .map(|table| escape_xml(&table.name))
+        .collect::<Vec<_>>();
+    if panels.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "<p>Only the first {} rows of the results of these panels are included: {}</p>",
+        get_config().limit.report_data_max_rows,
+        panels.join(", ")
+    ))
+}
+
+fn panel_queries(dashboard: &Dashboard, tab_id: &str) -> (Vec<PanelQuery>, Vec<(String, String)>) {
+    match dashboard.version {
+        3 => {
+            let dash = dashboard.v3.as_ref().unwrap();
+            _get_panel_queries!(dash, tab_id)
+        }
+        4 => {
+            let dash = dashboard.v4.as_ref().unwrap();
+            _get_panel_queries!(dash, tab_id, vrl_function_query)
+        }
+        5 => {
+            let dash = dashboard.v5.as_ref().unwrap();
+            _get_panel_queries!(dash, tab_id, vrl_function_query)
+        }
+        // the earlier versions have no tabs
+        _ => (vec![], vec![]),
+    }
+}
+
+fn search_error(panel: &str, e: infra::errors::Error) -> DataReportError {
+    let e = match e {
+        infra::errors::Error::ErrorCode(e) => {
+            format!("{} {}", e.get_message(), e.get_inner_message())
+        }
+        e => e.to_string(),
+    };
+    DataReportError::QueryError {
+        panel: panel.to_string(),
+        e,
+    }
+}
+
+/// Replaces the `$name` and `${name}` variables of the query. The variables are expected to be
+/// sorted by decreasing name length.
+fn substitute_variables(query: &str, variables: &[(String, String)]) -> String {
+    let mut query = query.to_string();
+    for (name, value) in variables.iter() {
+        query = query
+            .replace(&format!("${{{name}}}"), value)
+            .replace(&format!("${name}"), value);
+    }
+    query
+}
+
+/// Flattens a PromQL result into one record per sample.
+fn promql_records(value: promql::value::Value) -> Vec<Map<String, Value>> {
+    let record = |labels: &promql::value::Labels, sample: &promql::value::Sample| {
+        let mut record = Map::with_capacity(labels.len() + 2);
+        record.extend(
+            labels
+                .iter()
+                .map(|label| (label.name.to_string(), label.value.to_string().into())),
+        );
+        record.insert(TIMESTAMP_COL_NAME.to_string(), sample.timestamp.into());
+        record.insert("value".to_string(), sample.value.into());
+        record
+    };
+    match value {
+        promql::value::Value::Matrix(matrix) => matrix
+            .iter()
+            .flat_map(|series| {
+                series
+                    .samples
+                    .iter()
+                    .map(|sample| record(&series.labels, sample))
+            })
+            .collect(),
+        promql::value::Value::Vector(vector) => vector
+            .iter()
+            .map(|instant| record(&instant.labels, &instant.sample))
+            .collect(),
+        promql::value::Value::Instant(instant) => vec![record(&instant.labels, &instant.sample)],
+        promql::value::Value::Sample(sample) => {
+            vec![record(&promql::value::Labels::new(), &sample)]
+        }
+        _ => vec![],
+    }
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.to_string(),
+        v => v.to_string(),
+    }
+}
+
+/// Prefixes the text of the cell with a quote when spreadsheet applications would read it as a
+/// formula.
+fn csv_cell(text: &str) -> String {
+    if text.starts_with(CSV_FORMULA_CHARS) {
+        format!("'{text}")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Writes the table as a csv file with a header row.
+pub fn to_csv(table: &ReportTable) -> Result<Vec<u8>, DataReportError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(table.columns.iter().map(|column| csv_cell(column)))?;
+    for row in table.rows.iter() {
+        writer.write_record(row.iter().map(|value| match value {
+            // numbers are never read as formulas, negative ones keep their sign
+            Value::String(s) => csv_cell(s),
+            v => cell_text(v),
+        }))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| DataReportError::IoError(e.into_error()))
+}
+
+/// Writes the tables as an xlsx workbook with one sheet per table. The workbook has only the
+/// parts required by spreadsheet applications, and the cells use inline strings so it doesn't
+/// need a shared strings part.
+pub fn to_xlsx(tables: &[ReportTable]) -> Result<Vec<u8>, DataReportError> {
+    let sheet_names = sheet_names(tables);
+    let mut content_types = format!(
+        r#"{XML_HEADER}<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#
+    );
+    let mut workbook = format!(
+        r#"{XML_HEADER}<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#
+    );
+    let mut workbook_rels = format!(
+        r#"{XML_HEADER}<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#
+    );
+    for (idx, name) in sheet_names.iter().enumerate() {
+        let id = idx + 1;
+        let _ = write!(
+            content_types,
+            r#"<Override PartName="/xl/worksheets/sheet{id}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#
+        );
+        let _ = write!(
+            workbook,
+            r#"<sheet name="{}" sheetId="{id}" r:id="rId{id}"/>"#,
+            escape_xml(name)
+        );
+        let _ = write!(
+            workbook_rels,
+            r#"<Relationship Id="rId{id}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{id}.xml"/>"#
+        );
+    }
+    content_types.push_str("</Types>");
+    workbook.push_str("</sheets></workbook>");
+    workbook_rels.push_str("</Relationships>");
+    let rels = format!(
+        r#"{XML_HEADER}<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#
+    );
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    zip.start_file("[Content_Types].xml", options)?;
+    zip.write_all(content_types.as_bytes())?;
+    zip.start_file("_rels/.rels", options)?;
+    zip.write_all(rels.as_bytes())?;
+    zip.start_file("xl/workbook.xml", options)?;
+    zip.write_all(workbook.as_bytes())?;
+    zip.start_file("xl/_rels/workbook.xml.rels", options)?;
+    zip.write_all(workbook_rels.as_bytes())?;
+    for (idx, table) in tables.iter().enumerate() {
+        zip.start_file(format!("xl/worksheets/sheet{}.xml", idx + 1), options)?;
+        zip.write_all(sheet_xml(table).as_bytes())?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+fn sheet_xml(table: &ReportTable) -> String {
+    let mut xml = format!(
+        r#"{XML_HEADER}<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#
+    );
+    let header = table
+        .columns
+        .iter()
+        .map(|column| Value::String(column.to_string()))
+        .collect::<Vec<_>>();
+    push_row(&mut xml, 1, &header);
+    for (idx, row) in table.rows.iter().enumerate() {
+        push_row(&mut xml, idx + 2, row);
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+fn push_row(xml: &mut String, row_num: usize, cells: &[Value]) {
+    let _ = write!(xml, r#"<row r="{row_num}">"#);
+    for (idx, value) in cells.iter().enumerate() {
+        let cell_ref = format!("{}{row_num}", column_name(idx));
+        let _ = match value {
+            Value::Null => Ok(()),
+            Value::Number(n) if n.as_f64().is_some_and(f64::is_finite) => {
+                write!(xml, r#"<c r="{cell_ref}"><v>{n}</v></c>"#)
+            }
+            Value::Bool(b) => write!(
+                xml,
+                r#"<c r="{cell_ref}" t="b"><v>{}</v></c>"#,
+                u8::from(*b)
+            ),
+            v => write!(
+                xml,
+                r#"<c r="{cell_ref}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                escape_xml(&cell_text(v))
+            ),
+        };
+    }
+    xml.push_str("</row>");
+}
+
+/// Returns the letters of the column, `A` for the first column, `AA` for the 27th column.
+fn column_name(mut idx: usize) -> String {
+    let mut name = vec![];
+    loop {
+        name.push((b'A' + (idx % 26) as u8) as char);
+        if idx < 26 {
+            break;
+        }
+        idx = idx / 26 - 1;
+    }
+    name.iter().rev().collect()
+}
+
+/// Returns unique and valid sheet names for the tables.
+fn sheet_names(tables: &[ReportTable]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(tables.len());
+    for (idx, table) in tables.iter().enumerate() {
+        let base = table
+            .name
+            .chars()
+            .filter(|c| !INVALID_SHEET_NAME_CHARS.contains(c) && !c.is_control())
+            .collect::<String>();
+        let base = base.trim().trim_matches('\'');
+        let base = if base.is_empty() {
+            format!("Sheet{}", idx + 1)
+        } else {
+            base.to_string()
+        };
+        let mut name = base.chars().take(MAX_SHEET_NAME_LEN).collect::<String>();
+        let mut suffix_num = 2;
+        // sheet names are case insensitive
+        while names
+            .iter()
+            .any(|existing| existing.to_lowercase() == name.to_lowercase())
+        {
+            let suffix = format!(" ({suffix_num})");
+            name = base
+                .chars()
+                .take(MAX_SHEET_NAME_LEN - suffix.len())
+                .chain(suffix.chars())
+                .collect();
+            suffix_num += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            // not allowed in xml documents
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use config::utils::json::json;
+    use zip::ZipArchive;
+
+    use super::*;
+
+    fn table(name: &str) -> ReportTable {
+        let records = vec![
+            json!({"host": "a", "count": 3}),
+            json!({"host": "b,\"c\"", "count": 1.5, "up": true}),
+        ];
+        ReportTable::new(
+            name.to_string(),
+            records
+                .into_iter()
+                .map(|r| r.as_object().unwrap().clone())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_report_table() {
+        let table = table("panel");
+        assert_eq!(table.columns.len(), 3);
+        assert!(table.columns.contains(&"up".to_string()));
+        let up = table.columns.iter().position(|c| c == "up").unwrap();
+        assert_eq!(table.rows[0][up], Value::Null);
+        assert_eq!(table.rows[1][up], Value::Bool(true));
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let table = ReportTable {
+            name: "panel".to_string(),
+            columns: vec!["host".to_string(), "count".to_string()],
+            rows: vec![
+                vec![json!("a"), json!(3)],
+                vec![json!("b,\"c\""), Value::Null],
+                vec![json!("=HYPERLINK(\"x\")"), json!(-2)],
+                vec![json!("@SUM(A1)"), json!("-2")],
+            ],
+            ..Default::default()
+        };
+        let csv = String::from_utf8(to_csv(&table).unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "host,count\na,3\n\"b,\"\"c\"\"\",\n\"'=HYPERLINK(\"\"x\"\")\",-2\n'@SUM(A1),'-2\n"
+        );
+    }
+
+    #[test]
+    fn test_truncation_note() {
+        let mut tables = vec![table("a"), table("<b>")];
+        assert_eq!(truncation_note(&tables), None);
+        tables[1].truncated = true;
+        let note = truncation_note(&tables).unwrap();
+        assert!(note.contains(": &lt;b&gt;</p>"));
+    }
+
+    #[test]
+    fn test_to_xlsx() {
+        let tables = vec![table("Errors <by host>"), table("errors <by host>")];
+        let data = to_xlsx(&tables).unwrap();
+        let mut zip = ZipArchive::new(Cursor::new(data)).unwrap();
+        let mut workbook = String::new();
+        zip.by_name("xl/workbook.xml")
+            .unwrap()
+            .read_to_string(&mut workbook)
+            .unwrap();
+        assert!(workbook.contains(r#"name="Errors &lt;by host&gt;" sheetId="1""#));
+        assert!(workbook.contains(r#"name="errors &lt;by host&gt; (2)" sheetId="2""#));
+        let mut sheet = String::new();
+        zip.by_name("xl/worksheets/sheet2.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+        assert!(sheet.contains(r#"<row r="3">"#));
+        assert!(sheet.contains("<v>1.5</v>"));
+        assert!(sheet.contains(r#"t="b"><v>1</v>"#));
+        assert!(sheet.contains("b,&quot;c&quot;"));
+        assert!(zip.by_name("[Content_Types].xml").is_ok());
+        assert!(zip.by_name("_rels/.rels").is_ok());
+    }
+
+    #[test]
+    fn test_column_name() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+    }
+
+    #[test]
+    fn test_sheet_names() {
+        let tables = ["a/b", "", "A/B", &"x".repeat(40)]
+            .iter()
+            .map(|name| ReportTable {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let names = sheet_names(&tables);
+        assert_eq!(names[0], "ab");
+        assert_eq!(names[1], "Sheet2");
+        assert_eq!(names[2], "AB (2)");
+        assert_eq!(names[3].chars().count(), MAX_SHEET_NAME_LEN);
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let variables = vec![
+            ("hostname".to_string(), "web-1".to_string()),
+            ("host".to_string(), "web".to_string()),
+        ];
+        assert_eq!(
+            substitute_variables(
+                "SELECT * FROM logs WHERE h = '$host' AND n = '${hostname}' AND m = '$hostname'",
+                &variables
+            ),
+            "SELECT * FROM logs WHERE h = 'web' AND n = 'web-1' AND m = 'web-1'"
+        );
+    }
+}
@@ -0,0 +1,319 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Delivery of the report files to the http and storage destinations of a report. Http
+//! destinations reuse the endpoint of a pipeline destination, and get the files either as a
+//! multipart upload or as presigned links to the files written by a storage destination.
+
+use std::time::Duration;
+
+use chrono::{FixedOffset, Utc};
+use config::{
+    ider,
+    meta::{
+        dashboards::reports::{
+            Report, ReportDestination, ReportHttpDelivery, ReportHttpDestination,
+            ReportStorageDestination,
+        },
+        destinations::{HTTPType, Module},
+    },
+    utils::{json, time::now_micros},
+};
+
+use crate::service::{
+    alerts::destinations,
+    secrets::{self, SecretError},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeliveryError {
+    #[error("Report destination {0} must be an existing pipeline destination")]
+    DestinationNotFound(String),
+
+    #[error("Link delivery needs a storage destination in the report")]
+    LinkWithoutStorage,
+
+    #[error("Storage pattern {0} must be a relative path with {{file}} and valid date specifiers")]
+    InvalidStoragePattern(String),
+
+    #[error("Storage prefix {0} must be a relative path without .. segments")]
+    InvalidStoragePrefix(String),
+
+    #[error("Storage account {0} is not configured")]
+    UnknownStorageAccount(String),
+
+    #[error(transparent)]
+    SecretError(#[from] SecretError),
+
+    #[error(transparent)]
+    StorageError(#[from] object_store::Error),
+
+    #[error(transparent)]
+    ParseUrlError(#[from] url::ParseError),
+
+    #[error("Error contacting destination: {0}")]
+    HttpClientError(#[from] reqwest::Error),
+
+    #[error("Destination {0} error status: {1}, body: {2}")]
+    HttpErrorResponse(String, reqwest::StatusCode, String),
+}
+
+/// Checks the http destinations of the report reference pipeline destinations, and the
+/// storage destinations use a configured account and have a valid prefix and pattern.
+pub async fn validate(report: &Report) -> Result<(), DeliveryError> {
+    let has_storage = report
+        .destinations
+        .iter()
+        .any(|d| matches!(d, ReportDestination::Storage(_)));
+    for destination in report.destinations.iter() {
+        match destination {
+            ReportDestination::Email(_) => {}
+            ReportDestination::Http(http) => {
+                get_endpoint(&report.org_id, &http.destination).await?;
+                if http.delivery == ReportHttpDelivery::Link && !has_storage {
+                    return Err(DeliveryError::LinkWithoutStorage);
+                }
+            }
+            ReportDestination::Storage(storage) => {
+                if !infra::storage::is_account_configured(&storage.account) {
+                    return Err(DeliveryError::UnknownStorageAccount(
+                        storage.account.clone(),
+                    ));
+                }
+                if !storage.is_valid_prefix() {
+                    return Err(DeliveryError::InvalidStoragePrefix(storage.prefix.clone()));
+                }
+                if !storage.is_valid_pattern() {
+                    return Err(DeliveryError::InvalidStoragePattern(
+                        storage.pattern.clone(),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Delivers the files to the http and storage destinations of the report. Every destination
+/// is tried, and the first error is returned.
+pub async fn deliver(
+    report: &Report,
+    files: &[(String, Vec<u8>)],
+    dashb_url: &str,
+) -> Result<(), DeliveryError> {
+    let mut first_error: Option<DeliveryError> = None;
+
+    // storage first, so the link deliveries can point to the written files
+    let offset = FixedOffset::east_opt(report.tz_offset * 60)
+        .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+    let date = Utc::now().with_timezone(&offset);
+    let mut stored = None;
+    for destination in report.destinations.iter() {
+        let ReportDestination::Storage(storage) = destination else {
+            continue;
+        };
+        let mut keys = Vec::with_capacity(files.len());
+        for (name, data) in files.iter() {
+            let key = storage.file_key(&report.org_id, &report.name, name, &date);
+            match infra::storage::put(&storage.account, &key, data.clone().into()).await {
+                Ok(_) => keys.push((name.as_str(), key)),
+                Err(e) => {
+                    log::error!(
+                        "[REPORT] writing file {key} of report {} error: {e}",
+                        report.name
+                    );
+                    first_error.get_or_insert(e.into());
+                }
+            }
+        }
+        if keys.len() == files.len() && stored.is_none() {
+            stored = Some((storage, keys));
+        }
+    }
+
+    for destination in report.destinations.iter() {
+        let ReportDestination::Http(http) = destination else {
+            continue;
+        };
+        if let Err(e) = send_http(report, http, files, stored.as_ref(), dashb_url).await {
+            log::error!(
+                "[REPORT] sending report {} to destination {} error: {e}",
+                report.name,
+                http.destination
+            );
+            first_error.get_or_insert(e);
+        }
+    }
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+async fn get_endpoint(
+    org_id: &str,
+    name: &str,
+) -> Result<config::meta::destinations::Endpoint, DeliveryError> {
+    match destinations::get(org_id, name).await {
+        Ok(destination) => match destination.module {
+            Module::Pipeline { endpoint } => Ok(endpoint),
+            _ => Err(DeliveryError::DestinationNotFound(name.to_string())),
+        },
+        Err(_) => Err(DeliveryError::DestinationNotFound(name.to_string())),
+    }
+}
+
+async fn send_http(
+    report: &Report,
+    http: &ReportHttpDestination,
+    files: &[(String, Vec<u8>)],
+    stored: Option<&(&ReportStorageDestination, Vec<(&str, String)>)>,
+    dashb_url: &str,
+) -> Result<(), DeliveryError> {
+    let endpoint = get_endpoint(&report.org_id, &http.destination).await?;
+    let (content_type, body) = match http.delivery {
+        ReportHttpDelivery::Multipart => {
+            let boundary = format!("openobserve-report-{}", ider::generate());
+            let fields = [
+                ("report", report.name.as_str()),
+                ("title", report.title.as_str()),
+                ("message", report.message.as_str()),
+                ("dashboard_url", dashb_url),
+            ];
+            let body = multipart_body(&boundary, &fields, files, report.media_type.content_type());
+            (format!("multipart/form-data; boundary={boundary}"), body)
+        }
+        ReportHttpDelivery::Link => {
+            let Some((storage, keys)) = stored else {
+                return Err(DeliveryError::LinkWithoutStorage);
+            };
+            let expires_in = Duration::from_secs(http.link_expiry_secs);
+            let expires_at = now_micros() + expires_in.as_micros() as i64;
+            let mut links = Vec::with_capacity(keys.len());
+            for (name, key) in keys.iter() {
+                let url = infra::storage::signed_url(&storage.account, key, expires_in).await?;
+                links.push(json::json!({
+                    "name": name,
+                    "url": url,
+                    "expires_at": expires_at,
+                }));
+            }
+            let body = json::json!({
+                "report": report.name,
+                "title": report.title,
+                "message": report.message,
+                "dashboard_url": dashb_url,
+                "files": links,
+            });
+            (
+                "application/json".to_string(),
+                json::to_vec(&body).unwrap_or_default(),
+            )
+        }
+    };
+
+    // resolve the `${secret:name}` references, the unresolved endpoint is the one logged
+    let resolved = secrets::resolve_endpoint(&report.org_id, &endpoint).await?;
+    let client = if endpoint.skip_tls_verify {
+        reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .build()?
+    } else {
+        reqwest::Client::new()
+    };
+    let url = url::Url::parse(&resolved.url)?;
+    let mut req = match endpoint.method {
+        HTTPType::POST => client.post(url),
+        HTTPType::PUT => client.put(url),
+        HTTPType::GET => client.get(url),
+    };
+    if let Some(headers) = &resolved.headers {
+        for (key, value) in headers.iter() {
+            // the content type is the one of the body
+            if !key.is_empty() && !value.is_empty() && !key.eq_ignore_ascii_case("content-type") {
+                req = req.header(key, value);
+            }
+        }
+    }
+    let resp = req
+        .header("Content-Type", content_type)
+        .body(body)
+        .send()
+        .await?;
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(DeliveryError::HttpErrorResponse(
+            http.destination.clone(),
+            status,
+            body,
+        ));
+    }
+    log::info!(
+        "report {} sent to destination {} with status: {status}",
+        report.name,
+        http.destination
+    );
+    Ok(())
+}
+
+/// Builds a multipart/form-data body with the text fields followed by the files.
+fn multipart_body(
+    boundary: &str,
+    fields: &[(&str, &str)],
+    files: &[(String, Vec<u8>)],
+    content_type: &str,
+) -> Vec<u8> {
+    let mut body = Vec::with_capacity(files.iter().map(|(_, data)| data.len() + 256).sum());
+    for (name, value) in fields.iter() {
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+            )
+            .as_bytes(),
+        );
+    }
+    for (filename, data) in files.iter() {
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {content_type}\r\n\r\n",
+                filename.replace('"', "")
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multipart_body() {
+        let files = vec![("report.csv".to_string(), b"a,b\n1,2\n".to_vec())];
+        let body = multipart_body("xyz", &[("title", "Weekly")], &files, "text/csv");
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "--xyz\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nWeekly\r\n\
+             --xyz\r\nContent-Disposition: form-data; name=\"file\"; filename=\"report.csv\"\r\n\
+             Content-Type: text/csv\r\n\r\na,b\n1,2\n\r\n--xyz--\r\n"
+        );
+    }
+}
@@ -24,7 +24,7 @@ use config::{
         datetime_now,
         reports::{
             HttpReportPayload, Report, ReportDashboard, ReportDestination, ReportEmailDetails,
-            ReportFrequencyType, ReportListFilters, ReportTimerangeType,
+            ReportFrequencyType, ReportListFilters, ReportMediaType, ReportTimerangeType,
         },
     },
     utils::time::now_micros,
@@ -42,6 +42,10 @@ use lettre::{
 };
 use reqwest::Client;
 
+use super::{
+    report_data::{self, DataReportError},
+    report_delivery::{self, DeliveryError},
+};
 use crate::{
     common::{
         meta::authz::Authz,
@@ -100,6 +104,12 @@ pub enum ReportError {
 
     #[error("Error creating default reports folder")]
     CreateDefaultFolderError,
+
+    #[error("The report server only sends pdf reports by email")]
+    ReportServerEmailOnly,
+
+    #[error(transparent)]
+    InvalidDestination(#[from] DeliveryError),
 }
 
 pub async fn save(
@@ -110,12 +120,26 @@ pub async fn save(
 ) -> Result<(), ReportError> {
     let conn = ORM_CLIENT.get_or_init(connect_to_orm).await;
     let cfg = get_config();
-    if cfg.common.report_server_url.is_empty() {
+    // Data reports are always sent from here, they don't go through the report server
+    let is_data = report.media_type.is_data();
+    let has_email = report
+        .destinations
+        .iter()
+        .any(|d| matches!(d, ReportDestination::Email(_)));
+    if cfg.common.report_server_url.is_empty() || is_data {
         // Check if SMTP is enabled, otherwise don't save the report
-        if !cfg.smtp.smtp_enabled && !report.destinations.is_empty() {
+        if !cfg.smtp.smtp_enabled && has_email {
             return Err(ReportError::SmtpNotEnabled);
         }
-
+    } else if report
+        .destinations
+        .iter()
+        .any(|d| !matches!(d, ReportDestination::Email(_)))
+    {
+        return Err(ReportError::ReportServerEmailOnly);
+    }
+    // Data reports run the panel queries directly, they don't need a headless browser
+    if cfg.common.report_server_url.is_empty() && !is_data {
         // Check if Chrome is enabled, otherwise don't save the report
         if !cfg.chrome.chrome_enabled || cfg.chrome.chrome_path.is_empty() {
             return Err(ReportError::ChromeNotEnabled);
@@ -141,6 +165,8 @@ pub async fn save(
         return Err(ReportError::NameContainsForwardSlash);
     }
 
+    report_delivery::validate(&report).await?;
+
     if report.frequency.frequency_type == ReportFrequencyType::Cron {
         let now = chrono::Utc::now().second();
         report.frequency.cron =
@@ -335,6 +361,12 @@ pub enum SendReportError {
 
     #[error(transparent)]
     GenerateReportError(#[from] GenerateReportError),
+
+    #[error(transparent)]
+    DataReportError(#[from] DataReportError),
+
+    #[error(transparent)]
+    DeliveryError(#[from] DeliveryError),
 }
 
 #[async_trait]
@@ -354,12 +386,22 @@ impl SendReport for Report {
         let cfg = get_config();
         let mut recipients = vec![];
         for recipient in &self.destinations {
-            match recipient {
-                ReportDestination::Email(email) => recipients.push(email.clone()),
+            if let ReportDestination::Email(email) = recipient {
+                recipients.push(email.clone());
             }
         }
-        let no_of_recipients = recipients.len();
-        if !cfg.common.report_server_url.is_empty() {
+        // The report files are only generated when there is a destination to send them to
+        let no_of_recipients = self.destinations.len();
+        if self.media_type.is_data() {
+            // Without recipients there is nothing to send
+            if no_of_recipients == 0 {
+                return Ok(());
+            }
+            // Currently only one `ReportDashboard` can be sent
+            let (attachments, dashb_url, note) =
+                generate_data_report(self, &self.dashboards[0]).await?;
+            deliver(self, &attachments, dashb_url, note.as_deref()).await
+        } else if !cfg.common.report_server_url.is_empty() {
             let report_data = HttpReportPayload {
                 dashboards: self.dashboards.clone(),
                 email_details: ReportEmailDetails {
@@ -413,26 +455,37 @@ impl SendReport for Report {
                 &self.name,
             )
             .await?;
-            send_email(self, &report.0, report.1).await
+            let attachment = (format!("{}.pdf", sanitize_filename(&self.title)), report.0);
+            deliver(self, &[attachment], report.1, None).await
         }
     }
 }
 
-/// Sends emails to the [`Report`] recipients. Currently only one pdf data is supported.
-async fn send_email(
+/// Sends the report files to all the destinations of the [`Report`]. The emails are sent even
+/// when the delivery to another destination fails, with the html `note` after the message.
+async fn deliver(
     report: &Report,
-    pdf_data: &[u8],
+    attachments: &[(String, Vec<u8>)],
     dashb_url: String,
+    note: Option<&str>,
 ) -> Result<(), SendReportError> {
-    let cfg = get_config();
-    if !cfg.smtp.smtp_enabled {
-        return Err(SendReportError::SmtpNotEnabled);
-    }
+    let delivered = report_delivery::deliver(report, attachments, &dashb_url).await;
+    send_email(report, attachments, dashb_url, note).await?;
+    Ok(delivered?)
+}
 
+/// Sends emails to the [`Report`] recipients. The attachments are given as file name and
+/// content, their content type is the one of the report media type.
+async fn send_email(
+    report: &Report,
+    attachments: &[(String, Vec<u8>)],
+    dashb_url: String,
+    note: Option<&str>,
+) -> Result<(), SendReportError> {
     let mut recipients = vec![];
     for recipient in &report.destinations {
-        match recipient {
-            ReportDestination::Email(email) => recipients.push(email),
+        if let ReportDestination::Email(email) = recipient {
+            recipients.push(email);
         }
     }
 
@@ -440,6 +493,11 @@ async fn send_email(
         return Ok(());
     }
 
+    let cfg = get_config();
+    if !cfg.smtp.smtp_enabled {
+        return Err(SendReportError::SmtpNotEnabled);
+    }
+
     let mut email = Message::builder()
         .from(cfg.smtp.smtp_from_email.parse()?)
         .subject(report.title.to_string());
@@ -452,23 +510,19 @@ async fn send_email(
         email = email.reply_to(cfg.smtp.smtp_reply_to.parse()?);
     }
 
-    let email = email
-        .multipart(
-            MultiPart::mixed()
-                .singlepart(SinglePart::html(format!(
-                    "{}\n\n<p><a href='{dashb_url}' target='_blank'>Link to dashboard</a></p>",
-                    report.message
-                )))
-                .singlepart(
-                    // Only supports PDF for now, attach the PDF
-                    lettre::message::Attachment::new(format!(
-                        "{}.pdf",
-                        sanitize_filename(&report.title)
-                    ))
-                    .body(pdf_data.to_owned(), ContentType::parse("application/pdf")?),
-                ),
-        )
-        .unwrap();
+    let content_type = ContentType::parse(report.media_type.content_type())?;
+    let mut body = MultiPart::mixed().singlepart(SinglePart::html(format!(
+        "{}{}\n\n<p><a href='{dashb_url}' target='_blank'>Link to dashboard</a></p>",
+        report.message,
+        note.unwrap_or_default()
+    )));
+    for (filename, data) in attachments {
+        body = body.singlepart(
+            lettre::message::Attachment::new(filename.to_string())
+                .body(data.to_owned(), content_type.clone()),
+        );
+    }
+    let email = email.multipart(body).unwrap();
 
     // Send the email
     match SMTP_CLIENT.as_ref().unwrap().send(email).await {
@@ -599,52 +653,13 @@ async fn generate_report(
     let (dashb_url, email_dashb_url) = match timerange.range_type {
         ReportTimerangeType::Relative => {
             let period = &timerange.period;
-            let (time_duration, time_unit) = period.split_at(period.len() - 1);
             let dashb_url = format!(
                 "{web_url}/dashboards/view?org_identifier={org_id}&dashboard={dashboard_id}&folder={folder_id}&tab={tab_id}&refresh=Off&{search_type_params}&period={period}&timezone={timezone}&var-Dynamic+filters=%255B%255D&print=true{dashb_vars}",
             );
 
-            let time_duration: i64 = time_duration
-                .parse()
-                .map_err(GenerateReportError::ParseTimeDurationError)?;
             let end_time = now_micros();
-            let start_time = match time_unit {
-                "m" => {
-                    end_time
-                        - chrono::Duration::try_minutes(time_duration)
-                            .unwrap()
-                            .num_microseconds()
-                            .unwrap()
-                }
-                "h" => {
-                    end_time
-                        - chrono::Duration::try_hours(time_duration)
-                            .unwrap()
-                            .num_microseconds()
-                            .unwrap()
-                }
-                "d" => {
-                    end_time
-                        - chrono::Duration::try_days(time_duration)
-                            .unwrap()
-                            .num_microseconds()
-                            .unwrap()
-                }
-                "w" => {
-                    end_time
-                        - chrono::Duration::try_weeks(time_duration)
-                            .unwrap()
-                            .num_microseconds()
-                            .unwrap()
-                }
-                _ => {
-                    end_time
-                        - chrono::Duration::try_days(30 * time_duration)
-                            .unwrap()
-                            .num_microseconds()
-                            .unwrap()
-                }
-            };
+            let start_time = period_start_time(period, end_time)
+                .map_err(GenerateReportError::ParseTimeDurationError)?;
 
             let email_dashb_url = format!(
                 "{web_url}/dashboards/view?org_identifier={org_id}&dashboard={dashboard_id}&folder={folder_id}&tab={tab_id}&refresh=Off&from={start_time}&to={end_time}&timezone={timezone}&var-Dynamic+filters=%255B%255D&print=true{dashb_vars}",
@@ -742,6 +757,88 @@ async fn generate_report(
     Ok((pdf_data, email_dashb_url))
 }
 
+/// Runs the panel queries of the dashboard and returns the attachments of the report along with
+/// the link to the dashboard over the same period, and the note about the truncated panels.
+async fn generate_data_report(
+    report: &Report,
+    dashboard: &ReportDashboard,
+) -> Result<(Vec<(String, Vec<u8>)>, String, Option<String>), SendReportError> {
+    let cfg = get_config();
+    let org_id = &report.org_id;
+    let timerange = &dashboard.timerange;
+    let (start_time, end_time) = match timerange.range_type {
+        ReportTimerangeType::Relative => {
+            let end_time = now_micros();
+            let start_time = period_start_time(&timerange.period, end_time)
+                .map_err(GenerateReportError::ParseTimeDurationError)?;
+            (start_time, end_time)
+        }
+        ReportTimerangeType::Absolute => (timerange.from, timerange.to),
+    };
+    let user_id = Some(report.owner.as_str()).filter(|owner| !owner.is_empty());
+    let tables = report_data::query_panels(
+        org_id,
+        &report.name,
+        dashboard,
+        user_id,
+        (start_time, end_time),
+    )
+    .await?;
+
+    let note = report_data::truncation_note(&tables);
+    let title = sanitize_filename(&report.title);
+    let attachments = match report.media_type {
+        ReportMediaType::Xlsx => vec![(format!("{title}.xlsx"), report_data::to_xlsx(&tables)?)],
+        _ if tables.len() == 1 => vec![(format!("{title}.csv"), report_data::to_csv(&tables[0])?)],
+        _ => tables
+            .iter()
+            .map(|table| {
+                let filename = format!("{title} - {}.csv", sanitize_filename(&table.name));
+                Ok((filename, report_data::to_csv(table)?))
+            })
+            .collect::<Result<_, DataReportError>>()?,
+    };
+
+    let web_url = format!("{}{}/web", cfg.common.web_url, cfg.common.base_uri);
+    let tab_id = dashboard
+        .tabs
+        .first()
+        .map(String::as_str)
+        .unwrap_or_default();
+    let dashb_vars = dashboard
+        .variables
+        .iter()
+        .map(|variable| format!("&var-{}={}", variable.key, variable.value))
+        .collect::<String>();
+    let dashb_url = format!(
+        "{web_url}/dashboards/view?org_identifier={org_id}&dashboard={}&folder={}&tab={tab_id}&refresh=Off&from={start_time}&to={end_time}&timezone={}&var-Dynamic+filters=%255B%255D{dashb_vars}",
+        dashboard.dashboard, dashboard.folder, report.timezone
+    );
+    let dashb_url = match short_url::shorten(org_id, &dashb_url).await {
+        Ok(short_url) => short_url,
+        Err(e) => {
+            log::error!("Error shortening email dashboard url: {e}");
+            dashb_url
+        }
+    };
+    Ok((attachments, dashb_url, note))
+}
+
+/// Returns the start of a relative period, like `15m` or `4w`, ending at `end_time`. Both
+/// times are in microseconds, and months are 30 days.
+fn period_start_time(period: &str, end_time: i64) -> Result<i64, std::num::ParseIntError> {
+    let (time_duration, time_unit) = period.split_at(period.len() - 1);
+    let time_duration: i64 = time_duration.parse()?;
+    let duration = match time_unit {
+        "m" => chrono::Duration::try_minutes(time_duration),
+        "h" => chrono::Duration::try_hours(time_duration),
+        "d" => chrono::Duration::try_days(time_duration),
+        "w" => chrono::Duration::try_weeks(time_duration),
+        _ => chrono::Duration::try_days(30 * time_duration),
+    };
+    Ok(end_time - duration.unwrap().num_microseconds().unwrap())
+}
+
 async fn wait_for_panel_data_load(page: &Page) -> Result<(), GenerateReportError> {
     let start = std::time::Instant::now();
     let timeout = Duration::from_secs(get_config().chrome.chrome_sleep_secs.into());
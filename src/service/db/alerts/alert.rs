@@ -243,6 +243,9 @@ pub async fn delete_by_id<C: ConnectionTrait>(
     {
         log::error!("Failed to delete trigger: {}", e);
     };
+    if let Err(e) = db::alerts::firing_states::delete(org_id, &alert_id_str).await {
+        log::error!("Failed to delete alert firing state: {}", e);
+    };
     Ok(())
 }
 
@@ -274,6 +277,9 @@ pub async fn delete_by_name(
     {
         log::error!("Failed to delete trigger: {}", e);
     };
+    if let Err(e) = db::alerts::firing_states::delete(org_id, &alert_id_str).await {
+        log::error!("Failed to delete alert firing state: {}", e);
+    };
     Ok(())
 }
 
@@ -58,6 +58,12 @@ pub enum DestinationError {
     UsedByAlert(String),
     #[error("Destination is currently used by pipeline: {0}")]
     UsedByPipeline(String),
+    #[error("Destination header {0} is redacted, set its value or reference a secret instead")]
+    RedactedValue(String),
+    #[error("Destination references a secret which does not exist: {0}")]
+    SecretNotFound(String),
+    #[error("Destination url {0} must reference a secret, e.g. ${{secret:name}}")]
+    PlaintextUrlCredential(String),
     #[cfg(feature = "enterprise")]
     #[error("Invalid action id: {0}")]
     InvalidActionId(anyhow::Error),
@@ -0,0 +1,112 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Firing states of the scheduled alerts, read by the alerts depending on them. The state is
+//! the `firing` field of the trigger data of an alert, and only its changes are written here.
+
+use std::sync::Arc;
+
+use config::utils::json;
+use infra::errors;
+
+use crate::{common::infra::config::ALERT_FIRING_STATES, service::db};
+
+pub const ALERT_FIRING_STATE_KEY_PREFIX: &str = "/alerts/firing_states/";
+
+pub fn is_firing(org_id: &str, alert_id: &str) -> bool {
+    ALERT_FIRING_STATES
+        .get(&format!("{org_id}/{alert_id}"))
+        .is_some_and(|firing| *firing)
+}
+
+pub async fn set(org_id: &str, alert_id: &str, firing: bool) -> errors::Result<()> {
+    if is_firing(org_id, alert_id) == firing {
+        return Ok(());
+    }
+    let key = format!("{org_id}/{alert_id}");
+    db::put(
+        &format!("{ALERT_FIRING_STATE_KEY_PREFIX}{key}"),
+        json::to_vec(&firing)?.into(),
+        db::NEED_WATCH,
+        None,
+    )
+    .await?;
+    ALERT_FIRING_STATES.insert(key, firing);
+    Ok(())
+}
+
+pub async fn delete(org_id: &str, alert_id: &str) -> errors::Result<()> {
+    let key = format!("{org_id}/{alert_id}");
+    db::delete_if_exists(
+        &format!("{ALERT_FIRING_STATE_KEY_PREFIX}{key}"),
+        false,
+        db::NEED_WATCH,
+    )
+    .await?;
+    ALERT_FIRING_STATES.remove(&key);
+    Ok(())
+}
+
+pub async fn watch() -> Result<(), anyhow::Error> {
+    let key = ALERT_FIRING_STATE_KEY_PREFIX;
+    let cluster_coordinator = db::get_coordinator().await;
+    let mut events = cluster_coordinator.watch(key).await?;
+    let events = Arc::get_mut(&mut events).unwrap();
+    log::info!("Start watching alert firing states");
+    loop {
+        let ev = match events.recv().await {
+            Some(ev) => ev,
+            None => {
+                log::error!("watch_alert_firing_states: event channel closed");
+                return Ok(());
+            }
+        };
+        match ev {
+            db::Event::Put(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                let item_value: bool = match db::get(&ev.key).await {
+                    Ok(val) => match json::from_slice(&val) {
+                        Ok(val) => val,
+                        Err(e) => {
+                            log::error!("Error getting value: {}", e);
+                            continue;
+                        }
+                    },
+                    Err(e) => {
+                        log::error!("Error getting value: {}", e);
+                        continue;
+                    }
+                };
+                ALERT_FIRING_STATES.insert(item_key.to_string(), item_value);
+            }
+            db::Event::Delete(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                ALERT_FIRING_STATES.remove(item_key);
+            }
+            db::Event::Empty => {}
+        }
+    }
+}
+
+pub async fn cache() -> Result<(), anyhow::Error> {
+    let ret = db::list(ALERT_FIRING_STATE_KEY_PREFIX).await?;
+    for (key, item_value) in ret {
+        let item_key = key.strip_prefix(ALERT_FIRING_STATE_KEY_PREFIX).unwrap();
+        let firing: bool = json::from_slice(&item_value)?;
+        ALERT_FIRING_STATES.insert(item_key.to_string(), firing);
+    }
+    log::info!("Alert firing states Cached");
+    Ok(())
+}
@@ -0,0 +1,116 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use config::{meta::alerts::maintenance_window::MaintenanceWindow, utils::json};
+use infra::errors;
+
+use crate::{common::infra::config::MAINTENANCE_WINDOWS, service::db};
+
+pub const MAINTENANCE_WINDOW_KEY_PREFIX: &str = "/alerts/maintenance_windows/";
+
+pub fn get(org_id: &str, id: &str) -> Option<MaintenanceWindow> {
+    MAINTENANCE_WINDOWS
+        .get(&format!("{org_id}/{id}"))
+        .map(|window| window.value().clone())
+}
+
+pub fn list(org_id: &str) -> Vec<MaintenanceWindow> {
+    let prefix = format!("{org_id}/");
+    MAINTENANCE_WINDOWS
+        .iter()
+        .filter(|window| window.key().starts_with(&prefix))
+        .map(|window| window.value().clone())
+        .collect()
+}
+
+pub async fn set(org_id: &str, window: &MaintenanceWindow) -> errors::Result<()> {
+    let key = format!("{org_id}/{}", window.id);
+    db::put(
+        &format!("{MAINTENANCE_WINDOW_KEY_PREFIX}{key}"),
+        json::to_vec(window)?.into(),
+        db::NEED_WATCH,
+        None,
+    )
+    .await?;
+    MAINTENANCE_WINDOWS.insert(key, window.clone());
+    Ok(())
+}
+
+pub async fn delete(org_id: &str, id: &str) -> errors::Result<()> {
+    let key = format!("{org_id}/{id}");
+    db::delete(
+        &format!("{MAINTENANCE_WINDOW_KEY_PREFIX}{key}"),
+        false,
+        db::NEED_WATCH,
+        None,
+    )
+    .await?;
+    MAINTENANCE_WINDOWS.remove(&key);
+    Ok(())
+}
+
+pub async fn watch() -> Result<(), anyhow::Error> {
+    let key = MAINTENANCE_WINDOW_KEY_PREFIX;
+    let cluster_coordinator = db::get_coordinator().await;
+    let mut events = cluster_coordinator.watch(key).await?;
+    let events = Arc::get_mut(&mut events).unwrap();
+    log::info!("Start watching alert maintenance windows");
+    loop {
+        let ev = match events.recv().await {
+            Some(ev) => ev,
+            None => {
+                log::error!("watch_maintenance_windows: event channel closed");
+                return Ok(());
+            }
+        };
+        match ev {
+            db::Event::Put(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                let item_value: MaintenanceWindow = match db::get(&ev.key).await {
+                    Ok(val) => match json::from_slice(&val) {
+                        Ok(val) => val,
+                        Err(e) => {
+                            log::error!("Error getting value: {}", e);
+                            continue;
+                        }
+                    },
+                    Err(e) => {
+                        log::error!("Error getting value: {}", e);
+                        continue;
+                    }
+                };
+                MAINTENANCE_WINDOWS.insert(item_key.to_string(), item_value);
+            }
+            db::Event::Delete(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                MAINTENANCE_WINDOWS.remove(item_key);
+            }
+            db::Event::Empty => {}
+        }
+    }
+}
+
+pub async fn cache() -> Result<(), anyhow::Error> {
+    let ret = db::list(MAINTENANCE_WINDOW_KEY_PREFIX).await?;
+    for (key, item_value) in ret {
+        let item_key = key.strip_prefix(MAINTENANCE_WINDOW_KEY_PREFIX).unwrap();
+        let window: MaintenanceWindow = json::from_slice(&item_value)?;
+        MAINTENANCE_WINDOWS.insert(item_key.to_string(), window);
+    }
+    log::info!("Alert maintenance windows Cached");
+    Ok(())
+}
@@ -15,5 +15,7 @@
 
 pub mod alert;
 pub mod destinations;
+pub mod firing_states;
+pub mod maintenance_windows;
 pub mod realtime_triggers;
 pub mod templates;
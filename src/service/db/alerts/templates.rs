@@ -50,6 +50,8 @@ pub enum TemplateError {
     DeleteWithDestination(String),
     #[error("Template not found")]
     NotFound,
+    #[error("Template cannot be rendered: {0}")]
+    Render(String),
 }
 
 pub async fn get(org_id: &str, name: &str) -> Result<Template, TemplateError> {
@@ -0,0 +1,172 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::Utc;
+use config::{RwHashMap, meta::api_key::ApiKey};
+use infra::{
+    db::{self, delete_from_db_coordinator, get_coordinator, put_into_db_coordinator},
+    table::api_keys::{self, ApiKeyRecord},
+};
+use once_cell::sync::Lazy;
+
+use crate::common::infra::config::API_KEYS;
+
+pub const API_KEYS_KEY_PREFIX: &str = "/api_keys/";
+
+/// How long, in microseconds, a key hash that matches no key is remembered.
+const MISSING_KEY_TTL: i64 = 60 * 1_000_000;
+
+/// Maximum number of missing key hashes remembered.
+const MISSING_KEYS_MAX: usize = 10_000;
+
+/// Hashes of keys not found in the db, with the time they were looked up at, so that unknown
+/// keys don't hit the db on every request.
+static MISSING_KEYS: Lazy<RwHashMap<String, i64>> = Lazy::new(Default::default);
+
+pub async fn add(record: &ApiKeyRecord) -> Result<(), infra::errors::Error> {
+    let key = format!(
+        "{API_KEYS_KEY_PREFIX}{}/{}",
+        record.api_key.org_id, record.api_key.id
+    );
+    api_keys::add(record).await?;
+    let _ = put_into_db_coordinator(&key, Bytes::new(), true, None).await;
+    Ok(())
+}
+
+pub async fn remove(org_id: &str, id: &str) -> Result<(), anyhow::Error> {
+    let key = format!("{API_KEYS_KEY_PREFIX}{org_id}/{id}");
+    api_keys::remove(org_id, id)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to remove api key: {}", e))?;
+    let _ = delete_from_db_coordinator(&key, false, true, None).await;
+    Ok(())
+}
+
+pub async fn get(org_id: &str, id: &str) -> Result<Option<ApiKey>, anyhow::Error> {
+    let record = api_keys::get(org_id, id)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch api key: {}", e))?;
+    Ok(record.map(|record| record.api_key))
+}
+
+pub async fn get_by_hash(key_hash: &str) -> Result<Option<ApiKeyRecord>, anyhow::Error> {
+    if let Some(record) = API_KEYS.get(key_hash) {
+        return Ok(Some(record.value().clone()));
+    }
+    let now = Utc::now().timestamp_micros();
+    if MISSING_KEYS
+        .get(key_hash)
+        .is_some_and(|looked_up_at| now - *looked_up_at < MISSING_KEY_TTL)
+    {
+        return Ok(None);
+    }
+    let record = api_keys::get_by_hash(key_hash)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch api key: {}", e))?;
+    match &record {
+        Some(record) => {
+            MISSING_KEYS.remove(key_hash);
+            API_KEYS.insert(key_hash.to_string(), record.clone());
+        }
+        None => {
+            if MISSING_KEYS.len() >= MISSING_KEYS_MAX {
+                MISSING_KEYS.retain(|_, looked_up_at| now - *looked_up_at < MISSING_KEY_TTL);
+                if MISSING_KEYS.len() >= MISSING_KEYS_MAX {
+                    MISSING_KEYS.clear();
+                }
+            }
+            MISSING_KEYS.insert(key_hash.to_string(), now);
+        }
+    }
+    Ok(record)
+}
+
+pub async fn list(org_id: &str, user_email: Option<&str>) -> Result<Vec<ApiKey>, anyhow::Error> {
+    let keys = api_keys::list(org_id, user_email)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch api keys: {}", e))?;
+    Ok(keys)
+}
+
+/// Records when the key was last used. Only the local cache is refreshed, other nodes pick the
+/// time up the next time they load the key.
+pub async fn update_last_used(key_hash: &str, id: &str, last_used_at: i64) {
+    if let Some(mut record) = API_KEYS.get_mut(key_hash) {
+        record.api_key.last_used_at = Some(last_used_at);
+    }
+    if let Err(e) = api_keys::update_last_used(id, last_used_at).await {
+        log::error!("Failed to update last used time of api key {id}: {e}");
+    }
+}
+
+pub async fn watch() -> Result<(), anyhow::Error> {
+    let key = API_KEYS_KEY_PREFIX;
+    let cluster_coordinator = get_coordinator().await;
+    let mut events = cluster_coordinator.watch(key).await?;
+    let events = Arc::get_mut(&mut events).unwrap();
+    log::info!("Start watching api_keys");
+    loop {
+        let ev = match events.recv().await {
+            Some(ev) => ev,
+            None => {
+                log::error!("watch_api_keys: event channel closed");
+                break;
+            }
+        };
+        match ev {
+            db::Event::Put(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                let Some((org_id, id)) = item_key.split_once('/') else {
+                    continue;
+                };
+                match api_keys::get(org_id, id).await {
+                    Ok(Some(record)) => {
+                        MISSING_KEYS.remove(&record.key_hash);
+                        API_KEYS.insert(record.key_hash.clone(), record);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        log::error!("Error getting value: {}", e);
+                        continue;
+                    }
+                }
+            }
+            db::Event::Delete(ev) => {
+                let item_key = ev.key.strip_prefix(key).unwrap();
+                let Some((org_id, id)) = item_key.split_once('/') else {
+                    continue;
+                };
+                API_KEYS
+                    .retain(|_, record| record.api_key.org_id != org_id || record.api_key.id != id);
+            }
+            db::Event::Empty => {}
+        }
+    }
+    Ok(())
+}
+
+pub async fn cache() -> Result<(), anyhow::Error> {
+    let records = api_keys::list_all()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to fetch api keys: {}", e))?;
+    for record in records {
+        API_KEYS.insert(record.key_hash.clone(), record);
+    }
+    log::info!("API keys Cached");
+    Ok(())
+}
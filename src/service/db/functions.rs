@@ -17,7 +17,10 @@ use std::sync::Arc;
 
 use config::{meta::function::Transform, utils::json};
 
-use crate::{common::infra::config::QUERY_FUNCTIONS, service::db};
+use crate::{
+    common::infra::config::QUERY_FUNCTIONS,
+    service::{db, ingestion::wasm},
+};
 
 pub async fn set(org_id: &str, name: &str, js_func: &Transform) -> Result<(), anyhow::Error> {
     let key = format!("/function/{org_id}/{name}");
@@ -94,10 +97,12 @@ pub async fn watch() -> Result<(), anyhow::Error> {
                         continue;
                     }
                 };
+                wasm::remove_wasm_function(item_key);
                 QUERY_FUNCTIONS.insert(item_key.to_owned(), item_value);
             }
             db::Event::Delete(ev) => {
                 let item_key = ev.key.strip_prefix(key).unwrap();
+                wasm::remove_wasm_function(item_key);
                 QUERY_FUNCTIONS.remove(item_key);
             }
             db::Event::Empty => {}
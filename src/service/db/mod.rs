@@ -22,6 +22,7 @@ use {
 };
 
 pub mod alerts;
+pub mod api_keys;
 pub mod compact;
 pub mod dashboards;
 pub mod distinct_values;
@@ -38,6 +39,7 @@ pub mod ofga;
 pub mod org_users;
 pub mod organization;
 pub mod pipeline;
+pub mod quotas;
 pub mod saved_view;
 pub mod scheduler;
 pub mod schema;
@@ -18,7 +18,7 @@ use std::sync::Arc;
 use config::{
     cluster::LOCAL_NODE,
     meta::{
-        pipeline::{Pipeline, components::PipelineSource},
+        pipeline::{Pipeline, PipelineVersion, components::PipelineSource},
         stream::StreamParams,
     },
 };
@@ -31,7 +31,7 @@ use once_cell::sync::Lazy;
 
 use crate::{
     common::infra::config::{PIPELINE_STREAM_MAPPING, STREAM_EXECUTABLE_PIPELINES},
-    service::pipeline::batch_execution::ExecutablePipeline,
+    service::pipeline::batch_execution::{ExecutablePipeline, remove_dedup_state},
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -54,36 +54,80 @@ pub enum PipelineError {
     InvalidDerivedStream(String),
     #[error("Error deleting previous DerivedStream: {0}")]
     DeleteDerivedStream(String),
+    #[error("Error replaying dead letter records: {0}")]
+    DeadLetterReplay(String),
+    #[error("Error running pipeline test: {0}")]
+    TestRun(String),
+    // not found
+    #[error("Version {1} of pipeline with ID {0} not found.")]
+    VersionNotFound(String, i32),
 }
 
-/// Stores a new pipeline to database.
+/// Stores a new pipeline to database, along with its first version.
 ///
 /// Pipeline validation should be handled by the caller.
-pub async fn set(pipeline: &Pipeline) -> Result<(), PipelineError> {
-    infra_pipeline::put(pipeline).await?;
+pub async fn set(pipeline: &Pipeline, version: &PipelineVersion) -> Result<(), PipelineError> {
+    infra_pipeline::put_with_version(pipeline, Some(version)).await?;
     update_cache(PipelineTableEvent::Add(pipeline)).await;
 
     Ok(())
 }
 
-/// Updates a pipeline entry with the sane values.
+/// Updates a pipeline entry with the sane values. The version, if given, is recorded in the
+/// pipeline history within the same transaction.
 ///
 /// Pipeline validation should be handled by the caller.
 pub async fn update(
     pipeline: &Pipeline,
     prev_source_stream: Option<StreamParams>,
+    version: Option<&PipelineVersion>,
 ) -> Result<(), PipelineError> {
     if prev_source_stream.is_some() {
         // remove first since source stream changed
         update_cache(PipelineTableEvent::Remove(&pipeline.id)).await;
     }
 
-    infra_pipeline::put(pipeline).await?;
+    infra_pipeline::put_with_version(pipeline, version).await?;
     update_cache(PipelineTableEvent::Add(pipeline)).await;
 
     Ok(())
 }
 
+/// Replaces a pipeline entry with a previous version of it, recorded as a new version, and
+/// pushes the rolled back pipeline to all nodes.
+///
+/// Pipeline validation should be handled by the caller.
+pub async fn rollback(
+    pipeline: &Pipeline,
+    prev_source_stream: Option<StreamParams>,
+    version: &PipelineVersion,
+) -> Result<(), PipelineError> {
+    if prev_source_stream.is_some() {
+        // remove first since source stream changed
+        update_cache(PipelineTableEvent::Remove(&pipeline.id)).await;
+    }
+
+    infra_pipeline::put_with_version(pipeline, Some(version)).await?;
+    update_cache(PipelineTableEvent::Rollback(pipeline)).await;
+
+    Ok(())
+}
+
+/// Lists all the versions of a pipeline, latest first.
+pub async fn list_versions(pipeline_id: &str) -> Result<Vec<PipelineVersion>, PipelineError> {
+    Ok(infra_pipeline::list_versions(pipeline_id).await?)
+}
+
+/// Returns the given version of a pipeline.
+pub async fn get_version(
+    pipeline_id: &str,
+    version: i32,
+) -> Result<PipelineVersion, PipelineError> {
+    infra_pipeline::get_version(pipeline_id, version)
+        .await
+        .map_err(|_| PipelineError::VersionNotFound(pipeline_id.to_string(), version))
+}
+
 /// Returns all streams with existing pipelines.
 pub async fn list_streams_with_pipeline(org: &str) -> Result<Vec<StreamParams>, PipelineError> {
     Ok(infra_pipeline::list_streams_with_pipeline(org).await?)
@@ -215,10 +259,13 @@ async fn update_cache(event: PipelineTableEvent<'_>) {
                 }
             }
         }
-        PipelineTableEvent::Add(pipeline) => {
-            if let Err(e) =
+        PipelineTableEvent::Add(pipeline) | PipelineTableEvent::Rollback(pipeline) => {
+            let emitted = if matches!(event, PipelineTableEvent::Rollback(_)) {
+                infra::cluster_coordinator::pipelines::emit_rollback_event(pipeline).await
+            } else {
                 infra::cluster_coordinator::pipelines::emit_put_event(&pipeline.id).await
-            {
+            };
+            if let Err(e) = emitted {
                 log::error!("[Pipeline] error triggering event to add pipeline to cache: {e}");
             }
 
@@ -271,10 +318,23 @@ pub async fn watch() -> Result<(), anyhow::Error> {
         match ev {
             db::Event::Put(ev) => {
                 let pipeline_id = ev.key.strip_prefix(PIPELINES_WATCH_PREFIX).unwrap();
-                let Ok(pipeline) = get_by_id(pipeline_id).await else {
-                    log::error!("[Pipeline::watch] error getting pipeline by id from db");
-                    continue;
+                // rolled back pipelines are pushed along with the event
+                let pushed = ev
+                    .value
+                    .filter(|value| !value.is_empty())
+                    .and_then(|value| config::utils::json::from_slice::<Pipeline>(&value).ok());
+                let pipeline = match pushed {
+                    Some(pipeline) => pipeline,
+                    None => {
+                        let Ok(pipeline) = get_by_id(pipeline_id).await else {
+                            log::error!("[Pipeline::watch] error getting pipeline by id from db");
+                            continue;
+                        };
+                        pipeline
+                    }
                 };
+                // the nodes of the pipeline may have changed
+                remove_dedup_state(pipeline_id);
                 // Only realtime & enabled pipeline should be added cache
                 if let PipelineSource::Realtime(stream_params) = &pipeline.source {
                     let mut pipeline_stream_mapping_cache = PIPELINE_STREAM_MAPPING.write().await;
@@ -320,6 +380,7 @@ pub async fn watch() -> Result<(), anyhow::Error> {
             }
             db::Event::Delete(ev) => {
                 let pipeline_id = ev.key.strip_prefix(PIPELINES_WATCH_PREFIX).unwrap();
+                remove_dedup_state(pipeline_id);
                 if let Some(removed) = PIPELINE_STREAM_MAPPING.write().await.remove(pipeline_id) {
                     if STREAM_EXECUTABLE_PIPELINES
                         .write()
@@ -350,5 +411,6 @@ pub async fn watch() -> Result<(), anyhow::Error> {
 #[derive(Debug)]
 enum PipelineTableEvent<'a> {
     Add(&'a Pipeline),
+    Rollback(&'a Pipeline),
     Remove(&'a str),
 }
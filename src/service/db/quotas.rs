@@ -0,0 +1,93 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use config::{meta::quota::OrgQuota, utils::json};
+use infra::errors;
+
+use crate::{common::infra::config::ORG_QUOTAS, service::db};
+
+pub const ORG_QUOTA_KEY_PREFIX: &str = "/organization/quota/";
+
+pub async fn set(org_id: &str, quota: &OrgQuota) -> errors::Result<()> {
+    let key = format!("{ORG_QUOTA_KEY_PREFIX}{org_id}");
+    db::put(&key, json::to_vec(quota)?.into(), db::NEED_WATCH, None).await?;
+    ORG_QUOTAS.insert(org_id.to_string(), quota.clone());
+    Ok(())
+}
+
+pub fn get(org_id: &str) -> Option<OrgQuota> {
+    ORG_QUOTAS.get(org_id).map(|quota| quota.value().clone())
+}
+
+pub async fn delete(org_id: &str) -> errors::Result<()> {
+    let key = format!("{ORG_QUOTA_KEY_PREFIX}{org_id}");
+    db::delete(&key, false, db::NEED_WATCH, None).await?;
+    ORG_QUOTAS.remove(org_id);
+    Ok(())
+}
+
+pub async fn watch() -> Result<(), anyhow::Error> {
+    let key = ORG_QUOTA_KEY_PREFIX;
+    let cluster_coordinator = db::get_coordinator().await;
+    let mut events = cluster_coordinator.watch(key).await?;
+    let events = Arc::get_mut(&mut events).unwrap();
+    log::info!("Start watching organization quotas");
+    loop {
+        let ev = match events.recv().await {
+            Some(ev) => ev,
+            None => {
+                log::error!("watch_org_quotas: event channel closed");
+                return Ok(());
+            }
+        };
+        match ev {
+            db::Event::Put(ev) => {
+                let org_id = ev.key.strip_prefix(key).unwrap();
+                let item_value: OrgQuota = match db::get(&ev.key).await {
+                    Ok(val) => match json::from_slice(&val) {
+                        Ok(val) => val,
+                        Err(e) => {
+                            log::error!("Error getting value: {}", e);
+                            continue;
+                        }
+                    },
+                    Err(e) => {
+                        log::error!("Error getting value: {}", e);
+                        continue;
+                    }
+                };
+                ORG_QUOTAS.insert(org_id.to_string(), item_value);
+            }
+            db::Event::Delete(ev) => {
+                let org_id = ev.key.strip_prefix(key).unwrap();
+                ORG_QUOTAS.remove(org_id);
+            }
+            db::Event::Empty => {}
+        }
+    }
+}
+
+pub async fn cache() -> Result<(), anyhow::Error> {
+    let ret = db::list(ORG_QUOTA_KEY_PREFIX).await?;
+    for (key, item_value) in ret {
+        let org_id = key.strip_prefix(ORG_QUOTA_KEY_PREFIX).unwrap();
+        let quota: OrgQuota = json::from_slice(&item_value)?;
+        ORG_QUOTAS.insert(org_id.to_string(), quota);
+    }
+    log::info!("Organization quotas Cached");
+    Ok(())
+}
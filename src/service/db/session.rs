@@ -52,6 +52,8 @@ pub async fn set(session_id: &str, val: &str) -> Result<(), anyhow::Error> {
 }
 
 pub async fn delete(session_id: &str) -> Result<(), anyhow::Error> {
+    // evict right away, other nodes catch up through the watcher
+    USER_SESSIONS.remove(session_id);
     Ok(db::delete(
         &format!("{USER_SESSION_KEY}{session_id}"),
         false,
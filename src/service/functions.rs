@@ -38,7 +38,10 @@ use crate::{
     handler::http::{
         request::search::error_utils::map_error_to_http_response, router::ERROR_HEADER,
     },
-    service::{db, ingestion::compile_vrl_function},
+    service::{
+        db,
+        ingestion::{compile_vrl_function, wasm::compile_wasm_function},
+    },
 };
 
 const FN_SUCCESS: &str = "Function saved successfully";
@@ -55,14 +58,9 @@ pub async fn save_function(org_id: String, mut func: Transform) -> Result<HttpRe
             FN_ALREADY_EXIST,
         )))
     } else {
-        if !func.function.ends_with('.') {
-            func.function = format!("{} \n .", func.function);
-        }
-        if func.trans_type.unwrap() == 0 {
-            if let Err(e) = compile_vrl_function(func.function.as_str(), &org_id) {
-                return Ok(HttpResponse::BadRequest()
-                    .json(MetaHttpResponse::error(StatusCode::BAD_REQUEST, e)));
-            }
+        if let Err(e) = validate_function(&org_id, &mut func) {
+            return Ok(HttpResponse::BadRequest()
+                .json(MetaHttpResponse::error(StatusCode::BAD_REQUEST, e)));
         }
         extract_num_args(&mut func);
         if let Err(error) = db::functions::set(&org_id, &func.name, &func).await {
@@ -78,6 +76,21 @@ pub async fn save_function(org_id: String, mut func: Transform) -> Result<HttpRe
     }
 }
 
+/// Compiles the function to make sure it can be executed. VRL functions get their trailing `.`
+/// appended when missing, WASM modules are checked against the function ABI.
+fn validate_function(org_id: &str, func: &mut Transform) -> Result<(), Error> {
+    if func.is_wasm() {
+        return compile_wasm_function(&func.function).map(|_| ());
+    }
+    if !func.function.ends_with('.') {
+        func.function = format!("{} \n .", func.function);
+    }
+    if func.is_vrl() {
+        compile_vrl_function(&func.function, org_id)?;
+    }
+    Ok(())
+}
+
 #[tracing::instrument(skip(org_id, function))]
 pub async fn test_run_function(
     org_id: &str,
@@ -202,14 +215,10 @@ pub async fn update_function(
         return Ok(HttpResponse::Ok().json(func));
     }
 
-    if !func.function.ends_with('.') {
-        func.function = format!("{} \n .", func.function);
-    }
-    if func.trans_type.unwrap() == 0 {
-        if let Err(e) = compile_vrl_function(&func.function, org_id) {
-            return Ok(HttpResponse::BadRequest()
-                .json(MetaHttpResponse::error(StatusCode::BAD_REQUEST, e)));
-        }
+    if let Err(e) = validate_function(org_id, &mut func) {
+        return Ok(
+            HttpResponse::BadRequest().json(MetaHttpResponse::error(StatusCode::BAD_REQUEST, e))
+        );
     }
     extract_num_args(&mut func);
 
@@ -221,7 +230,7 @@ pub async fn update_function(
     if let Ok(associated_pipelines) = db::pipeline::list_by_org(org_id).await {
         for pipeline in associated_pipelines {
             if pipeline.contains_function(&func.name) {
-                if let Err(e) = db::pipeline::update(&pipeline, None).await {
+                if let Err(e) = db::pipeline::update(&pipeline, None, None).await {
                     return Ok(HttpResponse::InternalServerError()
                         .append_header((ERROR_HEADER, e.to_string()))
                         .json(MetaHttpResponse::message(
@@ -56,14 +56,16 @@ use crate::{
         utils::functions::get_vrl_compiler_config,
     },
     service::{
-        alerts::alert::AlertExt,
+        alerts::{alert::AlertExt, dependencies, maintenance_windows},
         db::{self, alerts::alert::scheduler_key},
         logs::bulk::TRANSFORM_FAILED,
+        quotas,
     },
 };
 
 pub mod grpc;
 pub mod ingestion_service;
+pub mod wasm;
 
 pub type TriggerAlertData = Vec<(Alert, Vec<Map<String, Value>>)>;
 
@@ -262,6 +264,44 @@ pub async fn evaluate_trigger(triggers: TriggerAlertData) {
             scheduler_trace_id: None,
             time_in_queue_ms: None,
         };
+        if let Some((window, until)) =
+            maintenance_windows::active_window_of_realtime(alert, now).await
+        {
+            log::info!(
+                "Realtime alert {}/{}/{}/{} notification suppressed by maintenance window {} active until {}",
+                &alert.org_id,
+                &alert.stream_type,
+                &alert.stream_name,
+                &alert.name,
+                &window.name,
+                until
+            );
+            trigger_data_stream.status = TriggerDataStatus::Suppressed;
+            trigger_data_stream.success_response = Some(format!(
+                "Notification suppressed by maintenance window {}",
+                window.name
+            ));
+            trigger_data_stream.end_time = Utc::now().timestamp_micros();
+            trigger_usage_reports.push(trigger_data_stream);
+            continue;
+        }
+        if let Some(parent) = dependencies::firing_parent(&alert.org_id, alert).await {
+            log::info!(
+                "Realtime alert {}/{}/{}/{} notification suppressed by firing parent alert {}",
+                &alert.org_id,
+                &alert.stream_type,
+                &alert.stream_name,
+                &alert.name,
+                &parent
+            );
+            trigger_data_stream.status = TriggerDataStatus::Suppressed;
+            trigger_data_stream.success_response = Some(format!(
+                "Notification suppressed by firing parent alert {parent}"
+            ));
+            trigger_data_stream.end_time = Utc::now().timestamp_micros();
+            trigger_usage_reports.push(trigger_data_stream);
+            continue;
+        }
         match alert.send_notification(val, now, None, now).await {
             Err(e) => {
                 log::error!("Failed to send notification: {}", e);
@@ -425,6 +465,9 @@ pub fn check_ingestion_allowed(
         )));
     }
 
+    // check the org quota
+    quotas::check(org_id, stream_type, stream_name).map_err(Error::QuotaExceeded)?;
+
     // check if we are allowed to ingest
     if let Some(stream_name) = stream_name {
         if db::compact::retention::is_deleting_stream(org_id, stream_type, stream_name, None) {
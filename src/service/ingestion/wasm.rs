@@ -0,0 +1,314 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sandboxed runtime for WebAssembly functions.
+//!
+//! A WASM function is stored as a base64 encoded module in `Transform::function`. The module
+//! can't import anything from the host and must export:
+//!
+//! - `memory`: the linear memory used to exchange records
+//! - `alloc(len: i32) -> i32`: reserves `len` bytes and returns the offset of the buffer
+//! - `transform(ptr: i32, len: i32) -> i64`: receives a JSON encoded record and returns the
+//!   location of its JSON encoded output packed as `(out_ptr << 32) | out_len`
+//!
+//! The output can be an object (one record), an array of objects (many records) or `null`,
+//! as can a zero `out_len`, to drop the record. Every invocation runs in a fresh instance with
+//! its fuel and memory capped by `ZO_WASM_FUNCTION_FUEL_LIMIT` and
+//! `ZO_WASM_FUNCTION_MEMORY_LIMIT`.
+
+use anyhow::{Result, anyhow};
+use base64::{Engine as _, prelude::BASE64_STANDARD};
+use config::{
+    RwHashMap, SIZE_IN_MB,
+    utils::{
+        flatten,
+        hash::{Sum64, gxhash},
+        json::Value,
+    },
+};
+use once_cell::sync::Lazy;
+use wasmtime::{
+    Config, Engine, Instance, InstancePre, Linker, Memory, Module, Store, StoreLimits,
+    StoreLimitsBuilder, TypedFunc,
+};
+
+use crate::common::infra::config::QUERY_FUNCTIONS;
+
+static WASM_ENGINE: Lazy<Engine> = Lazy::new(|| {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config).expect("failed to initialize WASM engine")
+});
+
+/// Compiled query functions keyed by `{org_id}/{fn_name}`, along with the hash of the module
+/// they were compiled from.
+static WASM_FUNCTIONS: Lazy<RwHashMap<String, (u64, WasmFunction)>> = Lazy::new(Default::default);
+
+struct WasmState {
+    limits: StoreLimits,
+}
+
+struct WasmExports {
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    transform: TypedFunc<(i32, i32), i64>,
+}
+
+#[derive(Clone)]
+pub struct WasmFunction {
+    instance_pre: InstancePre<WasmState>,
+}
+
+impl std::fmt::Debug for WasmFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmFunction").finish_non_exhaustive()
+    }
+}
+
+/// Decodes and compiles a base64 encoded WASM module, checking that it follows the function ABI.
+pub fn compile_wasm_function(func: &str) -> Result<WasmFunction, std::io::Error> {
+    let to_io_err = |e: String| std::io::Error::new(std::io::ErrorKind::Other, e);
+
+    let bytes = BASE64_STANDARD.decode(func.trim()).map_err(|e| {
+        to_io_err(format!(
+            "WASM function must be a base64 encoded module: {e}"
+        ))
+    })?;
+    let module = Module::new(&WASM_ENGINE, bytes)
+        .map_err(|e| to_io_err(format!("invalid WASM module: {e}")))?;
+    if let Some(import) = module.imports().next() {
+        return Err(to_io_err(format!(
+            "WASM functions can't import from the host, found import {}::{}",
+            import.module(),
+            import.name()
+        )));
+    }
+    let instance_pre = Linker::new(&WASM_ENGINE)
+        .instantiate_pre(&module)
+        .map_err(|e| to_io_err(format!("invalid WASM module: {e}")))?;
+
+    // instantiate once so that missing or mistyped exports are reported on save
+    let wasm_fn = WasmFunction { instance_pre };
+    let mut store = wasm_fn.new_store().map_err(|e| to_io_err(e.to_string()))?;
+    wasm_fn
+        .instantiate(&mut store)
+        .map_err(|e| to_io_err(e.to_string()))?;
+    Ok(wasm_fn)
+}
+
+impl WasmFunction {
+    fn new_store(&self) -> Result<Store<WasmState>> {
+        let cfg = config::get_config();
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(cfg.limit.wasm_function_memory_limit * SIZE_IN_MB as usize)
+            .instances(1)
+            .build();
+        let mut store = Store::new(&WASM_ENGINE, WasmState { limits });
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(cfg.limit.wasm_function_fuel_limit)?;
+        Ok(store)
+    }
+
+    fn instantiate(&self, store: &mut Store<WasmState>) -> Result<WasmExports> {
+        let instance: Instance = self.instance_pre.instantiate(&mut *store)?;
+        let memory = instance
+            .get_memory(&mut *store, "memory")
+            .ok_or_else(|| anyhow!("WASM module must export `memory`"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut *store, "alloc")
+            .map_err(|e| anyhow!("WASM module must export `alloc(i32) -> i32`: {e}"))?;
+        let transform = instance
+            .get_typed_func::<(i32, i32), i64>(&mut *store, "transform")
+            .map_err(|e| anyhow!("WASM module must export `transform(i32, i32) -> i64`: {e}"))?;
+        Ok(WasmExports {
+            memory,
+            alloc,
+            transform,
+        })
+    }
+
+    /// Runs the function over a single record, returning the records it emits.
+    pub fn apply(&self, record: &Value) -> Result<Vec<Value>> {
+        let mut store = self.new_store()?;
+        let exports = self.instantiate(&mut store)?;
+
+        let input = config::utils::json::to_vec(record)?;
+        let input_len = i32::try_from(input.len())
+            .map_err(|_| anyhow!("record is too large for a WASM function"))?;
+        let ptr = exports.alloc.call(&mut store, input_len)?;
+        exports
+            .memory
+            .write(&mut store, ptr as u32 as usize, &input)?;
+
+        let packed = exports.transform.call(&mut store, (ptr, input_len))? as u64;
+        let out_ptr = (packed >> 32) as usize;
+        let out_len = (packed & 0xffff_ffff) as usize;
+        if out_len == 0 {
+            return Ok(vec![]);
+        }
+        let output = exports
+            .memory
+            .data(&store)
+            .get(out_ptr..out_ptr + out_len)
+            .ok_or_else(|| anyhow!("WASM function returned an output out of memory bounds"))?;
+
+        match config::utils::json::from_slice::<Value>(output)? {
+            Value::Null => Ok(vec![]),
+            Value::Object(record) => Ok(vec![Value::Object(record)]),
+            Value::Array(records) => records
+                .into_iter()
+                .map(|record| match record {
+                    Value::Object(_) => Ok(record),
+                    _ => Err(anyhow!("WASM function must return objects, got {record}")),
+                })
+                .collect(),
+            other => Err(anyhow!(
+                "WASM function must return an object, an array of objects or null, got {other}"
+            )),
+        }
+    }
+}
+
+/// Applies the saved WASM function `fn_name` to the search hits, returning the flattened
+/// records it emits.
+pub async fn apply_query_function(
+    org_id: &str,
+    fn_name: &str,
+    hits: &[Value],
+) -> Result<Vec<Value>> {
+    let func_key = format!("{org_id}/{fn_name}");
+    let transform = match QUERY_FUNCTIONS.get(&func_key) {
+        Some(transform) => transform.value().clone(),
+        None => crate::service::db::functions::get(org_id, fn_name).await?,
+    };
+    if !transform.is_wasm() {
+        return Err(anyhow!("function {fn_name} is not a WASM function"));
+    }
+    let wasm_fn = get_or_compile(&func_key, &transform.function)?;
+
+    let mut records = Vec::with_capacity(hits.len());
+    for hit in hits {
+        for record in wasm_fn.apply(hit)? {
+            records.push(flatten::flatten(record)?);
+        }
+    }
+    Ok(records)
+}
+
+fn get_or_compile(func_key: &str, func: &str) -> Result<WasmFunction, std::io::Error> {
+    let func_hash = gxhash::new().sum64(func);
+    if let Some(entry) = WASM_FUNCTIONS.get(func_key) {
+        let (hash, wasm_fn) = entry.value();
+        if *hash == func_hash {
+            return Ok(wasm_fn.clone());
+        }
+    }
+    let wasm_fn = compile_wasm_function(func)?;
+    WASM_FUNCTIONS.insert(func_key.to_string(), (func_hash, wasm_fn.clone()));
+    Ok(wasm_fn)
+}
+
+/// Drops the compiled module of the function `func_key`, called when the function is updated
+/// or deleted.
+pub fn remove_wasm_function(func_key: &str) {
+    WASM_FUNCTIONS.remove(func_key);
+}
+
+#[cfg(test)]
+mod tests {
+    use config::utils::json;
+
+    use super::*;
+
+    fn encode_module(wat: &str) -> String {
+        BASE64_STANDARD.encode(wat::parse_str(wat).unwrap())
+    }
+
+    // returns its input untouched
+    const IDENTITY_MODULE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "transform") (param $ptr i32) (param $len i32) (result i64)
+            (i64.or
+              (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+              (i64.extend_i32_u (local.get $len)))))
+    "#;
+
+    #[test]
+    fn test_wasm_function_apply() {
+        let wasm_fn = compile_wasm_function(&encode_module(IDENTITY_MODULE)).unwrap();
+        let record = json::json!({"level": "info", "count": 1});
+        assert_eq!(wasm_fn.apply(&record).unwrap(), vec![record]);
+
+        let records = json::json!([{"a": 1}, {"b": 2}]);
+        assert_eq!(wasm_fn.apply(&records).unwrap().len(), 2);
+        assert!(wasm_fn.apply(&json::Value::Null).unwrap().is_empty());
+        assert!(wasm_fn.apply(&json::json!("text")).is_err());
+    }
+
+    #[test]
+    fn test_wasm_function_fuel_limit() {
+        let module = r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param $len i32) (result i32)
+                (i32.const 0))
+              (func (export "transform") (param $ptr i32) (param $len i32) (result i64)
+                (loop $forever (br $forever))
+                (i64.const 0)))
+        "#;
+        let wasm_fn = compile_wasm_function(&encode_module(module)).unwrap();
+        assert!(wasm_fn.apply(&json::json!({"a": 1})).is_err());
+    }
+
+    #[test]
+    fn test_compile_wasm_function_abi() {
+        assert!(compile_wasm_function("not a module").is_err());
+
+        let missing_alloc = r#"(module (memory (export "memory") 1))"#;
+        assert!(compile_wasm_function(&encode_module(missing_alloc)).is_err());
+
+        let with_import = r#"
+            (module
+              (import "env" "log" (func $log (param i32)))
+              (memory (export "memory") 1))
+        "#;
+        assert!(compile_wasm_function(&encode_module(with_import)).is_err());
+    }
+
+    #[test]
+    fn test_wasm_function_cache() {
+        let func_key = "test_org/test_wasm_cache";
+        let func = encode_module(IDENTITY_MODULE);
+        get_or_compile(func_key, &func).unwrap();
+        let hash = WASM_FUNCTIONS.get(func_key).unwrap().0;
+        get_or_compile(func_key, &func).unwrap();
+        assert_eq!(WASM_FUNCTIONS.get(func_key).unwrap().0, hash);
+
+        // an updated module is recompiled
+        assert!(get_or_compile(func_key, "not a module").is_err());
+        get_or_compile(func_key, &format!("{func}\n")).unwrap();
+        assert_ne!(WASM_FUNCTIONS.get(func_key).unwrap().0, hash);
+
+        remove_wasm_function(func_key);
+        assert!(WASM_FUNCTIONS.get(func_key).is_none());
+    }
+}
@@ -216,6 +216,8 @@ impl TraceListIndex {
                 extended_retention_days: vec![],
                 index_all_values: false,
                 index_original_data: false,
+                field_policies: vec![],
+                row_filters: vec![],
             };
 
             stream::save_stream_settings(org_id, STREAM_NAME, StreamType::Metadata, settings)
@@ -60,6 +60,9 @@ pub async fn ingest(org_id: &str, body: web::Bytes) -> Result<IngestionResponse>
     // check system resource
     if let Err(e) = check_ingestion_allowed(org_id, StreamType::Metrics, None) {
         log::error!("Metrics ingestion error: {e}");
+        if matches!(e, infra::errors::Error::QuotaExceeded(_)) {
+            return Err(e.into());
+        }
         return Ok(IngestionResponse {
             code: http::StatusCode::SERVICE_UNAVAILABLE.into(),
             status: vec![],
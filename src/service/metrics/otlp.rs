@@ -57,6 +57,7 @@ use crate::{
         },
         metrics::{format_label_name, get_exclude_labels},
         pipeline::batch_execution::ExecutablePipeline,
+        quotas::quota_exceeded_response,
         schema::{check_for_schema, stream_schema_exists},
         self_reporting::report_request_usage_stats,
     },
@@ -121,6 +122,9 @@ pub async fn handle_otlp_request(
     // check system resource
     if let Err(e) = check_ingestion_allowed(org_id, StreamType::Metrics, None) {
         log::error!("Metrics ingestion error: {e}");
+        if let infra::errors::Error::QuotaExceeded(exceeded) = &e {
+            return Ok(quota_exceeded_response(exceeded));
+        }
         return Ok(
             HttpResponse::ServiceUnavailable().json(MetaHttpResponse::error(
                 http::StatusCode::SERVICE_UNAVAILABLE,
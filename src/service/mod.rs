@@ -18,6 +18,7 @@ use infra::errors::Result;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 pub mod alerts;
+pub mod api_keys;
 pub mod cluster_info;
 pub mod compact;
 pub mod dashboards;
@@ -36,15 +37,20 @@ pub mod logs;
 pub mod metadata;
 pub mod metrics;
 pub mod node;
+#[cfg(not(feature = "enterprise"))]
+pub mod oidc;
+pub mod org_bundle;
 #[cfg(feature = "cloud")]
 pub mod org_usage;
 pub mod organization;
 pub mod pipeline;
 pub mod promql;
+pub mod quotas;
 #[cfg(feature = "enterprise")]
 pub mod ratelimit;
 pub mod schema;
 pub mod search;
+pub mod secrets;
 pub mod websocket_events;
 
 #[cfg(feature = "enterprise")]
@@ -0,0 +1,719 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Built-in OpenID Connect login of the open source build. Users sign in at any compliant
+//! IdP with the authorization code flow and PKCE, their claims are mapped to orgs and roles,
+//! and they are created or updated just in time. The login is kept in a session, see
+//! [crate::service::session], and renewed with a refresh token issued by us.
+
+use std::time::Duration;
+
+use base64::Engine;
+use config::{
+    Oidc, get_config, ider,
+    meta::user::{DBUser, UserOrg, UserRole},
+    utils::{base64 as b64, json, rand::generate_random_string},
+};
+use hashbrown::HashMap;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::{
+    common::{
+        meta::user::{UpdateUser, UserOrgRole, UserRoleRequest},
+        utils::auth::is_root_user,
+    },
+    service::{db, organization, users},
+};
+
+/// The kv org holding the state of pending logins.
+pub const OIDC_STATE_ORG: &str = "o2_oidc_state";
+/// Prefix of the session keys holding refresh tokens.
+const REFRESH_SESSION_PREFIX: &str = "oidc_refresh_";
+/// Pending logins not completed within this many seconds are rejected.
+const LOGIN_STATE_TTL: i64 = 600;
+
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap()
+});
+
+static PROVIDER: Lazy<RwLock<Option<Provider>>> = Lazy::new(|| RwLock::new(None));
+
+#[derive(Clone, Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Clone, Debug)]
+struct Provider {
+    metadata: ProviderMetadata,
+    jwks: JwkSet,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct LoginState {
+    code_verifier: String,
+    nonce: String,
+    created_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    #[serde(default)]
+    id_token: Option<String>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Session {
+    user_email: String,
+    expires_at: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RefreshSession {
+    user_email: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    idp_refresh_token: Option<String>,
+    expires_at: i64,
+}
+
+/// The claims of a verified id token we care about.
+#[derive(Clone, Debug, Default)]
+pub struct IdTokenClaims {
+    pub email: String,
+    pub name: String,
+    pub family_name: String,
+    pub given_name: String,
+    pub groups: Vec<String>,
+    /// Whether the IdP vouched for the email with a true `email_verified` claim.
+    pub email_verified: bool,
+}
+
+/// The tokens of a login, stored in the `auth_tokens` cookie.
+#[derive(Clone, Debug)]
+pub struct SessionTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// Maps the claims of a user to the orgs they join and their role in each.
+#[derive(Clone, Debug, Default)]
+pub struct ClaimMapping {
+    groups: HashMap<String, Vec<(String, UserRole)>>,
+    domains: HashMap<String, Vec<(String, UserRole)>>,
+    default: Option<(String, UserRole)>,
+}
+
+impl ClaimMapping {
+    pub fn new(
+        group_role_mapping: &str,
+        domain_role_mapping: &str,
+        default_org: &str,
+        default_role: &str,
+    ) -> Result<Self, anyhow::Error> {
+        let default = if default_org.is_empty() {
+            None
+        } else {
+            Some((default_org.to_string(), parse_role(default_role)?))
+        };
+        Ok(Self {
+            groups: parse_mapping(group_role_mapping)?,
+            domains: parse_mapping(domain_role_mapping)?,
+            default,
+        })
+    }
+
+    fn from_config(cfg: &Oidc) -> Result<Self, anyhow::Error> {
+        Self::new(
+            &cfg.group_role_mapping,
+            &cfg.domain_role_mapping,
+            &cfg.default_org,
+            &cfg.default_role,
+        )
+    }
+
+    /// Gets the orgs of a user, with the most privileged role when several claims map to the
+    /// same org. Users without any matching claim join the default org, if one is set.
+    pub fn map_claims(&self, email: &str, groups: &[String]) -> Vec<(String, UserRole)> {
+        let domain = email
+            .rsplit_once('@')
+            .map(|(_, domain)| domain.to_lowercase())
+            .unwrap_or_default();
+        let mut orgs: Vec<(String, UserRole)> = vec![];
+        let matched = groups
+            .iter()
+            .filter_map(|group| self.groups.get(group))
+            .chain(self.domains.get(&domain))
+            .flatten();
+        for (org, role) in matched {
+            match orgs.iter_mut().find(|(name, _)| name == org) {
+                Some((_, existing)) => {
+                    if i16::from(role.clone()) < i16::from(existing.clone()) {
+                        *existing = role.clone();
+                    }
+                }
+                None => orgs.push((org.clone(), role.clone())),
+            }
+        }
+        if orgs.is_empty() {
+            if let Some(default) = &self.default {
+                orgs.push(default.clone());
+            }
+        }
+        orgs
+    }
+}
+
+/// Parses a role of a mapping, the root role can not be granted through claims.
+fn parse_role(role: &str) -> Result<UserRole, anyhow::Error> {
+    match role.trim().to_lowercase().as_str() {
+        "admin" => Ok(UserRole::Admin),
+        "editor" => Ok(UserRole::Editor),
+        "viewer" => Ok(UserRole::Viewer),
+        "user" => Ok(UserRole::User),
+        _ => Err(anyhow::anyhow!("Invalid role in oidc mapping: {role}")),
+    }
+}
+
+/// Parses comma separated `claim=org:role` entries.
+fn parse_mapping(mapping: &str) -> Result<HashMap<String, Vec<(String, UserRole)>>, anyhow::Error> {
+    let mut parsed: HashMap<String, Vec<(String, UserRole)>> = HashMap::new();
+    for entry in mapping
+        .split(',')
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+    {
+        let Some((claim, target)) = entry.rsplit_once('=') else {
+            return Err(anyhow::anyhow!("Invalid oidc mapping: {entry}"));
+        };
+        let Some((org, role)) = target.split_once(':') else {
+            return Err(anyhow::anyhow!("Invalid oidc mapping: {entry}"));
+        };
+        let org = org.trim();
+        if claim.trim().is_empty() || org.is_empty() {
+            return Err(anyhow::anyhow!("Invalid oidc mapping: {entry}"));
+        }
+        parsed
+            .entry(claim.trim().to_string())
+            .or_default()
+            .push((org.to_string(), parse_role(role)?));
+    }
+    Ok(parsed)
+}
+
+/// Derives the S256 code challenge of a PKCE code verifier.
+fn code_challenge(code_verifier: &str) -> String {
+    let digest = hex::decode(sha256::digest(code_verifier)).unwrap_or_default();
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(digest)
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+async fn get_json<T: for<'de> Deserialize<'de>>(url: &str) -> Result<T, anyhow::Error> {
+    let resp = HTTP_CLIENT.get(url).send().await?;
+    if !resp.status().is_success() {
+        return Err(anyhow::anyhow!(
+            "Request to {url} failed: {}",
+            resp.status()
+        ));
+    }
+    Ok(json::from_slice(&resp.bytes().await?)?)
+}
+
+/// Gets the metadata and keys of the IdP, they are fetched once and the keys are fetched
+/// again when `refresh_keys` is set, e.g. on a token signed with an unknown key.
+async fn get_provider(refresh_keys: bool) -> Result<Provider, anyhow::Error> {
+    if !refresh_keys {
+        if let Some(provider) = PROVIDER.read().await.as_ref() {
+            return Ok(provider.clone());
+        }
+    }
+    let cfg = get_config();
+    let cached = PROVIDER.read().await.as_ref().map(|p| p.metadata.clone());
+    let metadata = match cached {
+        Some(metadata) => metadata,
+        None => {
+            let metadata: ProviderMetadata = get_json(&format!(
+                "{}/.well-known/openid-configuration",
+                cfg.oidc.issuer_url
+            ))
+            .await?;
+            if metadata.issuer.trim_end_matches('/') != cfg.oidc.issuer_url {
+                return Err(anyhow::anyhow!(
+                    "Issuer of the oidc provider {} does not match {}",
+                    metadata.issuer,
+                    cfg.oidc.issuer_url
+                ));
+            }
+            metadata
+        }
+    };
+    let jwks: JwkSet = get_json(&metadata.jwks_uri).await?;
+    let provider = Provider { metadata, jwks };
+    *PROVIDER.write().await = Some(provider.clone());
+    Ok(provider)
+}
+
+/// Starts a login, returns the url of the IdP to send the user to.
+pub async fn login() -> Result<String, anyhow::Error> {
+    let cfg = get_config();
+    let provider = get_provider(false).await?;
+    let state = generate_random_string(32);
+    let login_state = LoginState {
+        code_verifier: generate_random_string(64),
+        nonce: generate_random_string(32),
+        created_at: now(),
+    };
+    crate::service::kv::set(OIDC_STATE_ORG, &state, json::to_vec(&login_state)?.into()).await?;
+    let url = url::Url::parse_with_params(
+        &provider.metadata.authorization_endpoint,
+        &[
+            ("response_type", "code"),
+            ("client_id", cfg.oidc.client_id.as_str()),
+            ("redirect_uri", cfg.oidc.redirect_url.as_str()),
+            ("scope", cfg.oidc.scopes.as_str()),
+            ("state", state.as_str()),
+            ("nonce", login_state.nonce.as_str()),
+            (
+                "code_challenge",
+                &code_challenge(&login_state.code_verifier),
+            ),
+            ("code_challenge_method", "S256"),
+        ],
+    )?;
+    Ok(url.to_string())
+}
+
+async fn request_tokens(params: &[(&str, &str)]) -> Result<TokenResponse, anyhow::Error> {
+    let cfg = get_config();
+    let provider = get_provider(false).await?;
+    let mut params = params.to_vec();
+    params.push(("client_id", cfg.oidc.client_id.as_str()));
+    let mut req = HTTP_CLIENT
+        .post(&provider.metadata.token_endpoint)
+        .form(&params);
+    if !cfg.oidc.client_secret.is_empty() {
+        req = req.basic_auth(&cfg.oidc.client_id, Some(&cfg.oidc.client_secret));
+    }
+    let resp = req.send().await?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!("Token request failed: {status} {body}"));
+    }
+    Ok(json::from_slice(&resp.bytes().await?)?)
+}
+
+/// Verifies the signature, issuer, audience and expiry of an id token, and its nonce when one
+/// is expected.
+async fn verify_id_token(
+    id_token: &str,
+    nonce: Option<&str>,
+) -> Result<IdTokenClaims, anyhow::Error> {
+    let cfg = get_config();
+    let header = decode_header(id_token)?;
+    if matches!(
+        header.alg,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    ) {
+        return Err(anyhow::anyhow!("Unsupported id token algorithm"));
+    }
+    let mut provider = get_provider(false).await?;
+    let find_key = |jwks: &JwkSet| match &header.kid {
+        Some(kid) => jwks.find(kid).cloned(),
+        None if jwks.keys.len() == 1 => jwks.keys.first().cloned(),
+        None => None,
+    };
+    let jwk = match find_key(&provider.jwks) {
+        Some(jwk) => jwk,
+        None => {
+            // the IdP may have rotated its keys
+            provider = get_provider(true).await?;
+            find_key(&provider.jwks)
+                .ok_or_else(|| anyhow::anyhow!("Unknown key of the id token"))?
+        }
+    };
+    let mut validation = Validation::new(header.alg);
+    validation.set_audience(&[&cfg.oidc.client_id]);
+    validation.set_issuer(&[&provider.metadata.issuer]);
+    let claims = decode::<HashMap<String, json::Value>>(
+        id_token,
+        &DecodingKey::from_jwk(&jwk)?,
+        &validation,
+    )?
+    .claims;
+
+    if let Some(nonce) = nonce {
+        if claims.get("nonce").and_then(|v| v.as_str()) != Some(nonce) {
+            return Err(anyhow::anyhow!("Invalid nonce in id token"));
+        }
+    }
+    let claim = |name: &str| {
+        claims
+            .get(name)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let email = claim("email").to_lowercase();
+    if email.is_empty() {
+        return Err(anyhow::anyhow!("No email in id token"));
+    }
+    let email_verified = claims.get("email_verified").and_then(|v| v.as_bool());
+    if email_verified == Some(false) {
+        return Err(anyhow::anyhow!("Email of the user is not verified"));
+    }
+    let groups = match claims.get(&cfg.oidc.groups_claim) {
+        Some(json::Value::Array(groups)) => groups
+            .iter()
+            .filter_map(|v| v.as_str().map(|v| v.to_string()))
+            .collect(),
+        Some(json::Value::String(group)) => vec![group.to_string()],
+        _ => vec![],
+    };
+    let name = match claim("name") {
+        name if name.is_empty() => email.clone(),
+        name => name,
+    };
+    Ok(IdTokenClaims {
+        email,
+        name,
+        family_name: claim("family_name"),
+        given_name: claim("given_name"),
+        groups,
+        email_verified: email_verified == Some(true),
+    })
+}
+
+/// Completes a login with the code the IdP sent back, the user is created or updated and a
+/// session is opened.
+pub async fn exchange(
+    code: &str,
+    state: &str,
+) -> Result<(IdTokenClaims, SessionTokens), anyhow::Error> {
+    let login_state = crate::service::kv::get(OIDC_STATE_ORG, state)
+        .await
+        .map_err(|_| anyhow::anyhow!("Invalid state in request"))?;
+    // a state can only be used once
+    let _ = crate::service::kv::delete(OIDC_STATE_ORG, state).await;
+    let login_state: LoginState = json::from_slice(&login_state)?;
+    if now() - login_state.created_at > LOGIN_STATE_TTL {
+        return Err(anyhow::anyhow!("Login expired"));
+    }
+
+    let cfg = get_config();
+    let tokens = request_tokens(&[
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", cfg.oidc.redirect_url.as_str()),
+        ("code_verifier", login_state.code_verifier.as_str()),
+    ])
+    .await?;
+    let id_token = tokens
+        .id_token
+        .ok_or_else(|| anyhow::anyhow!("No id token in token response"))?;
+    let claims = verify_id_token(&id_token, Some(&login_state.nonce)).await?;
+    provision_user(&claims).await?;
+    let tokens = create_session(&claims.email, tokens.refresh_token).await?;
+    Ok((claims, tokens))
+}
+
+/// An id token only logs in as an existing user when its email is verified, unless unverified
+/// emails are trusted.
+fn can_match_existing_user(claims: &IdTokenClaims, trust_unverified_email: bool) -> bool {
+    claims.email_verified || trust_unverified_email
+}
+
+/// Creates the user or syncs their orgs and roles with their claims. Users created locally
+/// only gain the orgs they are missing. The root user can't login with OIDC.
+async fn provision_user(claims: &IdTokenClaims) -> Result<(), anyhow::Error> {
+    let cfg = get_config();
+    let root_email = &cfg.auth.root_user_email;
+    if is_root_user(&claims.email) {
+        return Err(anyhow::anyhow!(
+            "The root user is not allowed to login with OIDC"
+        ));
+    }
+    let db_user = db::user::get_user_by_email(&claims.email).await;
+    if let Some(db_user) = &db_user {
+        if db_user
+            .organizations
+            .iter()
+            .any(|org| org.role.eq(&UserRole::ServiceAccount))
+        {
+            return Err(anyhow::anyhow!("Service accounts are not allowed to login"));
+        }
+        if !can_match_existing_user(claims, cfg.oidc.trust_unverified_email) {
+            return Err(anyhow::anyhow!(
+                "Email of the user is not verified, it can't be matched to an existing user"
+            ));
+        }
+    }
+
+    let orgs = ClaimMapping::from_config(&cfg.oidc)?.map_claims(&claims.email, &claims.groups);
+    let Some(db_user) = db_user else {
+        if orgs.is_empty() {
+            return Err(anyhow::anyhow!("No organization is mapped to the user"));
+        }
+        for (org, _) in orgs.iter() {
+            organization::check_and_create_org(org).await?;
+        }
+        log::info!("[OIDC] creating user {}", claims.email);
+        return users::create_new_user(DBUser {
+            email: claims.email.to_owned(),
+            first_name: claims.name.to_owned(),
+            last_name: "".to_owned(),
+            password: "".to_owned(),
+            salt: "".to_owned(),
+            organizations: orgs
+                .into_iter()
+                .map(|(name, role)| UserOrg {
+                    name,
+                    token: Default::default(),
+                    rum_token: Default::default(),
+                    role,
+                })
+                .collect(),
+            is_external: true,
+            password_ext: Some("".to_owned()),
+        })
+        .await;
+    };
+
+    for (org, role) in orgs.iter() {
+        match db_user.organizations.iter().find(|v| v.name.eq(org)) {
+            None => {
+                organization::check_and_create_org(org).await?;
+                let org_role = UserOrgRole {
+                    base_role: role.clone(),
+                    custom_role: None,
+                };
+                if let Err(e) =
+                    users::add_user_to_org(org, &claims.email, org_role, root_email).await
+                {
+                    log::error!("[OIDC] error adding {} to org {org}: {e}", claims.email);
+                }
+            }
+            Some(existing) if db_user.is_external && existing.role.ne(role) => {
+                let update = UpdateUser {
+                    role: Some(UserRoleRequest {
+                        role: role.to_string(),
+                        custom: None,
+                    }),
+                    ..Default::default()
+                };
+                if let Err(e) =
+                    users::update_user(org, &claims.email, false, root_email, update).await
+                {
+                    log::error!("[OIDC] error updating {} in org {org}: {e}", claims.email);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    if !db_user.is_external {
+        return Ok(());
+    }
+    for existing in db_user.organizations.iter() {
+        if orgs.iter().any(|(org, _)| org.eq(&existing.name)) {
+            continue;
+        }
+        if let Err(e) = users::remove_user_from_org(&existing.name, &claims.email, root_email).await
+        {
+            log::error!(
+                "[OIDC] error removing {} from org {}: {e}",
+                claims.email,
+                existing.name
+            );
+        }
+    }
+    Ok(())
+}
+
+fn encode<T: Serialize>(val: &T) -> Result<String, anyhow::Error> {
+    Ok(b64::encode(&json::to_string(val)?))
+}
+
+fn refresh_session_key(refresh_token: &str) -> String {
+    // only the digest is stored, a leaked session store does not leak usable tokens
+    format!("{REFRESH_SESSION_PREFIX}{}", sha256::digest(refresh_token))
+}
+
+async fn create_session(
+    user_email: &str,
+    idp_refresh_token: Option<String>,
+) -> Result<SessionTokens, anyhow::Error> {
+    let cfg = get_config();
+    let session_id = ider::uuid();
+    let session = Session {
+        user_email: user_email.to_string(),
+        expires_at: now() + cfg.oidc.session_ttl,
+    };
+    db::session::set(&session_id, &encode(&session)?).await?;
+
+    let refresh_token = format!("{}{}", ider::uuid(), generate_random_string(32));
+    let refresh_session = RefreshSession {
+        user_email: user_email.to_string(),
+        idp_refresh_token,
+        expires_at: now() + cfg.auth.cookie_max_age,
+    };
+    db::session::set(
+        &refresh_session_key(&refresh_token),
+        &encode(&refresh_session)?,
+    )
+    .await?;
+    Ok(SessionTokens {
+        access_token: format!("session {session_id}"),
+        refresh_token,
+    })
+}
+
+/// Gets the user of a login session, `None` when the session is unknown or expired.
+pub async fn get_session_user(session_id: &str) -> Option<String> {
+    if session_id.starts_with(REFRESH_SESSION_PREFIX) {
+        return None;
+    }
+    let val = crate::service::session::get_session(session_id).await?;
+    let session: Session = json::from_str(&b64::decode(&val).ok()?).ok()?;
+    if session.expires_at < now() {
+        crate::service::session::remove_session(session_id).await;
+        return None;
+    }
+    Some(session.user_email)
+}
+
+/// Exchanges a refresh token for a new session. Refresh tokens are rotated, and when the IdP
+/// issued one too the login is renewed there, so users disabled at the IdP are logged out.
+pub async fn refresh(refresh_token: &str) -> Result<SessionTokens, anyhow::Error> {
+    let key = refresh_session_key(refresh_token);
+    let val = db::session::get(&key)
+        .await
+        .map_err(|_| anyhow::anyhow!("Invalid refresh token"))?;
+    let _ = db::session::delete(&key).await;
+    let session: RefreshSession = json::from_str(&b64::decode(&val)?)?;
+    if session.expires_at < now() {
+        return Err(anyhow::anyhow!("Refresh token expired"));
+    }
+
+    let Some(idp_refresh_token) = session.idp_refresh_token else {
+        return create_session(&session.user_email, None).await;
+    };
+    let tokens = request_tokens(&[
+        ("grant_type", "refresh_token"),
+        ("refresh_token", idp_refresh_token.as_str()),
+    ])
+    .await?;
+    if let Some(id_token) = tokens.id_token {
+        let claims = verify_id_token(&id_token, None).await?;
+        if claims.email != session.user_email {
+            return Err(anyhow::anyhow!("Refreshed id token is for another user"));
+        }
+        provision_user(&claims).await?;
+    }
+    create_session(
+        &session.user_email,
+        tokens.refresh_token.or(Some(idp_refresh_token)),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_match_existing_user() {
+        let mut claims = IdTokenClaims {
+            email: "user@example.com".to_string(),
+            email_verified: true,
+            ..Default::default()
+        };
+        assert!(can_match_existing_user(&claims, false));
+        claims.email_verified = false;
+        assert!(!can_match_existing_user(&claims, false));
+        assert!(can_match_existing_user(&claims, true));
+    }
+
+    #[test]
+    fn test_code_challenge() {
+        // the example of RFC 7636 appendix B
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn test_parse_mapping() {
+        let mapping = parse_mapping("o2-admins=default:admin, o2-ops = ops:editor").unwrap();
+        assert_eq!(
+            mapping.get("o2-admins").unwrap(),
+            &vec![("default".to_string(), UserRole::Admin)]
+        );
+        assert_eq!(
+            mapping.get("o2-ops").unwrap(),
+            &vec![("ops".to_string(), UserRole::Editor)]
+        );
+        assert!(parse_mapping("").unwrap().is_empty());
+        assert!(parse_mapping("o2-admins=default").is_err());
+        assert!(parse_mapping("o2-admins=default:root").is_err());
+        assert!(parse_mapping("o2-admins=default:owner").is_err());
+    }
+
+    #[test]
+    fn test_map_claims() {
+        let mapping = ClaimMapping::new(
+            "o2-admins=default:admin,o2-devs=default:editor,o2-devs=dev:editor",
+            "example.com=default:viewer",
+            "guests",
+            "user",
+        )
+        .unwrap();
+
+        let orgs = mapping.map_claims(
+            "jane@example.com",
+            &["o2-devs".to_string(), "o2-admins".to_string()],
+        );
+        assert_eq!(
+            orgs,
+            vec![
+                ("default".to_string(), UserRole::Admin),
+                ("dev".to_string(), UserRole::Editor),
+            ]
+        );
+
+        let orgs = mapping.map_claims("joe@EXAMPLE.com", &[]);
+        assert_eq!(orgs, vec![("default".to_string(), UserRole::Viewer)]);
+
+        let orgs = mapping.map_claims("joe@other.org", &["unknown".to_string()]);
+        assert_eq!(orgs, vec![("guests".to_string(), UserRole::User)]);
+
+        let mapping = ClaimMapping::new("", "", "", "viewer").unwrap();
+        assert!(mapping.map_claims("joe@other.org", &[]).is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use config::{
+    TIMESTAMP_COL_NAME, get_config, ider,
+    meta::{
+        alerts::alert::ListAlertsParams,
+        dashboards::ListDashboardsParams,
+        folder::FolderType,
+        search::{Query, Request, RequestEncoding, SearchEventType},
+        stream::StreamType,
+    },
+    utils::{json, time::now_micros},
+};
+use infra::table;
+
+use super::{ArchiveWriter, BundleError};
+use crate::{
+    common::meta::org_bundle::{
+        BUNDLE_VERSION, BundleAlert, BundleDashboard, BundleEnrichmentTable, BundleFolder,
+        BundleKind, BundleManifest, BundleStream, OrgBundle, OrgBundleExportRequest,
+        StreamDataRange,
+    },
+    service::{alerts::alert, db, search as SearchService},
+};
+
+/// Exports the objects of the org, and the requested stream data, into a bundle archive. Stream
+/// data is searched as the user, so the field policies that apply to them are kept.
+pub async fn export(
+    org_id: &str,
+    user_id: &str,
+    req: OrgBundleExportRequest,
+) -> Result<Vec<u8>, BundleError> {
+    if let Some(range) = req
+        .data
+        .iter()
+        .find(|range| range.stream_type != StreamType::Logs)
+    {
+        return Err(BundleError::UnsupportedDataStream(range.stream_type));
+    }
+
+    let kinds = if req.kinds.is_empty() {
+        BundleKind::ALL.to_vec()
+    } else {
+        BundleKind::ALL
+            .into_iter()
+            .filter(|kind| req.kinds.contains(kind))
+            .collect()
+    };
+
+    let mut bundle = OrgBundle::default();
+    for kind in kinds.iter() {
+        match kind {
+            BundleKind::Folders => bundle.folders = export_folders(org_id).await?,
+            BundleKind::Dashboards => bundle.dashboards = export_dashboards(org_id).await?,
+            BundleKind::Alerts => bundle.alerts = export_alerts(org_id).await?,
+            BundleKind::Destinations => {
+                // credentials aren't exported, secret references are kept as they are
+                bundle.destinations = db::alerts::destinations::list(org_id, None)
+                    .await
+                    .map_err(|e| BundleError::Internal(e.into()))?
+                    .into_iter()
+                    .map(|dest| dest.redact())
+                    .collect();
+            }
+            BundleKind::Templates => {
+                // templates of the default org are shared with every org, so they aren't exported
+                bundle.templates = db::alerts::templates::list(org_id)
+                    .await
+                    .map_err(|e| BundleError::Internal(e.into()))?
+                    .into_iter()
+                    .filter(|template| template.org_id == org_id)
+                    .collect();
+            }
+            BundleKind::Functions => {
+                bundle.functions = db::functions::list(org_id)
+                    .await?
+                    .into_iter()
+                    .map(|mut func| {
+                        func.streams = None;
+                        func
+                    })
+                    .collect();
+            }
+            BundleKind::Pipelines => {
+                bundle.pipelines = db::pipeline::list_by_org(org_id)
+                    .await
+                    .map_err(|e| BundleError::Internal(e.into()))?;
+            }
+            BundleKind::SavedViews => {
+                let views = db::saved_view::get_views_list_only(org_id)
+                    .await
+                    .map_err(|e| BundleError::Internal(e.into()))?;
+                for view in views.views {
+                    let view = db::saved_view::get_view(org_id, &view.view_id)
+                        .await
+                        .map_err(|e| BundleError::Internal(e.into()))?;
+                    bundle.saved_views.push(view);
+                }
+            }
+            BundleKind::EnrichmentTables => {
+                bundle.enrichment_tables = export_enrichment_tables(org_id, user_id).await?;
+            }
+            BundleKind::Streams => bundle.streams = export_streams(org_id).await?,
+        }
+    }
+
+    let manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        org_id: org_id.to_string(),
+        exported_at: now_micros(),
+        kinds,
+        data: req.data,
+    };
+    let mut writer = ArchiveWriter::new(&manifest, &bundle)?;
+    for (index, range) in manifest.data.iter().enumerate() {
+        writer.start_data(index)?;
+        export_data(org_id, user_id, range, &mut writer).await?;
+    }
+    writer.finish()
+}
+
+async fn export_folders(org_id: &str) -> Result<Vec<BundleFolder>, BundleError> {
+    let mut folders = vec![];
+    for folder_type in [FolderType::Dashboards, FolderType::Alerts] {
+        for folder in table::folders::list_folders(org_id, folder_type)
+            .await
+            .map_err(|e| BundleError::Internal(e.into()))?
+        {
+            folders.push(BundleFolder::new(folder_type, folder));
+        }
+    }
+    Ok(folders)
+}
+
+async fn export_dashboards(org_id: &str) -> Result<Vec<BundleDashboard>, BundleError> {
+    let dashboards = table::dashboards::list(ListDashboardsParams::new(org_id))
+        .await
+        .map_err(|e| BundleError::Internal(e.into()))?;
+    Ok(dashboards
+        .into_iter()
+        .map(|(folder, dashboard)| BundleDashboard {
+            folder_id: folder.folder_id,
+            dashboard,
+        })
+        .collect())
+}
+
+async fn export_alerts(org_id: &str) -> Result<Vec<BundleAlert>, BundleError> {
+    let alerts = alert::list_with_folders_db(ListAlertsParams::new(org_id))
+        .await
+        .map_err(|e| BundleError::Internal(e.into()))?;
+    Ok(alerts
+        .into_iter()
+        .map(|(folder, alert)| BundleAlert {
+            folder_id: folder.folder_id,
+            alert,
+        })
+        .collect())
+}
+
+async fn export_streams(org_id: &str) -> Result<Vec<BundleStream>, BundleError> {
+    let mut streams = vec![];
+    for stream_type in [StreamType::Logs, StreamType::Metrics, StreamType::Traces] {
+        for stream_name in db::schema::list_streams_from_cache(org_id, stream_type).await {
+            let schema = infra::schema::get(org_id, &stream_name, stream_type)
+                .await
+                .map_err(|e| BundleError::Internal(e.into()))?;
+            if schema.fields().is_empty() {
+                continue;
+            }
+            streams.push(BundleStream {
+                stream_name,
+                stream_type,
+                schema,
+            });
+        }
+    }
+    Ok(streams)
+}
+
+async fn export_enrichment_tables(
+    org_id: &str,
+    user_id: &str,
+) -> Result<Vec<BundleEnrichmentTable>, BundleError> {
+    let mut tables = vec![];
+    for name in db::schema::list_streams_from_cache(org_id, StreamType::EnrichmentTables).await {
+        let start_time = db::enrichment_table::get_start_time(org_id, &name).await;
+        let query = Query {
+            sql: format!("SELECT * FROM \"{name}\""),
+            start_time,
+            end_time: now_micros(),
+            size: -1,
+            ..Default::default()
+        };
+        let records = search(org_id, user_id, StreamType::EnrichmentTables, query).await?;
+        tables.push(BundleEnrichmentTable { name, records });
+    }
+    Ok(tables)
+}
+
+/// Writes the records of the range to the archive page by page, oldest first, up to the
+/// configured maximum number of records.
+async fn export_data(
+    org_id: &str,
+    user_id: &str,
+    range: &StreamDataRange,
+    writer: &mut ArchiveWriter,
+) -> Result<(), BundleError> {
+    let cfg = get_config();
+    let page_size = cfg.limit.query_default_limit;
+    let max_records = cfg.limit.org_bundle_max_export_records;
+    let mut exported = 0;
+    while exported < max_records {
+        let size = page_size.min((max_records - exported) as i64);
+        let query = Query {
+            sql: format!(
+                "SELECT * FROM \"{}\" ORDER BY {TIMESTAMP_COL_NAME} ASC",
+                range.stream_name
+            ),
+            from: exported as i64,
+            size,
+            start_time: range.start_time,
+            end_time: range.end_time,
+            ..Default::default()
+        };
+        let hits = search(org_id, user_id, range.stream_type, query).await?;
+        writer.write_records(&hits)?;
+        exported += hits.len();
+        if (hits.len() as i64) < size {
+            return Ok(());
+        }
+    }
+    log::warn!(
+        "[ORG_BUNDLE] export of stream {} of org {org_id} stopped at {max_records} records",
+        range.stream_name
+    );
+    Ok(())
+}
+
+async fn search(
+    org_id: &str,
+    user_id: &str,
+    stream_type: StreamType,
+    query: Query,
+) -> Result<Vec<json::Value>, BundleError> {
+    let req = Request {
+        query,
+        encoding: RequestEncoding::Empty,
+        regions: vec![],
+        clusters: vec![],
+        timeout: 0,
+        search_type: Some(SearchEventType::Other),
+        search_event_context: None,
+        use_cache: false,
+        local_mode: None,
+    };
+    let trace_id = ider::generate_trace_id();
+    let resp = SearchService::search(
+        &trace_id,
+        org_id,
+        stream_type,
+        Some(user_id.to_string()),
+        &req,
+    )
+    .await
+    .map_err(|e| BundleError::Internal(e.into()))?;
+    Ok(resp.hits)
+}
@@ -0,0 +1,836 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{HashMap, HashSet};
+
+use actix_web::HttpResponse;
+use arrow_schema::Schema;
+use config::{
+    ider,
+    meta::{
+        alerts::alert::ListAlertsParams,
+        dashboards::ListDashboardsParams,
+        destinations::{Destination, Module, Template},
+        folder::{DEFAULT_FOLDER, FolderType},
+        function::Transform,
+        pipeline::{
+            Pipeline,
+            components::{NodeData, PipelineSource},
+        },
+        stream::StreamType,
+    },
+    utils::{json, time::now_micros},
+};
+use infra::{
+    db::{ORM_CLIENT, connect_to_orm},
+    table,
+};
+use proto::cluster_rpc;
+
+use super::{BundleError, Resolution, read_archive, resolve_name};
+use crate::{
+    common::meta::{
+        http::HttpResponse as MetaHttpResponse,
+        org_bundle::{
+            BundleAlert, BundleDashboard, BundleEnrichmentTable, BundleFolder, BundleKind,
+            BundleStream, BundleStreamData, ConflictStrategy, DataImportResult, ImportAction,
+            ImportItem, OrgBundleImportResponse,
+        },
+        saved_view::{CreateViewRequest, UpdateViewRequest, View},
+    },
+    service::{
+        alerts::{alert, destinations, templates},
+        dashboards, db, folders, functions,
+        ingestion::ingestion_service,
+        pipeline,
+    },
+};
+
+/// Records sent to the ingesters in one request when importing stream data.
+const DATA_BATCH_SIZE: usize = 1000;
+
+/// Imports a bundle archive into the org. Objects are imported one by one and the outcome of each
+/// one is reported, so a failing object doesn't stop the import.
+pub async fn import(
+    org_id: &str,
+    user_id: &str,
+    content: &[u8],
+    strategy: ConflictStrategy,
+) -> Result<OrgBundleImportResponse, BundleError> {
+    let (manifest, bundle) = read_archive(content)?;
+    let mut importer = Importer {
+        org_id,
+        user_id,
+        strategy,
+        comment: format!("Imported from org {}", manifest.org_id),
+        items: vec![],
+        templates: HashMap::new(),
+        destinations: HashMap::new(),
+        functions: HashMap::new(),
+        dashboard_folders: HashMap::new(),
+        alert_folders: HashMap::new(),
+        alerts: HashMap::new(),
+    };
+
+    // objects are imported before the objects referencing them
+    importer.import_templates(bundle.templates).await?;
+    importer.import_destinations(bundle.destinations).await?;
+    importer.import_streams(bundle.streams).await;
+    importer
+        .import_enrichment_tables(bundle.enrichment_tables)
+        .await;
+    importer.import_functions(bundle.functions).await?;
+    importer.import_folders(bundle.folders).await?;
+    importer.import_pipelines(bundle.pipelines).await?;
+    importer.import_alerts(bundle.alerts).await?;
+    importer.import_dashboards(bundle.dashboards).await?;
+    importer.import_saved_views(bundle.saved_views).await?;
+
+    let mut data = vec![];
+    for stream_data in bundle.data {
+        data.push(import_data(org_id, stream_data).await);
+    }
+    Ok(OrgBundleImportResponse {
+        items: importer.items,
+        data,
+    })
+}
+
+struct Importer<'a> {
+    org_id: &'a str,
+    user_id: &'a str,
+    strategy: ConflictStrategy,
+    /// Version comment of the imported pipelines.
+    comment: String,
+    items: Vec<ImportItem>,
+    /// Renamed templates, destinations and functions, by their name in the bundle.
+    templates: HashMap<String, String>,
+    destinations: HashMap<String, String>,
+    functions: HashMap<String, String>,
+    /// Ids of the folders in this org, by their id in the bundle.
+    dashboard_folders: HashMap<String, String>,
+    alert_folders: HashMap<String, String>,
+    /// Ids of the imported alerts in this org, by their id in the bundle.
+    alerts: HashMap<String, String>,
+}
+
+fn renamed(renames: &HashMap<String, String>, name: &str) -> String {
+    renames
+        .get(name)
+        .cloned()
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// Orders the alerts so the alerts of the bundle referenced by an alert, as parents or in its
+/// composite condition, are imported before it.
+fn order_by_references(mut list: Vec<BundleAlert>) -> Vec<BundleAlert> {
+    let mut ordered = Vec::with_capacity(list.len());
+    while !list.is_empty() {
+        let remaining = list
+            .iter()
+            .filter_map(|bundle_alert| bundle_alert.alert.id.map(|id| id.to_string()))
+            .collect::<HashSet<_>>();
+        let (ready, rest): (Vec<_>, Vec<_>) = list.into_iter().partition(|bundle_alert| {
+            bundle_alert
+                .alert
+                .upstream_alert_ids()
+                .iter()
+                .all(|id| !remaining.contains(*id))
+        });
+        if ready.is_empty() {
+            // the references make a cycle, importing these alerts will fail
+            ordered.extend(rest);
+            break;
+        }
+        ordered.extend(ready);
+        list = rest;
+    }
+    ordered
+}
+
+/// Returns the outcome of importing an object, `result` holding its id in this org.
+fn outcome(
+    kind: BundleKind,
+    name: &str,
+    resolution: &Resolution,
+    result: Result<Option<String>, String>,
+) -> ImportItem {
+    let new_id = match result {
+        Ok(new_id) => new_id,
+        Err(e) => return ImportItem::failed(kind, name, e),
+    };
+    let mut item = match resolution {
+        Resolution::Create => ImportItem::new(kind, name, ImportAction::Created),
+        Resolution::Overwrite => ImportItem::new(kind, name, ImportAction::Overwritten),
+        Resolution::Rename(new_name) => ImportItem {
+            new_name: Some(new_name.clone()),
+            ..ImportItem::new(kind, name, ImportAction::Renamed)
+        },
+        Resolution::Skip => ImportItem::new(kind, name, ImportAction::Skipped),
+    };
+    item.new_id = new_id;
+    item
+}
+
+/// Returns the error message of a failed response of the services answering with http responses.
+async fn response_error(resp: HttpResponse) -> Result<(), String> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let message = actix_web::body::to_bytes(resp.into_body())
+        .await
+        .ok()
+        .and_then(|body| json::from_slice::<MetaHttpResponse>(&body).ok())
+        .map(|resp| resp.message)
+        .unwrap_or_else(|| status.to_string());
+    Err(message)
+}
+
+async fn ingest(
+    org_id: &str,
+    stream_type: StreamType,
+    stream_name: &str,
+    records: &[json::Value],
+    metadata: Option<cluster_rpc::IngestRequestMetadata>,
+) -> Result<(), String> {
+    let req = cluster_rpc::IngestionRequest {
+        org_id: org_id.to_string(),
+        stream_type: stream_type.to_string(),
+        stream_name: stream_name.to_string(),
+        data: Some(cluster_rpc::IngestionData {
+            data: json::to_vec(records).map_err(|e| e.to_string())?,
+        }),
+        ingestion_type: Some(cluster_rpc::IngestionType::Json.into()),
+        metadata,
+    };
+    match ingestion_service::ingest(req).await {
+        Ok(resp) if resp.status_code == 200 => Ok(()),
+        Ok(resp) => Err(resp.message),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+impl Importer<'_> {
+    fn folders(&mut self, folder_type: FolderType) -> &mut HashMap<String, String> {
+        match folder_type {
+            FolderType::Alerts => &mut self.alert_folders,
+            _ => &mut self.dashboard_folders,
+        }
+    }
+
+    /// Returns the folder of this org an object of the bundle folder goes into. Objects of
+    /// folders which weren't imported go into the default folder.
+    fn folder_id(&self, folder_type: FolderType, folder_id: &str) -> String {
+        let folders = match folder_type {
+            FolderType::Alerts => &self.alert_folders,
+            _ => &self.dashboard_folders,
+        };
+        folders
+            .get(folder_id)
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_FOLDER.to_string())
+    }
+
+    fn push(&mut self, item: ImportItem) -> Option<String> {
+        let new_name = match item.action {
+            ImportAction::Renamed => item.new_name.clone(),
+            _ => None,
+        };
+        self.items.push(item);
+        new_name
+    }
+
+    async fn import_templates(&mut self, list: Vec<Template>) -> Result<(), BundleError> {
+        let org_templates = db::alerts::templates::list(self.org_id)
+            .await
+            .map_err(|e| BundleError::Internal(e.into()))?;
+        let owned = org_templates
+            .iter()
+            .filter(|template| template.org_id == self.org_id)
+            .map(|template| template.name.clone())
+            .collect::<HashSet<_>>();
+        let mut existing = org_templates
+            .into_iter()
+            .map(|template| template.name)
+            .collect::<HashSet<_>>();
+
+        for mut template in list {
+            let name = template.name.clone();
+            template.id = None;
+            template.org_id = self.org_id.to_string();
+            let mut resolution = resolve_name(&name, &mut existing, self.strategy);
+            // templates shared by the default org are never overwritten from another org
+            if resolution == Resolution::Overwrite && !owned.contains(&name) {
+                resolution = Resolution::Skip;
+            }
+            let result = match &resolution {
+                Resolution::Skip => Ok(()),
+                Resolution::Overwrite => templates::save(&name, template, false).await.map(|_| ()),
+                Resolution::Create => templates::save("", template, true).await.map(|_| ()),
+                Resolution::Rename(new_name) => {
+                    template.name = new_name.clone();
+                    templates::save("", template, true).await.map(|_| ())
+                }
+            };
+            let item = outcome(
+                BundleKind::Templates,
+                &name,
+                &resolution,
+                result.map(|_| None).map_err(|e| e.to_string()),
+            );
+            if let Some(new_name) = self.push(item) {
+                self.templates.insert(name, new_name);
+            }
+        }
+        Ok(())
+    }
+
+    async fn import_destinations(&mut self, list: Vec<Destination>) -> Result<(), BundleError> {
+        let mut existing = db::alerts::destinations::list(self.org_id, None)
+            .await
+            .map_err(|e| BundleError::Internal(e.into()))?
+            .into_iter()
+            .map(|destination| destination.name)
+            .collect::<HashSet<_>>();
+
+        for mut destination in list {
+            let name = destination.name.clone();
+            destination.id = None;
+            destination.org_id = self.org_id.to_string();
+            if let Module::Alert { template, .. } = &mut destination.module {
+                *template = renamed(&self.templates, template);
+            }
+            let resolution = resolve_name(&name, &mut existing, self.strategy);
+            let result = match &resolution {
+                Resolution::Skip => Ok(()),
+                Resolution::Overwrite => destinations::save(&name, destination, false)
+                    .await
+                    .map(|_| ()),
+                Resolution::Create => destinations::save("", destination, true).await.map(|_| ()),
+                Resolution::Rename(new_name) => {
+                    destination.name = new_name.clone();
+                    destinations::save("", destination, true).await.map(|_| ())
+                }
+            };
+            let item = outcome(
+                BundleKind::Destinations,
+                &name,
+                &resolution,
+                result.map(|_| None).map_err(|e| e.to_string()),
+            );
+            if let Some(new_name) = self.push(item) {
+                self.destinations.insert(name, new_name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the streams with their settings. Streams are referenced by name everywhere, so
+    /// they are never renamed.
+    async fn import_streams(&mut self, list: Vec<BundleStream>) {
+        let strategy = match self.strategy {
+            ConflictStrategy::Rename => ConflictStrategy::Skip,
+            strategy => strategy,
+        };
+        let mut existing = HashSet::new();
+        for stream_type in [StreamType::Logs, StreamType::Metrics, StreamType::Traces] {
+            for stream_name in db::schema::list_streams_from_cache(self.org_id, stream_type).await {
+                existing.insert(format!("{stream_type}/{stream_name}"));
+            }
+        }
+
+        for stream in list {
+            let name = format!("{}/{}", stream.stream_type, stream.stream_name);
+            let resolution = resolve_name(&name, &mut existing, strategy);
+            let result = match &resolution {
+                Resolution::Skip => Ok(()),
+                _ => self.put_stream(&stream).await,
+            };
+            let item = outcome(
+                BundleKind::Streams,
+                &name,
+                &resolution,
+                result.map(|_| None),
+            );
+            self.push(item);
+        }
+    }
+
+    async fn put_stream(&self, stream: &BundleStream) -> Result<(), String> {
+        let fields = Schema::new(stream.schema.fields().clone());
+        db::schema::merge(
+            self.org_id,
+            &stream.stream_name,
+            stream.stream_type,
+            &fields,
+            None,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        let Some(settings) = stream.schema.metadata().get("settings") else {
+            return Ok(());
+        };
+        let schema = infra::schema::get(self.org_id, &stream.stream_name, stream.stream_type)
+            .await
+            .map_err(|e| e.to_string())?;
+        let mut metadata = schema.metadata().clone();
+        metadata.insert("settings".to_string(), settings.clone());
+        metadata
+            .entry("created_at".to_string())
+            .or_insert_with(|| now_micros().to_string());
+        db::schema::update_setting(
+            self.org_id,
+            &stream.stream_name,
+            stream.stream_type,
+            metadata,
+        )
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    async fn import_enrichment_tables(&mut self, list: Vec<BundleEnrichmentTable>) {
+        let mut existing =
+            db::schema::list_streams_from_cache(self.org_id, StreamType::EnrichmentTables)
+                .await
+                .into_iter()
+                .collect::<HashSet<_>>();
+
+        for table in list {
+            let resolution = resolve_name(&table.name, &mut existing, self.strategy);
+            let table_name = match &resolution {
+                Resolution::Rename(new_name) => new_name.as_str(),
+                _ => table.name.as_str(),
+            };
+            let result = match &resolution {
+                Resolution::Skip => Ok(()),
+                _ if table.records.is_empty() => Err("the table has no records".to_string()),
+                _ => {
+                    // replace the records of the table rather than appending to them
+                    let metadata = cluster_rpc::IngestRequestMetadata {
+                        data: HashMap::from([("append_data".to_string(), "false".to_string())]),
+                    };
+                    ingest(
+                        self.org_id,
+                        StreamType::EnrichmentTables,
+                        table_name,
+                        &table.records,
+                        Some(metadata),
+                    )
+                    .await
+                }
+            };
+            let item = outcome(
+                BundleKind::EnrichmentTables,
+                &table.name,
+                &resolution,
+                result.map(|_| None),
+            );
+            self.push(item);
+        }
+    }
+
+    async fn import_functions(&mut self, list: Vec<Transform>) -> Result<(), BundleError> {
+        let mut existing = db::functions::list(self.org_id)
+            .await?
+            .into_iter()
+            .map(|func| func.name)
+            .collect::<HashSet<_>>();
+
+        for mut func in list {
+            let name = func.name.clone();
+            func.streams = None;
+            let resolution = resolve_name(&name, &mut existing, self.strategy);
+            let result = match &resolution {
+                Resolution::Skip => Ok(HttpResponse::Ok().finish()),
+                Resolution::Overwrite => functions::update_function(self.org_id, &name, func).await,
+                Resolution::Create => functions::save_function(self.org_id.to_string(), func).await,
+                Resolution::Rename(new_name) => {
+                    func.name = new_name.clone();
+                    functions::save_function(self.org_id.to_string(), func).await
+                }
+            };
+            let result = match result {
+                Ok(resp) => response_error(resp).await,
+                Err(e) => Err(e.to_string()),
+            };
+            let item = outcome(
+                BundleKind::Functions,
+                &name,
+                &resolution,
+                result.map(|_| None),
+            );
+            if let Some(new_name) = self.push(item) {
+                self.functions.insert(name, new_name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the folders and maps their ids. Skipped folders are reused, so the objects in them
+    /// still get imported.
+    async fn import_folders(&mut self, list: Vec<BundleFolder>) -> Result<(), BundleError> {
+        // ids of the folders of this org by their name
+        let mut dashboard_folder_ids = HashMap::new();
+        let mut alert_folder_ids = HashMap::new();
+        for (folder_type, ids) in [
+            (FolderType::Dashboards, &mut dashboard_folder_ids),
+            (FolderType::Alerts, &mut alert_folder_ids),
+        ] {
+            for folder in table::folders::list_folders(self.org_id, folder_type)
+                .await
+                .map_err(|e| BundleError::Internal(e.into()))?
+            {
+                ids.insert(folder.name, folder.folder_id);
+            }
+        }
+
+        for bundle_folder in list {
+            let folder_type = bundle_folder.folder_type;
+            if bundle_folder.folder_id == DEFAULT_FOLDER {
+                // the default folder is created along with the first object going into it
+                self.folders(folder_type)
+                    .insert(DEFAULT_FOLDER.to_string(), DEFAULT_FOLDER.to_string());
+                continue;
+            }
+            let ids = match folder_type {
+                FolderType::Alerts => &mut alert_folder_ids,
+                _ => &mut dashboard_folder_ids,
+            };
+            let mut existing = ids.keys().cloned().collect::<HashSet<_>>();
+            let name = bundle_folder.name.clone();
+            let mut folder = bundle_folder.folder();
+            let resolution = resolve_name(&name, &mut existing, self.strategy);
+            let result = match &resolution {
+                Resolution::Skip => Ok(ids.get(&name).cloned()),
+                Resolution::Overwrite => {
+                    let folder_id = ids[&name].clone();
+                    folders::update_folder(self.org_id, &folder_id, folder_type, folder)
+                        .await
+                        .map(|_| Some(folder_id))
+                        .map_err(|e| e.to_string())
+                }
+                Resolution::Create | Resolution::Rename(_) => {
+                    if let Resolution::Rename(new_name) = &resolution {
+                        folder.name = new_name.clone();
+                    }
+                    let folder_name = folder.name.clone();
+                    match folders::save_folder(self.org_id, folder, folder_type, false).await {
+                        Ok(saved) => {
+                            ids.insert(folder_name, saved.folder_id.clone());
+                            Ok(Some(saved.folder_id))
+                        }
+                        Err(e) => Err(e.to_string()),
+                    }
+                }
+            };
+            if let Ok(Some(folder_id)) = &result {
+                self.folders(folder_type)
+                    .insert(bundle_folder.folder_id.clone(), folder_id.clone());
+            }
+            let item = outcome(BundleKind::Folders, &name, &resolution, result);
+            let new_id = item.new_id.clone();
+            self.push(item.with_ids(Some(bundle_folder.folder_id), new_id));
+        }
+        Ok(())
+    }
+
+    fn remap_pipeline(&self, pipeline: &mut Pipeline) {
+        let org_id = self.org_id;
+        pipeline.org = org_id.to_string();
+        match &mut pipeline.source {
+            PipelineSource::Realtime(params) => params.org_id = org_id.to_string().into(),
+            PipelineSource::Scheduled(derived_stream) => derived_stream.org_id = org_id.to_string(),
+        }
+        for node in pipeline.nodes.iter_mut() {
+            match &mut node.data {
+                NodeData::Stream(params) => params.org_id = org_id.to_string().into(),
+                NodeData::RemoteStream(params) => {
+                    params.org_id = org_id.to_string().into();
+                    params.destination_name =
+                        renamed(&self.destinations, &params.destination_name).into();
+                }
+                NodeData::Query(derived_stream) => derived_stream.org_id = org_id.to_string(),
+                NodeData::Function(params) => params.name = renamed(&self.functions, &params.name),
+                _ => {}
+            }
+        }
+        if let Some(dead_letter) = &mut pipeline.dead_letter {
+            dead_letter.org_id = org_id.to_string().into();
+        }
+    }
+
+    async fn import_pipelines(&mut self, list: Vec<Pipeline>) -> Result<(), BundleError> {
+        let existing_pipelines = db::pipeline::list_by_org(self.org_id)
+            .await
+            .map_err(|e| BundleError::Internal(e.into()))?
+            .into_iter()
+            .map(|pipeline| (pipeline.name.clone(), pipeline))
+            .collect::<HashMap<_, _>>();
+        let mut existing = existing_pipelines.keys().cloned().collect::<HashSet<_>>();
+
+        for mut bundle_pipeline in list {
+            let name = bundle_pipeline.name.clone();
+            let old_id = bundle_pipeline.id.clone();
+            self.remap_pipeline(&mut bundle_pipeline);
+            let resolution = resolve_name(&name, &mut existing, self.strategy);
+            let result = match &resolution {
+                Resolution::Skip => Ok(existing_pipelines.get(&name).map(|p| p.id.clone())),
+                Resolution::Overwrite => {
+                    let existing_pipeline = &existing_pipelines[&name];
+                    bundle_pipeline.id = existing_pipeline.id.clone();
+                    bundle_pipeline.version = existing_pipeline.version;
+                    pipeline::update_pipeline(
+                        bundle_pipeline,
+                        self.user_id,
+                        Some(self.comment.clone()),
+                    )
+                    .await
+                    .map(|_| Some(existing_pipeline.id.clone()))
+                    .map_err(|e| e.to_string())
+                }
+                Resolution::Create | Resolution::Rename(_) => {
+                    if let Resolution::Rename(new_name) = &resolution {
+                        bundle_pipeline.name = new_name.clone();
+                    }
+                    let pipeline_id = ider::generate();
+                    bundle_pipeline.id = pipeline_id.clone();
+                    bundle_pipeline.version = 0;
+                    pipeline::save_pipeline(
+                        bundle_pipeline,
+                        self.user_id,
+                        Some(self.comment.clone()),
+                    )
+                    .await
+                    .map(|_| Some(pipeline_id))
+                    .map_err(|e| e.to_string())
+                }
+            };
+            let item = outcome(BundleKind::Pipelines, &name, &resolution, result);
+            let new_id = item.new_id.clone();
+            self.push(item.with_ids(Some(old_id), new_id));
+        }
+        Ok(())
+    }
+
+    async fn import_alerts(&mut self, list: Vec<BundleAlert>) -> Result<(), BundleError> {
+        let conn = ORM_CLIENT.get_or_init(connect_to_orm).await;
+        let existing_alerts = alert::list_with_folders_db(ListAlertsParams::new(self.org_id))
+            .await
+            .map_err(|e| BundleError::Internal(e.into()))?
+            .into_iter()
+            .map(|(folder, alert)| {
+                let key = format!("{}/{}/{}", alert.stream_type, alert.stream_name, alert.name);
+                (key, (folder.folder_id, alert.id))
+            })
+            .collect::<HashMap<_, _>>();
+        let mut existing = existing_alerts.keys().cloned().collect::<HashSet<_>>();
+
+        for BundleAlert {
+            folder_id,
+            alert: mut bundle_alert,
+        } in order_by_references(list)
+        {
+            let name = bundle_alert.name.clone();
+            let key = format!(
+                "{}/{}/{}",
+                bundle_alert.stream_type, bundle_alert.stream_name, name
+            );
+            let old_id = bundle_alert.id.map(|id| id.to_string());
+            let folder_id = self.folder_id(FolderType::Alerts, &folder_id);
+            bundle_alert.id = None;
+            bundle_alert.org_id = self.org_id.to_string();
+            bundle_alert.destinations = bundle_alert
+                .destinations
+                .iter()
+                .map(|destination| renamed(&self.destinations, destination))
+                .collect();
+            bundle_alert.depends_on = bundle_alert
+                .depends_on
+                .iter()
+                .map(|parent_id| renamed(&self.alerts, parent_id))
+                .collect();
+            if let Some(composite) = bundle_alert.query_condition.composite.as_mut() {
+                composite.map_alert_ids(&|alert_id| renamed(&self.alerts, alert_id));
+            }
+
+            let resolution = resolve_name(&key, &mut existing, self.strategy);
+            let result = match &resolution {
+                Resolution::Skip => Ok(existing_alerts
+                    .get(&key)
+                    .and_then(|(_, id)| id.map(|id| id.to_string()))),
+                Resolution::Overwrite => {
+                    let (curr_folder_id, alert_id) = &existing_alerts[&key];
+                    bundle_alert.id = *alert_id;
+                    let move_folder = (curr_folder_id != &folder_id)
+                        .then_some((curr_folder_id.as_str(), folder_id.as_str()));
+                    alert::update(conn, self.org_id, move_folder, bundle_alert)
+                        .await
+                        .map(|alert| alert.id.map(|id| id.to_string()))
+                        .map_err(|e| e.to_string())
+                }
+                Resolution::Create | Resolution::Rename(_) => {
+                    if let Resolution::Rename(new_key) = &resolution {
+                        // the renamed key ends with the new alert name
+                        bundle_alert.name = format!("{name}{}", &new_key[key.len()..]);
+                    }
+                    alert::create(conn, self.org_id, &folder_id, bundle_alert)
+                        .await
+                        .map(|alert| alert.id.map(|id| id.to_string()))
+                        .map_err(|e| e.to_string())
+                }
+            };
+            let mut item = outcome(BundleKind::Alerts, &name, &resolution, result);
+            if let Some(new_name) = &mut item.new_name {
+                *new_name = format!("{name}{}", &new_name[key.len()..]);
+            }
+            let new_id = item.new_id.clone();
+            if let (Some(old_id), Some(new_id)) = (&old_id, &new_id) {
+                self.alerts.insert(old_id.clone(), new_id.clone());
+            }
+            self.push(item.with_ids(old_id, new_id));
+        }
+        Ok(())
+    }
+
+    async fn import_dashboards(&mut self, list: Vec<BundleDashboard>) -> Result<(), BundleError> {
+        let existing_dashboards = table::dashboards::list(ListDashboardsParams::new(self.org_id))
+            .await
+            .map_err(|e| BundleError::Internal(e.into()))?
+            .into_iter()
+            .filter_map(|(folder, dashboard)| {
+                let key = format!("{}/{}", folder.folder_id, dashboard.title()?);
+                let dashboard_id = dashboard.dashboard_id()?.to_string();
+                Some((key, (dashboard_id, dashboard.hash)))
+            })
+            .collect::<HashMap<_, _>>();
+        let mut existing = existing_dashboards.keys().cloned().collect::<HashSet<_>>();
+
+        for BundleDashboard {
+            folder_id,
+            mut dashboard,
+        } in list
+        {
+            let Some(title) = dashboard.title().map(|title| title.to_string()) else {
+                continue;
+            };
+            let old_id = dashboard.dashboard_id().map(|id| id.to_string());
+            let folder_id = self.folder_id(FolderType::Dashboards, &folder_id);
+            let key = format!("{folder_id}/{title}");
+
+            let resolution = resolve_name(&key, &mut existing, self.strategy);
+            let result = match &resolution {
+                Resolution::Skip => Ok(existing_dashboards.get(&key).map(|(id, _)| id.clone())),
+                Resolution::Overwrite => {
+                    let (dashboard_id, hash) = &existing_dashboards[&key];
+                    dashboards::update_dashboard(
+                        self.org_id,
+                        dashboard_id,
+                        &folder_id,
+                        dashboard,
+                        Some(hash),
+                        self.user_id,
+                    )
+                    .await
+                    .map(|_| Some(dashboard_id.clone()))
+                    .map_err(|e| e.to_string())
+                }
+                Resolution::Create | Resolution::Rename(_) => {
+                    if let Resolution::Rename(new_key) = &resolution {
+                        dashboard.set_title(format!("{title}{}", &new_key[key.len()..]));
+                    }
+                    dashboards::create_dashboard(self.org_id, &folder_id, dashboard, self.user_id)
+                        .await
+                        .map(|dashboard| dashboard.dashboard_id().map(|id| id.to_string()))
+                        .map_err(|e| e.to_string())
+                }
+            };
+            let mut item = outcome(BundleKind::Dashboards, &title, &resolution, result);
+            if let Some(new_name) = &mut item.new_name {
+                *new_name = format!("{title}{}", &new_name[key.len()..]);
+            }
+            let new_id = item.new_id.clone();
+            self.push(item.with_ids(old_id, new_id));
+        }
+        Ok(())
+    }
+
+    async fn import_saved_views(&mut self, list: Vec<View>) -> Result<(), BundleError> {
+        let existing_views = db::saved_view::get_views_list_only(self.org_id)
+            .await
+            .map_err(|e| BundleError::Internal(e.into()))?
+            .views
+            .into_iter()
+            .map(|view| (view.view_name, view.view_id))
+            .collect::<HashMap<_, _>>();
+        let mut existing = existing_views.keys().cloned().collect::<HashSet<_>>();
+
+        for view in list {
+            let name = view.view_name.clone();
+            let resolution = resolve_name(&name, &mut existing, self.strategy);
+            let result = match &resolution {
+                Resolution::Skip => Ok(existing_views.get(&name).cloned()),
+                Resolution::Overwrite => {
+                    let view_id = &existing_views[&name];
+                    let req = UpdateViewRequest {
+                        data: view.data,
+                        view_name: name.clone(),
+                    };
+                    db::saved_view::update_view(self.org_id, view_id, &req)
+                        .await
+                        .map(|view| Some(view.view_id))
+                        .map_err(|e| e.to_string())
+                }
+                Resolution::Create | Resolution::Rename(_) => {
+                    let view_name = match &resolution {
+                        Resolution::Rename(new_name) => new_name.clone(),
+                        _ => name.clone(),
+                    };
+                    let req = CreateViewRequest {
+                        data: view.data,
+                        view_name,
+                    };
+                    db::saved_view::set_view(self.org_id, &req)
+                        .await
+                        .map(|view| Some(view.view_id))
+                        .map_err(|e| e.to_string())
+                }
+            };
+            let item = outcome(BundleKind::SavedViews, &name, &resolution, result);
+            let new_id = item.new_id.clone();
+            self.push(item.with_ids(Some(view.view_id), new_id));
+        }
+        Ok(())
+    }
+}
+
+/// Appends the records of the range to the stream, in batches.
+async fn import_data(org_id: &str, stream_data: BundleStreamData) -> DataImportResult {
+    let range = stream_data.range;
+    let mut result = DataImportResult {
+        stream_name: range.stream_name,
+        stream_type: range.stream_type,
+        records: 0,
+        error: None,
+    };
+    for batch in stream_data.records.chunks(DATA_BATCH_SIZE) {
+        if let Err(e) = ingest(org_id, result.stream_type, &result.stream_name, batch, None).await {
+            result.error = Some(e);
+            break;
+        }
+        result.records += batch.len();
+    }
+    result
+}
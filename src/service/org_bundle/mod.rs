@@ -0,0 +1,384 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Org bundles carry the objects of an org, and optionally some of its logs, in one zip archive
+//! to move them to another org or cluster. The archive has a `manifest.json`, one JSON file per
+//! kind of object and one `data/{n}.json` file per exported stream data range.
+
+use std::{
+    collections::HashSet,
+    io::{Cursor, Read, Write},
+};
+
+use config::{get_config, meta::stream::StreamType, utils::json};
+use serde::{Serialize, de::DeserializeOwned};
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use crate::common::meta::org_bundle::{
+    BUNDLE_VERSION, BundleKind, BundleManifest, BundleStreamData, ConflictStrategy, OrgBundle,
+};
+
+pub mod export;
+pub mod import;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Errors that can occur when exporting or importing an org bundle.
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    /// The uploaded archive isn't a bundle this build can read.
+    #[error("Invalid bundle: {0}")]
+    InvalidBundle(String),
+
+    /// The bundle was written by a newer build.
+    #[error("Unsupported bundle version {0}, the latest supported version is {BUNDLE_VERSION}")]
+    UnsupportedVersion(u32),
+
+    /// A file of the archive, or the whole archive, is over the size limit once uncompressed.
+    #[error("Bundle too large: {0} is over the limit of {1} bytes uncompressed")]
+    TooLarge(String, u64),
+
+    /// Only the data of logs streams can be exported.
+    #[error("Data of {0} streams can't be exported, only logs streams are supported")]
+    UnsupportedDataStream(StreamType),
+
+    /// An error that occurs while reading the objects of the org.
+    #[error("InternalError# {0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl From<zip::result::ZipError> for BundleError {
+    fn from(e: zip::result::ZipError) -> Self {
+        BundleError::InvalidBundle(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for BundleError {
+    fn from(e: std::io::Error) -> Self {
+        BundleError::InvalidBundle(e.to_string())
+    }
+}
+
+fn kind_file(kind: BundleKind) -> String {
+    format!("{}.json", kind.as_str())
+}
+
+fn data_file(index: usize) -> String {
+    format!("data/{index}.json")
+}
+
+fn write_file<W: Write + std::io::Seek, T: Serialize>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    value: &T,
+) -> Result<(), BundleError> {
+    zip.start_file(name, SimpleFileOptions::default())?;
+    let content = json::to_vec(value).map_err(|e| BundleError::Internal(e.into()))?;
+    zip.write_all(&content)?;
+    Ok(())
+}
+
+/// Uncompressed sizes, in bytes, the files of an archive are read up to.
+struct ReadLimits {
+    /// Bytes left for the whole archive.
+    remaining: u64,
+    file_size: u64,
+}
+
+impl ReadLimits {
+    fn new() -> Self {
+        let cfg = get_config();
+        Self {
+            remaining: cfg.limit.org_bundle_max_size * 1024 * 1024,
+            file_size: cfg.limit.org_bundle_max_file_size * 1024 * 1024,
+        }
+    }
+}
+
+/// Reads a file of the archive. The size recorded in the archive isn't trusted, the file is read
+/// up to the limits and rejected when it has more bytes.
+fn read_file<R: Read + std::io::Seek, T: DeserializeOwned>(
+    zip: &mut ZipArchive<R>,
+    name: &str,
+    limits: &mut ReadLimits,
+) -> Result<T, BundleError> {
+    let file = zip.by_name(name)?;
+    let limit = limits.remaining.min(limits.file_size);
+    let mut content = vec![];
+    file.take(limit.saturating_add(1)).read_to_end(&mut content)?;
+    if content.len() as u64 > limit {
+        return Err(BundleError::TooLarge(name.to_string(), limit));
+    }
+    limits.remaining -= content.len() as u64;
+    json::from_slice(&content).map_err(|e| BundleError::InvalidBundle(format!("{name}: {e}")))
+}
+
+/// Writes a bundle into a zip archive, the objects first and then the stream data, which is
+/// appended page by page so it's never held in memory all at once.
+pub struct ArchiveWriter {
+    zip: ZipWriter<Cursor<Vec<u8>>>,
+    /// Records written to the open data file, if any.
+    data_records: Option<usize>,
+}
+
+impl ArchiveWriter {
+    /// Starts an archive with the manifest and the objects of the kinds it lists.
+    pub fn new(manifest: &BundleManifest, bundle: &OrgBundle) -> Result<Self, BundleError> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        write_file(&mut zip, MANIFEST_FILE, manifest)?;
+        for kind in manifest.kinds.iter() {
+            let name = kind_file(*kind);
+            match kind {
+                BundleKind::Folders => write_file(&mut zip, &name, &bundle.folders)?,
+                BundleKind::Dashboards => write_file(&mut zip, &name, &bundle.dashboards)?,
+                BundleKind::Alerts => write_file(&mut zip, &name, &bundle.alerts)?,
+                BundleKind::Destinations => write_file(&mut zip, &name, &bundle.destinations)?,
+                BundleKind::Templates => write_file(&mut zip, &name, &bundle.templates)?,
+                BundleKind::Functions => write_file(&mut zip, &name, &bundle.functions)?,
+                BundleKind::Pipelines => write_file(&mut zip, &name, &bundle.pipelines)?,
+                BundleKind::SavedViews => write_file(&mut zip, &name, &bundle.saved_views)?,
+                BundleKind::EnrichmentTables => {
+                    write_file(&mut zip, &name, &bundle.enrichment_tables)?
+                }
+                BundleKind::Streams => write_file(&mut zip, &name, &bundle.streams)?,
+            }
+        }
+        Ok(Self {
+            zip,
+            data_records: None,
+        })
+    }
+
+    /// Starts the data file of the `index`th stream data range of the manifest.
+    pub fn start_data(&mut self, index: usize) -> Result<(), BundleError> {
+        self.finish_data()?;
+        self.zip
+            .start_file(data_file(index), SimpleFileOptions::default())?;
+        self.zip.write_all(b"[")?;
+        self.data_records = Some(0);
+        Ok(())
+    }
+
+    /// Appends records to the open data file.
+    pub fn write_records(&mut self, records: &[json::Value]) -> Result<(), BundleError> {
+        let Some(written) = self.data_records.as_mut() else {
+            return Err(BundleError::Internal(anyhow::anyhow!(
+                "No data file is open in the bundle archive"
+            )));
+        };
+        for record in records {
+            if *written > 0 {
+                self.zip.write_all(b",")?;
+            }
+            let content = json::to_vec(record).map_err(|e| BundleError::Internal(e.into()))?;
+            self.zip.write_all(&content)?;
+            *written += 1;
+        }
+        Ok(())
+    }
+
+    fn finish_data(&mut self) -> Result<(), BundleError> {
+        if self.data_records.take().is_some() {
+            self.zip.write_all(b"]")?;
+        }
+        Ok(())
+    }
+
+    /// Finishes the archive and returns its content.
+    pub fn finish(mut self) -> Result<Vec<u8>, BundleError> {
+        self.finish_data()?;
+        Ok(self.zip.finish()?.into_inner())
+    }
+}
+
+/// Reads a bundle written by [ArchiveWriter], within the configured uncompressed size limits.
+pub fn read_archive(content: &[u8]) -> Result<(BundleManifest, OrgBundle), BundleError> {
+    read_archive_within(content, &mut ReadLimits::new())
+}
+
+fn read_archive_within(
+    content: &[u8],
+    limits: &mut ReadLimits,
+) -> Result<(BundleManifest, OrgBundle), BundleError> {
+    let mut zip = ZipArchive::new(Cursor::new(content))?;
+    let manifest: BundleManifest = read_file(&mut zip, MANIFEST_FILE, limits)?;
+    if manifest.version > BUNDLE_VERSION {
+        return Err(BundleError::UnsupportedVersion(manifest.version));
+    }
+
+    let mut bundle = OrgBundle::default();
+    for kind in manifest.kinds.iter() {
+        let name = kind_file(*kind);
+        match kind {
+            BundleKind::Folders => bundle.folders = read_file(&mut zip, &name, limits)?,
+            BundleKind::Dashboards => bundle.dashboards = read_file(&mut zip, &name, limits)?,
+            BundleKind::Alerts => bundle.alerts = read_file(&mut zip, &name, limits)?,
+            BundleKind::Destinations => bundle.destinations = read_file(&mut zip, &name, limits)?,
+            BundleKind::Templates => bundle.templates = read_file(&mut zip, &name, limits)?,
+            BundleKind::Functions => bundle.functions = read_file(&mut zip, &name, limits)?,
+            BundleKind::Pipelines => bundle.pipelines = read_file(&mut zip, &name, limits)?,
+            BundleKind::SavedViews => bundle.saved_views = read_file(&mut zip, &name, limits)?,
+            BundleKind::EnrichmentTables => {
+                bundle.enrichment_tables = read_file(&mut zip, &name, limits)?
+            }
+            BundleKind::Streams => bundle.streams = read_file(&mut zip, &name, limits)?,
+        }
+    }
+    for (index, range) in manifest.data.iter().enumerate() {
+        bundle.data.push(BundleStreamData {
+            range: range.clone(),
+            records: read_file(&mut zip, &data_file(index), limits)?,
+        });
+    }
+    Ok((manifest, bundle))
+}
+
+/// How a bundle object is imported once its name is checked against the org.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Resolution {
+    Create,
+    Overwrite,
+    Rename(String),
+    Skip,
+}
+
+/// Resolves the name of a bundle object against the names `existing` in the org. Renamed
+/// objects get the first free `{name}_imported` or `{name}_imported_{n}` name, which is added
+/// to `existing`.
+pub(crate) fn resolve_name(
+    name: &str,
+    existing: &mut HashSet<String>,
+    strategy: ConflictStrategy,
+) -> Resolution {
+    if existing.insert(name.to_string()) {
+        return Resolution::Create;
+    }
+    match strategy {
+        ConflictStrategy::Skip => Resolution::Skip,
+        ConflictStrategy::Overwrite => Resolution::Overwrite,
+        ConflictStrategy::Rename => {
+            let mut new_name = format!("{name}_imported");
+            let mut n = 2;
+            while existing.contains(&new_name) {
+                new_name = format!("{name}_imported_{n}");
+                n += 1;
+            }
+            existing.insert(new_name.clone());
+            Resolution::Rename(new_name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use config::meta::{function::Transform, stream::StreamType};
+
+    use super::*;
+    use crate::common::meta::org_bundle::StreamDataRange;
+
+    #[test]
+    fn test_resolve_name() {
+        let mut existing = HashSet::from(["a".to_string(), "a_imported".to_string()]);
+        assert_eq!(
+            resolve_name("b", &mut existing, ConflictStrategy::Skip),
+            Resolution::Create
+        );
+        assert_eq!(
+            resolve_name("a", &mut existing, ConflictStrategy::Skip),
+            Resolution::Skip
+        );
+        assert_eq!(
+            resolve_name("a", &mut existing, ConflictStrategy::Overwrite),
+            Resolution::Overwrite
+        );
+        assert_eq!(
+            resolve_name("a", &mut existing, ConflictStrategy::Rename),
+            Resolution::Rename("a_imported_2".to_string())
+        );
+        assert_eq!(
+            resolve_name("a", &mut existing, ConflictStrategy::Rename),
+            Resolution::Rename("a_imported_3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_archive_roundtrip() {
+        let range = StreamDataRange {
+            stream_name: "default".to_string(),
+            stream_type: StreamType::Logs,
+            start_time: 0,
+            end_time: 1,
+        };
+        let manifest = BundleManifest {
+            version: BUNDLE_VERSION,
+            org_id: "default".to_string(),
+            exported_at: 0,
+            kinds: vec![BundleKind::Functions],
+            data: vec![range.clone()],
+        };
+        let bundle = OrgBundle {
+            functions: vec![Transform {
+                function: ".a = 1".to_string(),
+                name: "set_a".to_string(),
+                params: "row".to_string(),
+                num_args: 0,
+                trans_type: Some(0),
+                streams: None,
+            }],
+            data: vec![BundleStreamData {
+                range,
+                records: vec![json::json!({"a": 1})],
+            }],
+            ..Default::default()
+        };
+
+        let mut writer = ArchiveWriter::new(&manifest, &bundle).unwrap();
+        writer.start_data(0).unwrap();
+        writer.write_records(&bundle.data[0].records).unwrap();
+        writer.write_records(&[json::json!({"a": 2})]).unwrap();
+        let content = writer.finish().unwrap();
+        let (read_manifest, read_bundle) = read_archive(&content).unwrap();
+        assert_eq!(read_manifest.kinds, vec![BundleKind::Functions]);
+        assert_eq!(read_bundle.functions.len(), 1);
+        assert_eq!(read_bundle.functions[0].name, "set_a");
+        assert_eq!(
+            read_bundle.data[0].records,
+            vec![json::json!({"a": 1}), json::json!({"a": 2})]
+        );
+        assert!(read_bundle.dashboards.is_empty());
+
+        let mut limits = ReadLimits {
+            remaining: u64::MAX,
+            file_size: 16,
+        };
+        assert!(matches!(
+            read_archive_within(&content, &mut limits),
+            Err(BundleError::TooLarge(name, 16)) if name == MANIFEST_FILE
+        ));
+        let mut limits = ReadLimits {
+            remaining: 200,
+            file_size: u64::MAX,
+        };
+        assert!(matches!(
+            read_archive_within(&content, &mut limits),
+            Err(BundleError::TooLarge(..))
+        ));
+
+        assert!(matches!(
+            read_archive(b"not a zip"),
+            Err(BundleError::InvalidBundle(_))
+        ));
+    }
+}
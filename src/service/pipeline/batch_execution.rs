@@ -19,10 +19,17 @@ use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use chrono::Utc;
 use config::{
+    RwHashMap,
     meta::{
         function::{Transform, VRLResultResolver},
-        pipeline::{Pipeline, components::NodeData},
-        self_reporting::error::{ErrorData, ErrorSource, PipelineError},
+        pipeline::{
+            DeadLetterRecord, Pipeline, PipelineTestError, PipelineTestNodeResult,
+            PipelineTestRecord, PipelineTestResponse, components::NodeData,
+        },
+        self_reporting::{
+            error::{ErrorData, ErrorSource, PipelineError},
+            usage::{RequestStats, UsageType},
+        },
         stream::{StreamParams, StreamType},
     },
     utils::{
@@ -35,42 +42,64 @@ use futures::future::try_join_all;
 #[cfg(feature = "enterprise")]
 use o2_enterprise::enterprise::pipeline::pipeline_wal_writer::get_pipeline_wal_writer;
 use once_cell::sync::Lazy;
-use tokio::sync::mpsc::{Receiver, Sender, channel};
+use tokio::sync::mpsc::{Receiver, Sender, UnboundedSender, channel, unbounded_channel};
 
 use crate::{
     common::infra::config::QUERY_FUNCTIONS,
     service::{
-        ingestion::{apply_vrl_fn, compile_vrl_function},
-        self_reporting::publish_error,
+        ingestion::{
+            apply_vrl_fn, compile_vrl_function,
+            wasm::{WasmFunction, compile_wasm_function},
+        },
+        pipeline::dead_letter,
+        self_reporting::{publish_error, report_request_usage_stats},
     },
 };
 
 static DYNAMIC_STREAM_NAME_PATTERN: Lazy<regex::Regex> =
     Lazy::new(|| regex::Regex::new(r"\{([^}]+)\}").unwrap());
 
+/// Dedup keys seen by each DedupNode, keyed by `{pipeline_id}/{node_id}`, with the time in micros
+/// the key was first seen. The state is local to each node running the pipeline, bounded by
+/// `ZO_PIPELINE_DEDUP_MAX_KEYS` per DedupNode and removed when the pipeline is updated or deleted.
+static DEDUP_CACHE: Lazy<RwHashMap<String, HashMap<u64, i64>>> = Lazy::new(Default::default);
+
+/// The compiled function executed by a FunctionNode
+#[derive(Debug, Clone)]
+pub enum NodeFunction {
+    /// VRL program, and whether it is applied over the array of all records
+    Vrl(VRLResultResolver, bool),
+    Wasm(WasmFunction),
+}
+
 #[async_trait]
 pub trait PipelineExt: Sync + Send + 'static {
     /// Registers the function of all the FunctionNode of this pipeline once for execution.
-    /// Returns a map of node_id -> VRLResultResolver for quick lookup
-    async fn register_functions(&self) -> Result<HashMap<String, (VRLResultResolver, bool)>>;
+    /// Returns a map of node_id -> NodeFunction for quick lookup
+    async fn register_functions(&self) -> Result<HashMap<String, NodeFunction>>;
 }
 
 #[async_trait]
 impl PipelineExt for Pipeline {
-    async fn register_functions(&self) -> Result<HashMap<String, (VRLResultResolver, bool)>> {
-        let mut vrl_map = HashMap::new();
+    async fn register_functions(&self) -> Result<HashMap<String, NodeFunction>> {
+        let mut function_map = HashMap::new();
         for node in &self.nodes {
             if let NodeData::Function(func_params) = &node.data {
                 let transform = get_transforms(&self.org, &func_params.name).await?;
+                if transform.is_wasm() {
+                    let wasm_fn = compile_wasm_function(&transform.function)?;
+                    function_map.insert(node.get_node_id(), NodeFunction::Wasm(wasm_fn));
+                    continue;
+                }
                 let vrl_runtime_config = compile_vrl_function(&transform.function, &self.org)?;
                 let registry = vrl_runtime_config
                     .config
                     .get_custom::<vector_enrichment::TableRegistry>()
                     .unwrap();
                 registry.finish_load();
-                vrl_map.insert(
+                function_map.insert(
                     node.get_node_id(),
-                    (
+                    NodeFunction::Vrl(
                         VRLResultResolver {
                             program: vrl_runtime_config.program,
                             fields: vrl_runtime_config.fields,
@@ -80,7 +109,7 @@ impl PipelineExt for Pipeline {
                 );
             }
         }
-        Ok(vrl_map)
+        Ok(function_map)
     }
 }
 
@@ -90,8 +119,9 @@ pub struct ExecutablePipeline {
     name: String,
     source_node_id: String,
     sorted_nodes: Vec<String>,
-    vrl_map: HashMap<String, (VRLResultResolver, bool)>,
+    function_map: HashMap<String, NodeFunction>,
     node_map: HashMap<String, ExecutableNode>,
+    dead_letter: Option<StreamParams>,
 }
 
 #[derive(Debug, Clone)]
@@ -101,6 +131,21 @@ pub struct ExecutableNode {
     children: Vec<String>,
 }
 
+/// A record observed on an edge of the pipeline during a dry run.
+#[derive(Debug)]
+struct TraceEvent {
+    source: String,
+    target: TraceTarget,
+    idx: usize,
+    record: Value,
+}
+
+#[derive(Debug)]
+enum TraceTarget {
+    Node(String),
+    Stream(StreamParams),
+}
+
 #[derive(Debug)]
 pub struct ExecutablePipelineBulkInputs {
     records: Vec<Value>,
@@ -120,6 +165,16 @@ pub struct ExecutablePipelineTraceInputs {
 
 impl ExecutablePipeline {
     pub async fn new(pipeline: &Pipeline) -> Result<Self> {
+        Self::build(pipeline, true).await
+    }
+
+    /// Prepares the pipeline for a dry run. Unlike [ExecutablePipeline::new], initialization
+    /// errors are only returned and not published.
+    pub async fn new_sandbox(pipeline: &Pipeline) -> Result<Self> {
+        Self::build(pipeline, false).await
+    }
+
+    async fn build(pipeline: &Pipeline, publish_errors: bool) -> Result<Self> {
         let node_map = pipeline
             .nodes
             .iter()
@@ -140,8 +195,9 @@ impl ExecutablePipeline {
             })
             .collect();
 
-        let vrl_map = match pipeline.register_functions().await {
-            Ok(vrl_map) => vrl_map,
+        let function_map = match pipeline.register_functions().await {
+            Ok(function_map) => function_map,
+            Err(e) if !publish_errors => return Err(e),
             Err(e) => {
                 let pipeline_error = PipelineError {
                     pipeline_id: pipeline.id.to_string(),
@@ -160,6 +216,7 @@ impl ExecutablePipeline {
         };
         let sorted_nodes = match topological_sort(&node_map) {
             Ok(sorted) => sorted,
+            Err(e) if !publish_errors => return Err(e),
             Err(e) => {
                 let pipeline_error = PipelineError {
                     pipeline_id: pipeline.id.to_string(),
@@ -186,7 +243,8 @@ impl ExecutablePipeline {
             source_node_id,
             node_map,
             sorted_nodes,
-            vrl_map,
+            function_map,
+            dead_letter: pipeline.dead_letter.clone(),
         })
     }
 
@@ -196,6 +254,145 @@ impl ExecutablePipeline {
         records: Vec<Value>,
         stream_name: Option<String>,
     ) -> Result<HashMap<StreamParams, Vec<(usize, Value)>>> {
+        self.execute(org_id, records, stream_name, None)
+            .await
+            .map(|(results, _)| results)
+    }
+
+    /// Dry runs the given records through the pipeline and traces the records received and
+    /// emitted by every node, along with the errors they ran into.
+    ///
+    /// Nothing is written during a dry run: results are not ingested, errors are neither
+    /// published nor written to the dead letter stream, usage is not reported, remote
+    /// destinations are skipped and DedupNode starts from an empty state.
+    pub async fn test_batch(
+        &self,
+        org_id: &str,
+        records: Vec<Value>,
+    ) -> Result<PipelineTestResponse> {
+        let (trace_sender, mut trace_receiver) = unbounded_channel::<TraceEvent>();
+        let trace_task = tokio::spawn(async move {
+            let mut events = Vec::new();
+            while let Some(event) = trace_receiver.recv().await {
+                events.push(event);
+            }
+            events
+        });
+
+        let inputs = records.clone();
+        let (_, failed) = self
+            .execute(org_id, records, None, Some(trace_sender))
+            .await?;
+        let events = trace_task.await.map_err(|e| {
+            log::error!("[Pipeline] trace collecting job failed: {}", e);
+            anyhow!("[Pipeline] trace collecting job failed: {}", e)
+        })?;
+
+        let mut node_results: HashMap<String, PipelineTestNodeResult> = self
+            .node_map
+            .values()
+            .map(|node| {
+                (
+                    node.id.clone(),
+                    PipelineTestNodeResult {
+                        node_id: node.id.clone(),
+                        node_type: node.node_type(),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+
+        if let Some(source) = node_results.get_mut(&self.source_node_id) {
+            source.received = inputs
+                .into_iter()
+                .enumerate()
+                .map(|(idx, record)| PipelineTestRecord {
+                    index: Some(idx),
+                    target: None,
+                    record,
+                })
+                .collect();
+        }
+        for event in events {
+            let index = (event.idx != usize::MAX).then_some(event.idx);
+            let target = match event.target {
+                TraceTarget::Node(child_id) => {
+                    if let Some(child) = node_results.get_mut(&child_id) {
+                        child.received.push(PipelineTestRecord {
+                            index,
+                            target: None,
+                            record: event.record.clone(),
+                        });
+                    }
+                    child_id
+                }
+                TraceTarget::Stream(stream_params) => stream_params.stream_name.to_string(),
+            };
+            if let Some(source) = node_results.get_mut(&event.source) {
+                source.emitted.push(PipelineTestRecord {
+                    index,
+                    target: Some(target),
+                    record: event.record,
+                });
+            }
+        }
+        for (idx, node_id, _, error) in failed {
+            if let Some(node_result) = node_results.get_mut(&node_id) {
+                node_result.errors.push(PipelineTestError {
+                    index: (idx != usize::MAX).then_some(idx),
+                    error,
+                });
+            }
+        }
+
+        let nodes = self
+            .sorted_nodes
+            .iter()
+            .filter_map(|node_id| node_results.remove(node_id))
+            .map(|mut node_result| {
+                // records travel through the nodes concurrently, restore the sample order
+                node_result
+                    .received
+                    .sort_by_key(|r| r.index.unwrap_or(usize::MAX));
+                node_result
+                    .emitted
+                    .sort_by_key(|r| r.index.unwrap_or(usize::MAX));
+                if let Some(NodeData::Condition(_)) = self
+                    .node_map
+                    .get(&node_result.node_id)
+                    .map(|node| &node.node_data)
+                {
+                    let passed: HashSet<_> = node_result.emitted.iter().map(|r| r.index).collect();
+                    node_result.condition_passed = Some(
+                        node_result
+                            .received
+                            .iter()
+                            .map(|r| passed.contains(&r.index))
+                            .collect(),
+                    );
+                }
+                node_result
+            })
+            .collect();
+
+        Ok(PipelineTestResponse { nodes })
+    }
+
+    /// Executes the pipeline. When `trace_sender` is given, the pipeline runs as a sandbox: every
+    /// record sent between nodes is traced and no side effects are performed.
+    #[allow(clippy::type_complexity)]
+    async fn execute(
+        &self,
+        org_id: &str,
+        records: Vec<Value>,
+        stream_name: Option<String>,
+        trace_sender: Option<UnboundedSender<TraceEvent>>,
+    ) -> Result<(
+        HashMap<StreamParams, Vec<(usize, Value)>>,
+        Vec<(usize, String, String, String)>,
+    )> {
+        let sandbox = trace_sender.is_some();
         let batch_size = records.len();
         let pipeline_name = self.name.clone();
         log::debug!(
@@ -204,7 +401,7 @@ impl ExecutablePipeline {
             batch_size
         );
         if batch_size == 0 {
-            return Ok(HashMap::default());
+            return Ok((HashMap::default(), Vec::new()));
         }
 
         // result_channel
@@ -212,7 +409,8 @@ impl ExecutablePipeline {
             channel::<(usize, StreamParams, Value)>(batch_size);
 
         // error_channel
-        let (error_sender, mut error_receiver) = channel::<(String, String, String)>(batch_size);
+        let (error_sender, mut error_receiver) =
+            channel::<(usize, String, String, String)>(batch_size);
 
         let mut node_senders = HashMap::new();
         let mut node_receivers = HashMap::new();
@@ -224,6 +422,7 @@ impl ExecutablePipeline {
         }
 
         // Spawn tasks for each node
+        let source_stream_params = self.get_source_stream_params();
         let mut node_tasks = Vec::new();
         for (idx, node_id) in self.sorted_nodes.iter().enumerate() {
             let pl_id_cp = self.id.to_string();
@@ -233,13 +432,34 @@ impl ExecutablePipeline {
             let child_senders: Vec<_> = node
                 .children
                 .iter()
-                .map(|child| node_senders.get(child).unwrap().clone())
+                .map(|child| {
+                    let child_sender = node_senders.get(child).unwrap().clone();
+                    match &trace_sender {
+                        Some(trace_sender) => tap_edge(
+                            node_id,
+                            child,
+                            child_sender,
+                            trace_sender.clone(),
+                            batch_size,
+                        ),
+                        None => child_sender,
+                    }
+                })
                 .collect();
-            let result_sender_cp = node.children.is_empty().then_some(result_sender.clone());
+            let result_sender_cp = node.children.is_empty().then(|| match &trace_sender {
+                Some(trace_sender) => tap_results(
+                    node_id,
+                    result_sender.clone(),
+                    trace_sender.clone(),
+                    batch_size,
+                ),
+                None => result_sender.clone(),
+            });
             let error_sender_cp = error_sender.clone();
-            let vrl_runtime = self.vrl_map.get(node_id).cloned();
+            let node_function = self.function_map.get(node_id).cloned();
             let pipeline_name = pipeline_name.clone();
             let stream_name = stream_name.clone();
+            let source_stream_params = source_stream_params.clone();
 
             // WARN: Do not change. Processing node can only be done in a task, as the internals of
             // remote wal writer depends on the task id.
@@ -251,16 +471,20 @@ impl ExecutablePipeline {
                     node,
                     node_receiver,
                     child_senders,
-                    vrl_runtime,
+                    node_function,
                     result_sender_cp,
                     error_sender_cp,
                     pipeline_name,
                     stream_name,
+                    source_stream_params,
+                    sandbox,
                 )
                 .await
             });
             node_tasks.push(task);
         }
+        // taps hold their own clones of the trace sender
+        drop(trace_sender);
 
         // task to collect results
         let result_task = tokio::spawn(async move {
@@ -280,21 +504,29 @@ impl ExecutablePipeline {
 
         // task to collect errors
         let mut pipeline_error = PipelineError::new(&self.id, &self.name);
+        let capture_failed = self.dead_letter.is_some() || sandbox;
         let error_task = tokio::spawn(async move {
             log::debug!("[Pipeline]: starts error collecting job");
             let mut count = 0;
-            while let Some((node_id, node_type, error)) = error_receiver.recv().await {
+            let mut failed = Vec::new();
+            while let Some((idx, node_id, node_type, error)) = error_receiver.recv().await {
+                if capture_failed {
+                    failed.push((idx, node_id.clone(), node_type.clone(), error.clone()));
+                }
                 pipeline_error.add_node_error(node_id, node_type, error);
                 count += 1;
             }
             log::debug!("[Pipeline]: collected {count} errors");
             if count > 0 {
-                Some(pipeline_error)
+                (Some(pipeline_error), failed)
             } else {
-                None
+                (None, failed)
             }
         });
 
+        // keep the original records to write failed ones to the dead letter stream
+        let originals = (self.dead_letter.is_some() && !sandbox).then(|| records.clone());
+
         // Send records to the source node to begin processing
         let flattened = {
             let source_node = self.node_map.get(&self.source_node_id).unwrap();
@@ -320,15 +552,36 @@ impl ExecutablePipeline {
             log::error!("[Pipeline] node processing jobs failed: {}", e);
         }
 
-        // Publish errors if received any
-        if let Some(pipeline_errors) = error_task.await.map_err(|e| {
+        let (pipeline_errors, failed) = error_task.await.map_err(|e| {
             log::error!("[Pipeline] error collecting job failed: {}", e);
             anyhow!("[Pipeline] error collecting job failed: {}", e)
-        })? {
-            let stream_params = self.get_source_stream_params();
+        })?;
+
+        // Write failed records to the dead letter stream if configured
+        if let (Some(dead_letter), Some(originals)) = (&self.dead_letter, originals) {
+            if !failed.is_empty() {
+                let now = Utc::now().timestamp_micros();
+                let dead_letters = failed
+                    .iter()
+                    .map(|(idx, node_id, node_type, error)| DeadLetterRecord {
+                        _timestamp: now,
+                        pipeline_id: self.id.clone(),
+                        pipeline_name: self.name.clone(),
+                        node_id: node_id.clone(),
+                        node_type: node_type.clone(),
+                        error: error.clone(),
+                        payload: originals.get(*idx).map(|record| record.to_string()),
+                    })
+                    .collect();
+                dead_letter::publish(dead_letter.clone(), dead_letters).await;
+            }
+        }
+
+        // Publish errors if received any
+        if let Some(pipeline_errors) = pipeline_errors.filter(|_| !sandbox) {
             let error_data = ErrorData {
                 _timestamp: Utc::now().timestamp_micros(),
-                stream_params,
+                stream_params: source_stream_params,
                 error_source: ErrorSource::Pipeline(pipeline_errors),
             };
             log::debug!("[Pipeline]: execution errors occurred and published");
@@ -340,7 +593,7 @@ impl ExecutablePipeline {
             anyhow!("[Pipeline] result collecting job failed: {}", e)
         })?;
 
-        Ok(results)
+        Ok((results, failed))
     }
 
     pub fn get_all_destination_streams(&self) -> Vec<StreamParams> {
@@ -399,6 +652,8 @@ impl std::fmt::Display for ExecutableNode {
             NodeData::Function(_) => write!(f, "function"),
             NodeData::Condition(_) => write!(f, "condition"),
             NodeData::RemoteStream(_) => write!(f, "remote_stream"),
+            NodeData::Sampling(_) => write!(f, "sampling"),
+            NodeData::Dedup(_) => write!(f, "dedup"),
         }
     }
 }
@@ -501,11 +756,13 @@ async fn process_node(
     node: ExecutableNode,
     mut receiver: Receiver<(usize, Value, bool)>,
     mut child_senders: Vec<Sender<(usize, Value, bool)>>,
-    vrl_runtime: Option<(VRLResultResolver, bool)>,
+    node_function: Option<NodeFunction>,
     result_sender: Option<Sender<(usize, StreamParams, Value)>>,
-    error_sender: Sender<(String, String, String)>,
+    error_sender: Sender<(usize, String, String, String)>,
     pipeline_name: String,
     stream_name: Option<String>,
+    source_stream_params: StreamParams,
+    sandbox: bool,
 ) -> Result<()> {
     let cfg = config::get_config();
     let mut count: usize = 0;
@@ -529,7 +786,7 @@ async fn process_node(
                             Err(e) => {
                                 let err_msg = format!("LeafNode error with flattening: {}", e);
                                 if let Err(send_err) = error_sender
-                                    .send((node.id.to_string(), node.node_type(), err_msg))
+                                    .send((idx, node.id.to_string(), node.node_type(), err_msg))
                                     .await
                                 {
                                     log::error!(
@@ -565,7 +822,7 @@ async fn process_node(
                                 };
                                 log::warn!("{err_msg}");
                                 if let Err(send_err) = error_sender
-                                    .send((node.id.to_string(), node.node_type(), err_msg))
+                                    .send((idx, node.id.to_string(), node.node_type(), err_msg))
                                     .await
                                 {
                                     log::error!(
@@ -617,7 +874,7 @@ async fn process_node(
                         Err(e) => {
                             let err_msg = format!("ConditionNode error with flattening: {}", e);
                             if let Err(send_err) = error_sender
-                                .send((node.id.to_string(), node.node_type(), err_msg))
+                                .send((idx, node.id.to_string(), node.node_type(), err_msg))
                                 .await
                             {
                                 log::error!(
@@ -648,13 +905,138 @@ async fn process_node(
             }
             log::debug!("[Pipeline]: cond node {node_idx} done processing {count} records");
         }
+        NodeData::Sampling(sampling_params) => {
+            log::debug!("[Pipeline]: sampling node {node_idx} starts processing");
+            let mut dropped: usize = 0;
+            while let Some((idx, mut record, mut flattened)) = receiver.recv().await {
+                // value must be flattened before sampling rules can take effect
+                if !flattened {
+                    record = match flatten::flatten_with_level(
+                        record,
+                        cfg.limit.ingest_flatten_level,
+                    ) {
+                        Ok(flattened) => flattened,
+                        Err(e) => {
+                            let err_msg = format!("SamplingNode error with flattening: {}", e);
+                            if let Err(send_err) = error_sender
+                                .send((idx, node.id.to_string(), node.node_type(), err_msg))
+                                .await
+                            {
+                                log::error!(
+                                    "[Pipeline] {} : SamplingNode failed sending errors for collection caused by: {send_err}",
+                                    pipeline_name
+                                );
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                    flattened = true;
+                }
+                count += 1;
+                if sampling_params.should_keep(record.as_object().unwrap(), rand::random::<f64>()) {
+                    send_to_children(&mut child_senders, (idx, record, flattened), "SamplingNode")
+                        .await;
+                } else {
+                    dropped += 1;
+                }
+            }
+            if !sandbox {
+                report_dropped_records(
+                    &org_id,
+                    &source_stream_params,
+                    &pipeline_name,
+                    &node,
+                    count,
+                    dropped,
+                )
+                .await;
+            }
+            log::debug!(
+                "[Pipeline]: sampling node {node_idx} done processing {count} records, dropped {dropped}"
+            );
+        }
+        NodeData::Dedup(dedup_params) => {
+            log::debug!("[Pipeline]: dedup node {node_idx} starts processing");
+            let cache_key = format!("{pipeline_id}/{}", node.id);
+            let window = dedup_params.window_secs * 1_000_000;
+            let max_keys = cfg.pipeline.dedup_max_keys;
+            // a dry run starts from, and only updates, an empty state
+            let mut sandbox_seen = HashMap::new();
+            // evict keys that fell out of the window before processing this batch
+            if let Some(mut seen) = DEDUP_CACHE.get_mut(&cache_key).filter(|_| !sandbox) {
+                let now = Utc::now().timestamp_micros();
+                seen.retain(|_, first_seen| now - *first_seen < window);
+            }
+            let mut dropped: usize = 0;
+            while let Some((idx, mut record, mut flattened)) = receiver.recv().await {
+                // value must be flattened before dedup fields can be looked up
+                if !flattened {
+                    record = match flatten::flatten_with_level(
+                        record,
+                        cfg.limit.ingest_flatten_level,
+                    ) {
+                        Ok(flattened) => flattened,
+                        Err(e) => {
+                            let err_msg = format!("DedupNode error with flattening: {}", e);
+                            if let Err(send_err) = error_sender
+                                .send((idx, node.id.to_string(), node.node_type(), err_msg))
+                                .await
+                            {
+                                log::error!(
+                                    "[Pipeline] {} : DedupNode failed sending errors for collection caused by: {send_err}",
+                                    pipeline_name
+                                );
+                                break;
+                            }
+                            continue;
+                        }
+                    };
+                    flattened = true;
+                }
+                count += 1;
+                let key_hash = dedup_params.key_hash(record.as_object().unwrap());
+                let now = Utc::now().timestamp_micros();
+                let is_duplicate = if sandbox {
+                    check_and_mark_seen(&mut sandbox_seen, key_hash, now, window, max_keys)
+                } else {
+                    check_and_mark_seen(
+                        &mut DEDUP_CACHE.entry(cache_key.clone()).or_default(),
+                        key_hash,
+                        now,
+                        window,
+                        max_keys,
+                    )
+                };
+                if is_duplicate {
+                    dropped += 1;
+                } else {
+                    send_to_children(&mut child_senders, (idx, record, flattened), "DedupNode")
+                        .await;
+                }
+            }
+            if !sandbox {
+                report_dropped_records(
+                    &org_id,
+                    &source_stream_params,
+                    &pipeline_name,
+                    &node,
+                    count,
+                    dropped,
+                )
+                .await;
+            }
+            log::debug!(
+                "[Pipeline]: dedup node {node_idx} done processing {count} records, dropped {dropped}"
+            );
+        }
         NodeData::Function(func_params) => {
             log::debug!("[Pipeline]: func node {node_idx} starts processing");
             let mut runtime = crate::service::ingestion::init_functions_runtime();
             let stream_name = stream_name.unwrap_or("pipeline".to_string());
             let mut result_array_records = Vec::new();
             while let Some((idx, mut record, mut flattened)) = receiver.recv().await {
-                if let Some((vrl_runtime, is_result_array_vrl)) = &vrl_runtime {
+                if let Some(node_function) = &node_function {
                     if func_params.after_flatten && !flattened {
                         record = match flatten::flatten_with_level(
                             record,
@@ -664,7 +1046,7 @@ async fn process_node(
                             Err(e) => {
                                 let err_msg = format!("FunctionNode error with flattening: {}", e);
                                 if let Err(send_err) = error_sender
-                                    .send((node.id.to_string(), node.node_type(), err_msg))
+                                    .send((idx, node.id.to_string(), node.node_type(), err_msg))
                                     .await
                                 {
                                     log::error!(
@@ -677,45 +1059,77 @@ async fn process_node(
                             }
                         };
                     }
-                    if !is_result_array_vrl {
-                        record = match apply_vrl_fn(
-                            &mut runtime,
-                            vrl_runtime,
-                            record,
-                            &org_id,
-                            &[stream_name.clone()],
-                        ) {
-                            (res, None) => res,
-                            (res, Some(error)) => {
-                                let err_msg = format!("FunctionNode error: {}", error);
-                                if let Err(send_err) = error_sender
-                                    .send((node.id.to_string(), node.node_type(), err_msg))
-                                    .await
-                                {
-                                    log::error!(
-                                        "[Pipeline] {} : FunctionNode failed sending errors for collection caused by: {send_err}",
-                                        pipeline_name
-                                    );
-                                    break;
+                    match node_function {
+                        NodeFunction::Wasm(wasm_fn) => {
+                            let records = match wasm_fn.apply(&record) {
+                                Ok(records) => records,
+                                Err(e) => {
+                                    let err_msg = format!("FunctionNode error: {}", e);
+                                    if let Err(send_err) = error_sender
+                                        .send((idx, node.id.to_string(), node.node_type(), err_msg))
+                                        .await
+                                    {
+                                        log::error!(
+                                            "[Pipeline] {} : FunctionNode failed sending errors for collection caused by: {send_err}",
+                                            pipeline_name
+                                        );
+                                        break;
+                                    }
+                                    // same as vrl, pass on the original record
+                                    vec![record]
                                 }
-                                res
+                            };
+                            for record in records {
+                                // wasm functions produce unflattened data
+                                send_to_children(
+                                    &mut child_senders,
+                                    (idx, record, false),
+                                    "FunctionNode",
+                                )
+                                .await;
                             }
-                        };
-                        flattened = false; // since apply_vrl_fn can produce unflattened data
-                        send_to_children(
-                            &mut child_senders,
-                            (idx, record, flattened),
-                            "FunctionNode",
-                        )
-                        .await;
-                    } else {
-                        result_array_records.push(record);
+                        }
+                        NodeFunction::Vrl(vrl_runtime, false) => {
+                            record = match apply_vrl_fn(
+                                &mut runtime,
+                                vrl_runtime,
+                                record,
+                                &org_id,
+                                &[stream_name.clone()],
+                            ) {
+                                (res, None) => res,
+                                (res, Some(error)) => {
+                                    let err_msg = format!("FunctionNode error: {}", error);
+                                    if let Err(send_err) = error_sender
+                                        .send((idx, node.id.to_string(), node.node_type(), err_msg))
+                                        .await
+                                    {
+                                        log::error!(
+                                            "[Pipeline] {} : FunctionNode failed sending errors for collection caused by: {send_err}",
+                                            pipeline_name
+                                        );
+                                        break;
+                                    }
+                                    res
+                                }
+                            };
+                            flattened = false; // since apply_vrl_fn can produce unflattened data
+                            send_to_children(
+                                &mut child_senders,
+                                (idx, record, flattened),
+                                "FunctionNode",
+                            )
+                            .await;
+                        }
+                        NodeFunction::Vrl(_, true) => {
+                            result_array_records.push(record);
+                        }
                     }
                 }
                 count += 1;
             }
             if !result_array_records.is_empty() {
-                if let Some((vrl_runtime, true)) = &vrl_runtime {
+                if let Some(NodeFunction::Vrl(vrl_runtime, true)) = &node_function {
                     let result = match apply_vrl_fn(
                         &mut runtime,
                         vrl_runtime,
@@ -727,7 +1141,7 @@ async fn process_node(
                         (res, Some(error)) => {
                             let err_msg = format!("FunctionNode error: {}", error);
                             if let Err(send_err) = error_sender
-                                .send((node.id.to_string(), node.node_type(), err_msg))
+                                .send((usize::MAX, node.id.to_string(), node.node_type(), err_msg))
                                 .await
                             {
                                 log::error!(
@@ -775,7 +1189,7 @@ async fn process_node(
             let max_ts = (Utc::now()
                 + chrono::Duration::try_hours(cfg.limit.ingest_allowed_in_future).unwrap())
             .timestamp_micros();
-            while let Some((_, mut record, flattened)) = receiver.recv().await {
+            while let Some((idx, mut record, flattened)) = receiver.recv().await {
                 // handle timestamp before sending to remote_write service
                 if !flattened {
                     record = match flatten::flatten_with_level(
@@ -786,7 +1200,7 @@ async fn process_node(
                         Err(e) => {
                             let err_msg = format!("DestinationNode error with flattening: {}", e);
                             if let Err(send_err) = error_sender
-                                .send((node.id.to_string(), node.node_type(), err_msg))
+                                .send((idx, node.id.to_string(), node.node_type(), err_msg))
                                 .await
                             {
                                 log::error!(
@@ -804,7 +1218,7 @@ async fn process_node(
                 {
                     let err_msg = format!("DestinationNode error handling timestamp: {}", e);
                     if let Err(send_err) = error_sender
-                        .send((node.id.to_string(), node.node_type(), err_msg))
+                        .send((idx, node.id.to_string(), node.node_type(), err_msg))
                         .await
                     {
                         log::error!(
@@ -819,7 +1233,7 @@ async fn process_node(
                 count += 1;
             }
 
-            if !records.is_empty() {
+            if !records.is_empty() && !sandbox {
                 let mut remote_stream = remote_stream.clone();
                 remote_stream.org_id = org_id.into();
                 let writer = get_pipeline_wal_writer(&pipeline_id, remote_stream).await?;
@@ -829,7 +1243,7 @@ async fn process_node(
                         e
                     );
                     if let Err(send_err) = error_sender
-                        .send((node.id.to_string(), node.node_type(), err_msg))
+                        .send((usize::MAX, node.id.to_string(), node.node_type(), err_msg))
                         .await
                     {
                         log::error!(
@@ -846,7 +1260,7 @@ async fn process_node(
             let err_msg = "[Pipeline]: remote destination is not supported in open source version. Records dropped".to_string();
             log::error!("{err_msg}");
             if let Err(send_err) = error_sender
-                .send((node.id.to_string(), node.node_type(), err_msg))
+                .send((usize::MAX, node.id.to_string(), node.node_type(), err_msg))
                 .await
             {
                 log::error!(
@@ -885,6 +1299,125 @@ async fn send_to_children(
     }
 }
 
+/// Returns true if the key was seen within the window, otherwise marks it as seen now. When more
+/// than `max_keys` keys are seen, the oldest tenth of them is evicted.
+fn check_and_mark_seen(
+    seen: &mut HashMap<u64, i64>,
+    key_hash: u64,
+    now: i64,
+    window: i64,
+    max_keys: usize,
+) -> bool {
+    match seen.get(&key_hash) {
+        Some(first_seen) if now - *first_seen < window => true,
+        _ => {
+            seen.insert(key_hash, now);
+            if max_keys > 0 && seen.len() > max_keys {
+                let evicted = (seen.len() - max_keys).max(max_keys / 10);
+                let mut oldest = seen
+                    .iter()
+                    .map(|(key, first_seen)| (*first_seen, *key))
+                    .collect::<Vec<_>>();
+                oldest.select_nth_unstable(evicted - 1);
+                for (_, key) in &oldest[..evicted] {
+                    seen.remove(key);
+                }
+            }
+            false
+        }
+    }
+}
+
+/// Removes the dedup state of the DedupNodes of the pipeline, as the nodes may have changed.
+pub fn remove_dedup_state(pipeline_id: &str) {
+    let prefix = format!("{pipeline_id}/");
+    DEDUP_CACHE.retain(|cache_key, _| !cache_key.starts_with(&prefix));
+}
+
+/// Puts a forwarding task between a node and one of its children that traces every record sent
+/// over the edge.
+fn tap_edge(
+    source: &str,
+    target: &str,
+    child_sender: Sender<(usize, Value, bool)>,
+    trace_sender: UnboundedSender<TraceEvent>,
+    buffer: usize,
+) -> Sender<(usize, Value, bool)> {
+    let (sender, mut receiver) = channel::<(usize, Value, bool)>(buffer);
+    let source = source.to_string();
+    let target = target.to_string();
+    tokio::spawn(async move {
+        while let Some(item) = receiver.recv().await {
+            let _ = trace_sender.send(TraceEvent {
+                source: source.clone(),
+                target: TraceTarget::Node(target.clone()),
+                idx: item.0,
+                record: item.1.clone(),
+            });
+            if child_sender.send(item).await.is_err() {
+                break;
+            }
+        }
+    });
+    sender
+}
+
+/// Puts a forwarding task between a leaf node and the result collection that traces every
+/// record the leaf node emits.
+fn tap_results(
+    source: &str,
+    result_sender: Sender<(usize, StreamParams, Value)>,
+    trace_sender: UnboundedSender<TraceEvent>,
+    buffer: usize,
+) -> Sender<(usize, StreamParams, Value)> {
+    let (sender, mut receiver) = channel::<(usize, StreamParams, Value)>(buffer);
+    let source = source.to_string();
+    tokio::spawn(async move {
+        while let Some(item) = receiver.recv().await {
+            let _ = trace_sender.send(TraceEvent {
+                source: source.clone(),
+                target: TraceTarget::Stream(item.1.clone()),
+                idx: item.0,
+                record: item.2.clone(),
+            });
+            if result_sender.send(item).await.is_err() {
+                break;
+            }
+        }
+    });
+    sender
+}
+
+/// Reports the number of records a filtering node dropped as pipeline usage.
+async fn report_dropped_records(
+    org_id: &str,
+    stream_params: &StreamParams,
+    pipeline_name: &str,
+    node: &ExecutableNode,
+    received: usize,
+    dropped: usize,
+) {
+    if received == 0 {
+        return;
+    }
+    let req_stats = RequestStats {
+        records: received as i64,
+        dropped_records: dropped as i64,
+        function: Some(format!("{pipeline_name}/{}/{}", node.node_type(), node.id)),
+        ..Default::default()
+    };
+    report_request_usage_stats(
+        req_stats,
+        org_id,
+        &stream_params.stream_name,
+        stream_params.stream_type,
+        UsageType::Pipeline,
+        0,
+        Utc::now().timestamp_micros(),
+    )
+    .await;
+}
+
 fn topological_sort(node_map: &HashMap<String, ExecutableNode>) -> Result<Vec<String>> {
     let mut result = Vec::new();
     let mut visited = HashSet::new();
@@ -1005,4 +1538,113 @@ mod tests {
         let err1 = resolve_stream_name("{{eulav}}", &record);
         assert!(err1.is_err());
     }
+
+    #[test]
+    fn test_check_and_mark_seen() {
+        let mut seen = HashMap::new();
+        assert!(!check_and_mark_seen(&mut seen, 1, 100, 50, 0));
+        assert!(check_and_mark_seen(&mut seen, 1, 120, 50, 0));
+        // seen again once the window passed
+        assert!(!check_and_mark_seen(&mut seen, 1, 160, 50, 0));
+
+        let mut seen = HashMap::new();
+        for key in 0..20 {
+            check_and_mark_seen(&mut seen, key, key as i64, 1000, 10);
+            assert!(seen.len() <= 10);
+        }
+        // the oldest keys were evicted
+        assert!(!seen.contains_key(&0));
+        assert!(seen.contains_key(&19));
+    }
+
+    #[test]
+    fn test_remove_dedup_state() {
+        DEDUP_CACHE.insert("p1/n1".to_string(), HashMap::from([(1, 1)]));
+        DEDUP_CACHE.insert("p10/n1".to_string(), HashMap::from([(1, 1)]));
+        remove_dedup_state("p1");
+        assert!(!DEDUP_CACHE.contains_key("p1/n1"));
+        assert!(DEDUP_CACHE.contains_key("p10/n1"));
+        DEDUP_CACHE.remove("p10/n1");
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_test_batch() {
+        let payload = json::json!(
+          {
+            "name": "pipeline test",
+            "org": "default",
+            "nodes": [
+              {
+                "id": "1",
+                "data": {
+                  "node_type": "stream",
+                  "org_id": "default",
+                  "stream_name": "default",
+                  "stream_type": "logs"
+                },
+                "position": { "x": 100, "y": 100 },
+                "io_type": "input",
+              },
+              {
+                "id": "2",
+                "data": {
+                  "node_type": "condition",
+                  "conditions": [{ "column": "level", "operator": "=", "value": "error" }]
+                },
+                "position": { "x": 200, "y": 100 },
+                "io_type": "default",
+              },
+              {
+                "id": "3",
+                "data": {
+                  "node_type": "stream",
+                  "org_id": "default",
+                  "stream_name": "errors",
+                  "stream_type": "logs"
+                },
+                "position": { "x": 300, "y": 100 },
+                "io_type": "output",
+              }
+            ],
+            "edges": [
+              { "id": "e1-2", "source": "1", "target": "2" },
+              { "id": "e2-3", "source": "2", "target": "3" }
+            ]
+          }
+        );
+        let mut pipeline = json::from_value::<Pipeline>(payload).unwrap();
+        assert!(pipeline.validate().is_ok());
+        let executable_pipeline = ExecutablePipeline::new_sandbox(&pipeline).await.unwrap();
+        let records = vec![
+            json::json!({ "level": "info", "msg": "a" }),
+            json::json!({ "level": "error", "msg": "b" }),
+        ];
+        let resp = executable_pipeline
+            .test_batch("default", records)
+            .await
+            .unwrap();
+
+        let node_ids = resp
+            .nodes
+            .iter()
+            .map(|n| n.node_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(node_ids, vec!["1", "2", "3"]);
+
+        let source = &resp.nodes[0];
+        assert_eq!(source.received.len(), 2);
+        assert_eq!(source.emitted.len(), 2);
+        assert_eq!(source.emitted[0].target.as_deref(), Some("2"));
+
+        let condition = &resp.nodes[1];
+        assert_eq!(condition.received.len(), 2);
+        assert_eq!(condition.condition_passed, Some(vec![false, true]));
+        assert_eq!(condition.emitted.len(), 1);
+        assert_eq!(condition.emitted[0].index, Some(1));
+
+        let destination = &resp.nodes[2];
+        assert_eq!(destination.received.len(), 1);
+        assert_eq!(destination.emitted[0].target.as_deref(), Some("errors"));
+        assert!(destination.errors.is_empty());
+    }
 }
@@ -0,0 +1,424 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{Result, anyhow};
+use config::{
+    TIMESTAMP_COL_NAME,
+    cluster::LOCAL_NODE,
+    get_config,
+    meta::{
+        pipeline::{DeadLetterRecord, DeadLetterReplayResponse, components::PipelineSource},
+        search,
+        stream::{StreamParams, StreamType},
+    },
+    utils::{json, time::now_micros},
+};
+use infra::{dist_lock, errors::DbError};
+use once_cell::sync::Lazy;
+use proto::cluster_rpc;
+use tokio::sync::mpsc;
+
+use crate::{
+    common::meta::ingestion::IngestionRequest,
+    service::{
+        db::{
+            self,
+            pipeline::{self, PipelineError},
+        },
+        search as SearchService,
+    },
+};
+
+const DEAD_LETTER_QUEUE_SIZE: usize = 1024;
+
+/// Number of dead letter records read per search when replaying.
+const REPLAY_PAGE_SIZE: usize = 1000;
+
+/// Failed records more recent than this aren't replayed yet.
+const REPLAY_DELAY_MICROS: i64 = 60_000_000;
+
+/// Key prefix of the time ranges of the dead letter stream already replayed, per pipeline.
+const REPLAYED_RANGES_PREFIX: &str = "/pipeline_dead_letter/replayed/";
+
+/// Failed records are handed over to a background job so that writing them never blocks, nor
+/// recurses into, the pipeline execution that produced them.
+static DEAD_LETTER_QUEUE: Lazy<mpsc::Sender<(StreamParams, Vec<json::Value>)>> =
+    Lazy::new(initialize_dead_letter_queue);
+
+fn initialize_dead_letter_queue() -> mpsc::Sender<(StreamParams, Vec<json::Value>)> {
+    let (msg_sender, mut msg_receiver) =
+        mpsc::channel::<(StreamParams, Vec<json::Value>)>(DEAD_LETTER_QUEUE_SIZE);
+    tokio::task::spawn(async move {
+        log::debug!("[Pipeline] dead letter job starts waiting for failed records");
+        while let Some((stream_params, records)) = msg_receiver.recv().await {
+            if let Err(e) = ingest_records(&stream_params, records).await {
+                log::error!(
+                    "[Pipeline] failed to write records to dead letter stream {}: {e}",
+                    stream_params
+                );
+            }
+        }
+    });
+    msg_sender
+}
+
+/// Queues the failed records to be written into the given dead letter stream.
+pub async fn publish(stream_params: StreamParams, records: Vec<DeadLetterRecord>) {
+    let records = records
+        .into_iter()
+        .map(|record| json::to_value(record).unwrap())
+        .collect::<Vec<_>>();
+    if let Err(e) = DEAD_LETTER_QUEUE.send((stream_params, records)).await {
+        log::error!("[Pipeline] failed to queue records for dead letter stream: {e}");
+    }
+}
+
+/// Reads the failed records of the pipeline captured within the given time range from its dead
+/// letter stream and ingests their original payloads into the pipeline's source stream again.
+/// The payloads run through the whole pipeline, not only the node which failed, so the other
+/// branches of the pipeline get them twice. Callers must have the user opt into this.
+///
+/// The time ranges already replayed are remembered and skipped, so that replaying a range twice
+/// doesn't ingest its records again. At most `ZO_PIPELINE_DEAD_LETTER_REPLAY_MAX_RECORDS` are
+/// replayed per call, the response tells whether records are left to replay in the range.
+///
+/// Only realtime pipelines with a logs source stream can be replayed.
+pub async fn replay(
+    org_id: &str,
+    pipeline_id: &str,
+    start_time: i64,
+    end_time: i64,
+) -> Result<DeadLetterReplayResponse, PipelineError> {
+    let Ok(pipeline) = pipeline::get_by_id(pipeline_id).await else {
+        return Err(PipelineError::NotFound(pipeline_id.to_string()));
+    };
+    if pipeline.org != org_id {
+        return Err(PipelineError::NotFound(pipeline_id.to_string()));
+    }
+    let Some(dead_letter) = pipeline.dead_letter.as_ref() else {
+        return Err(PipelineError::DeadLetterReplay(
+            "pipeline has no dead letter destination".to_string(),
+        ));
+    };
+    let source_stream = match &pipeline.source {
+        PipelineSource::Realtime(stream_params)
+            if stream_params.stream_type == StreamType::Logs =>
+        {
+            stream_params.clone()
+        }
+        _ => {
+            return Err(PipelineError::DeadLetterReplay(
+                "only realtime pipelines with a logs source stream can be replayed".to_string(),
+            ));
+        }
+    };
+    if start_time >= end_time {
+        return Err(PipelineError::DeadLetterReplay(
+            "start_time must be before end_time".to_string(),
+        ));
+    }
+    // the latest failed records may still be on their way to the dead letter stream, and would
+    // be skipped by the next replays once their time range is replayed
+    let end_time = end_time.min(now_micros() - REPLAY_DELAY_MICROS);
+    if start_time >= end_time {
+        return Ok(DeadLetterReplayResponse {
+            replayed: 0,
+            has_more: false,
+        });
+    }
+
+    let lock_key = format!("{REPLAYED_RANGES_PREFIX}{}", pipeline.id);
+    let locker = dist_lock::lock(&lock_key, 0).await?;
+    let ret = replay_ranges(
+        org_id,
+        &pipeline.id,
+        &dead_letter.stream_name,
+        &source_stream,
+        start_time,
+        end_time,
+    )
+    .await;
+    dist_lock::unlock(&locker).await?;
+    ret
+}
+
+/// Removes the replayed time ranges of a deleted pipeline.
+pub async fn delete_replayed_ranges(pipeline_id: &str) -> Result<(), PipelineError> {
+    let key = format!("{REPLAYED_RANGES_PREFIX}{pipeline_id}");
+    db::delete_if_exists(&key, false, db::NO_NEED_WATCH).await?;
+    Ok(())
+}
+
+async fn replay_ranges(
+    org_id: &str,
+    pipeline_id: &str,
+    dead_letter_stream: &str,
+    source_stream: &StreamParams,
+    start_time: i64,
+    end_time: i64,
+) -> Result<DeadLetterReplayResponse, PipelineError> {
+    let max_records = get_config().pipeline.dead_letter_replay_max_records;
+    let key = format!("{REPLAYED_RANGES_PREFIX}{pipeline_id}");
+    let mut replayed_ranges = match db::get(&key).await {
+        Ok(value) => json::from_slice::<Vec<(i64, i64)>>(&value)
+            .map_err(|e| PipelineError::DeadLetterReplay(e.to_string()))?,
+        Err(infra::errors::Error::DbError(DbError::KeyNotExists(_))) => vec![],
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut replayed = 0;
+    for (range_start, range_end) in uncovered_ranges(&replayed_ranges, start_time, end_time) {
+        let mut from = range_start;
+        while from < range_end {
+            let limit = max_records.saturating_sub(replayed).min(REPLAY_PAGE_SIZE);
+            // read one more record than needed, to know whether the last timestamp is complete
+            let mut records = read_payloads(
+                org_id,
+                pipeline_id,
+                dead_letter_stream,
+                from,
+                range_end,
+                limit + 1,
+            )
+            .await?;
+            if limit == 0 {
+                return Ok(DeadLetterReplayResponse {
+                    replayed,
+                    has_more: !records.is_empty(),
+                });
+            }
+            let next_from = match page_end(&records, limit) {
+                None => range_end,
+                Some(next_from) => {
+                    records.retain(|(timestamp, _)| *timestamp < next_from);
+                    if records.is_empty() {
+                        // all the records of the page share the same timestamp, read them at once
+                        records = read_payloads(
+                            org_id,
+                            pipeline_id,
+                            dead_letter_stream,
+                            from,
+                            next_from + 1,
+                            max_records + 1,
+                        )
+                        .await?;
+                        if records.len() > max_records {
+                            return Err(PipelineError::DeadLetterReplay(format!(
+                                "more than {max_records} records failed at timestamp {from}, \
+                                 the limit of ZO_PIPELINE_DEAD_LETTER_REPLAY_MAX_RECORDS"
+                            )));
+                        }
+                        next_from + 1
+                    } else {
+                        next_from
+                    }
+                }
+            };
+            replayed += records.len();
+            let records = records
+                .into_iter()
+                .map(|(_, payload)| payload)
+                .collect::<Vec<_>>();
+            ingest_records(source_stream, records)
+                .await
+                .map_err(|e| PipelineError::DeadLetterReplay(e.to_string()))?;
+            add_replayed_range(&mut replayed_ranges, from, next_from);
+            db::put(
+                &key,
+                json::to_vec(&replayed_ranges).unwrap().into(),
+                db::NO_NEED_WATCH,
+                None,
+            )
+            .await?;
+            from = next_from;
+        }
+    }
+    Ok(DeadLetterReplayResponse {
+        replayed,
+        has_more: false,
+    })
+}
+
+/// Reads the payloads of the failed records of the pipeline in `[start_time, end_time)`, oldest
+/// first, along with their timestamp.
+async fn read_payloads(
+    org_id: &str,
+    pipeline_id: &str,
+    dead_letter_stream: &str,
+    start_time: i64,
+    end_time: i64,
+    size: usize,
+) -> Result<Vec<(i64, json::Value)>, PipelineError> {
+    let query = search::Query {
+        sql: format!(
+            "SELECT _timestamp, payload FROM \"{dead_letter_stream}\" WHERE pipeline_id = '{}' AND payload IS NOT NULL ORDER BY _timestamp ASC",
+            pipeline_id.replace('\'', "''")
+        ),
+        start_time,
+        end_time,
+        size: size as i64,
+        ..Default::default()
+    };
+    let req = search::Request {
+        query,
+        encoding: search::RequestEncoding::Empty,
+        regions: vec![],
+        clusters: vec![],
+        timeout: 0,
+        search_type: None,
+        search_event_context: None,
+        use_cache: false,
+        local_mode: None,
+    };
+    let trace_id = config::ider::generate_trace_id();
+    let resp = SearchService::search(&trace_id, org_id, StreamType::Logs, None, &req)
+        .await
+        .map_err(|e| PipelineError::DeadLetterReplay(e.to_string()))?;
+
+    Ok(resp
+        .hits
+        .iter()
+        .filter_map(|hit| {
+            let timestamp = hit.get(TIMESTAMP_COL_NAME)?.as_i64()?;
+            let payload = json::from_str::<json::Value>(hit.get("payload")?.as_str()?).ok()?;
+            Some((timestamp, payload))
+        })
+        .collect())
+}
+
+/// Given the records read for a page of `limit` records, oldest first, returns the timestamp
+/// the next page starts at, or `None` if there are no more records. Records sharing the
+/// timestamp of the next page are left for the next page, so that a page always covers whole
+/// timestamps.
+fn page_end<T>(records: &[(i64, T)], limit: usize) -> Option<i64> {
+    records.get(limit).map(|(timestamp, _)| *timestamp)
+}
+
+/// Returns the parts of `[start, end)` which weren't replayed yet. The replayed ranges are sorted
+/// and don't overlap.
+fn uncovered_ranges(replayed: &[(i64, i64)], start: i64, end: i64) -> Vec<(i64, i64)> {
+    let mut uncovered = vec![];
+    let mut from = start;
+    for &(replayed_start, replayed_end) in replayed {
+        if replayed_end <= from {
+            continue;
+        }
+        if replayed_start >= end {
+            break;
+        }
+        if replayed_start > from {
+            uncovered.push((from, replayed_start));
+        }
+        from = replayed_end;
+    }
+    if from < end {
+        uncovered.push((from, end));
+    }
+    uncovered
+}
+
+/// Adds `[start, end)` to the replayed ranges, merging overlapping and adjacent ranges.
+fn add_replayed_range(replayed: &mut Vec<(i64, i64)>, start: i64, end: i64) {
+    replayed.push((start, end));
+    replayed.sort_unstable();
+    let mut merged: Vec<(i64, i64)> = Vec::with_capacity(replayed.len());
+    for &(range_start, range_end) in replayed.iter() {
+        match merged.last_mut() {
+            Some(last) if range_start <= last.1 => last.1 = last.1.max(range_end),
+            _ => merged.push((range_start, range_end)),
+        }
+    }
+    *replayed = merged;
+}
+
+async fn ingest_records(stream_params: &StreamParams, records: Vec<json::Value>) -> Result<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    if LOCAL_NODE.is_ingester() {
+        // ingest directly for ingester node
+        let bytes = bytes::Bytes::from(json::to_string(&records).unwrap());
+        let req = IngestionRequest::JSON(&bytes);
+        match crate::service::logs::ingest::ingest(
+            0,
+            &stream_params.org_id,
+            &stream_params.stream_name,
+            req,
+            "",
+            None,
+        )
+        .await
+        {
+            Ok(resp) if resp.code == 200 => Ok(()),
+            error => Err(anyhow!(error.map_or_else(
+                |e| e.to_string(),
+                |resp| resp.error.unwrap_or_default()
+            ))),
+        }
+    } else {
+        // call gRPC ingestion service
+        let req = cluster_rpc::IngestionRequest {
+            org_id: stream_params.org_id.to_string(),
+            stream_name: stream_params.stream_name.to_string(),
+            stream_type: stream_params.stream_type.to_string(),
+            data: Some(cluster_rpc::IngestionData::from(records)),
+            ingestion_type: Some(cluster_rpc::IngestionType::Json.into()),
+            metadata: None,
+        };
+        match crate::service::ingestion::ingestion_service::ingest(req).await {
+            Ok(resp) if resp.status_code == 200 => Ok(()),
+            error => Err(anyhow!(
+                error.map_or_else(|e| e.to_string(), |resp| resp.message)
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uncovered_ranges() {
+        assert_eq!(uncovered_ranges(&[], 0, 100), vec![(0, 100)]);
+        let replayed = vec![(10, 20), (30, 40)];
+        assert_eq!(
+            uncovered_ranges(&replayed, 0, 100),
+            vec![(0, 10), (20, 30), (40, 100)]
+        );
+        assert_eq!(uncovered_ranges(&replayed, 12, 35), vec![(20, 30)]);
+        assert!(uncovered_ranges(&replayed, 10, 20).is_empty());
+    }
+
+    #[test]
+    fn test_add_replayed_range() {
+        let mut replayed = vec![];
+        add_replayed_range(&mut replayed, 30, 40);
+        add_replayed_range(&mut replayed, 10, 20);
+        assert_eq!(replayed, vec![(10, 20), (30, 40)]);
+        // adjacent and overlapping ranges are merged
+        add_replayed_range(&mut replayed, 20, 25);
+        add_replayed_range(&mut replayed, 24, 35);
+        assert_eq!(replayed, vec![(10, 40)]);
+    }
+
+    #[test]
+    fn test_page_end() {
+        let records = vec![(1, ()), (2, ()), (2, ())];
+        assert_eq!(page_end(&records, 3), None);
+        assert_eq!(page_end(&records, 2), Some(2));
+    }
+}
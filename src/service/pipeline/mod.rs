@@ -13,10 +13,16 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use config::meta::{
-    pipeline::{Pipeline, PipelineList, components::PipelineSource},
-    search::SearchEventType,
-    stream::ListStreamParams,
+use config::{
+    meta::{
+        pipeline::{
+            Pipeline, PipelineList, PipelineTestResponse, PipelineVersion, PipelineVersionDiff,
+            PipelineVersionList, components::PipelineSource,
+        },
+        search::SearchEventType,
+        stream::ListStreamParams,
+    },
+    utils::json,
 };
 
 use super::db::pipeline::{self, PipelineError};
@@ -26,9 +32,14 @@ use crate::common::{
 };
 
 pub mod batch_execution;
+pub mod dead_letter;
 
 #[tracing::instrument(skip(pipeline))]
-pub async fn save_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError> {
+pub async fn save_pipeline(
+    mut pipeline: Pipeline,
+    author: &str,
+    comment: Option<String>,
+) -> Result<(), PipelineError> {
     // check if another realtime pipeline with the same source stream already exists
     if let PipelineSource::Realtime(stream) = &pipeline.source {
         if pipeline::list_streams_with_pipeline(&pipeline.org)
@@ -48,6 +59,7 @@ pub async fn save_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError>
     if let PipelineSource::Scheduled(derived_stream) = &mut pipeline.source {
         derived_stream.query_condition.search_event_type = Some(SearchEventType::DerivedStream);
         derived_stream.org_id = pipeline.org.clone();
+        derived_stream.last_edited_by = Some(author.to_string());
         // save derived_stream to triggers table
         if let Err(e) = super::alerts::derived_streams::save(
             derived_stream.clone(),
@@ -61,7 +73,8 @@ pub async fn save_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError>
         }
     }
 
-    if let Err(e) = pipeline::set(&pipeline).await {
+    let version = PipelineVersion::new(&pipeline, author, comment);
+    if let Err(e) = pipeline::set(&pipeline, &version).await {
         log::error!("Failed to save pipeline: {e}");
         return Err(e);
     }
@@ -69,8 +82,93 @@ pub async fn save_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError>
     Ok(())
 }
 
+/// Dry runs the given pipeline, saved or not, against the sample records without writing
+/// anything.
+#[tracing::instrument(skip(pipeline, records))]
+pub async fn test_pipeline(
+    org_id: &str,
+    mut pipeline: Pipeline,
+    records: Vec<json::Value>,
+) -> Result<PipelineTestResponse, PipelineError> {
+    pipeline.org = org_id.to_string();
+    pipeline
+        .validate()
+        .map_err(|e| PipelineError::InvalidPipeline(e.to_string()))?;
+
+    let executable_pipeline = batch_execution::ExecutablePipeline::new_sandbox(&pipeline)
+        .await
+        .map_err(|e| PipelineError::InvalidPipeline(e.to_string()))?;
+    executable_pipeline
+        .test_batch(org_id, records)
+        .await
+        .map_err(|e| PipelineError::TestRun(e.to_string()))
+}
+
 #[tracing::instrument(skip(pipeline))]
-pub async fn update_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError> {
+pub async fn update_pipeline(
+    pipeline: Pipeline,
+    author: &str,
+    comment: Option<String>,
+) -> Result<(), PipelineError> {
+    apply_update(pipeline, author, comment, false).await
+}
+
+/// Rolls the pipeline back to the given version. The rollback is stored as a new version of the
+/// pipeline and the pipeline keeps its current enabled status.
+#[tracing::instrument]
+pub async fn rollback_pipeline(
+    org_id: &str,
+    pipeline_id: &str,
+    version: i32,
+    author: &str,
+    comment: Option<String>,
+) -> Result<(), PipelineError> {
+    let existing_pipeline = get_org_pipeline(org_id, pipeline_id).await?;
+    let mut pipeline = pipeline::get_version(pipeline_id, version).await?.pipeline;
+    pipeline.version = existing_pipeline.version;
+    pipeline.enabled = existing_pipeline.enabled;
+    let comment = comment.or_else(|| Some(format!("Rolled back to version {version}")));
+    apply_update(pipeline, author, comment, true).await
+}
+
+/// Lists all the versions of the pipeline, latest first.
+#[tracing::instrument]
+pub async fn list_pipeline_versions(
+    org_id: &str,
+    pipeline_id: &str,
+) -> Result<PipelineVersionList, PipelineError> {
+    get_org_pipeline(org_id, pipeline_id).await?;
+    let list = pipeline::list_versions(pipeline_id).await?;
+    Ok(PipelineVersionList { list })
+}
+
+/// Compares two versions of the pipeline.
+#[tracing::instrument]
+pub async fn diff_pipeline_versions(
+    org_id: &str,
+    pipeline_id: &str,
+    from_version: i32,
+    to_version: i32,
+) -> Result<PipelineVersionDiff, PipelineError> {
+    get_org_pipeline(org_id, pipeline_id).await?;
+    let from = pipeline::get_version(pipeline_id, from_version).await?;
+    let to = pipeline::get_version(pipeline_id, to_version).await?;
+    Ok(PipelineVersionDiff::new(&from.pipeline, &to.pipeline))
+}
+
+async fn get_org_pipeline(org_id: &str, pipeline_id: &str) -> Result<Pipeline, PipelineError> {
+    match pipeline::get_by_id(pipeline_id).await {
+        Ok(pipeline) if pipeline.org == org_id => Ok(pipeline),
+        _ => Err(PipelineError::NotFound(pipeline_id.to_string())),
+    }
+}
+
+async fn apply_update(
+    mut pipeline: Pipeline,
+    author: &str,
+    comment: Option<String>,
+    rollback: bool,
+) -> Result<(), PipelineError> {
     let Ok(existing_pipeline) = pipeline::get_by_id(&pipeline.id).await else {
         return Err(PipelineError::NotFound(pipeline.id));
     };
@@ -89,6 +187,13 @@ pub async fn update_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError
         .validate()
         .map_err(|e| PipelineError::InvalidPipeline(e.to_string()))?;
 
+    // the last editor isn't sent along with the source, keep it for the comparison below
+    if let (PipelineSource::Scheduled(existing), PipelineSource::Scheduled(derived_stream)) =
+        (&existing_pipeline.source, &mut pipeline.source)
+    {
+        derived_stream.last_edited_by = existing.last_edited_by.clone();
+    }
+
     // additional checks when the source is changed
     let prev_source_stream = if existing_pipeline.source != pipeline.source {
         // check if the new source exists in another pipeline
@@ -125,6 +230,7 @@ pub async fn update_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError
     // Save DerivedStream details if there's any
     if let PipelineSource::Scheduled(derived_stream) = &mut pipeline.source {
         derived_stream.query_condition.search_event_type = Some(SearchEventType::DerivedStream);
+        derived_stream.last_edited_by = Some(author.to_string());
         if let Err(e) = super::alerts::derived_streams::save(
             derived_stream.clone(),
             &pipeline.name,
@@ -137,7 +243,12 @@ pub async fn update_pipeline(mut pipeline: Pipeline) -> Result<(), PipelineError
         }
     }
 
-    pipeline::update(&pipeline, prev_source_stream).await?;
+    let version = PipelineVersion::new(&pipeline, author, comment);
+    if rollback {
+        pipeline::rollback(&pipeline, prev_source_stream, &version).await?;
+    } else {
+        pipeline::update(&pipeline, prev_source_stream, Some(&version)).await?;
+    }
     Ok(())
 }
 
@@ -175,10 +286,15 @@ pub async fn enable_pipeline(
     org_id: &str,
     pipeline_id: &str,
     value: bool,
+    author: &str,
+    comment: Option<String>,
 ) -> Result<(), PipelineError> {
     let Ok(mut pipeline) = pipeline::get_by_id(pipeline_id).await else {
         return Err(PipelineError::NotFound(pipeline_id.to_string()));
     };
+    if pipeline.enabled == value {
+        return Ok(());
+    }
 
     pipeline.enabled = value;
     // add or remove trigger if it's a scheduled pipeline
@@ -200,7 +316,11 @@ pub async fn enable_pipeline(
         }
     }
 
-    pipeline::update(&pipeline, None).await?;
+    // the toggle is recorded as a new version, so the history matches the stored pipeline
+    pipeline.version += 1;
+    let comment = comment.or_else(|| Some(if value { "Enabled" } else { "Disabled" }.to_string()));
+    let version = PipelineVersion::new(&pipeline, author, comment);
+    pipeline::update(&pipeline, None, Some(&version)).await?;
     Ok(())
 }
 
@@ -224,6 +344,9 @@ pub async fn delete_pipeline(pipeline_id: &str) -> Result<(), PipelineError> {
     }
 
     pipeline::delete(pipeline_id).await?;
+    if let Err(e) = dead_letter::delete_replayed_ranges(pipeline_id).await {
+        log::error!("[Pipeline] error deleting replayed dead letter ranges of {pipeline_id}: {e}");
+    }
     remove_ownership(
         &existing_pipeline.org,
         "pipelines",
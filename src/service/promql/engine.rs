@@ -19,7 +19,10 @@ use arrow::array::Array;
 use async_recursion::async_recursion;
 use config::{
     TIMESTAMP_COL_NAME,
-    meta::promql::{EXEMPLARS_LABEL, HASH_LABEL, HashLabelValue, NAME_LABEL, VALUE_LABEL},
+    meta::{
+        promql::{EXEMPLARS_LABEL, HASH_LABEL, HashLabelValue, NAME_LABEL, VALUE_LABEL},
+        stream::StreamType,
+    },
     utils::json,
 };
 use datafusion::{
@@ -626,6 +629,21 @@ impl Engine {
                 }
             })
             .collect::<Vec<(_, _)>>();
+        // the row filters of the stream that apply to the user, the labels they use must be
+        // loaded even when the query doesn't select them
+        let row_filter = crate::service::search::row_filter::get_stream_row_filter(
+            &self.ctx.org_id,
+            table_name,
+            StreamType::Metrics,
+            &self.ctx.user_id,
+        )
+        .await
+        .map_err(|e| DataFusionError::Execution(e.to_string()))?;
+        let label_selector = if row_filter.is_some() {
+            None
+        } else {
+            self.col_filters.clone()
+        };
         let ctxs = self
             .ctx
             .table_provider
@@ -634,7 +652,7 @@ impl Engine {
                 table_name,
                 (start, end),
                 selector.matchers.clone(),
-                self.col_filters.clone(),
+                label_selector,
                 &mut filters,
             )
             .await?;
@@ -643,6 +661,7 @@ impl Engine {
         for (ctx, schema, scan_stats) in ctxs {
             let selector = selector.clone();
             let col_filters = &self.col_filters;
+            let row_filter = row_filter.as_deref();
             let query_exemplars = self.ctx.query_exemplars;
             let trace_id = self.trace_id.to_string();
             let task = tokio::time::timeout(Duration::from_secs(self.ctx.timeout), async move {
@@ -654,6 +673,7 @@ impl Engine {
                     start,
                     end,
                     col_filters,
+                    row_filter,
                     query_exemplars,
                 )
                 .await
@@ -1121,6 +1141,7 @@ async fn selector_load_data_from_datafusion(
     start: i64,
     end: i64,
     label_selector: &Option<HashSet<String>>,
+    row_filter: Option<&str>,
     query_exemplars: bool,
 ) -> Result<HashMap<HashLabelValue, RangeValue>> {
     let cfg = config::get_config();
@@ -1136,6 +1157,14 @@ async fn selector_load_data_from_datafusion(
         }
     };
 
+    // a row filter on labels the stream doesn't have matches no series
+    if let Some(row_filter) = row_filter {
+        match df_group.parse_sql_expr(row_filter) {
+            Ok(expr) => df_group = df_group.filter(expr)?,
+            Err(_) => return Ok(HashMap::default()),
+        }
+    }
+
     df_group = apply_matchers(df_group, &schema, &selector.matchers)?;
 
     match apply_label_selector(df_group, &schema, label_selector) {
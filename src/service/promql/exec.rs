@@ -48,10 +48,19 @@ pub struct PromqlContext {
     pub scan_stats: Arc<RwLock<ScanStats>>,
     pub timeout: u64, // seconds, query timeout
     pub data_loading: Arc<Mutex<HashSet<String>>>,
+    /// The user running the query, whose row filters apply to the streams. Empty for internal
+    /// queries.
+    pub user_id: String,
 }
 
 impl PromqlContext {
-    pub fn new<P>(org_id: &str, provider: P, query_exemplars: bool, timeout: u64) -> Self
+    pub fn new<P>(
+        org_id: &str,
+        provider: P,
+        query_exemplars: bool,
+        timeout: u64,
+        user_id: &str,
+    ) -> Self
     where
         P: TableProvider,
     {
@@ -69,6 +78,7 @@ impl PromqlContext {
             data_loading: Arc::new(Mutex::new(HashSet::default())),
             scan_stats: Arc::new(RwLock::new(ScanStats::default())),
             timeout,
+            user_id: user_id.to_string(),
         }
     }
 
@@ -40,6 +40,7 @@ pub mod value;
 
 pub use engine::Engine;
 pub use exec::PromqlContext;
+pub(crate) use functions::holt_winters_calculation;
 
 pub(crate) const DEFAULT_LOOKBACK: Duration = Duration::from_secs(300); // 5m
 pub(crate) const MINIMAL_INTERVAL: Duration = Duration::from_secs(1); // 1s
@@ -220,6 +220,7 @@ pub async fn search_inner(
         },
         query.query_exemplars,
         timeout,
+        &req.user_email,
     );
 
     let (value, result_type, mut scan_stats) = if query.query_exemplars {
@@ -43,9 +43,9 @@ use crate::{
         grpc::make_grpc_metrics_client,
         promql::{
             DEFAULT_LOOKBACK, DEFAULT_MAX_POINTS_PER_SERIES, MetricsQueryRequest, adjust_start_end,
-            micros, value::*,
+            micros, name_visitor::MetricNameVisitor, value::*,
         },
-        search::server_internal_error,
+        search::{row_filter, server_internal_error},
         self_reporting::report_request_usage_stats,
     },
 };
@@ -80,9 +80,10 @@ pub async fn search(
 #[tracing::instrument(name = "promql:search:cluster", skip_all, fields(org_id = req.org_id))]
 async fn search_in_cluster(
     trace_id: &str,
-    req: cluster_rpc::MetricsQueryRequest,
+    mut req: cluster_rpc::MetricsQueryRequest,
     user_email: &str,
 ) -> Result<Value> {
+    req.user_email = user_email.to_string();
     let op_start = std::time::Instant::now();
     let started_at = now_micros();
     let cfg = get_config();
@@ -135,13 +136,23 @@ async fn search_in_cluster(
         n => n,
     };
 
+    // users with row filters see other series, so the filters are part of the cache key
+    let cache_query = if cache_disabled {
+        query.to_string()
+    } else {
+        match row_filter_cache_key(&req.org_id, query, user_email).await {
+            Some(filter_key) => format!("{query}-{filter_key}"),
+            None => query.to_string(),
+        }
+    };
+
     // get cache data
     let original_start = start;
     let (start, cached_values) = if cache_disabled {
         (start, vec![])
     } else {
         let start_time = std::time::Instant::now();
-        match cache::get(query, start, end, step).await {
+        match cache::get(&cache_query, start, end, step).await {
             Ok(Some((new_start, values))) => {
                 let took = start_time.elapsed().as_millis() as i32;
                 config::metrics::QUERY_METRICS_CACHE_RATIO
@@ -359,7 +370,7 @@ async fn search_in_cluster(
             if let Err(err) = cache::set(
                 trace_id,
                 &req.org_id,
-                query,
+                &cache_query,
                 original_start,
                 end,
                 step,
@@ -378,6 +389,18 @@ async fn search_in_cluster(
     Ok(values)
 }
 
+/// Builds a key of the row filters that apply to the user in the metrics read by the query.
+async fn row_filter_cache_key(org_id: &str, query: &str, user_email: &str) -> Option<String> {
+    if user_email.is_empty() {
+        return None;
+    }
+    let ast = promql_parser::parser::parse(query).ok()?;
+    let mut visitor = MetricNameVisitor::default();
+    promql_parser::util::walk_expr(&mut visitor, &ast).ok()?;
+    let stream_names = visitor.name.into_iter().collect::<Vec<_>>();
+    row_filter::cache_key(org_id, StreamType::Metrics, &stream_names, Some(user_email)).await
+}
+
 fn merge_matrix_query(series: &[cluster_rpc::Series]) -> Value {
     let mut merged_data = HashMap::new();
     let mut merged_metrics = HashMap::new();
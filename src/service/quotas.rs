@@ -0,0 +1,336 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+
+use actix_web::{
+    HttpResponse,
+    http::{StatusCode, header},
+};
+use chrono::Utc;
+use config::{
+    META_ORG_ID, RwHashMap, SIZE_IN_MB, get_config, ider,
+    meta::{
+        quota::{
+            OrgQuota, OrgQuotaResponse, QUOTA_KIND_HEADER, QUOTA_LIMIT_HEADER, QUOTA_USAGE_HEADER,
+            QuotaExceeded, QuotaKind, QuotaUsage,
+        },
+        search::{Query, Request, RequestEncoding, SearchEventType},
+        self_reporting::{
+            error::{ErrorData, ErrorSource},
+            usage::{USAGE_STREAM, UsageEvent},
+        },
+        stream::{StreamParams, StreamType},
+    },
+    utils::{json, time::now_micros},
+};
+use hashbrown::HashMap;
+use once_cell::sync::Lazy;
+
+use crate::{
+    common::{
+        infra::{cluster::get_cached_online_ingester_nodes, config::ORG_QUOTAS},
+        meta::http::HttpResponse as MetaHttpResponse,
+    },
+    service::{db, search as SearchService, self_reporting::publish_error},
+};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Usage of an org with a quota, as seen by this ingester.
+#[derive(Default)]
+struct OrgUsage {
+    /// Usage as of the last refresh.
+    usage: QuotaUsage,
+    /// Day the daily ingestion is counted for, in days since the epoch.
+    day: i64,
+    /// Bytes this node ingested which aren't counted in the daily ingestion of `usage` yet.
+    local_ingest_bytes: u64,
+    /// Part of `local_ingest_bytes` this node published to the usage stream.
+    published_ingest_bytes: u64,
+    /// Ingesters sharing the daily ingestion quota when it can't be read from the usage stream,
+    /// the local ingestion is counted once per ingester so that each one enforces its share.
+    ingesters: u64,
+    /// Streams of the org as `{stream_type}/{stream_name}`.
+    streams: HashSet<String>,
+    /// Quotas a soft limit warning was reported for today.
+    warned: HashSet<QuotaKind>,
+}
+
+impl OrgUsage {
+    fn reset_day(&mut self, day: i64) {
+        if self.day != day {
+            self.day = day;
+            self.usage.daily_ingest_bytes = 0;
+            self.local_ingest_bytes = 0;
+            self.published_ingest_bytes = 0;
+            self.warned.clear();
+        }
+    }
+
+    fn current(&self) -> QuotaUsage {
+        let mut usage = self.usage.clone();
+        usage.daily_ingest_bytes += self.local_ingest_bytes * self.ingesters.max(1);
+        usage
+    }
+}
+
+static USAGE: Lazy<RwHashMap<String, OrgUsage>> = Lazy::new(Default::default);
+
+fn today() -> i64 {
+    Utc::now().timestamp() / SECONDS_PER_DAY
+}
+
+fn stream_key(stream_type: StreamType, stream_name: &str) -> String {
+    format!("{stream_type}/{stream_name}")
+}
+
+/// Checks the org against its quota. Ingestion into a stream the org doesn't have yet is only
+/// rejected by the stream quota when the stream name is known up front.
+pub fn check(
+    org_id: &str,
+    stream_type: StreamType,
+    stream_name: Option<&str>,
+) -> Result<(), QuotaExceeded> {
+    let Some(quota) = ORG_QUOTAS.get(org_id) else {
+        return Ok(());
+    };
+    let Some(usage) = USAGE.get(org_id) else {
+        return Ok(());
+    };
+    let mut current = usage.current();
+    if usage.day != today() {
+        current.daily_ingest_bytes = 0;
+    }
+    let new_stream = stream_name.is_some_and(|stream_name| {
+        !usage
+            .streams
+            .contains(&stream_key(stream_type, stream_name))
+    });
+    match quota.exceeded(org_id, &current, new_stream) {
+        Some(exceeded) => Err(exceeded),
+        None => Ok(()),
+    }
+}
+
+/// Counts bytes ingested by this node against the daily quota until the next refresh.
+pub fn record_ingested(org_id: &str, bytes: u64) {
+    if !ORG_QUOTAS.contains_key(org_id) {
+        return;
+    }
+    let mut usage = USAGE.entry(org_id.to_string()).or_default();
+    usage.reset_day(today());
+    usage.local_ingest_bytes += bytes;
+}
+
+/// Counts bytes ingested by this node which were published to the usage stream, they are no longer
+/// counted locally once the daily ingestion is read again from the stream.
+pub fn record_published(org_id: &str, bytes: u64) {
+    if !ORG_QUOTAS.contains_key(org_id) {
+        return;
+    }
+    let mut usage = USAGE.entry(org_id.to_string()).or_default();
+    usage.reset_day(today());
+    usage.published_ingest_bytes += bytes;
+}
+
+/// Response to ingestion requests rejected by a quota.
+pub fn quota_exceeded_response(exceeded: &QuotaExceeded) -> HttpResponse {
+    let mut resp = HttpResponse::TooManyRequests();
+    resp.insert_header((QUOTA_KIND_HEADER, exceeded.kind.as_str()))
+        .insert_header((QUOTA_LIMIT_HEADER, exceeded.limit.to_string()))
+        .insert_header((QUOTA_USAGE_HEADER, exceeded.usage.to_string()));
+    if exceeded.kind == QuotaKind::DailyIngestBytes {
+        let retry_after = SECONDS_PER_DAY - Utc::now().timestamp() % SECONDS_PER_DAY;
+        resp.insert_header((header::RETRY_AFTER, retry_after.to_string()));
+    }
+    resp.json(MetaHttpResponse::error(
+        StatusCode::TOO_MANY_REQUESTS,
+        exceeded,
+    ))
+}
+
+pub async fn get(org_id: &str) -> OrgQuotaResponse {
+    let quota = db::quotas::get(org_id).unwrap_or_default();
+    let (mut usage, _) = storage_usage(org_id).await;
+    usage.daily_ingest_bytes = match daily_ingest_bytes().await {
+        Ok(Some(daily)) => daily.get(org_id).copied().unwrap_or_default(),
+        Ok(None) | Err(_) => USAGE
+            .get(org_id)
+            .filter(|usage| usage.day == today())
+            .map(|usage| usage.current().daily_ingest_bytes)
+            .unwrap_or_default(),
+    };
+    OrgQuotaResponse { quota, usage }
+}
+
+pub async fn set(org_id: &str, quota: OrgQuota) -> Result<(), anyhow::Error> {
+    db::quotas::set(org_id, &quota).await?;
+    Ok(())
+}
+
+pub async fn delete(org_id: &str) -> Result<(), anyhow::Error> {
+    db::quotas::delete(org_id).await?;
+    USAGE.remove(org_id);
+    Ok(())
+}
+
+/// Returns the stored bytes and the streams of the org, without the daily ingestion.
+async fn storage_usage(org_id: &str) -> (QuotaUsage, HashSet<String>) {
+    let mut streams = HashSet::new();
+    for stream_type in [StreamType::Logs, StreamType::Metrics, StreamType::Traces] {
+        for stream_name in db::schema::list_streams_from_cache(org_id, stream_type).await {
+            streams.insert(stream_key(stream_type, &stream_name));
+        }
+    }
+    let prefix = format!("{org_id}/");
+    let storage_bytes = infra::cache::stats::get_stats()
+        .iter()
+        .filter(|stats| stats.key().starts_with(&prefix))
+        .map(|stats| stats.value().compressed_size)
+        .sum::<f64>();
+    let usage = QuotaUsage {
+        daily_ingest_bytes: 0,
+        storage_bytes: storage_bytes as u64,
+        streams: streams.len() as u64,
+    };
+    (usage, streams)
+}
+
+/// Bytes ingested today by every org, summed up from the usage stream. Returns `None` when usage
+/// isn't reported to this cluster.
+async fn daily_ingest_bytes() -> Result<Option<HashMap<String, u64>>, anyhow::Error> {
+    usage_by_org(UsageEvent::Ingestion, today() * SECONDS_PER_DAY * 1_000_000).await
+}
+
+/// Bytes of the usage event of every org since `start_time`, summed up from the usage stream.
+/// Returns `None` when usage isn't reported to this cluster.
+pub(crate) async fn usage_by_org(
+    event: UsageEvent,
+    start_time: i64,
+) -> Result<Option<HashMap<String, u64>>, anyhow::Error> {
+    let cfg = get_config();
+    if !cfg.common.usage_enabled || cfg.common.usage_reporting_mode == "remote" {
+        return Ok(None);
+    }
+    let query = Query {
+        sql: format!(
+            "SELECT org_id, SUM(size) AS size FROM \"{USAGE_STREAM}\" WHERE event = '{event}' GROUP BY org_id"
+        ),
+        start_time,
+        end_time: Utc::now().timestamp_micros(),
+        size: -1,
+        ..Default::default()
+    };
+    let req = Request {
+        query,
+        encoding: RequestEncoding::Empty,
+        regions: vec![],
+        clusters: vec![],
+        timeout: 0,
+        search_type: Some(SearchEventType::Other),
+        search_event_context: None,
+        use_cache: false,
+        local_mode: None,
+    };
+    let trace_id = ider::generate_trace_id();
+    let resp = SearchService::search(&trace_id, META_ORG_ID, StreamType::Logs, None, &req).await?;
+    let usage = resp
+        .hits
+        .iter()
+        .filter_map(|hit| {
+            let org_id = hit.get("org_id")?.as_str()?;
+            let size = hit.get("size").and_then(json::Value::as_f64)?;
+            Some((org_id.to_string(), (size * SIZE_IN_MB) as u64))
+        })
+        .collect();
+    Ok(Some(usage))
+}
+
+/// Refreshes the usage of the orgs with a quota and reports the quotas which reached their soft
+/// limit, once a day per quota.
+pub async fn refresh_usage() -> Result<(), anyhow::Error> {
+    USAGE.retain(|org_id, _| ORG_QUOTAS.contains_key(org_id));
+    let org_ids = ORG_QUOTAS
+        .iter()
+        .map(|quota| quota.key().clone())
+        .collect::<Vec<_>>();
+    if org_ids.is_empty() {
+        return Ok(());
+    }
+
+    // bytes published from now on may be missing from the daily ingestion read below, so only
+    // the ones published so far are no longer counted locally after it
+    let published = USAGE
+        .iter()
+        .map(|usage| (usage.key().clone(), usage.published_ingest_bytes))
+        .collect::<HashMap<_, _>>();
+    let daily = match daily_ingest_bytes().await {
+        Ok(daily) => daily,
+        Err(e) => {
+            log::error!("[QUOTA] failed to read daily ingestion from the usage stream: {e}");
+            None
+        }
+    };
+    let ingesters = match daily {
+        Some(_) => 1,
+        None => get_cached_online_ingester_nodes()
+            .await
+            .map_or(1, |nodes| nodes.len() as u64),
+    };
+    let day = today();
+    for org_id in org_ids {
+        let (storage, streams) = storage_usage(&org_id).await;
+        let Some(quota) = db::quotas::get(&org_id) else {
+            continue;
+        };
+        let warnings = {
+            let mut usage = USAGE.entry(org_id.clone()).or_default();
+            usage.reset_day(day);
+            if let Some(daily) = &daily {
+                usage.usage.daily_ingest_bytes = daily.get(&org_id).copied().unwrap_or_default();
+                let published = published
+                    .get(&org_id)
+                    .copied()
+                    .unwrap_or_default()
+                    .min(usage.published_ingest_bytes);
+                usage.published_ingest_bytes -= published;
+                usage.local_ingest_bytes = usage.local_ingest_bytes.saturating_sub(published);
+            }
+            usage.ingesters = ingesters;
+            usage.usage.storage_bytes = storage.storage_bytes;
+            usage.usage.streams = storage.streams;
+            usage.streams = streams;
+            quota
+                .soft_limit_reached(&org_id, &usage.current())
+                .into_iter()
+                .filter(|warning| usage.warned.insert(warning.kind))
+                .collect::<Vec<_>>()
+        };
+        for warning in warnings {
+            log::warn!(
+                "[QUOTA] org {org_id} reached the soft limit of its {} quota",
+                warning.kind
+            );
+            publish_error(ErrorData {
+                _timestamp: now_micros(),
+                stream_params: StreamParams::new(&org_id, "", StreamType::Logs),
+                error_source: ErrorSource::Quota(warning.into()),
+            })
+            .await;
+        }
+    }
+    Ok(())
+}
@@ -45,7 +45,9 @@ use crate::{
         search::{
             self as SearchService,
             cache::cacher::check_cache,
+            field_policy,
             inspector::{SearchInspectorFieldsBuilder, search_inspector_fields},
+            row_filter,
         },
         self_reporting::{http_report_metrics, report_request_usage_stats},
     },
@@ -78,13 +80,14 @@ pub async fn search(
     let mut origin_sql = in_req.query.sql.clone();
     origin_sql = origin_sql.replace('\n', " ");
     let is_aggregate = is_aggregate_query(&origin_sql).unwrap_or_default();
-    let (stream_name, all_streams) = match resolve_stream_names(&origin_sql) {
-        // TODO: cache don't not support multiple stream names
-        Ok(v) => (v[0].clone(), v.join(",")),
+    let stream_names = match resolve_stream_names(&origin_sql) {
+        Ok(v) => v,
         Err(e) => {
             return Err(Error::Message(e.to_string()));
         }
     };
+    // TODO: cache don't not support multiple stream names
+    let (stream_name, all_streams) = (stream_names[0].clone(), stream_names.join(","));
 
     let mut req = in_req.clone();
     let mut query_fn = req
@@ -115,6 +118,24 @@ pub async fn search(
     if !req.clusters.is_empty() {
         hash_body.extend(req.clusters.clone());
     }
+    // the searches of reports run as their last editor, see SearchService::search
+    let policy_user = field_policy::resolve_search_user(
+        org_id,
+        user_id.clone(),
+        in_req.search_type,
+        in_req.search_event_context.as_ref(),
+    )
+    .await;
+    if let Some(policy_key) =
+        field_policy::cache_key(org_id, stream_type, &stream_names, policy_user.as_deref()).await
+    {
+        hash_body.push(policy_key);
+    }
+    if let Some(filter_key) =
+        row_filter::cache_key(org_id, stream_type, &stream_names, policy_user.as_deref()).await
+    {
+        hash_body.push(filter_key);
+    }
     let mut h = config::utils::hash::gxhash::new();
     let hashed_query = h.sum64(&hash_body.join(","));
 
@@ -856,18 +877,20 @@ pub async fn check_cache_v2(
     stream_type: StreamType,
     in_req: &search::Request,
     use_cache: bool,
+    user_id: Option<&str>,
 ) -> Result<MultiCachedQueryResponse, Error> {
     // Result caching check start
     let mut origin_sql = in_req.query.sql.clone();
     origin_sql = origin_sql.replace('\n', " ");
     let is_aggregate = is_aggregate_query(&origin_sql).unwrap_or_default();
-    let stream_name = match resolve_stream_names(&origin_sql) {
-        // TODO: cache don't not support multiple stream names
-        Ok(v) => v[0].clone(),
+    let stream_names = match resolve_stream_names(&origin_sql) {
+        Ok(v) => v,
         Err(e) => {
             return Err(Error::Message(e.to_string()));
         }
     };
+    // TODO: cache don't not support multiple stream names
+    let stream_name = stream_names[0].clone();
 
     let mut req = in_req.clone();
     let query_fn = req
@@ -887,6 +910,24 @@ pub async fn check_cache_v2(
     if !req.clusters.is_empty() {
         hash_body.extend(req.clusters.clone());
     }
+    // the searches of reports run as their last editor, see SearchService::search
+    let policy_user = field_policy::resolve_search_user(
+        org_id,
+        user_id.map(|v| v.to_string()),
+        in_req.search_type,
+        in_req.search_event_context.as_ref(),
+    )
+    .await;
+    if let Some(policy_key) =
+        field_policy::cache_key(org_id, stream_type, &stream_names, policy_user.as_deref()).await
+    {
+        hash_body.push(policy_key);
+    }
+    if let Some(filter_key) =
+        row_filter::cache_key(org_id, stream_type, &stream_names, policy_user.as_deref()).await
+    {
+        hash_body.push(filter_key);
+    }
     let mut h = config::utils::hash::gxhash::new();
     let hashed_query = h.sum64(&hash_body.join(","));
 
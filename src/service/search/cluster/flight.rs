@@ -64,6 +64,7 @@ use crate::{
             },
             generate_filter_from_equal_items,
             inspector::{SearchInspectorFieldsBuilder, search_inspector_fields},
+            org_limits,
             request::Request,
             sql::Sql,
             utils::{AsyncDefer, ScanStatsVisitor},
@@ -147,6 +148,15 @@ pub async fn search(
         idx_took,
     );
 
+    // check the search limits of the org before sending the search to the queriers
+    let _org_search_permit = org_limits::admit(
+        trace_id,
+        &sql.org_id,
+        (scan_stats.original_size + scan_stats.idx_scan_size) as u64,
+        timeout,
+    )
+    .await?;
+
     // 3. get nodes
     let get_node_start = std::time::Instant::now();
     let role_group = req
@@ -17,7 +17,11 @@ use std::sync::Arc;
 
 use ::datafusion::arrow::record_batch::RecordBatch;
 use config::{
-    meta::{function::VRLResultResolver, search, sql::TableReferenceExt},
+    meta::{
+        function::{VRLResultResolver, get_wasm_function_name},
+        search,
+        sql::TableReferenceExt,
+    },
     metrics::QUERY_PARQUET_CACHE_RATIO,
     utils::{
         arrow::record_batches_to_json_rows,
@@ -131,6 +135,23 @@ pub async fn search(
                 .filter(|v| !v.is_empty())
                 .map(json::Value::Object)
                 .collect()
+        } else if let Some(fn_name) = get_wasm_function_name(&query_fn) {
+            // apply the saved wasm function before returning the response
+            let hits = json_rows
+                .into_iter()
+                .filter(|v| !v.is_empty())
+                .map(json::Value::Object)
+                .collect::<Vec<_>>();
+            match crate::service::ingestion::wasm::apply_query_function(&sql.org_id, fn_name, &hits)
+                .await
+            {
+                Ok(records) => records,
+                Err(err) => {
+                    log::error!("[trace_id {trace_id}] search->wasm: apply err: {:?}", err);
+                    result.function_error = vec![err.to_string()];
+                    hits
+                }
+            }
         } else {
             // compile vrl function & apply the same before returning the response
             let input_fn = query_fn.trim();
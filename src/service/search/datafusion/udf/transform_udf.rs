@@ -51,8 +51,9 @@ pub fn get_all_transform(org_id: &str) -> Result<Vec<ScalarUDF>> {
     let mut udf_list = Vec::new();
     for transform in QUERY_FUNCTIONS.clone().iter() {
         let key = transform.key();
-        // do not register ingest_time transforms
-        if key.contains(org_id) {
+        // do not register ingest_time transforms, WASM functions are applied to the hits
+        // instead of being called from SQL
+        if key.contains(org_id) && !transform.is_wasm() {
             udf_list.push(get_udf_vrl(
                 transform.name.to_owned(),
                 transform.function.to_owned().as_str(),
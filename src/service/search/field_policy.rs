@@ -0,0 +1,271 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Field policies hide or mask fields of a stream in the searches of some roles or groups. They
+//! are applied while planning the sql, by removing the fields from the schema of the stream.
+//! Masked fields are then read as a constant by the query.
+
+use std::sync::Arc;
+
+use config::{
+    ALL_VALUES_COL_NAME, ORIGINAL_DATA_COL_NAME, get_config,
+    meta::{
+        search::{SearchEventContext, SearchEventType},
+        stream::{FieldPolicy, StreamSettings, StreamType},
+    },
+    utils::json,
+};
+use datafusion::{arrow::datatypes::Schema, common::TableReference};
+use hashbrown::HashMap;
+use infra::schema::{SchemaCache, unwrap_stream_settings};
+
+use crate::common::utils::auth::is_root_user;
+
+/// Roles and groups of a user in an org, which field policies are matched against.
+#[derive(Debug, Default)]
+pub(crate) struct UserPrincipals {
+    pub roles: Vec<String>,
+    pub groups: Vec<String>,
+}
+
+/// Gets the roles and groups of the user in the org. Root users aren't subject to field
+/// policies, so `None` is returned for them.
+pub(crate) async fn get_user_principals(org_id: &str, user_id: &str) -> Option<UserPrincipals> {
+    if is_root_user(user_id) {
+        return None;
+    }
+    let mut principals = UserPrincipals::default();
+    if let Some(user) = crate::service::users::get_user(Some(org_id), user_id).await {
+        principals.roles.push(user.role.to_string());
+    }
+    #[cfg(feature = "enterprise")]
+    if o2_openfga::config::get_config().enabled {
+        principals
+            .roles
+            .extend(crate::service::users::get_user_roles(user_id, Some(org_id)).await);
+        principals.groups =
+            o2_openfga::authorizer::groups::get_groups_for_org_user(org_id, user_id)
+                .await
+                .unwrap_or_default();
+    }
+    Some(principals)
+}
+
+/// Reports are rendered by the report user, so their searches run as the user who last edited
+/// the report, or its owner, for the field policies and row filters of that user to apply.
+pub(crate) async fn resolve_search_user(
+    org_id: &str,
+    user_id: Option<String>,
+    search_type: Option<SearchEventType>,
+    search_event_context: Option<&SearchEventContext>,
+) -> Option<String> {
+    let report_user = &get_config().common.report_user_name;
+    if search_type != Some(SearchEventType::Reports)
+        || report_user.is_empty()
+        || user_id.as_deref() != Some(report_user.as_str())
+    {
+        return user_id;
+    }
+    let report_name = search_event_context
+        .and_then(|ctx| ctx.report_key.as_deref())
+        .and_then(|key| key.strip_prefix(&format!("{org_id}-")));
+    let Some(report_name) = report_name else {
+        return user_id;
+    };
+    match crate::service::dashboards::reports::get(org_id, report_name).await {
+        Ok(report) if !report.last_edited_by.is_empty() => Some(report.last_edited_by),
+        Ok(report) if !report.owner.is_empty() => Some(report.owner),
+        _ => user_id,
+    }
+}
+
+/// Gets the field policies of the streams that apply to the user. Streams without any are left
+/// out, and the user is only looked up when some stream has policies.
+pub(crate) async fn get_field_policies(
+    org_id: &str,
+    user_id: &str,
+    schemas: &HashMap<TableReference, Arc<SchemaCache>>,
+) -> HashMap<TableReference, Vec<FieldPolicy>> {
+    let mut principals = None;
+    let mut field_policies = HashMap::new();
+    for (stream, schema) in schemas.iter() {
+        let Some(settings) = unwrap_stream_settings(schema.schema()) else {
+            continue;
+        };
+        if settings.field_policies.is_empty() {
+            continue;
+        }
+        if principals.is_none() {
+            principals = Some(get_user_principals(org_id, user_id).await);
+        }
+        let Some(Some(principals)) = principals.as_ref() else {
+            return HashMap::new();
+        };
+        let policies = applicable_policies(&settings, principals);
+        if !policies.is_empty() {
+            field_policies.insert(stream.clone(), policies);
+        }
+    }
+    field_policies
+}
+
+fn applicable_policies(settings: &StreamSettings, principals: &UserPrincipals) -> Vec<FieldPolicy> {
+    settings
+        .field_policies
+        .iter()
+        .filter(|policy| policy.applies_to(&principals.roles, &principals.groups))
+        .cloned()
+        .collect()
+}
+
+/// Removes the fields of the policies from the schema, and from the index settings of the
+/// stream so that the indexes can't match on them. The `_original` and `_all_values` columns
+/// carry every field of the record, so they are removed too.
+pub(crate) fn apply_to_schema(schema: &Schema, policies: &[FieldPolicy]) -> Schema {
+    let is_denied = |name: &str| {
+        name == ORIGINAL_DATA_COL_NAME
+            || name == ALL_VALUES_COL_NAME
+            || policies.iter().any(|policy| policy.field == name)
+    };
+    let fields = schema
+        .fields()
+        .iter()
+        .filter(|field| !is_denied(field.name()))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut metadata = schema.metadata().clone();
+    if let Some(mut settings) = unwrap_stream_settings(schema) {
+        settings.full_text_search_keys.retain(|f| !is_denied(f));
+        settings.index_fields.retain(|f| !is_denied(f));
+        settings.bloom_filter_fields.retain(|f| !is_denied(f));
+        settings.index_original_data = false;
+        settings.index_all_values = false;
+        metadata.insert("settings".to_string(), json::to_string(&settings).unwrap());
+    }
+    Schema::new(fields).with_metadata(metadata)
+}
+
+/// Builds a key of the field policies that apply to the user in the given streams, to keep the
+/// cached results of users seeing different fields apart. `None` when no policy applies.
+pub(crate) async fn cache_key(
+    org_id: &str,
+    stream_type: StreamType,
+    stream_names: &[String],
+    user_id: Option<&str>,
+) -> Option<String> {
+    let user_id = user_id?;
+    let mut principals = None;
+    let mut keys = vec![];
+    for stream_name in stream_names {
+        let Some(settings) = infra::schema::get_settings(org_id, stream_name, stream_type).await
+        else {
+            continue;
+        };
+        if settings.field_policies.is_empty() {
+            continue;
+        }
+        if principals.is_none() {
+            principals = Some(get_user_principals(org_id, user_id).await);
+        }
+        let Some(Some(principals)) = principals.as_ref() else {
+            return None;
+        };
+        for policy in applicable_policies(&settings, principals) {
+            keys.push(format!(
+                "{stream_name}/{}/{}",
+                policy.field,
+                json::to_string(&policy.action).unwrap()
+            ));
+        }
+    }
+    if keys.is_empty() {
+        return None;
+    }
+    keys.sort();
+    Some(keys.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_schema::{DataType, Field};
+    use config::meta::stream::FieldPolicyAction;
+
+    use super::*;
+
+    fn policy(field: &str, roles: &[&str], groups: &[&str]) -> FieldPolicy {
+        FieldPolicy {
+            field: field.to_string(),
+            action: FieldPolicyAction::Hide,
+            roles: roles.iter().map(|v| v.to_string()).collect(),
+            groups: groups.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_applicable_policies() {
+        let settings = StreamSettings {
+            field_policies: vec![
+                policy("email", &["viewer"], &[]),
+                policy("ip", &[], &["contractors"]),
+            ],
+            ..Default::default()
+        };
+        let principals = UserPrincipals {
+            roles: vec!["viewer".to_string()],
+            groups: vec![],
+        };
+        let policies = applicable_policies(&settings, &principals);
+        assert_eq!(policies.len(), 1);
+        assert_eq!(policies[0].field, "email");
+
+        let principals = UserPrincipals {
+            roles: vec!["admin".to_string()],
+            groups: vec!["contractors".to_string()],
+        };
+        let policies = applicable_policies(&settings, &principals);
+        assert_eq!(policies.len(), 1);
+        assert_eq!(policies[0].field, "ip");
+    }
+
+    #[test]
+    fn test_apply_to_schema() {
+        let settings = StreamSettings {
+            full_text_search_keys: vec!["log".to_string(), "email".to_string()],
+            index_fields: vec!["email".to_string()],
+            ..Default::default()
+        };
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("settings".to_string(), json::to_string(&settings).unwrap());
+        let schema = Schema::new(vec![
+            Field::new("_timestamp", DataType::Int64, false),
+            Field::new("log", DataType::Utf8, true),
+            Field::new("email", DataType::Utf8, true),
+            Field::new(ORIGINAL_DATA_COL_NAME, DataType::Utf8, true),
+        ])
+        .with_metadata(metadata);
+
+        let schema = apply_to_schema(&schema, &[policy("email", &["viewer"], &[])]);
+        let fields = schema
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec!["_timestamp", "log"]);
+        let settings = unwrap_stream_settings(&schema).unwrap();
+        assert_eq!(settings.full_text_search_keys, vec!["log".to_string()]);
+        assert!(settings.index_fields.is_empty());
+    }
+}
@@ -25,7 +25,7 @@ use config::{
     get_config, ider,
     meta::{
         cluster::RoleGroup,
-        function::RESULT_ARRAY,
+        function::{RESULT_ARRAY, get_wasm_function_name},
         search,
         self_reporting::usage::{RequestStats, UsageType},
         sql::{OrderBy, SqlOperator, TableReferenceExt, resolve_stream_names},
@@ -83,12 +83,15 @@ use crate::{
 pub(crate) mod cache;
 pub(crate) mod cluster;
 pub(crate) mod datafusion;
+pub(crate) mod field_policy;
 pub(crate) mod grpc;
 pub(crate) mod grpc_search;
 pub(crate) mod index;
 pub(crate) mod inspector;
+pub(crate) mod org_limits;
 pub(crate) mod partition;
 pub(crate) mod request;
+pub(crate) mod row_filter;
 pub(crate) mod search_stream;
 pub(crate) mod sql;
 #[cfg(feature = "enterprise")]
@@ -138,6 +141,14 @@ pub async fn search(
         trace_id.to_string()
     };
 
+    let user_id = field_policy::resolve_search_user(
+        org_id,
+        user_id,
+        in_req.search_type,
+        in_req.search_event_context.as_ref(),
+    )
+    .await;
+
     #[cfg(feature = "enterprise")]
     {
         let sql = Some(in_req.query.sql.clone());
@@ -367,6 +378,11 @@ pub async fn search_multi(
             query_fn = Some(format!("{} \n .", vrl_function));
         }
     }
+    // wasm functions are applied to the hits of each query
+    let is_wasm_fn = query_fn
+        .as_deref()
+        .and_then(get_wasm_function_name)
+        .is_some();
 
     let mut queries = multi_req.to_query_req();
     log::info!(
@@ -395,7 +411,7 @@ pub async fn search_multi(
             }
         };
         sqls.push(req.query.sql.clone());
-        if !per_query_resp {
+        if !per_query_resp || is_wasm_fn {
             req.query.query_fn = query_fn.clone();
         }
 
@@ -456,6 +472,8 @@ pub async fn search_multi(
     }
 
     let mut report_function_usage = false;
+    // wasm functions were already applied to the hits of each query
+    let query_fn = query_fn.filter(|_| !is_wasm_fn);
     multi_res.hits = if query_fn.is_some() && !multi_res.hits.is_empty() && !multi_res.is_partial {
         // compile vrl function & apply the same before returning the response
         let mut input_fn = query_fn.unwrap().trim().to_string();
@@ -1046,7 +1064,10 @@ pub async fn query_status() -> Result<search::QueryStatusResponse, Error> {
         });
     }
 
-    Ok(search::QueryStatusResponse { status })
+    Ok(search::QueryStatusResponse {
+        status,
+        org_limits: None,
+    })
 }
 
 #[cfg(feature = "enterprise")]
@@ -0,0 +1,262 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use chrono::Utc;
+use config::{
+    RwHashMap,
+    meta::{
+        quota::{OrgSearchLimits, OrgSearchStatus},
+        self_reporting::usage::UsageEvent,
+    },
+};
+use infra::errors::{Error, ErrorCodes};
+use once_cell::sync::Lazy;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{common::infra::config::ORG_QUOTAS, service::quotas};
+
+const SECONDS_PER_HOUR: i64 = 60 * 60;
+
+#[derive(Default)]
+struct Counters {
+    running: AtomicU64,
+    queued: AtomicU64,
+    rejected: AtomicU64,
+}
+
+/// Searches of an org with limits, planned by this node.
+#[derive(Default)]
+struct OrgSearches {
+    /// Concurrency limit the semaphore was created for, which only counts the searches of this
+    /// node.
+    max_concurrent_queries_per_node: Option<u32>,
+    semaphore: Option<Arc<Semaphore>>,
+    counters: Arc<Counters>,
+    /// Hour the scanned bytes are counted for, in hours since the epoch.
+    hour: i64,
+    /// Bytes the org scanned this hour, as of the last refresh.
+    hourly_scan_bytes: u64,
+    /// Bytes of the searches this node admitted since the last refresh.
+    local_scan_bytes: u64,
+}
+
+/// Releases the bytes reserved for a search of the `hour` which was rejected after its scan was
+/// admitted.
+fn release_scan_bytes(org_id: &str, hour: i64, scan_bytes: u64) {
+    if let Some(mut searches) = SEARCHES.get_mut(org_id) {
+        if searches.hour == hour {
+            searches.local_scan_bytes = searches.local_scan_bytes.saturating_sub(scan_bytes);
+        }
+    }
+}
+
+impl OrgSearches {
+    fn sync_limits(&mut self, limits: &OrgSearchLimits) {
+        if self.max_concurrent_queries_per_node != limits.max_concurrent_queries_per_node {
+            self.max_concurrent_queries_per_node = limits.max_concurrent_queries_per_node;
+            self.semaphore = limits
+                .max_concurrent_queries_per_node
+                .map(|max| Arc::new(Semaphore::new(max as usize)));
+        }
+    }
+
+    fn reset_hour(&mut self, hour: i64) {
+        if self.hour != hour {
+            self.hour = hour;
+            self.hourly_scan_bytes = 0;
+            self.local_scan_bytes = 0;
+        }
+    }
+}
+
+static SEARCHES: Lazy<RwHashMap<String, OrgSearches>> = Lazy::new(Default::default);
+
+/// Admission of a search within the limits of its org, held until the search is done.
+pub struct OrgSearchPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+    counters: Arc<Counters>,
+}
+
+impl Drop for OrgSearchPermit {
+    fn drop(&mut self) {
+        self.counters.running.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn current_hour() -> i64 {
+    Utc::now().timestamp() / SECONDS_PER_HOUR
+}
+
+fn get_limits(org_id: &str) -> Option<OrgSearchLimits> {
+    ORG_QUOTAS
+        .get(org_id)
+        .map(|quota| quota.search.clone())
+        .filter(|limits| !limits.is_empty())
+}
+
+fn reject(org_id: &str, counters: &Counters, reason: String) -> Error {
+    counters.rejected.fetch_add(1, Ordering::Relaxed);
+    Error::ErrorCode(ErrorCodes::RatelimitExceeded(format!(
+        "Search limit exceeded for this organization [{org_id}]: {reason}"
+    )))
+}
+
+/// Admits a search scanning `scan_bytes` into the limits of its org, waiting up to `timeout`
+/// seconds for a running search to finish when the org queues searches over its concurrency
+/// limit. The scanned bytes are reserved along with the hourly check, so searches admitted at
+/// the same time can't overrun the hourly limit together. Returns `None` when the org has no
+/// search limits.
+pub async fn admit(
+    trace_id: &str,
+    org_id: &str,
+    scan_bytes: u64,
+    timeout: u64,
+) -> Result<Option<OrgSearchPermit>, Error> {
+    let Some(limits) = get_limits(org_id) else {
+        return Ok(None);
+    };
+
+    let hour = current_hour();
+    let (semaphore, counters) = {
+        let mut searches = SEARCHES.entry(org_id.to_string()).or_default();
+        searches.sync_limits(&limits);
+        searches.reset_hour(hour);
+        if let Some(max) = limits.max_query_scan_bytes {
+            if scan_bytes > max {
+                return Err(reject(
+                    org_id,
+                    &searches.counters,
+                    format!("the search scans {scan_bytes} bytes, more than the limit of {max}"),
+                ));
+            }
+        }
+        if let Some(max) = limits.max_hourly_scan_bytes {
+            let scanned = searches.hourly_scan_bytes + searches.local_scan_bytes;
+            if scanned + scan_bytes > max {
+                return Err(reject(
+                    org_id,
+                    &searches.counters,
+                    format!("{scanned} bytes were scanned this hour, the limit is {max}"),
+                ));
+            }
+        }
+        searches.local_scan_bytes += scan_bytes;
+        (searches.semaphore.clone(), searches.counters.clone())
+    };
+
+    let permit = match semaphore {
+        None => None,
+        Some(semaphore) => match semaphore.clone().try_acquire_owned() {
+            Ok(permit) => Some(permit),
+            Err(_) if limits.queue_when_busy => {
+                log::info!(
+                    "[trace_id {trace_id}] search of org {org_id} queued for its concurrency limit"
+                );
+                counters.queued.fetch_add(1, Ordering::Relaxed);
+                let permit =
+                    tokio::time::timeout(Duration::from_secs(timeout), semaphore.acquire_owned())
+                        .await;
+                counters.queued.fetch_sub(1, Ordering::Relaxed);
+                match permit {
+                    Ok(Ok(permit)) => Some(permit),
+                    _ => {
+                        release_scan_bytes(org_id, hour, scan_bytes);
+                        return Err(reject(
+                            org_id,
+                            &counters,
+                            "timed out waiting for other searches of the org to finish".to_string(),
+                        ));
+                    }
+                }
+            }
+            Err(_) => {
+                release_scan_bytes(org_id, hour, scan_bytes);
+                return Err(reject(
+                    org_id,
+                    &counters,
+                    format!(
+                        "{} searches of the org are already running on this node",
+                        limits.max_concurrent_queries_per_node.unwrap_or_default()
+                    ),
+                ));
+            }
+        },
+    };
+
+    counters.running.fetch_add(1, Ordering::Relaxed);
+    Ok(Some(OrgSearchPermit {
+        _permit: permit,
+        counters,
+    }))
+}
+
+/// Returns the searches of the org planned by this node, when it has search limits.
+pub fn status(org_id: &str) -> Option<OrgSearchStatus> {
+    let limits = get_limits(org_id)?;
+    let Some(searches) = SEARCHES.get(org_id) else {
+        return Some(OrgSearchStatus {
+            limits,
+            ..Default::default()
+        });
+    };
+    let hourly_scan_bytes = if searches.hour == current_hour() {
+        searches.hourly_scan_bytes + searches.local_scan_bytes
+    } else {
+        0
+    };
+    Some(OrgSearchStatus {
+        limits,
+        running: searches.counters.running.load(Ordering::Relaxed),
+        queued: searches.counters.queued.load(Ordering::Relaxed),
+        rejected: searches.counters.rejected.load(Ordering::Relaxed),
+        hourly_scan_bytes,
+    })
+}
+
+/// Refreshes the bytes scanned this hour by the orgs with an hourly limit from the usage stream.
+pub async fn refresh_usage() -> Result<(), anyhow::Error> {
+    SEARCHES.retain(|org_id, _| ORG_QUOTAS.contains_key(org_id));
+    let org_ids = ORG_QUOTAS
+        .iter()
+        .filter(|quota| quota.search.max_hourly_scan_bytes.is_some())
+        .map(|quota| quota.key().clone())
+        .collect::<Vec<_>>();
+    if org_ids.is_empty() {
+        return Ok(());
+    }
+
+    let hour = current_hour();
+    let Some(usage) =
+        quotas::usage_by_org(UsageEvent::Search, hour * SECONDS_PER_HOUR * 1_000_000).await?
+    else {
+        return Ok(());
+    };
+    for org_id in org_ids {
+        let scanned = usage.get(&org_id).copied().unwrap_or_default();
+        let mut searches = SEARCHES.entry(org_id).or_default();
+        searches.reset_hour(hour);
+        searches.hourly_scan_bytes = scanned;
+        searches.local_scan_bytes = 0;
+    }
+    Ok(())
+}
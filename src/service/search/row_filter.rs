@@ -0,0 +1,196 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Row filters limit the records some roles or groups see in a stream. The predicates of the
+//! filters that apply to a user are added to every query on the stream, see
+//! [super::sql::Sql::new_with_user] for sql and the promql engine for metrics.
+
+use std::sync::Arc;
+
+use config::meta::stream::{RowFilter, StreamSettings, StreamType};
+use datafusion::common::TableReference;
+use hashbrown::HashMap;
+use infra::{
+    errors::{Error, Result},
+    schema::{SchemaCache, unwrap_stream_settings},
+};
+use sqlparser::{
+    ast::{BinaryOperator, Expr},
+    dialect::PostgreSqlDialect,
+    parser::Parser,
+};
+
+use super::field_policy::{UserPrincipals, get_user_principals};
+
+/// Parses the predicate of a row filter.
+pub fn parse_filter(filter: &str) -> Result<Expr> {
+    Parser::new(&PostgreSqlDialect {})
+        .try_with_sql(filter)
+        .and_then(|mut parser| parser.parse_expr())
+        .map_err(|e| Error::Message(format!("Invalid row filter [{filter}]: {e}")))
+}
+
+fn applicable_filters<'a>(
+    settings: &'a StreamSettings,
+    principals: &UserPrincipals,
+) -> Vec<&'a RowFilter> {
+    settings
+        .row_filters
+        .iter()
+        .filter(|filter| filter.applies_to(&principals.roles, &principals.groups))
+        .collect()
+}
+
+/// Combines the filters a user is bound to, the user sees the records matching any of them.
+fn combine_filters(filters: &[&RowFilter]) -> Result<Option<Expr>> {
+    let mut combined: Option<Expr> = None;
+    for filter in filters {
+        let expr = Expr::Nested(Box::new(parse_filter(&filter.filter)?));
+        combined = Some(match combined {
+            None => expr,
+            Some(left) => Expr::BinaryOp {
+                left: Box::new(left),
+                op: BinaryOperator::Or,
+                right: Box::new(expr),
+            },
+        });
+    }
+    Ok(combined.map(|expr| Expr::Nested(Box::new(expr))))
+}
+
+/// Gets the predicate of the row filters that apply to the user in each stream. Streams without
+/// any are left out, and the user is only looked up when some stream has row filters.
+pub(crate) async fn get_row_filters(
+    org_id: &str,
+    user_id: &str,
+    schemas: &HashMap<TableReference, Arc<SchemaCache>>,
+) -> Result<HashMap<TableReference, Expr>> {
+    let mut principals = None;
+    let mut row_filters = HashMap::new();
+    for (stream, schema) in schemas.iter() {
+        let Some(settings) = unwrap_stream_settings(schema.schema()) else {
+            continue;
+        };
+        if settings.row_filters.is_empty() {
+            continue;
+        }
+        if principals.is_none() {
+            principals = Some(get_user_principals(org_id, user_id).await);
+        }
+        let Some(Some(principals)) = principals.as_ref() else {
+            return Ok(HashMap::new());
+        };
+        if let Some(expr) = combine_filters(&applicable_filters(&settings, principals))? {
+            row_filters.insert(stream.clone(), expr);
+        }
+    }
+    Ok(row_filters)
+}
+
+/// Gets the predicate of the row filters that apply to the user in the stream, as sql.
+pub(crate) async fn get_stream_row_filter(
+    org_id: &str,
+    stream_name: &str,
+    stream_type: StreamType,
+    user_id: &str,
+) -> Result<Option<String>> {
+    if user_id.is_empty() {
+        return Ok(None);
+    }
+    let Some(settings) = infra::schema::get_settings(org_id, stream_name, stream_type).await else {
+        return Ok(None);
+    };
+    if settings.row_filters.is_empty() {
+        return Ok(None);
+    }
+    let Some(principals) = get_user_principals(org_id, user_id).await else {
+        return Ok(None);
+    };
+    Ok(combine_filters(&applicable_filters(&settings, &principals))?.map(|expr| expr.to_string()))
+}
+
+/// Builds a key of the row filters that apply to the user in the given streams, to keep the
+/// cached results of users seeing different records apart. `None` when no filter applies.
+pub(crate) async fn cache_key(
+    org_id: &str,
+    stream_type: StreamType,
+    stream_names: &[String],
+    user_id: Option<&str>,
+) -> Option<String> {
+    let user_id = user_id?;
+    let mut keys = vec![];
+    for stream_name in stream_names {
+        if let Ok(Some(filter)) =
+            get_stream_row_filter(org_id, stream_name, stream_type, user_id).await
+        {
+            keys.push(format!("{stream_name}/{filter}"));
+        }
+    }
+    if keys.is_empty() {
+        return None;
+    }
+    keys.sort();
+    Some(keys.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row_filter(name: &str, filter: &str, groups: &[&str]) -> RowFilter {
+        RowFilter {
+            name: name.to_string(),
+            filter: filter.to_string(),
+            roles: vec![],
+            groups: groups.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        assert!(parse_filter("team = 'payments' AND env <> 'dev'").is_ok());
+        assert!(parse_filter("team = ").is_err());
+    }
+
+    #[test]
+    fn test_combine_filters() {
+        let settings = StreamSettings {
+            row_filters: vec![
+                row_filter("payments", "team = 'payments'", &["payments"]),
+                row_filter(
+                    "billing",
+                    "team = 'billing' OR team = 'invoices'",
+                    &["billing"],
+                ),
+                row_filter("search", "team = 'search'", &["search"]),
+            ],
+            ..Default::default()
+        };
+        let principals = UserPrincipals {
+            roles: vec![],
+            groups: vec!["payments".to_string(), "billing".to_string()],
+        };
+        let filters = applicable_filters(&settings, &principals);
+        let expr = combine_filters(&filters).unwrap().unwrap();
+        assert_eq!(
+            expr.to_string(),
+            "((team = 'payments') OR (team = 'billing' OR team = 'invoices'))"
+        );
+
+        let principals = UserPrincipals::default();
+        let filters = applicable_filters(&settings, &principals);
+        assert!(combine_filters(&filters).unwrap().is_none());
+    }
+}
@@ -141,9 +141,16 @@ pub async fn process_search_stream_request(
 
     if req.query.from == 0 && !req.query.track_total_hits && req.query.streaming_id.is_none() {
         // check cache for the first page
-        let c_resp = match cache::check_cache_v2(&trace_id, &org_id, stream_type, &req, use_cache)
-            .instrument(search_span.clone())
-            .await
+        let c_resp = match cache::check_cache_v2(
+            &trace_id,
+            &org_id,
+            stream_type,
+            &req,
+            use_cache,
+            Some(user_id.as_str()),
+        )
+        .instrument(search_span.clone())
+        .await
         {
             Ok(v) => v,
             Err(e) => {
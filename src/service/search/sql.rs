@@ -23,7 +23,7 @@ use config::{
         inverted_index::InvertedIndexOptimizeMode,
         search::SearchEventType,
         sql::{OrderBy, Sql as MetaSql, TableReferenceExt, resolve_stream_names_with_type},
-        stream::StreamType,
+        stream::{FIELD_MASK_VALUE, FieldPolicyAction, StreamType},
     },
     utils::sql::AGGREGATE_UDF_LIST,
 };
@@ -44,7 +44,7 @@ use sqlparser::{
         BinaryOperator, DuplicateTreatment, Expr, Function, FunctionArg, FunctionArgExpr,
         FunctionArgumentList, FunctionArguments, GroupByExpr, Ident, ObjectName, OrderByExpr,
         Query, Select, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins, Value,
-        VisitMut, VisitorMut, helpers::attached_token::AttachedToken,
+        VisitMut, VisitorMut, helpers::attached_token::AttachedToken, visit_expressions_mut,
     },
     dialect::PostgreSqlDialect,
     parser::Parser,
@@ -54,8 +54,10 @@ use sqlparser::{
 use super::datafusion::udf::cipher_udf::{DECRYPT_UDF_NAME, ENCRYPT_UDF_NAME};
 use super::{
     datafusion::udf::match_all_udf::{FUZZY_MATCH_ALL_UDF_NAME, MATCH_ALL_UDF_NAME},
+    field_policy,
     index::{Condition, IndexCondition, get_index_condition_from_expr},
     request::Request,
+    row_filter,
     utils::{conjunction, is_field, is_value, split_conjunction, trim_quotes},
 };
 use crate::service::search::{
@@ -105,7 +107,14 @@ impl Sql {
             .search_event_type
             .as_ref()
             .and_then(|s| SearchEventType::try_from(s.as_str()).ok());
-        Self::new(query, &req.org_id, req.stream_type, search_event_type).await
+        Self::new_with_user(
+            query,
+            &req.org_id,
+            req.stream_type,
+            search_event_type,
+            req.user_id.as_deref(),
+        )
+        .await
     }
 
     pub async fn new(
@@ -113,6 +122,18 @@ impl Sql {
         org_id: &str,
         stream_type: StreamType,
         search_event_type: Option<SearchEventType>,
+    ) -> Result<Sql, Error> {
+        Self::new_with_user(query, org_id, stream_type, search_event_type, None).await
+    }
+
+    /// Plans the sql for the user, applying the field policies and row filters of the streams
+    /// that apply to them. Internal searches don't have a user and see every record and field.
+    pub async fn new_with_user(
+        query: &SearchQuery,
+        org_id: &str,
+        stream_type: StreamType,
+        search_event_type: Option<SearchEventType>,
+        user_id: Option<&str>,
     ) -> Result<Sql, Error> {
         let cfg = get_config();
         let sql = query.sql.clone();
@@ -143,6 +164,46 @@ impl Sql {
             .pop()
             .unwrap();
 
+        // 1.1 apply field policies, hidden and masked fields are removed from the schema and
+        // masked fields are read as a constant
+        let mut has_field_policies = false;
+        if let Some(user_id) = user_id {
+            let field_policies =
+                field_policy::get_field_policies(org_id, user_id, &total_schemas).await;
+            let mut masked_fields = HashSet::new();
+            for (stream, policies) in field_policies {
+                let Some(schema) = total_schemas.get_mut(&stream) else {
+                    continue;
+                };
+                for policy in policies.iter() {
+                    if policy.action == FieldPolicyAction::Mask
+                        && schema.contains_field(&policy.field)
+                    {
+                        masked_fields.insert(policy.field.clone());
+                    }
+                }
+                let new_schema = field_policy::apply_to_schema(schema.schema(), &policies);
+                *schema = Arc::new(SchemaCache::new(new_schema));
+                has_field_policies = true;
+            }
+            if !masked_fields.is_empty() {
+                let mut mask_field_visitor = MaskFieldVisitor::new(masked_fields);
+                let _ = statement.visit(&mut mask_field_visitor);
+            }
+        }
+
+        // 1.2 apply row filters, the records of the streams are limited to the ones matching
+        // the filters that apply to the user
+        let mut has_row_filters = false;
+        if let Some(user_id) = user_id {
+            let row_filters = row_filter::get_row_filters(org_id, user_id, &total_schemas).await?;
+            if !row_filters.is_empty() {
+                let mut row_filter_visitor = RowFilterVisitor::new(row_filters);
+                let _ = statement.visit(&mut row_filter_visitor);
+                has_row_filters = true;
+            }
+        }
+
         //********************Change the sql here*********************************//
         // 2. rewrite track_total_hits
         if query.track_total_hits {
@@ -181,7 +242,8 @@ impl Sql {
         let need_sort_by_time = order_by.len() == 1
             && order_by[0].0 == TIMESTAMP_COL_NAME
             && order_by[0].1 == OrderBy::Desc;
-        let use_inverted_index = column_visitor.use_inverted_index;
+        // the inverted index covers every field, including the ones hidden by field policies
+        let use_inverted_index = column_visitor.use_inverted_index && !has_field_policies;
 
         // check if need exact limit and offset
         if limit == -1 || limit == 0 {
@@ -278,6 +340,8 @@ impl Sql {
                 cfg.common.feature_query_remove_filter_with_index,
                 cfg.common.inverted_index_count_optimizer_enabled,
             );
+            // row filters must stay in the where clause
+            index_visitor.keep_filter = has_row_filters;
             let _ = statement.visit(&mut index_visitor);
             index_condition = index_visitor.index_condition;
             can_optimize = index_visitor.can_optimize;
@@ -802,6 +866,7 @@ impl VisitorMut for ColumnVisitor<'_> {
 struct IndexVisitor {
     index_fields: HashSet<String>,
     is_remove_filter: bool,
+    keep_filter: bool,
     count_optimizer_enabled: bool,
     index_condition: Option<IndexCondition>,
     pub can_optimize: bool,
@@ -823,6 +888,7 @@ impl IndexVisitor {
         Self {
             index_fields,
             is_remove_filter,
+            keep_filter: false,
             count_optimizer_enabled,
             index_condition: None,
             can_optimize: false,
@@ -838,6 +904,7 @@ impl IndexVisitor {
         Self {
             index_fields,
             is_remove_filter,
+            keep_filter: false,
             count_optimizer_enabled,
             index_condition: None,
             can_optimize: false,
@@ -865,7 +932,7 @@ impl VisitorMut for IndexVisitor {
                 {
                     self.can_optimize = true;
                 }
-                if self.is_remove_filter || can_remove_filter {
+                if !self.keep_filter && (self.is_remove_filter || can_remove_filter) {
                     select.selection = other_expr;
                 }
             } else if is_simple_count_query(select) || is_simple_histogram_query(select) {
@@ -1138,6 +1205,180 @@ impl VisitorMut for FieldNameVisitor {
     }
 }
 
+// read masked fields as a constant, like `SELECT email FROM t WHERE email = 'a'` ->
+// `SELECT '***' AS email FROM t WHERE '***' = 'a'`, `SELECT * FROM t` ->
+// `SELECT *, '***' AS email FROM t`
+struct MaskFieldVisitor {
+    fields: HashSet<String>,
+}
+
+impl MaskFieldVisitor {
+    fn new(fields: HashSet<String>) -> Self {
+        Self { fields }
+    }
+
+    fn masked_field(&self, expr: &Expr) -> Option<Ident> {
+        let ident = match expr {
+            Expr::Identifier(ident) => ident,
+            Expr::CompoundIdentifier(idents) => idents.last()?,
+            _ => return None,
+        };
+        if self.fields.contains(&ident.value) {
+            Some(ident.clone())
+        } else {
+            None
+        }
+    }
+}
+
+fn mask_value() -> Expr {
+    Expr::Value(Value::SingleQuotedString(FIELD_MASK_VALUE.to_string()))
+}
+
+impl VisitorMut for MaskFieldVisitor {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &mut Query) -> ControlFlow<Self::Break> {
+        if let SetExpr::Select(select) = query.body.as_mut() {
+            let mut has_wildcard = false;
+            let mut aliases = HashSet::new();
+            for item in select.projection.iter_mut() {
+                match item {
+                    SelectItem::UnnamedExpr(expr) => {
+                        if let Some(ident) = self.masked_field(expr) {
+                            aliases.insert(ident.value.clone());
+                            *item = SelectItem::ExprWithAlias {
+                                expr: mask_value(),
+                                alias: ident,
+                            };
+                        }
+                    }
+                    SelectItem::ExprWithAlias { alias, .. } => {
+                        aliases.insert(alias.value.clone());
+                    }
+                    _ => {
+                        has_wildcard = true;
+                    }
+                }
+            }
+            if has_wildcard {
+                let mut fields = self.fields.iter().collect::<Vec<_>>();
+                fields.sort();
+                for field in fields {
+                    if !aliases.contains(field) {
+                        select.projection.push(SelectItem::ExprWithAlias {
+                            expr: mask_value(),
+                            alias: Ident::with_quote('"', field),
+                        });
+                    }
+                }
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<Self::Break> {
+        if self.masked_field(expr).is_some() {
+            *expr = mask_value();
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+// add the row filters of the streams to the where clause of every select reading them,
+// like `SELECT * FROM t WHERE a = 1` -> `SELECT * FROM t WHERE (a = 1) AND (team = 'payments')`
+struct RowFilterVisitor {
+    row_filters: HashMap<TableReference, Expr>,
+}
+
+impl RowFilterVisitor {
+    fn new(row_filters: HashMap<TableReference, Expr>) -> Self {
+        Self { row_filters }
+    }
+
+    fn table_reference(name: &ObjectName) -> Option<TableReference> {
+        // unquoted identifiers are normalized to lowercase when resolving the streams
+        let normalize = |ident: &Ident| {
+            if ident.quote_style.is_some() {
+                ident.value.clone()
+            } else {
+                ident.value.to_lowercase()
+            }
+        };
+        match name.0.as_slice() {
+            [table] => Some(TableReference::bare(normalize(table))),
+            [schema, table] => Some(TableReference::partial(normalize(schema), normalize(table))),
+            _ => None,
+        }
+    }
+
+    fn add_filters(&self, select: &mut Select) {
+        let relations = select
+            .from
+            .iter()
+            .flat_map(|table| {
+                std::iter::once(&table.relation)
+                    .chain(table.joins.iter().map(|join| &join.relation))
+            })
+            .collect::<Vec<_>>();
+        let need_qualify = relations.len() > 1;
+        let mut filters = vec![];
+        for relation in relations {
+            let TableFactor::Table { name, alias, .. } = relation else {
+                continue;
+            };
+            let Some(filter) =
+                Self::table_reference(name).and_then(|table| self.row_filters.get(&table))
+            else {
+                continue;
+            };
+            let mut filter = filter.clone();
+            if need_qualify {
+                let qualifier = match alias {
+                    Some(alias) => vec![alias.name.clone()],
+                    None => name.0.clone(),
+                };
+                let _ = visit_expressions_mut(&mut filter, |expr| {
+                    if let Expr::Identifier(ident) = expr {
+                        let mut idents = qualifier.clone();
+                        idents.push(ident.clone());
+                        *expr = Expr::CompoundIdentifier(idents);
+                    }
+                    ControlFlow::<()>::Continue(())
+                });
+            }
+            filters.push(filter);
+        }
+        if filters.is_empty() {
+            return;
+        }
+        if let Some(selection) = select.selection.take() {
+            filters.insert(0, Expr::Nested(Box::new(selection)));
+        }
+        select.selection = conjunction(filters.iter().collect());
+    }
+
+    fn add_filters_to_set_expr(&self, set_expr: &mut SetExpr) {
+        match set_expr {
+            SetExpr::Select(select) => self.add_filters(select),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.add_filters_to_set_expr(left);
+                self.add_filters_to_set_expr(right);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl VisitorMut for RowFilterVisitor {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &mut Query) -> ControlFlow<Self::Break> {
+        self.add_filters_to_set_expr(query.body.as_mut());
+        ControlFlow::Continue(())
+    }
+}
+
 // add _timestamp to the query like `SELECT name FROM t` -> `SELECT _timestamp, name FROM t`
 struct AddTimestampVisitor {}
 
@@ -2379,6 +2620,75 @@ mod tests {
         visitor.is_simple_count
     }
 
+    #[test]
+    fn test_mask_field_visitor() {
+        let cases = [
+            (
+                "SELECT * FROM t WHERE email = 'a'",
+                "SELECT *, '***' AS \"email\" FROM t WHERE '***' = 'a'",
+            ),
+            (
+                "SELECT name, email, count(*) FROM t GROUP BY name, t.email",
+                "SELECT name, '***' AS email, count(*) FROM t GROUP BY name, '***'",
+            ),
+            (
+                "SELECT name AS email FROM t WHERE name = 'a'",
+                "SELECT name AS email FROM t WHERE name = 'a'",
+            ),
+        ];
+        for (sql, expected_sql) in cases {
+            let mut statement = sqlparser::parser::Parser::parse_sql(&GenericDialect {}, sql)
+                .unwrap()
+                .pop()
+                .unwrap();
+            let mut visitor = MaskFieldVisitor::new(HashSet::from_iter(["email".to_string()]));
+            let _ = statement.visit(&mut visitor);
+            assert_eq!(statement.to_string(), expected_sql);
+            // masking an already masked query doesn't change it
+            let _ = statement.visit(&mut visitor);
+            assert_eq!(statement.to_string(), expected_sql);
+        }
+    }
+
+    #[test]
+    fn test_row_filter_visitor() {
+        let cases = [
+            (
+                "SELECT * FROM t",
+                "SELECT * FROM t WHERE (team = 'payments')",
+            ),
+            (
+                "SELECT * FROM T WHERE a = 1 OR b = 2",
+                "SELECT * FROM T WHERE (a = 1 OR b = 2) AND (team = 'payments')",
+            ),
+            (
+                "SELECT * FROM t AS x JOIN u ON x.id = u.id",
+                "SELECT * FROM t AS x JOIN u ON x.id = u.id WHERE (x.team = 'payments')",
+            ),
+            (
+                "SELECT a FROM t UNION ALL SELECT a FROM u",
+                "SELECT a FROM t WHERE (team = 'payments') UNION ALL SELECT a FROM u",
+            ),
+            (
+                "SELECT * FROM u WHERE id IN (SELECT id FROM t)",
+                "SELECT * FROM u WHERE id IN (SELECT id FROM t WHERE (team = 'payments'))",
+            ),
+        ];
+        let filter = row_filter::parse_filter("team = 'payments'").unwrap();
+        for (sql, expected_sql) in cases {
+            let mut statement = sqlparser::parser::Parser::parse_sql(&GenericDialect {}, sql)
+                .unwrap()
+                .pop()
+                .unwrap();
+            let mut visitor = RowFilterVisitor::new(HashMap::from_iter([(
+                TableReference::bare("t"),
+                Expr::Nested(Box::new(filter.clone())),
+            )]));
+            let _ = statement.visit(&mut visitor);
+            assert_eq!(statement.to_string(), expected_sql);
+        }
+    }
+
     #[test]
     fn test_is_simple_count_visit1() {
         let sql = "SELECT count(*) from t";
@@ -0,0 +1,195 @@
+// Copyright 2025 OpenObserve Inc.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Per-org secrets, stored encrypted in the cipher table and referenced as `${secret:name}`
+//! from destination urls and headers. Values are resolved at send time and never returned by
+//! the API.
+
+use config::{
+    get_config,
+    meta::{
+        destinations::Endpoint,
+        secrets::{SecretInfo, is_valid_secret_name, replace_secret_refs, secret_refs},
+    },
+    utils::time::now_micros,
+};
+use hashbrown::HashMap;
+use infra::{
+    errors::{DbError, Error as InfraError},
+    table::cipher::{self, CipherEntry, EntryKind, ListFilter},
+};
+
+use crate::service::db;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SecretError {
+    #[error("InfraError# {0}")]
+    InfraError(#[from] InfraError),
+    #[error(
+        "Secret name can only contain letters, digits, '_', '.' and '-' and be at most 128 characters"
+    )]
+    InvalidName,
+    #[error("Secret value cannot be empty")]
+    EmptyValue,
+    #[error("Secret not found: {0}")]
+    NotFound(String),
+    #[error("Secret with the same name already exists")]
+    AlreadyExists,
+    #[error("Secrets need the master encryption key to be configured")]
+    EncryptionNotConfigured,
+    #[error("Secret is currently used by destination: {0}")]
+    UsedByDestination(String),
+}
+
+pub async fn save(
+    org_id: &str,
+    name: &str,
+    value: &str,
+    user_id: &str,
+    create: bool,
+) -> Result<(), SecretError> {
+    // without a master key the cipher table stores the values as plaintext
+    if get_config().encryption.algorithm.is_empty() {
+        return Err(SecretError::EncryptionNotConfigured);
+    }
+    if !is_valid_secret_name(name) {
+        return Err(SecretError::InvalidName);
+    }
+    if value.is_empty() {
+        return Err(SecretError::EmptyValue);
+    }
+
+    let entry = CipherEntry {
+        org: org_id.to_string(),
+        created_at: now_micros(),
+        created_by: user_id.to_string(),
+        name: name.to_string(),
+        data: value.to_string(),
+        kind: EntryKind::Secret,
+    };
+    if create {
+        return match cipher::add(entry).await {
+            Ok(()) => Ok(()),
+            Err(InfraError::DbError(DbError::UniqueViolation)) => Err(SecretError::AlreadyExists),
+            Err(e) => Err(e.into()),
+        };
+    }
+
+    let Some(existing) = get_info(org_id, name).await? else {
+        return Err(SecretError::NotFound(name.to_string()));
+    };
+    cipher::update(CipherEntry {
+        created_at: existing.created_at,
+        created_by: existing.created_by,
+        ..entry
+    })
+    .await?;
+    Ok(())
+}
+
+/// Lists the secrets of the org, without their values.
+pub async fn list(org_id: &str) -> Result<Vec<SecretInfo>, SecretError> {
+    let filter = ListFilter {
+        org: Some(org_id.to_string()),
+        kind: Some(EntryKind::Secret),
+    };
+    Ok(cipher::list_filtered(filter, None)
+        .await?
+        .into_iter()
+        .map(|entry| SecretInfo {
+            name: entry.name,
+            created_at: entry.created_at,
+            created_by: entry.created_by,
+        })
+        .collect())
+}
+
+async fn get_info(org_id: &str, name: &str) -> Result<Option<SecretInfo>, SecretError> {
+    Ok(list(org_id)
+        .await?
+        .into_iter()
+        .find(|secret| secret.name == name))
+}
+
+pub async fn delete(org_id: &str, name: &str) -> Result<(), SecretError> {
+    if get_info(org_id, name).await?.is_none() {
+        return Err(SecretError::NotFound(name.to_string()));
+    }
+
+    let destinations = db::alerts::destinations::list(org_id, None)
+        .await
+        .map_err(|e| InfraError::Message(e.to_string()))?;
+    for dest in destinations {
+        if dest
+            .endpoint()
+            .is_some_and(|endpoint| endpoint.secret_refs().contains(&name))
+        {
+            return Err(SecretError::UsedByDestination(dest.name));
+        }
+    }
+
+    cipher::remove(org_id, EntryKind::Secret, name).await?;
+    Ok(())
+}
+
+/// Checks the referenced secrets exist in the org.
+pub async fn check_refs(org_id: &str, refs: &[&str]) -> Result<(), SecretError> {
+    if refs.is_empty() {
+        return Ok(());
+    }
+    let secrets = list(org_id).await?;
+    for name in refs {
+        if !secrets.iter().any(|secret| secret.name == *name) {
+            return Err(SecretError::NotFound(name.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the secret references in the text with the values of the secrets.
+pub async fn resolve(org_id: &str, text: &str) -> Result<String, SecretError> {
+    let refs = secret_refs(text);
+    if refs.is_empty() {
+        return Ok(text.to_string());
+    }
+    let mut values = HashMap::with_capacity(refs.len());
+    for name in refs {
+        if values.contains_key(name) {
+            continue;
+        }
+        let Some(value) = cipher::get_data(org_id, EntryKind::Secret, name).await? else {
+            return Err(SecretError::NotFound(name.to_string()));
+        };
+        values.insert(name, value);
+    }
+    replace_secret_refs(text, |name| {
+        values
+            .get(name)
+            .cloned()
+            .ok_or_else(|| SecretError::NotFound(name.to_string()))
+    })
+}
+
+/// Gets the endpoint with the secret references in its url and headers resolved, for sending.
+pub async fn resolve_endpoint(org_id: &str, endpoint: &Endpoint) -> Result<Endpoint, SecretError> {
+    let mut resolved = endpoint.clone();
+    resolved.url = resolve(org_id, &endpoint.url).await?;
+    if let Some(headers) = resolved.headers.as_mut() {
+        for value in headers.values_mut() {
+            *value = resolve(org_id, value).await?;
+        }
+    }
+    Ok(resolved)
+}
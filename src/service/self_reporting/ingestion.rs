@@ -15,16 +15,17 @@
 
 use anyhow::{Result, anyhow};
 use config::{
-    META_ORG_ID,
+    META_ORG_ID, SIZE_IN_MB,
     cluster::LOCAL_NODE,
     get_config,
     meta::{
         search::SearchEventType,
         self_reporting::{
             ReportingData,
+            audit::{AUDIT_STREAM, AuditData},
             usage::{AggregatedData, GroupKey, USAGE_STREAM, UsageData, UsageEvent},
         },
-        stream::{StreamParams, StreamType},
+        stream::{StreamParams, StreamSettings, StreamType},
     },
     utils::json,
 };
@@ -92,6 +93,7 @@ pub(super) async fn ingest_usages(mut curr_usages: Vec<UsageData>) {
             Entry::Occupied(mut occupied) => {
                 let entry = occupied.get_mut();
                 entry.usage_data.num_records += usage_data.num_records;
+                entry.usage_data.dropped_records += usage_data.dropped_records;
                 entry.usage_data.size += usage_data.size;
                 entry.usage_data.response_time += usage_data.response_time;
                 entry.count += 1;
@@ -178,17 +180,25 @@ pub(super) async fn ingest_usages(mut curr_usages: Vec<UsageData>) {
     }
 
     if &cfg.common.usage_reporting_mode != "remote" {
+        let mut published = HashMap::<String, u64>::new();
+        for usage in report_data
+            .iter()
+            .filter(|usage| usage.event == UsageEvent::Ingestion)
+        {
+            *published.entry(usage.org_id.clone()).or_default() += (usage.size * SIZE_IN_MB) as u64;
+        }
         let report_data = report_data
             .iter_mut()
             .map(|usage| json::to_value(usage).unwrap())
             .collect::<Vec<_>>();
         // report usage data
         let usage_stream = StreamParams::new(META_ORG_ID, USAGE_STREAM, StreamType::Logs);
-        if ingest_reporting_data(report_data, usage_stream)
-            .await
-            .is_err()
-            && &cfg.common.usage_reporting_mode != "both"
-        {
+        let ingested = ingest_reporting_data(report_data, usage_stream).await;
+        if ingested.is_ok() {
+            for (org_id, bytes) in published {
+                service::quotas::record_published(&org_id, bytes);
+            }
+        } else if &cfg.common.usage_reporting_mode != "both" {
             // on error in ingesting usage data, push back the data
             for usage_data in curr_usages {
                 if let Err(e) = super::queues::USAGE_QUEUE
@@ -204,6 +214,62 @@ pub(super) async fn ingest_usages(mut curr_usages: Vec<UsageData>) {
     }
 }
 
+/// Ingests the audit records into the `_audit` stream of their org, records of requests which
+/// aren't scoped to an org go to the meta org.
+pub(super) async fn ingest_audits(audits: Vec<AuditData>) {
+    let mut groups: HashMap<String, Vec<json::Value>> = HashMap::new();
+    for audit in audits {
+        let org_id = if audit.org_id.is_empty() {
+            META_ORG_ID.to_string()
+        } else {
+            audit.org_id.clone()
+        };
+        groups
+            .entry(org_id)
+            .or_default()
+            .push(json::to_value(audit).unwrap());
+    }
+
+    for (org_id, records) in groups {
+        let audit_stream = StreamParams::new(&org_id, AUDIT_STREAM, StreamType::Logs);
+        if ingest_reporting_data(records, audit_stream).await.is_ok() {
+            set_audit_retention(&org_id).await;
+        }
+    }
+}
+
+/// Applies `ZO_AUDIT_RETENTION_DAYS` to the audit stream of the org, unless the stream already
+/// has its own retention.
+async fn set_audit_retention(org_id: &str) {
+    let retention_days = get_config().common.audit_retention_days;
+    if retention_days <= 0 {
+        return;
+    }
+    let settings = infra::schema::get_settings(org_id, AUDIT_STREAM, StreamType::Logs).await;
+    if settings
+        .as_ref()
+        .is_some_and(|settings| settings.data_retention > 0)
+    {
+        return;
+    }
+    let settings = StreamSettings {
+        data_retention: retention_days,
+        ..settings.unwrap_or_default()
+    };
+    match service::stream::save_stream_settings(org_id, AUDIT_STREAM, StreamType::Logs, settings)
+        .await
+    {
+        Ok(resp) if resp.status().is_success() => {}
+        Ok(resp) => log::error!(
+            "[SELF-REPORTING] Failed to set retention of audit stream {org_id}/{AUDIT_STREAM}: {}",
+            resp.status()
+        ),
+        Err(e) => log::error!(
+            "[SELF-REPORTING] Failed to set retention of audit stream {org_id}/{AUDIT_STREAM}: {e}"
+        ),
+    }
+}
+
 pub(super) async fn ingest_reporting_data(
     reporting_data_json: Vec<json::Value>,
     stream_params: StreamParams,
@@ -23,6 +23,7 @@ use config::{
     meta::{
         self_reporting::{
             ReportingData,
+            audit::AuditData,
             error::ErrorData,
             usage::{RequestStats, TriggerData, UsageData, UsageEvent, UsageType},
         },
@@ -41,6 +42,17 @@ mod queues;
 
 pub async fn run() {
     let cfg = get_config();
+    #[cfg(not(feature = "enterprise"))]
+    if cfg.common.audit_enabled {
+        // Force initialization audit queue
+        let (audit_start_sender, audit_start_receiver) = oneshot::channel();
+        if let Err(e) = queues::AUDIT_QUEUE.start(audit_start_sender).await {
+            log::error!("[SELF-REPORTING] Failed to initialize audit queue: {e}");
+        } else if let Err(e) = audit_start_receiver.await {
+            log::error!("[SELF-REPORTING] Audit queue initialization failed: {e}");
+        }
+    }
+
     if !cfg.common.usage_enabled {
         return;
     }
@@ -89,6 +101,7 @@ pub async fn report_request_usage_stats(
         metrics::INGEST_BYTES
             .with_label_values(&[org_id, stream_type.as_str()])
             .inc_by((stats.size * SIZE_IN_MB) as u64);
+        super::quotas::record_ingested(org_id, (stats.size * SIZE_IN_MB) as u64);
     }
 
     if !get_config().common.usage_enabled {
@@ -251,6 +264,16 @@ pub async fn flush() {
     flush_audit().await;
 
     let cfg = get_config();
+    #[cfg(not(feature = "enterprise"))]
+    if cfg.common.audit_enabled {
+        let (res_sender, res_receiver) = oneshot::channel();
+        if let Err(e) = queues::AUDIT_QUEUE.shutdown(res_sender).await {
+            log::error!("[SELF-REPORTING] Error shutting down AUDIT_QUEUE: {e}");
+        }
+        // wait for flush ingestion job
+        res_receiver.await.ok();
+    }
+
     // only ingester and querier nodes report usage
     if !cfg.common.usage_enabled || (!LOCAL_NODE.is_ingester() && !LOCAL_NODE.is_querier()) {
         return;
@@ -274,6 +297,20 @@ pub async fn flush() {
     }
 }
 
+/// Queues the audit record of an API call to be ingested into the `_audit` stream.
+pub async fn publish_audit_data(audit_data: AuditData) {
+    if !get_config().common.audit_enabled {
+        return;
+    }
+
+    if let Err(e) = queues::AUDIT_QUEUE
+        .enqueue(ReportingData::Audit(Box::new(audit_data)))
+        .await
+    {
+        log::error!("[SELF-REPORTING] Failed to send audit data to background ingesting job: {e}");
+    }
+}
+
 // Cron job to frequently publish auditted events
 #[cfg(feature = "enterprise")]
 pub async fn run_audit_publish() {
@@ -39,6 +39,9 @@ pub(super) static USAGE_QUEUE: Lazy<Arc<ReportingQueue>> =
 pub(super) static ERROR_QUEUE: Lazy<Arc<ReportingQueue>> =
     Lazy::new(|| Arc::new(initialize_error_queue()));
 
+pub(super) static AUDIT_QUEUE: Lazy<Arc<ReportingQueue>> =
+    Lazy::new(|| Arc::new(initialize_audit_queue()));
+
 fn initialize_usage_queue() -> ReportingQueue {
     let cfg = get_config();
     let timeout = time::Duration::from_secs(
@@ -89,6 +92,27 @@ fn initialize_error_queue() -> ReportingQueue {
     ReportingQueue::new(msg_sender)
 }
 
+fn initialize_audit_queue() -> ReportingQueue {
+    let cfg = get_config();
+    let timeout = time::Duration::from_secs(
+        cfg.common
+            .usage_publish_interval
+            .try_into()
+            .expect("Env ZO_USAGE_PUBLISH_INTERVAL invalid format. Should be set as integer"),
+    );
+    let batch_size = cfg.common.usage_batch_size;
+
+    let (msg_sender, msg_receiver) = mpsc::channel::<ReportingMessage>(batch_size * 2);
+    let msg_receiver = Arc::new(Mutex::new(msg_receiver));
+
+    // a single thread keeps the audit records of a node in order
+    tokio::task::spawn(async move {
+        self_reporting_ingest_job(0, msg_receiver, batch_size, timeout).await
+    });
+
+    ReportingQueue::new(msg_sender)
+}
+
 async fn self_reporting_ingest_job(
     thread_id: usize,
     msg_receiver: Arc<Mutex<mpsc::Receiver<ReportingMessage>>>,
@@ -144,15 +168,16 @@ async fn ingest_buffered_data(thread_id: usize, buffered: Vec<ReportingData>) {
         buffered.len()
     );
 
-    let (usages, triggers, errors) = buffered.into_iter().fold(
-        (Vec::new(), Vec::new(), Vec::new()),
-        |(mut usages, mut triggers, mut errors), item| {
+    let (usages, triggers, errors, audits) = buffered.into_iter().fold(
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new()),
+        |(mut usages, mut triggers, mut errors, mut audits), item| {
             match item {
                 ReportingData::Usage(usage) => usages.push(*usage),
                 ReportingData::Trigger(trigger) => triggers.push(json::to_value(*trigger).unwrap()),
                 ReportingData::Error(error) => errors.push(json::to_value(*error).unwrap()),
+                ReportingData::Audit(audit) => audits.push(*audit),
             }
-            (usages, triggers, errors)
+            (usages, triggers, errors, audits)
         },
     );
 
@@ -221,4 +246,8 @@ async fn ingest_buffered_data(thread_id: usize, buffered: Vec<ReportingData>) {
             }
         }
     }
+
+    if !audits.is_empty() {
+        super::ingestion::ingest_audits(audits).await;
+    }
 }
@@ -463,6 +463,60 @@ pub async fn update_stream_settings(
                     .retain(|range| !new_settings.extended_retention_days.remove.contains(range));
             }
 
+            // a field has at most one policy, so an added policy replaces the old one
+            if !new_settings.field_policies.remove.is_empty() {
+                settings.field_policies.retain(|policy| {
+                    !new_settings
+                        .field_policies
+                        .remove
+                        .iter()
+                        .any(|p| p.field == policy.field)
+                });
+            }
+            for policy in new_settings.field_policies.add {
+                if policy.field.is_empty() {
+                    return Ok(HttpResponse::BadRequest().json(MetaHttpResponse::error(
+                        http::StatusCode::BAD_REQUEST,
+                        "field policy must have a field",
+                    )));
+                }
+                if policy.field == TIMESTAMP_COL_NAME {
+                    return Ok(HttpResponse::BadRequest().json(MetaHttpResponse::error(
+                        http::StatusCode::BAD_REQUEST,
+                        format!("{TIMESTAMP_COL_NAME} can't have a field policy"),
+                    )));
+                }
+                settings.field_policies.retain(|p| p.field != policy.field);
+                settings.field_policies.push(policy);
+            }
+
+            // row filters are named, an added filter replaces the one of the same name
+            if !new_settings.row_filters.remove.is_empty() {
+                settings.row_filters.retain(|filter| {
+                    !new_settings
+                        .row_filters
+                        .remove
+                        .iter()
+                        .any(|f| f.name == filter.name)
+                });
+            }
+            for filter in new_settings.row_filters.add {
+                if filter.name.is_empty() {
+                    return Ok(HttpResponse::BadRequest().json(MetaHttpResponse::error(
+                        http::StatusCode::BAD_REQUEST,
+                        "row filter must have a name",
+                    )));
+                }
+                if let Err(e) = crate::service::search::row_filter::parse_filter(&filter.filter) {
+                    return Ok(HttpResponse::BadRequest().json(MetaHttpResponse::error(
+                        http::StatusCode::BAD_REQUEST,
+                        e.to_string(),
+                    )));
+                }
+                settings.row_filters.retain(|f| f.name != filter.name);
+                settings.row_filters.push(filter);
+            }
+
             if !new_settings.distinct_value_fields.add.is_empty() {
                 for f in &new_settings.distinct_value_fields.add {
                     if f == "count" || f == TIMESTAMP_COL_NAME {
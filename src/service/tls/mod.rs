@@ -14,16 +14,83 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::{
+    any::Any,
     io::BufReader,
     net::{Ipv4Addr, Ipv6Addr},
     sync::Arc,
 };
 
-use actix_tls::connect::rustls_0_23::{native_roots_cert_store, webpki_roots_cert_store};
+use actix_tls::{
+    accept::rustls_0_23::TlsStream,
+    connect::rustls_0_23::{native_roots_cert_store, webpki_roots_cert_store},
+};
+use actix_web::{dev::Extensions, rt::net::TcpStream};
+use config::{meta::user::UserRole, utils::str::wildcard_match};
+use hashbrown::HashSet;
 use itertools::Itertools;
+use once_cell::sync::Lazy;
+use rustls::{RootCertStore, server::WebPkiClientVerifier};
 use rustls_pemfile::{certs, private_key};
+use serde::Deserialize;
 use x509_parser::prelude::*;
 
+use crate::service::db::org_users::get_cached_user_org;
+
+/// Maps the clients presenting a matching certificate to a service account of an org. The set
+/// patterns, with `*` as wildcard, must all match.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ClientCertMapping {
+    /// Pattern for the common name of the certificate subject.
+    #[serde(default)]
+    pub common_name: Option<String>,
+    /// Pattern matched against the DNS, email and URI subject alternative names.
+    #[serde(default)]
+    pub san: Option<String>,
+    pub org_id: String,
+    pub service_account: String,
+}
+
+static CLIENT_CERT_MAPPINGS: Lazy<Vec<ClientCertMapping>> = Lazy::new(|| {
+    let path = &config::get_config().auth.client_cert_mapping_path;
+    if path.is_empty() {
+        return vec![];
+    }
+    let mappings = std::fs::read(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|data| config::utils::json::from_slice(&data).map_err(anyhow::Error::from));
+    match mappings {
+        Ok(mappings) => mappings,
+        Err(e) => {
+            log::error!("Failed to load client certificate mappings from {path}: {e}");
+            vec![]
+        }
+    }
+});
+
+static CLIENT_CERT_DENY_LIST: Lazy<HashSet<String>> = Lazy::new(|| {
+    config::get_config()
+        .auth
+        .client_cert_deny_fingerprints
+        .split(',')
+        .map(|v| v.trim().replace(':', "").to_lowercase())
+        .filter(|v| !v.is_empty())
+        .collect()
+});
+
+/// The DER encoded leaf certificate a client presented on a mutual TLS connection.
+#[derive(Clone, Debug)]
+pub struct ClientCertificate(pub Vec<u8>);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClientCertAuth {
+    /// The certificate fingerprint is on the deny-list.
+    Revoked,
+    /// No mapping matches the certificate for the org.
+    Unmapped,
+    /// The email of the service account the certificate maps to.
+    ServiceAccount(String),
+}
+
 pub fn http_tls_config() -> Result<rustls::ServerConfig, anyhow::Error> {
     let cfg = config::get_config();
     let cert_file =
@@ -52,16 +119,110 @@ pub fn http_tls_config() -> Result<rustls::ServerConfig, anyhow::Error> {
         _ => rustls::DEFAULT_VERSIONS,
     };
 
-    let tls_config = rustls::ServerConfig::builder_with_protocol_versions(versions)
-        .with_no_client_auth()
-        .with_single_cert(
-            cert_chain.try_collect::<_, Vec<_>, _>()?,
-            private_key(key_file)?.unwrap(),
-        )?;
+    let builder = rustls::ServerConfig::builder_with_protocol_versions(versions);
+    let builder = if cfg.http.tls_client_ca_path.is_empty() {
+        builder.with_no_client_auth()
+    } else {
+        builder.with_client_cert_verifier(client_cert_verifier(&cfg.http.tls_client_ca_path)?)
+    };
+    let tls_config = builder.with_single_cert(
+        cert_chain.try_collect::<_, Vec<_>, _>()?,
+        private_key(key_file)?.unwrap(),
+    )?;
 
     Ok(tls_config)
 }
 
+/// Verifies client certificates against the CA certificates of the file. Clients may still
+/// connect without a certificate and authenticate with credentials instead.
+fn client_cert_verifier(
+    ca_path: &str,
+) -> Result<Arc<dyn rustls::server::danger::ClientCertVerifier>, anyhow::Error> {
+    let ca_file = &mut BufReader::new(std::fs::File::open(ca_path).map_err(|e| {
+        anyhow::anyhow!("Failed to open TLS client CA certificate file {ca_path}: {e}")
+    })?);
+    let mut roots = RootCertStore::empty();
+    roots.add_parsable_certificates(certs(ca_file).try_collect::<_, Vec<_>, _>()?);
+    let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+        .allow_unauthenticated()
+        .build()?;
+    Ok(verifier)
+}
+
+/// Keeps the certificate of mutual TLS clients in the connection data of their requests.
+pub fn http_on_connect(conn: &dyn Any, data: &mut Extensions) {
+    let Some(tls_stream) = conn.downcast_ref::<TlsStream<TcpStream>>() else {
+        return;
+    };
+    let (_, session) = tls_stream.get_ref();
+    if let Some(cert) = session.peer_certificates().and_then(|certs| certs.first()) {
+        data.insert(ClientCertificate(cert.to_vec()));
+    }
+}
+
+/// Hex encoded SHA-256 fingerprint of a DER encoded certificate.
+pub fn cert_fingerprint(cert: &[u8]) -> String {
+    sha256::digest(cert)
+}
+
+/// Maps the client certificate, already verified by the TLS handshake, to a service account of
+/// the org.
+pub fn authenticate_client_cert(cert: &[u8], org_id: &str) -> ClientCertAuth {
+    if CLIENT_CERT_DENY_LIST.contains(&cert_fingerprint(cert)) {
+        return ClientCertAuth::Revoked;
+    }
+    let Some(service_account) = match_client_cert(&CLIENT_CERT_MAPPINGS, cert, org_id) else {
+        return ClientCertAuth::Unmapped;
+    };
+    match get_cached_user_org(org_id, &service_account) {
+        Some(user) if user.role == UserRole::ServiceAccount => {
+            ClientCertAuth::ServiceAccount(user.email)
+        }
+        _ => {
+            log::warn!(
+                "Client certificate maps to {service_account}, which isn't a service account of org {org_id}"
+            );
+            ClientCertAuth::Unmapped
+        }
+    }
+}
+
+fn match_client_cert(mappings: &[ClientCertMapping], cert: &[u8], org_id: &str) -> Option<String> {
+    let (_, certificate) = parse_x509_certificate(cert).ok()?;
+    let common_names = certificate
+        .subject()
+        .iter_common_name()
+        .filter_map(|cn| cn.as_str().ok())
+        .collect::<Vec<_>>();
+    let mut sans = vec![];
+    if let Ok(Some(san)) = certificate.subject_alternative_name() {
+        for name in san.value.general_names.iter() {
+            match name {
+                GeneralName::DNSName(v) | GeneralName::RFC822Name(v) | GeneralName::URI(v) => {
+                    sans.push(*v)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    mappings
+        .iter()
+        .find(|mapping| {
+            mapping.org_id == org_id
+                && (mapping.common_name.is_some() || mapping.san.is_some())
+                && mapping
+                    .common_name
+                    .as_ref()
+                    .is_none_or(|pattern| common_names.iter().any(|cn| wildcard_match(pattern, cn)))
+                && mapping
+                    .san
+                    .as_ref()
+                    .is_none_or(|pattern| sans.iter().any(|san| wildcard_match(pattern, san)))
+        })
+        .map(|mapping| mapping.service_account.to_lowercase())
+}
+
 pub fn client_tls_config() -> Result<Arc<rustls::ClientConfig>, anyhow::Error> {
     let cfg = config::get_config();
     let cert_store = if cfg.http.tls_root_certificates.as_str().to_lowercase() == "native" {
@@ -238,6 +399,40 @@ mod tests {
 
     use super::*;
 
+    fn mapping(common_name: Option<&str>, san: Option<&str>) -> ClientCertMapping {
+        ClientCertMapping {
+            common_name: common_name.map(String::from),
+            san: san.map(String::from),
+            org_id: "default".to_string(),
+            service_account: "Edge@example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_match_client_cert() {
+        let CertifiedKey { cert, key_pair: _ } =
+            generate_simple_self_signed(vec!["edge-1.collectors.example.com".to_string()]).unwrap();
+        let cert = cert.der().to_vec();
+
+        let mappings = vec![mapping(None, Some("*.collectors.example.com"))];
+        assert_eq!(
+            match_client_cert(&mappings, &cert, "default").as_deref(),
+            Some("edge@example.com")
+        );
+        assert_eq!(match_client_cert(&mappings, &cert, "other"), None);
+
+        // rcgen names the subject "rcgen self signed cert"
+        let mappings = vec![mapping(Some("rcgen*"), Some("edge-2.*"))];
+        assert_eq!(match_client_cert(&mappings, &cert, "default"), None);
+        let mappings = vec![mapping(Some("rcgen*"), None)];
+        assert!(match_client_cert(&mappings, &cert, "default").is_some());
+        assert_eq!(
+            match_client_cert(&[mapping(None, None)], &cert, "default"),
+            None
+        );
+        assert_eq!(cert_fingerprint(&cert).len(), 64);
+    }
+
     #[test]
     fn test_get_server_url_from_cert_prefers_dns_name() {
         // Generate an example certificate
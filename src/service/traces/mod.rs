@@ -62,6 +62,7 @@ use crate::{
             write,
         },
         pipeline::batch_execution::ExecutablePipelineTraceInputs,
+        quotas::quota_exceeded_response,
         schema::{check_for_schema, stream_schema_exists},
         self_reporting::report_request_usage_stats,
     },
@@ -154,6 +155,9 @@ pub async fn handle_otlp_request(
     // check system resource
     if let Err(e) = check_ingestion_allowed(org_id, StreamType::Traces, None) {
         log::error!("[TRACES:OTLP] ingestion error: {e}");
+        if let infra::errors::Error::QuotaExceeded(exceeded) = &e {
+            return Ok(quota_exceeded_response(exceeded));
+        }
         return Ok(
             HttpResponse::ServiceUnavailable().json(MetaHttpResponse::error(
                 http::StatusCode::SERVICE_UNAVAILABLE,
@@ -574,6 +578,9 @@ pub async fn ingest_json(
     // check system resource
     if let Err(e) = check_ingestion_allowed(org_id, StreamType::Traces, None) {
         log::error!("[TRACES:JSON] ingestion error: {e}");
+        if let infra::errors::Error::QuotaExceeded(exceeded) = &e {
+            return Ok(quota_exceeded_response(exceeded));
+        }
         return Ok(
             HttpResponse::ServiceUnavailable().json(MetaHttpResponse::error(
                 http::StatusCode::SERVICE_UNAVAILABLE,
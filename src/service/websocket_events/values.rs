@@ -115,6 +115,7 @@ pub async fn handle_values_request(
                 stream_type,
                 &search_req,
                 search_req.use_cache,
+                Some(user_id),
             )
             .instrument(ws_values_span.clone())
             .await?;
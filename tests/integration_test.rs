@@ -16,10 +16,22 @@
 #[cfg(test)]
 mod tests {
     use core::time;
-    use std::{env, fs, net::SocketAddr, str, sync::Once, thread};
+    use std::{
+        collections::HashMap,
+        env, fs,
+        net::SocketAddr,
+        str,
+        sync::{LazyLock, Mutex, Once},
+        thread,
+    };
 
-    use actix_web::{App, http::header::ContentType, test, web};
+    use actix_web::{
+        App, HttpResponse, HttpServer,
+        http::{StatusCode, header::ContentType},
+        test, web,
+    };
     use arrow_flight::flight_service_server::FlightServiceServer;
+    use base64::Engine;
     use bytes::{Bytes, BytesMut};
     use chrono::{Duration, Utc};
     use config::{
@@ -52,6 +64,131 @@ mod tests {
 
     static START: Once = Once::new();
 
+    const MOCK_IDP_URL: &str = "http://127.0.0.1:5099";
+    const MOCK_IDP_CLIENT_ID: &str = "o2-test";
+
+    /// A minimal OIDC provider, signing id tokens with an ES256 key.
+    struct MockIdp {
+        key_pem: String,
+        jwk: json::Value,
+        /// code -> (nonce, code challenge)
+        codes: Mutex<HashMap<String, (String, String)>>,
+    }
+
+    static MOCK_IDP: LazyLock<MockIdp> = LazyLock::new(|| {
+        let key = rcgen::KeyPair::generate().unwrap();
+        // uncompressed point: 0x04 | x | y
+        let point = key.public_key_raw();
+        let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        MockIdp {
+            key_pem: key.serialize_pem(),
+            jwk: json::json!({
+                "kty": "EC",
+                "crv": "P-256",
+                "alg": "ES256",
+                "use": "sig",
+                "kid": "mock-key",
+                "x": b64.encode(&point[1..33]),
+                "y": b64.encode(&point[33..65]),
+            }),
+            codes: Mutex::new(HashMap::new()),
+        }
+    });
+
+    fn mock_idp_id_token(nonce: Option<&str>) -> String {
+        let now = Utc::now().timestamp();
+        let mut claims = json::json!({
+            "iss": MOCK_IDP_URL,
+            "aud": MOCK_IDP_CLIENT_ID,
+            "sub": "oidc-user",
+            "email": "oidc.user@example.com",
+            "email_verified": true,
+            "name": "OIDC User",
+            "groups": ["o2-editors"],
+            "iat": now,
+            "exp": now + 300,
+        });
+        if let Some(nonce) = nonce {
+            claims["nonce"] = json::json!(nonce);
+        }
+        let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::ES256);
+        header.kid = Some("mock-key".to_string());
+        let key = jsonwebtoken::EncodingKey::from_ec_pem(MOCK_IDP.key_pem.as_bytes()).unwrap();
+        jsonwebtoken::encode(&header, &claims, &key).unwrap()
+    }
+
+    async fn mock_idp_token(form: web::Form<HashMap<String, String>>) -> HttpResponse {
+        if form.get("client_id").map(|v| v.as_str()) != Some(MOCK_IDP_CLIENT_ID) {
+            return HttpResponse::Unauthorized().finish();
+        }
+        match form.get("grant_type").map(|v| v.as_str()) {
+            Some("authorization_code") => {
+                let code = form.get("code").cloned().unwrap_or_default();
+                let Some((nonce, challenge)) = MOCK_IDP.codes.lock().unwrap().remove(&code) else {
+                    return HttpResponse::BadRequest()
+                        .json(json::json!({"error": "invalid_grant"}));
+                };
+                let verifier = form.get("code_verifier").cloned().unwrap_or_default();
+                let digest = hex::decode(sha256::digest(verifier)).unwrap();
+                if base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(digest) != challenge {
+                    return HttpResponse::BadRequest()
+                        .json(json::json!({"error": "invalid_grant"}));
+                }
+                HttpResponse::Ok().json(json::json!({
+                    "access_token": "mock-access-token",
+                    "token_type": "Bearer",
+                    "id_token": mock_idp_id_token(Some(&nonce)),
+                    "refresh_token": "idp-refresh-1",
+                }))
+            }
+            Some("refresh_token") => {
+                if !form
+                    .get("refresh_token")
+                    .is_some_and(|v| v.starts_with("idp-refresh-"))
+                {
+                    return HttpResponse::BadRequest()
+                        .json(json::json!({"error": "invalid_grant"}));
+                }
+                HttpResponse::Ok().json(json::json!({
+                    "access_token": "mock-access-token",
+                    "token_type": "Bearer",
+                    "id_token": mock_idp_id_token(None),
+                    "refresh_token": "idp-refresh-2",
+                }))
+            }
+            _ => HttpResponse::BadRequest().json(json::json!({"error": "unsupported_grant_type"})),
+        }
+    }
+
+    fn start_mock_idp() {
+        let server = HttpServer::new(|| {
+            App::new()
+                .route(
+                    "/.well-known/openid-configuration",
+                    web::get().to(|| async {
+                        HttpResponse::Ok().json(json::json!({
+                            "issuer": MOCK_IDP_URL,
+                            "authorization_endpoint": format!("{MOCK_IDP_URL}/auth"),
+                            "token_endpoint": format!("{MOCK_IDP_URL}/token"),
+                            "jwks_uri": format!("{MOCK_IDP_URL}/keys"),
+                        }))
+                    }),
+                )
+                .route(
+                    "/keys",
+                    web::get().to(|| async {
+                        HttpResponse::Ok().json(json::json!({"keys": [MOCK_IDP.jwk.clone()]}))
+                    }),
+                )
+                .route("/token", web::post().to(mock_idp_token))
+        })
+        .workers(1)
+        .bind(MOCK_IDP_URL.trim_start_matches("http://"))
+        .expect("mock idp bind failed")
+        .run();
+        tokio::task::spawn(server);
+    }
+
     fn setup() -> (&'static str, &'static str) {
         START.call_once(|| unsafe {
             env::set_var("ZO_ROOT_USER_EMAIL", "root@example.com");
@@ -65,6 +202,10 @@ mod tests {
             env::set_var("ZO_PRINT_KEY_SQL", "true");
             env::set_var("ZO_SMTP_ENABLED", "true");
             env::set_var("ZO_CREATE_ORG_THROUGH_INGESTION", "true");
+            env::set_var("ZO_OIDC_ENABLED", "true");
+            env::set_var("ZO_OIDC_ISSUER_URL", MOCK_IDP_URL);
+            env::set_var("ZO_OIDC_CLIENT_ID", MOCK_IDP_CLIENT_ID);
+            env::set_var("ZO_OIDC_GROUP_ROLE_MAPPING", "o2-editors=e2e:editor");
 
             env_logger::init_from_env(
                 env_logger::Env::new().default_filter_or(&get_config().log.level),
@@ -180,6 +321,7 @@ mod tests {
         e2e_update_user_passcode().await;
         e2e_user_authentication().await;
         e2e_user_authentication_with_error().await;
+        e2e_oidc_login().await;
 
         // dashboards
         {
@@ -2389,6 +2531,119 @@ mod tests {
         assert!(resp.status().is_success());
     }
 
+    async fn e2e_oidc_login() {
+        setup();
+        start_mock_idp();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::JsonConfig::default().limit(get_config().limit.req_json_limit))
+                .app_data(web::PayloadConfig::new(
+                    get_config().limit.req_payload_limit,
+                ))
+                .configure(get_config_routes)
+                .configure(get_service_routes)
+                .configure(get_basic_routes),
+        )
+        .await;
+
+        // start the login
+        let req = test::TestRequest::get()
+            .uri("/config/dex_login")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        let login_url: String = json::from_slice(&test::read_body(resp).await).unwrap();
+        let login_url = url::Url::parse(&login_url).unwrap();
+        assert!(
+            login_url
+                .as_str()
+                .starts_with(&format!("{MOCK_IDP_URL}/auth?"))
+        );
+        let params: HashMap<String, String> = login_url.query_pairs().into_owned().collect();
+        assert_eq!(params["client_id"], MOCK_IDP_CLIENT_ID);
+        assert_eq!(params["code_challenge_method"], "S256");
+
+        // the user signs in at the idp, which sends them back with a code
+        MOCK_IDP.codes.lock().unwrap().insert(
+            "mock-code".to_string(),
+            (params["nonce"].clone(), params["code_challenge"].clone()),
+        );
+        let callback = format!("/config/redirect?code=mock-code&state={}", params["state"]);
+        let req = test::TestRequest::get().uri(&callback).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        let location = resp.headers().get("location").unwrap().to_str().unwrap();
+        assert!(location.contains("#id_token="));
+        let cookie = resp
+            .response()
+            .cookies()
+            .find(|c| c.name() == "auth_tokens")
+            .unwrap()
+            .into_owned();
+
+        // a state can not be replayed
+        let req = test::TestRequest::get().uri(&callback).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        // the user was created just in time and can call the api of the mapped org
+        let req = test::TestRequest::get()
+            .uri("/api/e2e/streams")
+            .cookie(cookie.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        let req = test::TestRequest::get()
+            .uri("/api/e2e/users")
+            .append_header(setup())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        let body = test::read_body(resp).await;
+        let users: json::Value = json::from_slice(&body).unwrap();
+        let user = users["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|u| u["email"] == "oidc.user@example.com")
+            .unwrap();
+        assert_eq!(user["role"], "editor");
+
+        // the refresh token opens a new session and ends the old one
+        let req = test::TestRequest::get()
+            .uri("/config/dex_refresh")
+            .cookie(cookie.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        let refreshed = resp
+            .response()
+            .cookies()
+            .find(|c| c.name() == "auth_tokens")
+            .unwrap()
+            .into_owned();
+        let req = test::TestRequest::get()
+            .uri("/api/e2e/streams")
+            .cookie(cookie.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let req = test::TestRequest::get()
+            .uri("/api/e2e/streams")
+            .cookie(refreshed)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+
+        // refresh tokens are rotated
+        let req = test::TestRequest::get()
+            .uri("/config/dex_refresh")
+            .cookie(cookie)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
     async fn e2e_config() {
         let auth = setup();
         let app = test::init_service(